@@ -0,0 +1,51 @@
+use eldritch::formatter;
+use std::fs;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+// Expand directories into the .eldritch files beneath them
+fn tome_files(paths: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        for entry in WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let is_tome = entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "eldritch");
+            if entry.file_type().is_file() && (is_tome || entry.depth() == 0) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files
+}
+
+/// Formats the tomes at `paths` in place. With `check`, only reports the tomes
+/// that would change. Returns false if any tome is unformatted (in check mode)
+/// or can't be parsed.
+pub fn fmt(paths: &[String], check: bool) -> anyhow::Result<bool> {
+    let mut ok = true;
+    for path in tome_files(paths) {
+        let source = fs::read_to_string(&path)?;
+        match formatter::format(&source) {
+            Ok(formatted) if formatted == source => {}
+            Ok(_) if check => {
+                eprintln!("would reformat {}", path.display());
+                ok = false;
+            }
+            Ok(formatted) => {
+                fs::write(&path, formatted)?;
+                println!("reformatted {}", path.display());
+            }
+            Err(e) => {
+                eprintln!("{}:{}: error: {}", path.display(), e.span.line, e.message);
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
@@ -2,12 +2,14 @@
 extern crate alloc;
 
 use clap::{Arg, ArgAction, Command};
+use eldritch::ExecutionLimits;
 use eldritch::agent::{fake::AgentFake, std::StdAgentLibrary};
 use eldritch::assets::{
     AssetsLibrary,
     std::{EmbeddedAssets, StdAssetsLibrary},
 };
 use eldritch::conversion::ToValue;
+use eldritch::typecheck::Severity;
 #[cfg(not(feature = "print_debug_tome"))]
 use eldritch::{ForeignValue, Interpreter, NoopPrinter};
 #[cfg(feature = "print_debug_tome")]
@@ -16,12 +18,20 @@ use eldritch_agent::Context;
 use pb::c2::TaskContext;
 use std::collections::BTreeMap;
 use std::fs;
+use std::path::Path;
 use std::process::exit;
 use std::sync::Arc;
+use std::time::Duration;
 
 mod directorybackend;
+mod fmt;
+mod metadata;
+mod profile;
 mod repl;
+mod tometest;
 use crate::directorybackend::DirectoryAssetBackend;
+use crate::metadata::TomeMetadata;
+use crate::profile::Profiles;
 
 // Get some embedded assets and implement them as AssetBackend and RustEmbed
 #[cfg(not(debug_assertions))]
@@ -37,6 +47,48 @@ pub struct GolemEmbeddedAssets;
 pub struct ParsedTome {
     pub name: String,
     pub eldritch: String,
+    pub metadata: Option<TomeMetadata>,
+}
+
+// Read the metadata.yml next to a tome, from disk or else from the asset locker
+fn load_metadata(
+    tome_path: &str,
+    locker: &StdAssetsLibrary,
+) -> anyhow::Result<Option<TomeMetadata>> {
+    let asset = match tome_path.rsplit_once('/') {
+        Some((dir, _)) => format!("{}/metadata.yml", dir),
+        None => "metadata.yml".to_string(),
+    };
+    let yaml = fs::read_to_string(Path::new(tome_path).with_file_name("metadata.yml"))
+        .ok()
+        .or_else(|| locker.read(asset).ok());
+    yaml.map(|yaml| TomeMetadata::parse(&yaml))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("{}: {:#}", tome_path, e))
+}
+
+// Build a tome's input_params from the --param arguments, validated against its metadata.
+// Prints the problems and returns None if the tome reads or is given undeclared or mistyped
+// parameters.
+fn tome_params(
+    tome: &ParsedTome,
+    params: &BTreeMap<String, String>,
+) -> Option<BTreeMap<String, String>> {
+    let Some(metadata) = &tome.metadata else {
+        return Some(params.clone());
+    };
+    let undeclared = metadata.check_references(&tome.eldritch);
+    for (line, message) in &undeclared {
+        eprintln!("{}:{}: error: {}", tome.name, line, message);
+    }
+    match metadata.input_params(params) {
+        Ok(params) if undeclared.is_empty() => Some(params),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("{}: error: {}", tome.name, e);
+            None
+        }
+    }
 }
 
 // Build a new runtime
@@ -64,6 +116,28 @@ fn new_runtime(assetlib: impl ForeignValue + 'static) -> Interpreter {
     interp
 }
 
+// Print type checker diagnostics for each tome, returning false if any are errors
+fn check_tomes(interp: &Interpreter, tomes: &[ParsedTome]) -> bool {
+    let checker = interp.type_checker();
+    let mut ok = true;
+    for tome in tomes {
+        for diagnostic in checker.check_source(&tome.eldritch) {
+            let severity = match diagnostic.severity {
+                Severity::Error => {
+                    ok = false;
+                    "error"
+                }
+                Severity::Warning => "warning",
+            };
+            eprintln!(
+                "{}:{}: {}[{}]: {}",
+                tome.name, diagnostic.span.line, severity, diagnostic.code, diagnostic.message
+            );
+        }
+    }
+    ok
+}
+
 fn main() -> anyhow::Result<()> {
     let matches = Command::new("golem")
         .arg(
@@ -100,8 +174,116 @@ fn main() -> anyhow::Result<()> {
                 .action(ArgAction::SetTrue)
                 .help("Dump tomes to be run and assets"),
         )
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Abort each tome after running for this many seconds"),
+        )
+        .arg(
+            Arg::new("max-steps")
+                .long("max-steps")
+                .value_name("STEPS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Abort each tome after executing this many interpreter steps"),
+        )
+        .arg(
+            Arg::new("max-loop-iterations")
+                .long("max-loop-iterations")
+                .value_name("ITERATIONS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Abort a tome when a single while loop runs for this many iterations"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("Type check tomes against their annotations instead of running them"),
+        )
+        .arg(
+            Arg::new("param")
+                .short('p')
+                .long("param")
+                .value_name("NAME=VALUE")
+                .action(ArgAction::Append)
+                .help("Set an input parameter declared in the tome's metadata.yml"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("FILE")
+                .help("Time each function, library method and line, writing folded stacks to FILE"),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Format tomes in place")
+                .arg(
+                    Arg::new("PATHS")
+                        .help("Tome files or directories to format")
+                        .action(ArgAction::Append)
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(ArgAction::SetTrue)
+                        .help("Report tomes that are not formatted instead of rewriting them"),
+                ),
+        )
+        .subcommand(
+            Command::new("test")
+                .about("Run the *_test.eldritch files next to each tome's main.eldritch")
+                .arg(
+                    Arg::new("PATHS")
+                        .help("Tome directories to search for tests")
+                        .action(ArgAction::Append)
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("junit")
+                        .long("junit")
+                        .value_name("FILE")
+                        .help("Write a JUnit XML report of the results to FILE"),
+                )
+                .arg(
+                    Arg::new("coverage")
+                        .long("coverage")
+                        .value_name("FILE")
+                        .help("Write an lcov report of the tome lines and branches the tests ran to FILE"),
+                ),
+        )
         .get_matches();
 
+    if let Some(fmt_matches) = matches.subcommand_matches("fmt") {
+        let paths: Vec<String> = fmt_matches
+            .get_many::<String>("PATHS")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        if !fmt::fmt(&paths, fmt_matches.get_flag("check"))? {
+            exit(127);
+        }
+        return Ok(());
+    }
+
+    if let Some(test_matches) = matches.subcommand_matches("test") {
+        let paths: Vec<String> = test_matches
+            .get_many::<String>("PATHS")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let junit = test_matches.get_one::<String>("junit").map(String::as_str);
+        let coverage = test_matches
+            .get_one::<String>("coverage")
+            .map(String::as_str);
+        if !tometest::test(&paths, junit, coverage)? {
+            exit(127);
+        }
+        return Ok(());
+    }
+
     let mut locker = StdAssetsLibrary::new();
 
     let asset_directories: Vec<String> = matches
@@ -138,6 +320,7 @@ fn main() -> anyhow::Result<()> {
                 })?;
 
             parsed_tomes.push(ParsedTome {
+                metadata: load_metadata(&tome_path, &locker)?,
                 name: tome_path.clone(),
                 eldritch: tome_contents,
             });
@@ -154,6 +337,7 @@ fn main() -> anyhow::Result<()> {
                             Err(e) => return Err(anyhow::anyhow!(e)),
                         };
                         parsed_tomes.push(ParsedTome {
+                            metadata: load_metadata(&asset, &locker)?,
                             name: asset,
                             eldritch: eldr_str,
                         });
@@ -172,6 +356,22 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    let mut limits = ExecutionLimits::new();
+    if let Some(secs) = matches.get_one::<u64>("timeout") {
+        limits = limits.with_timeout(Duration::from_secs(*secs));
+    }
+    if let Some(steps) = matches.get_one::<u64>("max-steps") {
+        limits = limits.with_max_steps(*steps);
+    }
+    if let Some(iterations) = matches.get_one::<u64>("max-loop-iterations") {
+        limits = limits.with_max_loop_iterations(*iterations);
+    }
+    interp = interp.with_limits(limits);
+    let profile_path = matches.get_one::<String>("profile");
+    if profile_path.is_some() {
+        interp = interp.with_profiler();
+    }
+
     // Print a debug for the configured assets and tomes
     if matches.get_flag("dump") {
         let tome_names: Vec<&str> = parsed_tomes.iter().map(|tome| tome.name.as_str()).collect();
@@ -185,20 +385,46 @@ fn main() -> anyhow::Result<()> {
         }
     }
 
-    // Time to run some commands
-    for tome in parsed_tomes {
-        // In the future we would like to set input params here.
-        // We could compile them in for the default dropper assets
-        let params: BTreeMap<String, String> = BTreeMap::new();
+    let mut params: BTreeMap<String, String> = BTreeMap::new();
+    for arg in matches.get_many::<String>("param").unwrap_or_default() {
+        let (name, value) = metadata::parse_param(arg)?;
+        params.insert(name, value);
+    }
+    let mut tome_inputs = Vec::new();
+    for tome in &parsed_tomes {
+        match tome_params(tome, &params) {
+            Some(inputs) => tome_inputs.push(inputs),
+            None => exit(127),
+        }
+    }
+
+    if matches.get_flag("check") {
         interp.define_variable("input_params", params.to_value());
-        match interp.interpret(&tome.eldritch) {
-            Ok(_) => {}
-            Err(e) => {
-                eprintln!("{}: {}", tome.name, e);
-                exit(127);
+        if !check_tomes(&interp, &parsed_tomes) {
+            exit(127);
+        }
+        return Ok(());
+    }
+
+    // Time to run some commands
+    let mut profiles = Profiles::default();
+    for (tome, inputs) in parsed_tomes.into_iter().zip(tome_inputs) {
+        interp.define_variable("input_params", inputs.to_value());
+        let result = interp.run(&tome.eldritch);
+        if let Some(profile) = interp.take_profile() {
+            profiles.add(&tome.name, profile);
+        }
+        if let Err(e) = result {
+            eprintln!("{}: {}", tome.name, e.with_filename(&tome.name));
+            if let Some(path) = profile_path {
+                profiles.write(path)?;
             }
+            exit(127);
         }
     }
+    if let Some(path) = profile_path {
+        profiles.write(path)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,145 @@
+// Parses a tome's metadata.yml and checks input_params against its paramdefs
+use anyhow::{Context, anyhow};
+use eldritch_core::{Argument, Expr, ExprKind, Lexer, Parser, Value, walk_exprs};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The type of a tome parameter, as declared in metadata.yml.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    String,
+    Int,
+    Float,
+    Bool,
+    List,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParamDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub placeholder: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TomeMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub paramdefs: Vec<ParamDef>,
+}
+
+impl TomeMetadata {
+    pub fn parse(yaml: &str) -> anyhow::Result<Self> {
+        serde_yaml::from_str(yaml).context("invalid metadata.yml")
+    }
+
+    fn param(&self, name: &str) -> Option<&ParamDef> {
+        self.paramdefs.iter().find(|p| p.name == name)
+    }
+
+    /// Checks that every `input_params` key the tome reads is declared,
+    /// returning `(line, message)` for each undeclared key.
+    pub fn check_references(&self, source: &str) -> Vec<(usize, String)> {
+        referenced_params(source)
+            .into_iter()
+            .filter(|(name, _)| self.param(name).is_none())
+            .map(|(name, line)| {
+                (
+                    line,
+                    format!("input_params[\"{}\"] is not declared in paramdefs", name),
+                )
+            })
+            .collect()
+    }
+
+    /// Builds the tome's input_params from `name=value` pairs given on the
+    /// command line. Values are checked against their declared type and
+    /// normalized, but stay strings as that is what agents pass to tomes.
+    pub fn input_params(
+        &self,
+        params: &BTreeMap<String, String>,
+    ) -> anyhow::Result<BTreeMap<String, String>> {
+        params
+            .iter()
+            .map(|(name, value)| {
+                let def = self.param(name).ok_or_else(|| {
+                    anyhow!("parameter '{}' is not declared in metadata.yml", name)
+                })?;
+                let value = coerce(def.param_type, value).map_err(|expected| {
+                    anyhow!("parameter '{}' expects {}, got '{}'", name, expected, value)
+                })?;
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+}
+
+// The normalized string form of `value`, or a description of the expected type
+fn coerce(param_type: ParamType, value: &str) -> Result<String, &'static str> {
+    let trimmed = value.trim();
+    match param_type {
+        ParamType::String => Ok(value.to_string()),
+        ParamType::Int => trimmed
+            .parse::<i64>()
+            .map(|i| i.to_string())
+            .map_err(|_| "an int"),
+        ParamType::Float => trimmed
+            .parse::<f64>()
+            .map(|f| f.to_string())
+            .map_err(|_| "a float"),
+        ParamType::Bool => match trimmed.to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "no" | "0" => Ok("false".to_string()),
+            _ => Err("a bool"),
+        },
+        // Tomes parse lists themselves, either as JSON or as `[a, b]`
+        ParamType::List if trimmed.starts_with('[') && trimmed.ends_with(']') => {
+            Ok(trimmed.to_string())
+        }
+        ParamType::List => Err("a list such as [a, b]"),
+    }
+}
+
+// `input_params["x"]` and `input_params.get("x")` keys read by `source`, with their line
+fn referenced_params(source: &str) -> Vec<(String, usize)> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens();
+    let (ast, _) = Parser::new(tokens).parse();
+    let is_params =
+        |expr: &Expr| matches!(&expr.kind, ExprKind::Identifier(n) if n == "input_params");
+    let literal = |expr: &Expr| match &expr.kind {
+        ExprKind::Literal(Value::String(s)) => Some(s.clone()),
+        _ => None,
+    };
+
+    let mut keys = Vec::new();
+    walk_exprs(&ast, &mut |expr| {
+        let key = match &expr.kind {
+            ExprKind::Index(obj, key) if is_params(obj) => literal(key),
+            ExprKind::Call(callee, args) => match (&callee.kind, args.first()) {
+                (ExprKind::GetAttr(obj, method), Some(Argument::Positional(key)))
+                    if method == "get" && is_params(obj) =>
+                {
+                    literal(key)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(key) = key {
+            keys.push((key, expr.span.line));
+        }
+    });
+    keys
+}
+
+/// Parses a `--param name=value` argument.
+pub fn parse_param(arg: &str) -> anyhow::Result<(String, String)> {
+    arg.split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+        .ok_or_else(|| anyhow!("invalid --param '{}', expected name=value", arg))
+}
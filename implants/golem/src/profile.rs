@@ -0,0 +1,71 @@
+use eldritch::{Profile, ProfileStats};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::time::Duration;
+
+// How many of the slowest functions, methods and lines to summarize
+const TOP: usize = 10;
+
+/// Collects the profiles of the tomes run by golem.
+#[derive(Default)]
+pub struct Profiles {
+    tomes: Vec<(String, Profile)>,
+}
+
+impl Profiles {
+    pub fn add(&mut self, tome: &str, profile: Profile) {
+        self.tomes.push((tome.to_string(), profile));
+    }
+
+    /// Writes the folded stacks of every tome to `path`, each rooted at the tome's name,
+    /// and prints the slowest functions, library methods and lines to stderr.
+    pub fn write(&self, path: &str) -> anyhow::Result<()> {
+        let mut folded = String::new();
+        for (tome, profile) in &self.tomes {
+            for line in profile.folded().lines() {
+                writeln!(folded, "{};{}", tome, line)?;
+            }
+        }
+        fs::write(path, folded)?;
+
+        for (tome, profile) in &self.tomes {
+            eprintln!("profile of {}:", tome);
+            summarize("function", &profile.functions);
+            summarize("method", &profile.natives);
+            let lines: BTreeMap<String, ProfileStats> = profile
+                .lines
+                .iter()
+                .map(|(line, stats)| (format!("line {}", line), *stats))
+                .collect();
+            summarize("line", &lines);
+        }
+        eprintln!("wrote folded stacks to {}", path);
+        Ok(())
+    }
+}
+
+fn summarize(kind: &str, stats: &BTreeMap<String, ProfileStats>) {
+    if stats.is_empty() {
+        return;
+    }
+    let mut rows: Vec<(&String, &ProfileStats)> = stats.iter().collect();
+    rows.sort_by(|a, b| b.1.exclusive.cmp(&a.1.exclusive));
+    eprintln!(
+        "  {:<32} {:>8} {:>12} {:>12}",
+        kind, "calls", "inclusive", "exclusive"
+    );
+    for (name, stats) in rows.into_iter().take(TOP) {
+        eprintln!(
+            "  {:<32} {:>8} {:>12} {:>12}",
+            name,
+            stats.calls,
+            millis(stats.inclusive),
+            millis(stats.exclusive)
+        );
+    }
+}
+
+fn millis(time: Duration) -> String {
+    format!("{:.3}ms", time.as_secs_f64() * 1000.0)
+}
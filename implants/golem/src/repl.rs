@@ -59,13 +59,13 @@ pub fn repl(mut inter: Interpreter) -> io::Result<()> {
                         stdout.execute(cursor::MoveToNextLine(1))?;
 
                         terminal::disable_raw_mode()?;
-                        match inter.interpret(&code) {
+                        match inter.run(&code) {
                             Ok(v) => {
                                 if !matches!(v, Value::None) {
                                     println!("{v:?}");
                                 }
                             }
-                            Err(e) => println!("Error: {e}"),
+                            Err(e) => println!("{}", e.render()),
                         }
                         terminal::enable_raw_mode()?;
 
@@ -0,0 +1,477 @@
+// Runs `*_test.eldritch` files against their tome with a mock agent and a fake filesystem
+use crate::directorybackend::DirectoryAssetBackend;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use eldritch::agent::std::StdAgentLibrary;
+use eldritch::assets::std::StdAssetsLibrary;
+use eldritch::conversion::ToValue;
+use eldritch::file::FileLibrary;
+use eldritch::file::fake::FileLibraryFake;
+use eldritch::macros::{eldritch_library, eldritch_library_impl, eldritch_method};
+use eldritch::pivot::std::StdPivotLibrary;
+use eldritch::process::fake::ProcessLibraryFake;
+use eldritch::report::ReportLibrary;
+use eldritch::report::std::StdReportLibrary;
+use eldritch::{BufferPrinter, CoverageRecorder, Interpreter, NoopPrinter, Printer, Value};
+use eldritch_agent::{Agent, Context};
+use eldritch_mockagent::MockAgent;
+use pb::c2::{self, TaskContext, report_file_request};
+use pb::eldritch::{File, FileMetadata};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use walkdir::WalkDir;
+
+const TEST_SUFFIX: &str = "_test.eldritch";
+
+/// A test file and the tome directory it sits in.
+struct TestFile {
+    path: PathBuf,
+    tome_dir: PathBuf,
+}
+
+struct TestCase {
+    file: String,
+    name: String,
+    seconds: f64,
+    failure: Option<String>,
+}
+
+// Find the `*_test.eldritch` files that sit next to a tome's main.eldritch
+fn test_files(paths: &[String]) -> Vec<TestFile> {
+    let mut files = Vec::new();
+    for path in paths {
+        for entry in WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let is_test = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.ends_with(TEST_SUFFIX));
+            if !entry.file_type().is_file() || !is_test {
+                continue;
+            }
+            let Some(tome_dir) = entry.path().parent() else {
+                continue;
+            };
+            if tome_dir.join("main.eldritch").is_file() {
+                files.push(TestFile {
+                    tome_dir: tome_dir.to_path_buf(),
+                    path: entry.into_path(),
+                });
+            }
+        }
+    }
+    files
+}
+
+/// State shared by a test and every run of its tome: the mock agent that
+/// records what was reported and the fake filesystem.
+#[derive(Clone)]
+struct Mocks {
+    agent: Arc<MockAgent>,
+    files: FileLibraryFake,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            agent: Arc::new(MockAgent::new()),
+            files: FileLibraryFake::default(),
+        }
+    }
+
+    // An interpreter whose agent, report, file and process libraries are backed by the mocks
+    fn interpreter(&self, printer: Arc<dyn Printer + Send + Sync>) -> Interpreter {
+        let mut interp = Interpreter::new_with_printer(printer).with_default_libs();
+        let agent: Arc<dyn Agent> = self.agent.clone();
+        let context = Context::Task(TaskContext {
+            task_id: 0,
+            jwt: String::new(),
+        });
+        interp.register_lib(StdAgentLibrary::new(agent.clone(), context.clone()));
+        interp.register_lib(StdPivotLibrary::new(agent.clone(), context.clone()));
+        interp.register_lib(MockReportLibrary {
+            inner: StdReportLibrary::new(agent, context),
+            files: self.files.clone(),
+        });
+        interp.register_lib(self.files.clone());
+        interp.register_lib(ProcessLibraryFake);
+        interp
+    }
+}
+
+impl core::fmt::Debug for Mocks {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Mocks").finish_non_exhaustive()
+    }
+}
+
+/// The `report` library used by tome tests: `report.file` reads from the fake
+/// filesystem, everything else goes to the mock agent as usual.
+#[derive(Debug)]
+#[eldritch_library_impl(ReportLibrary)]
+struct MockReportLibrary {
+    inner: StdReportLibrary,
+    files: FileLibraryFake,
+}
+
+impl ReportLibrary for MockReportLibrary {
+    fn file(&self, path: String) -> Result<(), String> {
+        let chunk = match self.files.read_binary(path.clone())? {
+            Value::Bytes(data) => data,
+            _ => return Err(format!("path '{}' is not a file", path)),
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let req = c2::ReportFileRequest {
+            context: Some(report_file_request::Context::TaskContext(TaskContext {
+                task_id: 0,
+                jwt: String::new(),
+            })),
+            chunk: Some(File {
+                metadata: Some(FileMetadata {
+                    path,
+                    ..Default::default()
+                }),
+                chunk,
+            }),
+            kind: c2::ReportFileKind::Ondisk as i32,
+        };
+        tx.send(req).map_err(|e| e.to_string())?;
+        drop(tx);
+        self.inner.agent.report_file(rx).map(|_| ())
+    }
+
+    fn process_list(&self, list: Vec<BTreeMap<String, Value>>) -> Result<(), String> {
+        self.inner.process_list(list)
+    }
+
+    fn ssh_key(&self, username: String, key: String) -> Result<(), String> {
+        self.inner.ssh_key(username, key)
+    }
+
+    fn user_password(&self, username: String, password: String) -> Result<(), String> {
+        self.inner.user_password(username, password)
+    }
+
+    fn ntlm_hash(&self, username: String, hash: String) -> Result<(), String> {
+        self.inner.ntlm_hash(username, hash)
+    }
+
+    fn screenshot(&self) -> Result<(), String> {
+        Err("screenshots are not supported in tome tests".to_string())
+    }
+}
+
+#[eldritch_library("tome")]
+/// The `tome` library is available to `*_test.eldritch` files run by `golem test`.
+///
+/// It runs the tome under test and exposes what the tome reported to the mock agent.
+trait TomeLibrary {
+    #[eldritch_method]
+    /// Runs the tome's `main.eldritch` with the given `input_params`.
+    ///
+    /// **Parameters**
+    /// - `params` (`Option<Dict>`): The input parameters of the tome.
+    ///
+    /// **Errors**
+    /// - Returns the tome's error if it fails.
+    fn run(&self, params: Option<BTreeMap<String, String>>) -> Result<(), String>;
+
+    #[eldritch_method]
+    /// Returns the files reported by the tome as dictionaries with `path` and `content`.
+    fn files(&self) -> Result<Vec<BTreeMap<String, Value>>, String>;
+
+    #[eldritch_method]
+    /// Returns the credentials reported by the tome as dictionaries with
+    /// `principal`, `secret` and `kind` (`password`, `ssh_key` or `ntlm_hash`).
+    fn credentials(&self) -> Result<Vec<BTreeMap<String, Value>>, String>;
+
+    #[eldritch_method]
+    /// Returns the processes reported by the tome as dictionaries with `pid`,
+    /// `ppid`, `name`, `principal`, `path` and `cmd`.
+    fn processes(&self) -> Result<Vec<BTreeMap<String, Value>>, String>;
+
+    #[eldritch_method]
+    /// Returns everything the tome printed.
+    fn output(&self) -> Result<String, String>;
+}
+
+#[derive(Debug)]
+#[eldritch_library_impl(TomeLibrary)]
+struct TestTomeLibrary {
+    tome_dir: PathBuf,
+    mocks: Mocks,
+    printer: Arc<BufferPrinter>,
+    coverage: Option<CoverageRecorder>,
+}
+
+impl TomeLibrary for TestTomeLibrary {
+    fn run(&self, params: Option<BTreeMap<String, String>>) -> Result<(), String> {
+        let source =
+            fs::read_to_string(self.tome_dir.join("main.eldritch")).map_err(|e| e.to_string())?;
+        let mut interp = self.mocks.interpreter(self.printer.clone());
+        // Assets are looked up relative to the directory holding the tome, as with `golem -a`
+        if let Some(root) = self.tome_dir.parent().and_then(Path::to_str) {
+            let root = if root.is_empty() { "." } else { root };
+            let backend = DirectoryAssetBackend::new(root).map_err(|e| e.to_string())?;
+            let mut assets = StdAssetsLibrary::new();
+            assets.add(Arc::new(backend)).map_err(|e| e.to_string())?;
+            interp.register_lib(assets);
+        }
+        if let Some(coverage) = &self.coverage {
+            interp = interp.with_coverage(coverage.clone());
+        }
+        interp.define_variable("input_params", params.unwrap_or_default().to_value());
+        interp.interpret(&source).map(|_| ())
+    }
+
+    fn files(&self) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let files = self.mocks.agent.reported_files.lock().unwrap();
+        Ok(files
+            .iter()
+            .map(|f| {
+                let path = f.metadata.as_ref().map(|m| m.path.clone());
+                let content = String::from_utf8_lossy(&f.chunk).into_owned();
+                BTreeMap::from([
+                    ("path".to_string(), path.unwrap_or_default().to_value()),
+                    ("content".to_string(), content.to_value()),
+                ])
+            })
+            .collect())
+    }
+
+    fn credentials(&self) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        use pb::eldritch::credential::Kind;
+        let credentials = self.mocks.agent.reported_credentials.lock().unwrap();
+        Ok(credentials
+            .iter()
+            .map(|c| {
+                let kind = match Kind::try_from(c.kind) {
+                    Ok(Kind::Password) => "password",
+                    Ok(Kind::SshKey) => "ssh_key",
+                    Ok(Kind::NtlmHash) => "ntlm_hash",
+                    _ => "unspecified",
+                };
+                BTreeMap::from([
+                    ("principal".to_string(), c.principal.clone().to_value()),
+                    ("secret".to_string(), c.secret.clone().to_value()),
+                    ("kind".to_string(), kind.to_string().to_value()),
+                ])
+            })
+            .collect())
+    }
+
+    fn processes(&self) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let processes = self.mocks.agent.reported_processes.lock().unwrap();
+        Ok(processes
+            .iter()
+            .map(|p| {
+                BTreeMap::from([
+                    ("pid".to_string(), Value::Int(p.pid as i64)),
+                    ("ppid".to_string(), Value::Int(p.ppid as i64)),
+                    ("name".to_string(), p.name.clone().to_value()),
+                    ("principal".to_string(), p.principal.clone().to_value()),
+                    ("path".to_string(), p.path.clone().to_value()),
+                    ("cmd".to_string(), p.cmd.clone().to_value()),
+                ])
+            })
+            .collect())
+    }
+
+    fn output(&self) -> Result<String, String> {
+        Ok(self.printer.read())
+    }
+}
+
+// A fresh interpreter with the test file loaded, ready to call its test functions
+fn load_test(
+    file: &TestFile,
+    source: &str,
+    coverage: Option<&CoverageRecorder>,
+) -> Result<Interpreter, String> {
+    let mocks = Mocks::new();
+    let mut interp = mocks.interpreter(Arc::new(NoopPrinter));
+    interp.register_lib(TestTomeLibrary {
+        tome_dir: file.tome_dir.clone(),
+        mocks,
+        printer: Arc::new(BufferPrinter::new()),
+        coverage: coverage.cloned(),
+    });
+    interp.interpret(source)?;
+    Ok(interp)
+}
+
+// The `test_*` functions defined by a test file
+fn test_names(interp: &mut Interpreter) -> Result<Vec<String>, String> {
+    let Value::List(names) = interp.interpret("dir()")? else {
+        return Ok(Vec::new());
+    };
+    let names = names.read();
+    Ok(names
+        .iter()
+        .filter_map(|name| match name {
+            Value::String(name) if name.starts_with("test_") => Some(name.clone()),
+            _ => None,
+        })
+        .collect())
+}
+
+// Each test function runs in its own interpreter so mocks don't leak between tests.
+// Only the runs of the tome count towards `coverage`, not the test file itself.
+fn run_file(file: &TestFile, coverage: Option<&CoverageRecorder>) -> Vec<TestCase> {
+    let display = file.path.display().to_string();
+    let case = |name: &str, started: Instant, failure: Option<String>| TestCase {
+        file: display.clone(),
+        name: name.to_string(),
+        seconds: started.elapsed().as_secs_f64(),
+        failure,
+    };
+
+    let started = Instant::now();
+    let source = match fs::read_to_string(&file.path) {
+        Ok(source) => source,
+        Err(e) => return vec![case("<load>", started, Some(e.to_string()))],
+    };
+    let names = match load_test(file, &source, None).and_then(|mut interp| test_names(&mut interp))
+    {
+        Ok(names) => names,
+        Err(e) => return vec![case("<load>", started, Some(e))],
+    };
+
+    names
+        .iter()
+        .map(|name| {
+            let started = Instant::now();
+            let result = load_test(file, &source, coverage)
+                .and_then(|mut interp| interp.interpret(&format!("{}()", name)));
+            case(name, started, result.err())
+        })
+        .collect()
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// The error line of a failure, skipping the traceback
+fn failure_message(failure: &str) -> &str {
+    failure
+        .lines()
+        .find(|line| !line.starts_with("Traceback") && !line.starts_with(' '))
+        .unwrap_or(failure)
+}
+
+// One <testsuite> per test file
+fn junit(cases: &[TestCase]) -> String {
+    let mut suites: Vec<(&str, Vec<&TestCase>)> = Vec::new();
+    for case in cases {
+        match suites.last_mut() {
+            Some((file, suite)) if *file == case.file => suite.push(case),
+            _ => suites.push((&case.file, vec![case])),
+        }
+    }
+
+    let failures = cases.iter().filter(|c| c.failure.is_some()).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"golem\" tests=\"{}\" failures=\"{}\">\n",
+        cases.len(),
+        failures
+    ));
+    for (file, suite) in suites {
+        let suite_failures = suite.iter().filter(|c| c.failure.is_some()).count();
+        let seconds: f64 = suite.iter().map(|c| c.seconds).sum();
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(file),
+            suite.len(),
+            suite_failures,
+            seconds
+        ));
+        for case in suite {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                xml_escape(&case.name),
+                xml_escape(file),
+                case.seconds
+            ));
+            match &case.failure {
+                Some(failure) => xml.push_str(&format!(
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    xml_escape(failure_message(failure)),
+                    xml_escape(failure)
+                )),
+                None => xml.push_str("/>\n"),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// One lcov record per tome, covering every test that ran it
+fn lcov(coverage: &BTreeMap<PathBuf, CoverageRecorder>) -> anyhow::Result<String> {
+    let mut lcov = String::new();
+    for (tome_dir, recorder) in coverage {
+        let main = tome_dir.join("main.eldritch");
+        let source = fs::read_to_string(&main)?;
+        lcov.push_str(&recorder.report(&source).lcov(&main.display().to_string()));
+    }
+    Ok(lcov)
+}
+
+/// Runs the tome tests found under `paths`, optionally writing a JUnit XML
+/// report to `junit_path` and an lcov coverage report of the tomes to
+/// `coverage_path`. Returns false if any test failed.
+pub fn test(
+    paths: &[String],
+    junit_path: Option<&str>,
+    coverage_path: Option<&str>,
+) -> anyhow::Result<bool> {
+    let mut cases = Vec::new();
+    let mut coverage: BTreeMap<PathBuf, CoverageRecorder> = BTreeMap::new();
+    for file in test_files(paths) {
+        let recorder =
+            coverage_path.map(|_| coverage.entry(file.tome_dir.clone()).or_default().clone());
+        for case in run_file(&file, recorder.as_ref()) {
+            match &case.failure {
+                None => println!("test {}::{} ... ok", case.file, case.name),
+                Some(failure) => {
+                    println!("test {}::{} ... FAILED", case.file, case.name);
+                    eprintln!("{}::{}: {}", case.file, case.name, failure);
+                }
+            }
+            cases.push(case);
+        }
+    }
+
+    let failed = cases.iter().filter(|c| c.failure.is_some()).count();
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        cases.len() - failed,
+        failed
+    );
+    if let Some(path) = junit_path {
+        fs::write(path, junit(&cases))?;
+    }
+    if let Some(path) = coverage_path {
+        fs::write(path, lcov(&coverage)?)?;
+    }
+    Ok(failed == 0)
+}
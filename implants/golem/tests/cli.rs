@@ -30,6 +30,249 @@ fn test_golem_main_syntax_fail() -> anyhow::Result<()> {
 
     Ok(())
 }
+// Test running `./golem --timeout 1 ../../bin/golem_cli_test_shadow/infinite_loop/main.eldritch`
+#[test]
+fn test_golem_main_timeout() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg("--timeout").arg("1");
+    cmd.arg(format!(
+        "{GOLEM_CLI_TEST_DIR}_shadow/infinite_loop/main.eldritch"
+    ));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("TimeoutError"));
+
+    Ok(())
+}
+// Test running `./golem --max-steps 1000 ../../bin/golem_cli_test_shadow/infinite_loop/main.eldritch`
+#[test]
+fn test_golem_main_max_steps() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg("--max-steps").arg("1000");
+    cmd.arg(format!(
+        "{GOLEM_CLI_TEST_DIR}_shadow/infinite_loop/main.eldritch"
+    ));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "StepLimitError: Step limit of 1000 exceeded",
+    ));
+
+    Ok(())
+}
+// Test running `./golem --check ../../bin/golem_cli_test_shadow/type_error/main.eldritch`
+#[test]
+fn test_golem_main_check_type_error() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg("--check");
+    cmd.arg(format!(
+        "{GOLEM_CLI_TEST_DIR}_shadow/type_error/main.eldritch"
+    ));
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("hello").not())
+        .stderr(predicate::str::contains(
+            "main.eldritch:4: error[type-mismatch]: argument 'name' of greet() expects str, got int",
+        ));
+
+    Ok(())
+}
+// Test running `./golem --check ../../bin/golem_cli_test/valid_tome/main.eldritch`
+#[test]
+fn test_golem_main_check_valid_tome() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg("--check");
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}/valid_tome/main.eldritch"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("HELLO").not())
+        .stderr(predicate::str::is_empty());
+
+    Ok(())
+}
+// Test running `./golem -p name=bob -p times=2 -p shout=yes ../../bin/golem_cli_test_params/greet/main.eldritch`
+#[test]
+fn test_golem_main_params() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.args(["-p", "name=bob", "-p", "times=2", "-p", "shout=yes"]);
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_params/greet/main.eldritch"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("HELLO BOB\nHELLO BOB\n"));
+
+    Ok(())
+}
+// Test running `./golem -p times=two ../../bin/golem_cli_test_params/greet/main.eldritch`
+#[test]
+fn test_golem_main_params_mistyped() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.args(["-p", "times=two"]);
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_params/greet/main.eldritch"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "parameter 'times' expects an int, got 'two'",
+    ));
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.args(["-p", "colour=red"]);
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_params/greet/main.eldritch"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "parameter 'colour' is not declared in metadata.yml",
+    ));
+
+    Ok(())
+}
+// Test running `./golem ../../bin/golem_cli_test_params/undeclared/main.eldritch`
+#[test]
+fn test_golem_main_params_undeclared_reference() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg(format!(
+        "{GOLEM_CLI_TEST_DIR}_params/undeclared/main.eldritch"
+    ));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "main.eldritch:2: error: input_params[\"mode\"] is not declared in paramdefs",
+    ));
+
+    Ok(())
+}
+// Test running `./golem fmt --check ../../bin/golem_cli_test_shadow/unformatted/main.eldritch`
+#[test]
+fn test_golem_fmt_check_unformatted() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    cmd.arg("fmt").arg("--check");
+    cmd.arg(format!(
+        "{GOLEM_CLI_TEST_DIR}_shadow/unformatted/main.eldritch"
+    ));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("would reformat"));
+
+    Ok(())
+}
+// Test running `./golem fmt <tmp>/main.eldritch` rewrites the tome
+#[test]
+fn test_golem_fmt_in_place() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tome = dir.path().join("main.eldritch");
+    std::fs::copy(
+        format!("{GOLEM_CLI_TEST_DIR}_shadow/unformatted/main.eldritch"),
+        &tome,
+    )?;
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("fmt").arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("reformatted"));
+    assert_eq!(
+        std::fs::read_to_string(&tome)?,
+        "def greet(name):\n    print(\"hello \" + name)\n\n\ngreet(\"world\")\n"
+    );
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("fmt").arg("--check").arg(dir.path());
+    cmd.assert().success();
+
+    Ok(())
+}
+// Test running `./golem <tmp>/main.eldritch` uses the real `file` library, not the fake one
+// `golem test` runs tome tests against
+#[test]
+fn test_golem_main_uses_real_file_library() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let data = dir.path().join("data.txt");
+    std::fs::write(&data, "real contents")?;
+    let tome = dir.path().join("main.eldritch");
+    std::fs::write(
+        &tome,
+        format!(
+            "print(file.exists({0:?}))\nprint(file.read({0:?}))\n",
+            data.display().to_string()
+        ),
+    )?;
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg(&tome);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("True\nreal contents"));
+
+    Ok(())
+}
+// Test running `./golem test --junit <tmp>/junit.xml ../../bin/golem_cli_test_tome_tests`
+#[test]
+fn test_golem_test_tome_tests() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let junit = dir.path().join("junit.xml");
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("test").arg("--junit").arg(&junit);
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_tome_tests"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "test_reports_file_and_credentials ... ok",
+        ))
+        .stdout(predicate::str::contains("3 passed; 0 failed"));
+
+    let report = std::fs::read_to_string(&junit)?;
+    assert!(report.contains("<testsuites name=\"golem\" tests=\"3\" failures=\"0\">"));
+    assert!(report.contains("<testcase name=\"test_process_list\""));
+
+    Ok(())
+}
+// Test running `./golem test --coverage <tmp>/lcov.info ../../bin/golem_cli_test_tome_tests`
+#[test]
+fn test_golem_test_coverage() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let lcov = dir.path().join("lcov.info");
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("test").arg("--coverage").arg(&lcov);
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_tome_tests"));
+    cmd.assert().success();
+
+    let report = std::fs::read_to_string(&lcov)?;
+    assert!(report.contains("collect/main.eldritch\n"));
+    assert!(report.contains("FNDA:3,collect\n"));
+    // Every credential line has a ':', so the tests never skip one
+    assert!(report.contains("BRDA:7,1,1,0\n"));
+    assert!(report.ends_with("end_of_record\n"));
+
+    Ok(())
+}
+// Test running `./golem test` on a tome whose test fails
+#[test]
+fn test_golem_test_failing_tome_test() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(
+        dir.path().join("main.eldritch"),
+        "report.ssh_key(\"root\", \"KEY\")\n",
+    )?;
+    std::fs::write(
+        dir.path().join("main_test.eldritch"),
+        "def test_key():\n    tome.run()\n    assert_eq(tome.credentials(), [])\n",
+    )?;
+    let junit = dir.path().join("junit.xml");
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("test").arg("--junit").arg(&junit).arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("test_key ... FAILED"))
+        .stderr(predicate::str::contains("Assertion failed"));
+    assert!(
+        std::fs::read_to_string(&junit)?
+            .contains("<failure message=\"RuntimeError: Assertion failed")
+    );
+
+    Ok(())
+}
 // Test running `./golem ../../bin/golem_cli_test/valid_tome/main.eldritch`
 #[test]
 fn test_golem_main_basic_non_interactive() -> anyhow::Result<()> {
@@ -86,6 +329,18 @@ fn test_golem_main_loaded_and_embdedded_files() -> anyhow::Result<()> {
     Ok(())
 }
 
+// Test running `./golem -a ../../bin/golem_cli_test_load/` where the tome loads a helper module from its assets
+#[test]
+fn test_golem_main_load_module_from_assets() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("-a");
+    cmd.arg(format!("{GOLEM_CLI_TEST_DIR}_load"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("loaded size: 3MB"));
+    Ok(())
+}
+
 // Test running `./golem -a ./../bin/golem_cli_test/ -a ./../bin/golem_cli_test_shadow/`. Should fail
 #[test]
 fn test_golem_main_loaded_files_shadow() -> anyhow::Result<()> {
@@ -111,3 +366,47 @@ fn test_golem_main_embedded_files() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+// Test running `./golem ../../bin/golem_cli_test_shadow/runtime_error/main.eldritch`
+#[test]
+fn test_golem_main_runtime_error_traceback() -> anyhow::Result<()> {
+    let mut cmd = Command::new(cargo_bin!("golem"));
+
+    let tome = format!("{GOLEM_CLI_TEST_DIR}_shadow/runtime_error/main.eldritch");
+    cmd.arg(&tome);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "File \"{tome}\", line 2, in lookup\n    return config[\"port\"]"
+        )))
+        .stderr(predicate::str::contains("KeyError"))
+        .stderr(predicate::str::contains("^-- here"));
+
+    Ok(())
+}
+
+// Test running `./golem --profile <tmp>/profile.folded ../../bin/golem_cli_test_shadow/runtime_error/main.eldritch`
+#[test]
+fn test_golem_main_profile() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let folded = dir.path().join("profile.folded");
+    let tome = format!("{GOLEM_CLI_TEST_DIR}_shadow/runtime_error/main.eldritch");
+
+    let mut cmd = Command::new(cargo_bin!("golem"));
+    cmd.arg("--profile").arg(&folded).arg(&tome);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("KeyError"))
+        .stderr(predicate::str::contains(format!("profile of {tome}:")));
+
+    // The profile is written even though the tome failed
+    let stacks = std::fs::read_to_string(&folded)?;
+    assert!(
+        stacks
+            .lines()
+            .any(|line| line.starts_with(&format!("{tome};<module>;lookup "))),
+        "{stacks}"
+    );
+
+    Ok(())
+}
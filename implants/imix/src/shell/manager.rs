@@ -48,6 +48,7 @@ fn dispatch_output(
             let task_error = if is_error {
                 Some(TaskError {
                     msg: output.clone(),
+                    ..Default::default()
                 })
             } else {
                 None
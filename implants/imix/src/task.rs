@@ -5,19 +5,26 @@ use std::time::SystemTime;
 
 use eldritch::agent::agent::Agent;
 use eldritch::assets::std::EmbeddedAssets;
-use eldritch::{Interpreter, Value, conversion::ToValue};
+use eldritch::{
+    CancellationToken, ExecutionLimits, Interpreter, ScriptError, Value, conversion::ToValue,
+};
 use eldritch_agent::Context;
 use pb::c2::{
     ReportOutputRequest, ReportTaskOutputMessage, Task, TaskContext, TaskError, TaskOutput,
-    report_output_request,
+    report_output_request, task_error,
 };
 use prost_types::Timestamp;
 use tokio::sync::mpsc;
 
 use crate::printer::StreamPrinter;
 
+/// Upper bound on the iterations of a single `while` loop in a tome, so a runaway
+/// loop fails its task instead of spinning until an operator stops it.
+const MAX_LOOP_ITERATIONS: u64 = 1_000_000;
+
 struct TaskHandle {
     quest: String,
+    cancel: CancellationToken,
 }
 
 #[derive(Clone)]
@@ -47,7 +54,8 @@ impl TaskRegistry {
 
         // 1. Register logic
         // TODO: Should de-dupe Tasks and TaskContext?
-        if !self.register_task(&task) {
+        let cancel = CancellationToken::new();
+        if !self.register_task(&task, cancel.clone()) {
             return;
         }
 
@@ -61,7 +69,7 @@ impl TaskRegistry {
 
         thread::spawn(move || {
             if let Some(tome) = task.tome {
-                execute_task(context, tome, agent, runtime_handle);
+                execute_task(context, tome, agent, runtime_handle, cancel);
             } else {
                 #[cfg(feature = "print_debug")]
                 log::warn!("Task {0} has no tome", task_context.clone().task_id);
@@ -75,7 +83,7 @@ impl TaskRegistry {
         });
     }
 
-    fn register_task(&self, task: &Task) -> bool {
+    fn register_task(&self, task: &Task, cancel: CancellationToken) -> bool {
         let mut tasks = self.tasks.lock().unwrap();
         if tasks.contains_key(&task.id) {
             return false;
@@ -84,6 +92,7 @@ impl TaskRegistry {
             task.id,
             TaskHandle {
                 quest: task.quest_name.clone(),
+                cancel,
             },
         );
         true
@@ -104,9 +113,11 @@ impl TaskRegistry {
 
     pub fn stop(&self, task_id: i64) {
         let mut tasks = self.tasks.lock().unwrap();
-        if tasks.remove(&task_id).is_some() {
+        if let Some(handle) = tasks.remove(&task_id) {
+            // The interpreter stops at its next checkpoint; a native call in progress will finish first
+            handle.cancel.cancel();
             #[cfg(feature = "print_debug")]
-            log::info!("Task {task_id} stop requested (thread may persist)");
+            log::info!("Task {task_id} stop requested");
         }
     }
 }
@@ -116,12 +127,14 @@ fn execute_task(
     tome: pb::eldritch::Tome,
     agent: Arc<dyn Agent>,
     runtime_handle: tokio::runtime::Handle,
+    cancel: CancellationToken,
 ) {
     // Setup StreamPrinter and Interpreter
     let (tx, rx) = mpsc::unbounded_channel();
     let (error_tx, error_rx) = mpsc::unbounded_channel();
     let printer = Arc::new(StreamPrinter::new(tx, error_tx));
-    let mut interp = setup_interpreter(context.clone(), &tome, agent.clone(), printer.clone());
+    let mut interp = setup_interpreter(context.clone(), &tome, agent.clone(), printer.clone())
+        .with_cancellation_token(cancel);
 
     let task_id = match &context {
         Context::Task(tc) => tc.task_id,
@@ -140,16 +153,19 @@ fn execute_task(
         error_rx,
     );
 
-    // Run Interpreter with panic protection
+    // Run Interpreter with panic protection. Failures keep their kind and traceback so
+    // Tavern can show them, alongside the rendered text.
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        interp.interpret(&tome.eldritch)
+        interp
+            .run(&tome.eldritch)
+            .map_err(|e| script_task_error(&e))
     }));
 
     // TODO: @Kcarretto remove this
     // Send interpreter errors through the streaming error channel before closing it,
     // ensuring errors are reported via the same path as eprint() output.
     match &result {
-        Ok(Err(e)) => printer.report_error(e),
+        Ok(Err(e)) => printer.report_error(&e.msg),
         Err(e) => printer.report_error(&format!("panic: {e:?}")),
         Ok(Ok(_)) => {}
     }
@@ -185,7 +201,9 @@ fn setup_interpreter(
     agent: Arc<dyn Agent>,
     printer: Arc<StreamPrinter>,
 ) -> Interpreter {
-    let mut interp = Interpreter::new_with_printer(printer).with_default_libs();
+    let mut interp = Interpreter::new_with_printer(printer)
+        .with_default_libs()
+        .with_limits(ExecutionLimits::new().with_max_loop_iterations(MAX_LOOP_ITERATIONS));
 
     // Remote asset filenames
     let remote_assets = tome.file_names.clone();
@@ -296,7 +314,10 @@ fn spawn_output_consumer(
                                         output: Some(TaskOutput {
                                             id: task_id,
                                             output: String::new(),
-                                            error: Some(TaskError { msg }),
+                                            error: Some(TaskError {
+                                                msg,
+                                                ..Default::default()
+                                            }),
                                             exec_started_at: None,
                                             exec_finished_at: None,
                                         }),
@@ -337,7 +358,10 @@ fn report_panic(context: Context, agent: &Arc<dyn Agent>, err: String) {
                 output: Some(TaskOutput {
                     id: task_id,
                     output: String::new(),
-                    error: Some(TaskError { msg: err }),
+                    error: Some(TaskError {
+                        msg: err,
+                        ..Default::default()
+                    }),
                     exec_started_at: None,
                     exec_finished_at: Some(Timestamp::from(SystemTime::now())),
                 }),
@@ -352,7 +376,27 @@ fn report_panic(context: Context, agent: &Arc<dyn Agent>, err: String) {
     }
 }
 
-fn report_result(context: Context, result: Result<Value, String>, agent: &Arc<dyn Agent>) {
+/// The `TaskError` reported for a script that failed, with its rendered traceback as `msg`.
+fn script_task_error(err: &ScriptError) -> TaskError {
+    TaskError {
+        msg: err.render(),
+        kind: err.kind.to_string(),
+        code: err.code.to_string(),
+        traceback: err
+            .traceback
+            .iter()
+            .map(|frame| task_error::Frame {
+                function: frame.function.clone(),
+                filename: frame.filename.clone(),
+                line: u32::try_from(frame.line).unwrap_or(u32::MAX),
+            })
+            .collect(),
+        library: err.library.clone().unwrap_or_default(),
+        hint: err.hint.clone().unwrap_or_default(),
+    }
+}
+
+fn report_result(context: Context, result: Result<Value, TaskError>, agent: &Arc<dyn Agent>) {
     let (task_id, task_context) = match context {
         Context::Task(tc) => (tc.task_id, tc),
         _ => return, // Only reporting for TaskContext
@@ -380,7 +424,7 @@ fn report_result(context: Context, result: Result<Value, String>, agent: &Arc<dy
         }
         Err(e) => {
             #[cfg(feature = "print_debug")]
-            log::info!("task={task_id} Error: {e}");
+            log::info!("task={task_id} Error: {}", e.msg);
 
             match agent.report_output(ReportOutputRequest {
                 message: Some(report_output_request::Message::TaskOutput(
@@ -389,7 +433,7 @@ fn report_result(context: Context, result: Result<Value, String>, agent: &Arc<dy
                         output: Some(TaskOutput {
                             id: task_id,
                             output: String::new(),
-                            error: Some(TaskError { msg: e }),
+                            error: Some(e),
                             exec_started_at: None,
                             exec_finished_at: Some(Timestamp::from(SystemTime::now())),
                         }),
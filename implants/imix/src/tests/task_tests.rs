@@ -273,6 +273,26 @@ async fn test_task_streaming_error() {
         false
     });
     assert!(error_report.is_some(), "Should report error");
+
+    // The final report carries the script error's structure, not just its text
+    let script_error = reports
+        .iter()
+        .filter_map(|r| match &r.message {
+            Some(report_output_request::Message::TaskOutput(m)) => m.output.as_ref(),
+            _ => None,
+        })
+        .filter_map(|o| o.error.as_ref())
+        .find(|e| !e.kind.is_empty())
+        .expect("Should report a structured error");
+    assert_eq!(script_error.kind, "ZeroDivisionError");
+    assert_eq!(script_error.code, "E0010");
+    assert!(script_error.msg.contains("ZeroDivisionError"));
+    let frames: Vec<(&str, u32)> = script_error
+        .traceback
+        .iter()
+        .map(|f| (f.function.as_str(), f.line))
+        .collect();
+    assert_eq!(frames, vec![("<module>", 2)]);
 }
 
 #[tokio::test]
@@ -303,6 +323,44 @@ async fn test_task_registry_list_and_stop() {
     );
 }
 
+#[tokio::test]
+async fn test_task_registry_stop_cancels_running_tome() {
+    let agent = Arc::new(MockAgent::new());
+    let task_id = 1000;
+    let code = "for i in range(10000):\n    for j in range(10000):\n        pass";
+    let task = c2::Task {
+        id: task_id,
+        tome: Some(Tome {
+            eldritch: code.to_string(),
+            ..Default::default()
+        }),
+        quest_name: "stop_running_quest".to_string(),
+        ..Default::default()
+    };
+
+    let registry = TaskRegistry::new();
+    registry.spawn(task, agent.clone());
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    registry.stop(task_id);
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let reports = agent.output_reports.lock().unwrap();
+    let cancelled = reports.iter().any(|r| {
+        if let Some(report_output_request::Message::TaskOutput(m)) = &r.message
+            && let Some(o) = &m.output
+            && let Some(err) = &o.error
+        {
+            return o.exec_finished_at.is_some() && err.msg.contains("CancelledError");
+        }
+        false
+    });
+    assert!(
+        cancelled,
+        "Stopped task should finish with a CancelledError"
+    );
+}
+
 #[tokio::test]
 async fn test_task_eprint_behavior() {
     let agent = Arc::new(MockAgent::new());
@@ -1,7 +1,7 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 use arbitrary::{Unstructured, Result};
-use eldritch_core::{Interpreter, Value, Printer, Span};
+use eldritch_core::{Freezable, Interpreter, Value, Printer, Span};
 use std::sync::Arc;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -44,7 +44,7 @@ fn arbitrary_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
                 for _ in 0..len {
                     vec.push(arbitrary_value(u, depth - 1)?);
                 }
-                Ok(Value::List(Arc::new(spin::RwLock::new(vec))))
+                Ok(Value::List(Arc::new(Freezable::new(vec))))
             },
             7 => {
                 let len = u.int_in_range(0..=5)?;
@@ -62,7 +62,7 @@ fn arbitrary_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
                     let v = arbitrary_value(u, depth - 1)?;
                     map.insert(k, v);
                 }
-                Ok(Value::Dictionary(Arc::new(spin::RwLock::new(map))))
+                Ok(Value::Dictionary(Arc::new(Freezable::new(map))))
             },
             9 => {
                 let len = u.int_in_range(0..=5)?;
@@ -70,7 +70,7 @@ fn arbitrary_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
                 for _ in 0..len {
                     set.insert(arbitrary_value(u, depth - 1)?);
                 }
-                Ok(Value::Set(Arc::new(spin::RwLock::new(set))))
+                Ok(Value::Set(Arc::new(Freezable::new(set))))
             },
             _ => unreachable!(),
         }
@@ -104,6 +104,41 @@ fn find_in_stmt(stmt: &Stmt, offset: usize) -> Option<Node<'_>> {
             }
             find_node_at_offset(body, offset)
         }
+        StmtKind::While(cond, body, else_block) => {
+            if let Some(n) = find_in_expr(cond, offset) {
+                return Some(n);
+            }
+            if let Some(n) = find_node_at_offset(body, offset) {
+                return Some(n);
+            }
+            if let Some(block) = else_block
+                && let Some(n) = find_node_at_offset(block, offset)
+            {
+                return Some(n);
+            }
+            None
+        }
+        StmtKind::Try(body, handlers, finally_block) => {
+            if let Some(n) = find_node_at_offset(body, offset) {
+                return Some(n);
+            }
+            for handler in handlers {
+                if let Some(kind) = &handler.kind
+                    && let Some(n) = find_in_expr(kind, offset)
+                {
+                    return Some(n);
+                }
+                if let Some(n) = find_node_at_offset(&handler.body, offset) {
+                    return Some(n);
+                }
+            }
+            if let Some(block) = finally_block
+                && let Some(n) = find_node_at_offset(block, offset)
+            {
+                return Some(n);
+            }
+            None
+        }
         _ => None,
     }
 }
@@ -159,16 +194,10 @@ fn find_in_expr(expr: &Expr, offset: usize) -> Option<Node<'_>> {
             .or_else(|| start.as_ref().and_then(|e| find_in_expr(e, offset)))
             .or_else(|| stop.as_ref().and_then(|e| find_in_expr(e, offset)))
             .or_else(|| step.as_ref().and_then(|e| find_in_expr(e, offset))),
-        ExprKind::FString(segments) => {
-            for seg in segments {
-                if let FStringSegment::Expression(e) = seg
-                    && let Some(n) = find_in_expr(e, offset)
-                {
-                    return Some(n);
-                }
-            }
-            None
-        }
+        ExprKind::FString(segments) => segments
+            .iter()
+            .flat_map(FStringSegment::exprs)
+            .find_map(|e| find_in_expr(e, offset)),
         ExprKind::ListComp {
             body,
             iterable,
@@ -233,3 +262,342 @@ fn find_in_expr(expr: &Expr, offset: usize) -> Option<Node<'_>> {
         Some(Node::Expr(expr))
     }
 }
+
+/// Calls `f` on every expression in `ast`, parents before their children.
+pub fn walk_exprs<'a>(ast: &'a [Stmt], f: &mut impl FnMut(&'a Expr)) {
+    for stmt in ast {
+        walk_stmt(stmt, f);
+    }
+}
+
+fn walk_params<'a>(params: &'a [Param], f: &mut impl FnMut(&'a Expr)) {
+    for param in params {
+        match param {
+            Param::Normal(_, annot) | Param::Star(_, annot) | Param::StarStar(_, annot) => {
+                if let Some(a) = annot {
+                    walk_expr(a, f);
+                }
+            }
+            Param::WithDefault(_, annot, default) => {
+                if let Some(a) = annot {
+                    walk_expr(a, f);
+                }
+                walk_expr(default, f);
+            }
+        }
+    }
+}
+
+fn walk_stmt<'a>(stmt: &'a Stmt, f: &mut impl FnMut(&'a Expr)) {
+    match &stmt.kind {
+        StmtKind::Expression(expr) | StmtKind::Return(Some(expr)) => walk_expr(expr, f),
+        StmtKind::Assignment(lhs, type_annot, rhs) => {
+            walk_expr(lhs, f);
+            if let Some(annot) = type_annot {
+                walk_expr(annot, f);
+            }
+            walk_expr(rhs, f);
+        }
+        StmtKind::AugmentedAssignment(lhs, _, rhs) => {
+            walk_expr(lhs, f);
+            walk_expr(rhs, f);
+        }
+        StmtKind::If(cond, then_block, else_block)
+        | StmtKind::While(cond, then_block, else_block) => {
+            walk_expr(cond, f);
+            walk_exprs(then_block, f);
+            if let Some(block) = else_block {
+                walk_exprs(block, f);
+            }
+        }
+        StmtKind::Def(_, params, return_annot, body) => {
+            walk_params(params, f);
+            if let Some(annot) = return_annot {
+                walk_expr(annot, f);
+            }
+            walk_exprs(body, f);
+        }
+        StmtKind::For(_, iterable, body) => {
+            walk_expr(iterable, f);
+            walk_exprs(body, f);
+        }
+        StmtKind::Try(body, handlers, finally_block) => {
+            walk_exprs(body, f);
+            for handler in handlers {
+                if let Some(kind) = &handler.kind {
+                    walk_expr(kind, f);
+                }
+                walk_exprs(&handler.body, f);
+            }
+            if let Some(block) = finally_block {
+                walk_exprs(block, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_expr<'a>(expr: &'a Expr, f: &mut impl FnMut(&'a Expr)) {
+    f(expr);
+    match &expr.kind {
+        ExprKind::BinaryOp(l, _, r) | ExprKind::LogicalOp(l, _, r) | ExprKind::Index(l, r) => {
+            walk_expr(l, f);
+            walk_expr(r, f);
+        }
+        ExprKind::UnaryOp(_, e) | ExprKind::GetAttr(e, _) => walk_expr(e, f),
+        ExprKind::Call(callee, args) => {
+            walk_expr(callee, f);
+            for arg in args {
+                match arg {
+                    Argument::Positional(e)
+                    | Argument::StarArgs(e)
+                    | Argument::KwArgs(e)
+                    | Argument::Keyword(_, e) => walk_expr(e, f),
+                }
+            }
+        }
+        ExprKind::List(exprs) | ExprKind::Tuple(exprs) | ExprKind::Set(exprs) => {
+            for e in exprs {
+                walk_expr(e, f);
+            }
+        }
+        ExprKind::Dictionary(entries) => {
+            for (k, v) in entries {
+                walk_expr(k, f);
+                walk_expr(v, f);
+            }
+        }
+        ExprKind::Slice(obj, start, stop, step) => {
+            walk_expr(obj, f);
+            for e in [start, stop, step].into_iter().flatten() {
+                walk_expr(e, f);
+            }
+        }
+        ExprKind::FString(segments) => {
+            for e in segments.iter().flat_map(FStringSegment::exprs) {
+                walk_expr(e, f);
+            }
+        }
+        ExprKind::ListComp {
+            body,
+            iterable,
+            cond,
+            ..
+        }
+        | ExprKind::SetComp {
+            body,
+            iterable,
+            cond,
+            ..
+        } => {
+            walk_expr(body, f);
+            walk_expr(iterable, f);
+            if let Some(c) = cond {
+                walk_expr(c, f);
+            }
+        }
+        ExprKind::DictComp {
+            key,
+            value,
+            iterable,
+            cond,
+            ..
+        } => {
+            walk_expr(key, f);
+            walk_expr(value, f);
+            walk_expr(iterable, f);
+            if let Some(c) = cond {
+                walk_expr(c, f);
+            }
+        }
+        ExprKind::Lambda { params, body } => {
+            walk_params(params, f);
+            walk_expr(body, f);
+        }
+        ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => {
+            walk_expr(cond, f);
+            walk_expr(then_branch, f);
+            walk_expr(else_branch, f);
+        }
+        _ => {}
+    }
+}
+
+/// Moves every span in `ast` forward by `offset` characters and `lines` lines, for
+/// statements parsed from a slice of a larger source that starts at that position.
+pub fn shift_spans(ast: &mut [Stmt], offset: usize, lines: usize) {
+    let shift = |span: &mut Span| {
+        span.start += offset;
+        span.end += offset;
+        span.line += lines;
+    };
+    for stmt in ast {
+        shift_stmt(stmt, &shift);
+    }
+}
+
+fn shift_block(block: &mut [Stmt], shift: &impl Fn(&mut Span)) {
+    for stmt in block {
+        shift_stmt(stmt, shift);
+    }
+}
+
+fn shift_params(params: &mut [Param], shift: &impl Fn(&mut Span)) {
+    for param in params {
+        match param {
+            Param::Normal(_, annot) | Param::Star(_, annot) | Param::StarStar(_, annot) => {
+                if let Some(a) = annot {
+                    shift_expr(a, shift);
+                }
+            }
+            Param::WithDefault(_, annot, default) => {
+                if let Some(a) = annot {
+                    shift_expr(a, shift);
+                }
+                shift_expr(default, shift);
+            }
+        }
+    }
+}
+
+fn shift_stmt(stmt: &mut Stmt, shift: &impl Fn(&mut Span)) {
+    shift(&mut stmt.span);
+    match &mut stmt.kind {
+        StmtKind::Expression(expr) | StmtKind::Return(Some(expr)) => shift_expr(expr, shift),
+        StmtKind::Assignment(lhs, type_annot, rhs) => {
+            shift_expr(lhs, shift);
+            if let Some(annot) = type_annot {
+                shift_expr(annot, shift);
+            }
+            shift_expr(rhs, shift);
+        }
+        StmtKind::AugmentedAssignment(lhs, _, rhs) => {
+            shift_expr(lhs, shift);
+            shift_expr(rhs, shift);
+        }
+        StmtKind::If(cond, then_block, else_block)
+        | StmtKind::While(cond, then_block, else_block) => {
+            shift_expr(cond, shift);
+            shift_block(then_block, shift);
+            if let Some(block) = else_block {
+                shift_block(block, shift);
+            }
+        }
+        StmtKind::Def(_, params, return_annot, body) => {
+            shift_params(params, shift);
+            if let Some(annot) = return_annot {
+                shift_expr(annot, shift);
+            }
+            shift_block(body, shift);
+        }
+        StmtKind::For(_, iterable, body) => {
+            shift_expr(iterable, shift);
+            shift_block(body, shift);
+        }
+        StmtKind::Try(body, handlers, finally_block) => {
+            shift_block(body, shift);
+            for handler in handlers {
+                shift(&mut handler.span);
+                if let Some(kind) = &mut handler.kind {
+                    shift_expr(kind, shift);
+                }
+                shift_block(&mut handler.body, shift);
+            }
+            if let Some(block) = finally_block {
+                shift_block(block, shift);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn shift_expr(expr: &mut Expr, shift: &impl Fn(&mut Span)) {
+    shift(&mut expr.span);
+    match &mut expr.kind {
+        ExprKind::BinaryOp(l, _, r) | ExprKind::LogicalOp(l, _, r) | ExprKind::Index(l, r) => {
+            shift_expr(l, shift);
+            shift_expr(r, shift);
+        }
+        ExprKind::UnaryOp(_, e) | ExprKind::GetAttr(e, _) => shift_expr(e, shift),
+        ExprKind::Call(callee, args) => {
+            shift_expr(callee, shift);
+            for arg in args {
+                match arg {
+                    Argument::Positional(e)
+                    | Argument::StarArgs(e)
+                    | Argument::KwArgs(e)
+                    | Argument::Keyword(_, e) => shift_expr(e, shift),
+                }
+            }
+        }
+        ExprKind::List(exprs) | ExprKind::Tuple(exprs) | ExprKind::Set(exprs) => {
+            for e in exprs {
+                shift_expr(e, shift);
+            }
+        }
+        ExprKind::Dictionary(entries) => {
+            for (k, v) in entries {
+                shift_expr(k, shift);
+                shift_expr(v, shift);
+            }
+        }
+        ExprKind::Slice(obj, start, stop, step) => {
+            shift_expr(obj, shift);
+            for e in [start, stop, step].into_iter().flatten() {
+                shift_expr(e, shift);
+            }
+        }
+        // Expressions inside an f-string are parsed on their own, so their spans are
+        // relative to the f-string wherever it is
+        ExprKind::ListComp {
+            body,
+            iterable,
+            cond,
+            ..
+        }
+        | ExprKind::SetComp {
+            body,
+            iterable,
+            cond,
+            ..
+        } => {
+            shift_expr(body, shift);
+            shift_expr(iterable, shift);
+            if let Some(c) = cond {
+                shift_expr(c, shift);
+            }
+        }
+        ExprKind::DictComp {
+            key,
+            value,
+            iterable,
+            cond,
+            ..
+        } => {
+            shift_expr(key, shift);
+            shift_expr(value, shift);
+            shift_expr(iterable, shift);
+            if let Some(c) = cond {
+                shift_expr(c, shift);
+            }
+        }
+        ExprKind::Lambda { params, body } => {
+            shift_params(params, shift);
+            shift_expr(body, shift);
+        }
+        ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => {
+            shift_expr(cond, shift);
+            shift_expr(then_branch, shift);
+            shift_expr(else_branch, shift);
+        }
+        _ => {}
+    }
+}
@@ -1,16 +1,20 @@
 use super::interpreter::Printer;
 use super::token::{Span, TokenKind};
+use crate::docs::LibrarySignature;
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
-use alloc::string::String;
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 use spin::RwLock;
 
 // Resolve circular reference for ForeignValue signature
-use crate::interpreter::Interpreter;
+use crate::interpreter::{Freezable, Interpreter};
 
 #[derive(Debug)]
 pub struct Environment {
@@ -18,6 +22,8 @@ pub struct Environment {
     pub values: BTreeMap<String, Value>,
     pub printer: Arc<dyn Printer + Send + Sync>,
     pub libraries: BTreeSet<String>,
+    /// `ExecutionLimits::max_sequence_len`, for builtins that collect an iterable.
+    pub max_sequence_len: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +40,9 @@ pub struct Function {
     pub params: Vec<RuntimeParam>,
     pub body: Vec<Stmt>,
     pub closure: Arc<RwLock<Environment>>,
+    /// Compiled body, shared by every function value made from the same definition.
+    #[cfg(feature = "bytecode")]
+    pub(crate) code: Option<Arc<crate::interpreter::vm::Code>>,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +75,67 @@ pub trait ForeignValue: fmt::Debug + Send + Sync {
         args: &[Value],
         kwargs: &BTreeMap<String, Value>,
     ) -> Result<Value, String>;
+    /// The parameters and docs of each method, for libraries declared with
+    /// `#[eldritch_library]`.
+    fn signature(&self) -> Option<&'static LibrarySignature> {
+        None
+    }
+}
+
+/// An iterator over script values; an `Err` ends the iteration and is raised in the script.
+pub type ValueIter = Box<dyn Iterator<Item = Result<Value, String>> + Send>;
+
+/// A sequence whose items are produced on demand, such as `range()` or a streaming
+/// directory walk returned by a library. Loops, comprehensions and builtins consume it
+/// one item at a time; indexing, `len()`, comparisons and printing treat it as a list.
+pub trait LazyIterable: fmt::Debug + Send + Sync {
+    /// The name reported by `type()`.
+    fn type_name(&self) -> &str;
+    /// Starts a new pass over the items. Every pass must yield the same items.
+    fn iter(&self) -> ValueIter;
+    /// The number of items, if it is known without iterating.
+    fn known_len(&self) -> Option<usize> {
+        None
+    }
+    /// The item at `index`, or `None` past the end. The default walks a new pass up to it;
+    /// indexing and slicing call this, so override it when an item can be computed
+    /// directly, as `range()` does.
+    fn get(&self, index: usize) -> Option<Result<Value, String>> {
+        nth_item(self.iter(), index)
+    }
+}
+
+// The item at `index` of a pass, or the iteration error that came before it.
+fn nth_item(mut iter: ValueIter, index: usize) -> Option<Result<Value, String>> {
+    for _ in 0..index {
+        if let Err(msg) = iter.next()? {
+            return Some(Err(msg));
+        }
+    }
+    iter.next()
+}
+
+impl dyn LazyIterable {
+    /// Materializes every item, stopping at the first error. With a `max_len`, fails with
+    /// a `MemoryLimitError` as soon as there are more items than that.
+    pub fn items(&self, max_len: Option<usize>) -> Result<Vec<Value>, String> {
+        let Some(max) = max_len else {
+            return self.iter().collect();
+        };
+        let too_long =
+            |len| format!("MemoryLimitError: result of length {len} exceeds the maximum of {max}");
+        if let Some(len) = self.known_len().filter(|&len| len > max) {
+            return Err(too_long(len));
+        }
+        let mut items = Vec::new();
+        for item in self.iter() {
+            items.push(item?);
+            if items.len() > max {
+                return Err(too_long(items.len()));
+            }
+        }
+        Ok(items)
+    }
 }
 
 #[derive(Clone)]
@@ -73,13 +143,20 @@ pub enum Value {
     None,
     Bool(bool),
     Int(i64),
+    /// An integer outside the `i64` range. Results that fit are always stored as `Int`,
+    /// so scripts only ever see a single `int` type.
+    BigInt(BigInt),
     Float(f64),
     String(String),
     Bytes(Vec<u8>),
-    List(Arc<RwLock<Vec<Value>>>),
+    List(Arc<Freezable<Vec<Value>>>),
     Tuple(Vec<Value>),
-    Dictionary(Arc<RwLock<BTreeMap<Value, Value>>>),
-    Set(Arc<RwLock<BTreeSet<Value>>>),
+    Dictionary(Arc<Freezable<BTreeMap<Value, Value>>>),
+    Set(Arc<Freezable<BTreeSet<Value>>>),
+    /// A lazily evaluated sequence; see [`LazyIterable`].
+    Iterable(Arc<dyn LazyIterable>),
+    /// An immutable record created by `struct(a=1, b=2)`, with its fields sorted by name.
+    Struct(Arc<BTreeMap<String, Value>>),
     Function(Function),
     NativeFunction(String, BuiltinFn),
     NativeFunctionWithKwargs(String, BuiltinFnWithKwargs),
@@ -95,6 +172,82 @@ impl fmt::Debug for Value {
     }
 }
 
+impl Value {
+    /// Wraps an arbitrary-precision integer, narrowing it to `Int` when it fits.
+    pub fn from_bigint(i: BigInt) -> Value {
+        match i.to_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::BigInt(i),
+        }
+    }
+
+    /// The value of an `Int` or `BigInt` as an arbitrary-precision integer.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Value::Int(i) => Some(BigInt::from(*i)),
+            Value::BigInt(i) => Some(i.clone()),
+            _ => None,
+        }
+    }
+
+    /// Starts a pass over the items of an iterable value, or `None` if it is not
+    /// iterable. Containers are snapshotted; lazy iterables are not materialized.
+    pub fn try_iter(&self) -> Option<ValueIter> {
+        let items: Vec<Value> = match self {
+            Value::Iterable(it) => return Some(it.iter()),
+            Value::List(l) => l.read().clone(),
+            Value::Tuple(t) => t.clone(),
+            Value::Set(s) => s.read().iter().cloned().collect(),
+            Value::Dictionary(d) => d.read().keys().cloned().collect(),
+            Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
+            _ => return None,
+        };
+        Some(Box::new(items.into_iter().map(Ok)))
+    }
+
+    /// The item at `index` of an iterable value, without collecting a lazy iterable.
+    pub fn item_at(&self, index: usize) -> Option<Result<Value, String>> {
+        match self {
+            Value::List(l) => l.read().get(index).cloned().map(Ok),
+            Value::Tuple(t) => t.get(index).cloned().map(Ok),
+            Value::Iterable(it) => it.get(index),
+            _ => nth_item(self.try_iter()?, index),
+        }
+    }
+
+    /// The number of items in an iterable value, if it is known without iterating.
+    pub fn known_len(&self) -> Option<usize> {
+        match self {
+            Value::List(l) => Some(l.read().len()),
+            Value::Tuple(t) => Some(t.len()),
+            Value::Set(s) => Some(s.read().len()),
+            Value::Dictionary(d) => Some(d.read().len()),
+            Value::String(s) => Some(s.chars().count()),
+            Value::Iterable(it) => it.known_len(),
+            _ => None,
+        }
+    }
+
+    // A pass over a lazy iterable or a list, so a lazy iterable can be compared with either
+    // item by item without collecting it. `None` for every other value.
+    fn list_items(&self) -> Option<ValueIter> {
+        match self {
+            Value::Iterable(_) | Value::List(_) => self.try_iter(),
+            _ => None,
+        }
+    }
+
+    /// The value of an `Int`, `BigInt` or `Float` as a float, rounding big integers.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(*i as f64),
+            Value::BigInt(i) => i.to_f64(),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         let mut visited = BTreeSet::new();
@@ -104,6 +257,17 @@ impl PartialEq for Value {
 
 impl Value {
     fn eq_helper(&self, other: &Self, visited: &mut BTreeSet<(usize, usize)>) -> bool {
+        if let (Value::Iterable(a), Value::Iterable(b)) = (self, other)
+            && Arc::ptr_eq(a, b)
+        {
+            return true;
+        }
+        if matches!(self, Value::Iterable(_)) || matches!(other, Value::Iterable(_)) {
+            return match (self.list_items(), other.list_items()) {
+                (Some(a), Some(b)) => items_eq(a, b, visited),
+                _ => false,
+            };
+        }
         let p1 = match self {
             Value::List(l) => Arc::as_ptr(l) as usize,
             Value::Dictionary(d) => Arc::as_ptr(d) as usize,
@@ -129,6 +293,7 @@ impl Value {
             (Value::None, Value::None) => true,
             (Value::Bool(a), Value::Bool(b)) => a == b,
             (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Bytes(a), Value::Bytes(b)) => a == b,
@@ -186,6 +351,13 @@ impl Value {
                         .all(|(va, vb)| va.eq_helper(vb, visited))
                 }
             }
+            (Value::Struct(a), Value::Struct(b)) => {
+                Arc::ptr_eq(a, b)
+                    || (a.len() == b.len()
+                        && a.iter()
+                            .zip(b.iter())
+                            .all(|((ka, va), (kb, vb))| ka == kb && va.eq_helper(vb, visited)))
+            }
             (Value::Function(a), Value::Function(b)) => a.name == b.name,
             (Value::NativeFunction(a, _), Value::NativeFunction(b, _)) => a == b,
             (Value::NativeFunctionWithKwargs(a, _), Value::NativeFunctionWithKwargs(b, _)) => {
@@ -205,6 +377,39 @@ impl Value {
     }
 }
 
+// Item-by-item equality of two passes. An iteration error makes them unequal; scripts
+// compare lazy iterables through `binary_op`, which raises it instead.
+fn items_eq(mut a: ValueIter, mut b: ValueIter, visited: &mut BTreeSet<(usize, usize)>) -> bool {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => {
+                if !x.eq_helper(&y, visited) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+// Lexicographic ordering of two passes, where an iteration error ends its pass.
+fn items_cmp(
+    mut a: ValueIter,
+    mut b: ValueIter,
+    visited: &mut BTreeSet<(usize, usize)>,
+) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => match x.cmp_helper(&y, visited) {
+                Ordering::Equal => {}
+                ord => return ord,
+            },
+            (x, y) => return matches!(x, Some(Ok(_))).cmp(&matches!(y, Some(Ok(_)))),
+        }
+    }
+}
+
 impl Eq for Value {}
 
 impl PartialOrd for Value {
@@ -222,6 +427,11 @@ impl Ord for Value {
 
 impl Value {
     fn cmp_helper(&self, other: &Self, visited: &mut BTreeSet<(usize, usize)>) -> Ordering {
+        if (matches!(self, Value::Iterable(_)) || matches!(other, Value::Iterable(_)))
+            && let (Some(a), Some(b)) = (self.list_items(), other.list_items())
+        {
+            return items_cmp(a, b, visited);
+        }
         let p1 = match self {
             Value::List(l) => Arc::as_ptr(l) as usize,
             Value::Dictionary(d) => Arc::as_ptr(d) as usize,
@@ -261,11 +471,20 @@ impl Value {
                 }
                 return f.total_cmp(&(*i as f64));
             }
+            // Big integers never overlap with `Int`, but must still sort numerically among ints and floats
+            (Value::BigInt(_), Value::Int(_) | Value::BigInt(_))
+            | (Value::Int(_), Value::BigInt(_)) => {
+                return self.to_bigint().cmp(&other.to_bigint());
+            }
+            (Value::BigInt(_), Value::Float(_)) | (Value::Float(_), Value::BigInt(_)) => {
+                let (a, b) = (self.to_f64().unwrap_or(0.0), other.to_f64().unwrap_or(0.0));
+                return a.total_cmp(&b);
+            }
             _ => {}
         }
 
         // Define an ordering between types:
-        // None < Bool < Int < Float < String < Bytes < List < Tuple < Dict < Set < Function < Native < Bound < Foreign
+        // None < Bool < Int < Float < String < Bytes < List < Tuple < Dict < Set < Function < Native < Bound < Foreign < Struct
         let self_discriminant = self.discriminant_value();
         let other_discriminant = other.discriminant_value();
 
@@ -383,6 +602,24 @@ impl Value {
                     }
                 }
             }
+            (Value::Struct(a), Value::Struct(b)) => {
+                // Field by field, like a dictionary with string keys
+                let mut it1 = a.iter();
+                let mut it2 = b.iter();
+                loop {
+                    match (it1.next(), it2.next()) {
+                        (Some((k1, v1)), Some((k2, v2))) => {
+                            let ord = k1.cmp(k2).then_with(|| v1.cmp_helper(v2, visited));
+                            if ord != Ordering::Equal {
+                                break ord;
+                            }
+                        }
+                        (Some(_), None) => break Ordering::Greater,
+                        (None, Some(_)) => break Ordering::Less,
+                        (None, None) => break Ordering::Equal,
+                    }
+                }
+            }
             (Value::Function(a), Value::Function(b)) => a.name.cmp(&b.name),
             (Value::NativeFunction(a, _), Value::NativeFunction(b, _)) => a.cmp(b),
             (Value::NativeFunctionWithKwargs(a, _), Value::NativeFunctionWithKwargs(b, _)) => {
@@ -414,11 +651,11 @@ impl Value {
         match self {
             Value::None => 0,
             Value::Bool(_) => 1,
-            Value::Int(_) => 2,
+            Value::Int(_) | Value::BigInt(_) => 2,
             Value::Float(_) => 3,
             Value::String(_) => 4,
             Value::Bytes(_) => 5,
-            Value::List(_) => 6,
+            Value::List(_) | Value::Iterable(_) => 6,
             Value::Tuple(_) => 7,
             Value::Dictionary(_) => 8,
             Value::Set(_) => 9,
@@ -427,6 +664,7 @@ impl Value {
             Value::NativeFunctionWithKwargs(_, _) => 12,
             Value::BoundMethod(_, _) => 13,
             Value::Foreign(_) => 14,
+            Value::Struct(_) => 15,
         }
     }
 
@@ -435,6 +673,7 @@ impl Value {
             Value::None => write!(f, "None"),
             Value::Bool(b) => write!(f, "{}", if *b { "True" } else { "False" }),
             Value::Int(i) => write!(f, "{i}"),
+            Value::BigInt(i) => write!(f, "{i}"),
             Value::Float(fl) => write!(f, "{fl:?}"),
             Value::String(s) => write!(f, "{s:?}"),
             Value::Bytes(b) => {
@@ -533,6 +772,28 @@ impl Value {
                 visited.remove(&ptr);
                 Ok(())
             }
+            Value::Iterable(it) => {
+                write!(f, "[")?;
+                // An iteration error ends the output; `print()` and `str()` raise it first
+                for (i, item) in it.iter().map_while(Result::ok).enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.fmt_helper(f, visited)?;
+                }
+                write!(f, "]")
+            }
+            Value::Struct(fields) => {
+                write!(f, "struct(")?;
+                for (i, (name, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name}=")?;
+                    v.fmt_helper(f, visited)?;
+                }
+                write!(f, ")")
+            }
             Value::Function(func) => write!(f, "<function {}>", func.name),
             Value::NativeFunction(name, _) => write!(f, "<native function {name}>"),
             Value::NativeFunctionWithKwargs(name, _) => write!(f, "<native function {name}>"),
@@ -549,6 +810,7 @@ impl fmt::Display for Value {
             Value::None => write!(f, "None"),
             Value::Bool(b) => write!(f, "{}", if *b { "True" } else { "False" }),
             Value::Int(i) => write!(f, "{i}"),
+            Value::BigInt(i) => write!(f, "{i}"),
             Value::Float(fl) => write!(f, "{fl:?}"), // Use Debug for floats to get decent formatting (1.0 etc)
             Value::String(s) => write!(f, "{s}"),    // Strings print without quotes in str()
             Value::Bytes(b) => write!(f, "{:?}", Value::Bytes(b.clone())), // Delegate to Debug for bytes representation
@@ -565,6 +827,12 @@ impl fmt::Display for Value {
             Value::Set(s) => {
                 write!(f, "{:?}", Value::Set(s.clone()))
             }
+            Value::Struct(fields) => {
+                write!(f, "{:?}", Value::Struct(fields.clone()))
+            }
+            Value::Iterable(it) => {
+                write!(f, "{:?}", Value::Iterable(it.clone()))
+            }
             Value::Function(func) => write!(f, "<function {}>", func.name),
             Value::NativeFunction(name, _) => write!(f, "<native function {name}>"),
             Value::NativeFunctionWithKwargs(name, _) => write!(f, "<native function {name}>"),
@@ -578,6 +846,21 @@ impl fmt::Display for Value {
 pub enum FStringSegment {
     Literal(String),
     Expression(Expr),
+    /// An expression with a format spec, as in `{size:>8}`. The spec is a string literal, or
+    /// an f-string when it has nested fields, as in `{size:>{width}}`.
+    Formatted(Expr, Expr),
+}
+
+impl FStringSegment {
+    /// The expressions this segment evaluates: its value and, if it has one, its format spec.
+    pub fn exprs(&self) -> impl Iterator<Item = &Expr> {
+        let (value, spec) = match self {
+            FStringSegment::Literal(_) => (None, None),
+            FStringSegment::Expression(e) => (Some(e), None),
+            FStringSegment::Formatted(e, spec) => (Some(e), Some(spec)),
+        };
+        value.into_iter().chain(spec)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -644,6 +927,18 @@ pub struct Stmt {
     pub span: Span,
 }
 
+/// An `except [kind] [as name]:` clause of a `try` statement.
+///
+/// `kind` evaluates to an error kind name (e.g. `"KeyError"`) or a list/tuple of names.
+/// A handler without a kind catches every catchable error.
+#[derive(Debug, Clone)]
+pub struct ExceptHandler {
+    pub kind: Option<Expr>,
+    pub name: Option<String>,
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub enum StmtKind {
     Expression(Expr),
@@ -653,6 +948,8 @@ pub enum StmtKind {
     Return(Option<Expr>),
     Def(String, Vec<Param>, Option<Box<Expr>>, Vec<Stmt>),
     For(Vec<String>, Expr, Vec<Stmt>),
+    While(Expr, Vec<Stmt>, Option<Vec<Stmt>>),
+    Try(Vec<Stmt>, Vec<ExceptHandler>, Option<Vec<Stmt>>),
     Break,
     Continue,
     Pass,
@@ -1,10 +1,12 @@
 use super::ast::Value;
+use super::interpreter::Freezable;
 use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::RwLock;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 
 pub trait FromValue: Sized {
     fn from_value(v: &Value) -> Result<Self, String>;
@@ -19,16 +21,40 @@ impl FromValue for i64 {
     fn from_value(v: &Value) -> Result<Self, String> {
         match v {
             Value::Int(i) => Ok(*i),
+            Value::BigInt(i) => Err(format!("int {i} out of range for i64")),
             _ => Err(format!("Expected Int, got {}", get_type_name(v))),
         }
     }
 }
 
+impl FromValue for u64 {
+    fn from_value(v: &Value) -> Result<Self, String> {
+        match v {
+            Value::Int(i) => u64::try_from(*i).map_err(|_| format!("int {i} out of range for u64")),
+            Value::BigInt(i) => i
+                .to_u64()
+                .ok_or_else(|| format!("int {i} out of range for u64")),
+            _ => Err(format!("Expected Int, got {}", get_type_name(v))),
+        }
+    }
+}
+
+impl FromValue for BigInt {
+    fn from_value(v: &Value) -> Result<Self, String> {
+        v.to_bigint()
+            .ok_or_else(|| format!("Expected Int, got {}", get_type_name(v)))
+    }
+}
+
 impl FromValue for f64 {
     fn from_value(v: &Value) -> Result<Self, String> {
         match v {
             Value::Float(f) => Ok(*f),
             Value::Int(i) => Ok(*i as f64),
+            Value::BigInt(i) => i
+                .to_f64()
+                .filter(|f| f.is_finite())
+                .ok_or_else(|| format!("int {i} too large to convert to float")),
             _ => Err(format!("Expected Float or Int, got {}", get_type_name(v))),
         }
     }
@@ -79,6 +105,7 @@ impl<T: FromValue> FromValue for Vec<T> {
                 }
                 Ok(res)
             }
+            Value::Iterable(it) => it.iter().map(|item| T::from_value(&item?)).collect(),
             // Should we support Set -> Vec conversion automatically?
             // Python typing sometimes allows Iterable[T].
             // But strict Vec<T> mapping usually implies order.
@@ -128,6 +155,18 @@ impl ToValue for i64 {
     }
 }
 
+impl ToValue for u64 {
+    fn to_value(self) -> Value {
+        i64::try_from(self).map_or_else(|_| Value::BigInt(BigInt::from(self)), Value::Int)
+    }
+}
+
+impl ToValue for BigInt {
+    fn to_value(self) -> Value {
+        Value::from_bigint(self)
+    }
+}
+
 impl ToValue for f64 {
     fn to_value(self) -> Value {
         Value::Float(self)
@@ -161,7 +200,7 @@ impl ToValue for Vec<u8> {
 impl<T: ToValue> ToValue for Vec<T> {
     fn to_value(self) -> Value {
         let list: Vec<Value> = self.into_iter().map(|i| i.to_value()).collect();
-        Value::List(Arc::new(RwLock::new(list)))
+        Value::List(Arc::new(Freezable::new(list)))
     }
 }
 
@@ -171,7 +210,7 @@ impl<K: ToValue + Ord, V: ToValue> ToValue for BTreeMap<K, V> {
         for (k, v) in self {
             map.insert(k.to_value(), v.to_value());
         }
-        Value::Dictionary(Arc::new(RwLock::new(map)))
+        Value::Dictionary(Arc::new(Freezable::new(map)))
     }
 }
 
@@ -210,7 +249,7 @@ fn get_type_name(v: &Value) -> &'static str {
     match v {
         Value::None => "NoneType",
         Value::Bool(_) => "bool",
-        Value::Int(_) => "int",
+        Value::Int(_) | Value::BigInt(_) => "int",
         Value::Float(_) => "float",
         Value::String(_) => "str",
         Value::Bytes(_) => "bytes",
@@ -218,6 +257,8 @@ fn get_type_name(v: &Value) -> &'static str {
         Value::Tuple(_) => "tuple",
         Value::Dictionary(_) => "dict",
         Value::Set(_) => "set",
+        Value::Iterable(_) => "iterable",
+        Value::Struct(_) => "struct",
         Value::Function(_) => "function",
         Value::NativeFunction(_, _) => "native_function",
         Value::NativeFunctionWithKwargs(_, _) => "native_function_kwargs",
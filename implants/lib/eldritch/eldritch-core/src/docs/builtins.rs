@@ -0,0 +1,401 @@
+/// Documentation for builtin functions.
+/// This file is parsed by the documentation generator.
+
+// Output
+// ======
+
+//: print
+//: Converts each argument to a string and prints it to the standard output, separated by spaces.
+//:
+//: **Parameters**
+//: - `*args` (Any): The values to print.
+//:
+//: **Returns**
+//: - `None`
+
+//: eprint
+//: Converts each argument to a string and prints it to the standard error, separated by spaces.
+//:
+//: **Parameters**
+//: - `*args` (Any): The values to print.
+//:
+//: **Returns**
+//: - `None`
+
+//: pprint
+//: Pretty-prints an object with indentation.
+//:
+//: **Parameters**
+//: - `object` (Any): The value to print.
+//: - `indent` (Option<Int>): The indentation width. Defaults to 2.
+//:
+//: **Returns**
+//: - `None`
+
+//: tprint
+//: Prints a list of dictionaries as a markdown table.
+//:
+//: **Parameters**
+//: - `list_of_dicts` (List<Dict>): The rows to print.
+//:
+//: **Returns**
+//: - `None`
+
+// Conversions
+// ===========
+
+//: bool
+//: Converts a value to a Boolean.
+//:
+//: **Parameters**
+//: - `x` (Option<Any>): The value to convert.
+//:
+//: **Returns**
+//: - `Bool`
+
+//: bytes
+//: Creates a bytes object from an integer size, a string or an iterable of integers.
+//:
+//: **Parameters**
+//: - `source` (Option<Any>): The initial contents.
+//:
+//: **Returns**
+//: - `Bytes`
+
+//: chr
+//: Returns the string representing a character whose Unicode code point is the integer `i`.
+//:
+//: **Parameters**
+//: - `i` (Int): The integer code point.
+//:
+//: **Returns**
+//: - `String`
+
+//: dict
+//: Creates a dictionary.
+//:
+//: **Parameters**
+//: - `iterable` (Option<Iterable>): An iterable of key-value pairs.
+//: - `**kwargs` (Any): Keyword arguments to add to the dictionary.
+//:
+//: **Returns**
+//: - `Dict`
+
+//: float
+//: Converts a number or string to a floating point number.
+//:
+//: **Parameters**
+//: - `x` (Option<Int | Float | String>): The value to convert.
+//:
+//: **Returns**
+//: - `Float`
+
+//: hex
+//: Returns the hexadecimal representation of an integer.
+//:
+//: **Parameters**
+//: - `x` (Int): The integer to convert.
+//:
+//: **Returns**
+//: - `String`
+
+//: int
+//: Converts a number or string to an integer.
+//:
+//: **Parameters**
+//: - `x` (Option<Any>): The value to convert.
+//: - `base` (Option<Int>): The base of a string literal. Defaults to 10.
+//:
+//: **Returns**
+//: - `Int`
+
+//: list
+//: Creates a list, optionally from the items of an iterable.
+//:
+//: **Parameters**
+//: - `iterable` (Option<Iterable>): The items of the new list.
+//:
+//: **Returns**
+//: - `List`
+
+//: ord
+//: Returns the integer that represents the Unicode code point of the character `c`.
+//:
+//: **Parameters**
+//: - `c` (String | Bytes): A string of length 1 or bytes of length 1.
+//:
+//: **Returns**
+//: - `Int`
+
+//: repr
+//: Returns a string containing the printable representation of an object.
+//:
+//: **Parameters**
+//: - `x` (Any): The object to represent.
+//:
+//: **Returns**
+//: - `String`
+
+//: set
+//: Creates a set, optionally from the items of an iterable.
+//:
+//: **Parameters**
+//: - `iterable` (Option<Iterable>): The items of the new set.
+//:
+//: **Returns**
+//: - `Set`
+
+//: str
+//: Returns a string containing a nicely printable representation of an object.
+//:
+//: **Parameters**
+//: - `object` (Option<Any>): The object to convert.
+//:
+//: **Returns**
+//: - `String`
+
+//: struct
+//: Creates an immutable record whose fields are read with `s.name`.
+//:
+//: **Parameters**
+//: - `**kwargs` (Any): The fields of the struct.
+//:
+//: **Returns**
+//: - `Struct`
+
+//: tuple
+//: Creates a tuple, optionally from the items of an iterable.
+//:
+//: **Parameters**
+//: - `iterable` (Option<Iterable>): The items of the new tuple.
+//:
+//: **Returns**
+//: - `Tuple`
+
+// Sequences
+// =========
+
+//: all
+//: Returns True if all elements of the iterable are true.
+//:
+//: **Parameters**
+//: - `iterable` (Iterable): The iterable to check.
+//:
+//: **Returns**
+//: - `Bool`
+
+//: any
+//: Returns True if any element of the iterable is true.
+//:
+//: **Parameters**
+//: - `iterable` (Iterable): The iterable to check.
+//:
+//: **Returns**
+//: - `Bool`
+
+//: enumerate
+//: Returns a list of (index, value) tuples. The tuples are produced lazily as the result is iterated.
+//:
+//: **Parameters**
+//: - `iterable` (Iterable): The sequence to enumerate.
+//: - `start` (Option<Int>): The starting index. Defaults to 0.
+//:
+//: **Returns**
+//: - `List<Tuple>`
+
+//: filter
+//: Returns the items of the iterable for which the function returns a true value.
+//:
+//: **Parameters**
+//: - `function` (Function | None): The predicate. None keeps truthy items.
+//: - `iterable` (Iterable): The items to filter.
+//:
+//: **Returns**
+//: - `List`
+
+//: len
+//: Returns the length of a string, bytes, list, tuple, dictionary or set.
+//:
+//: **Parameters**
+//: - `s` (Any): The object to measure.
+//:
+//: **Returns**
+//: - `Int`
+
+//: map
+//: Applies the function to every item of the iterable.
+//:
+//: **Parameters**
+//: - `function` (Function): The function to apply.
+//: - `iterable` (Iterable): The items to map.
+//:
+//: **Returns**
+//: - `List`
+
+//: max
+//: Returns the largest item of an iterable, or the largest of two or more arguments.
+//:
+//: **Parameters**
+//: - `*args` (Any): An iterable, or the values to compare.
+//:
+//: **Returns**
+//: - `Any`
+
+//: min
+//: Returns the smallest item of an iterable, or the smallest of two or more arguments.
+//:
+//: **Parameters**
+//: - `*args` (Any): An iterable, or the values to compare.
+//:
+//: **Returns**
+//: - `Any`
+
+//: range
+//: Returns a list of numbers. `range(stop)` counts from 0; `range(start, stop[, step])` counts from start. The numbers are produced lazily, so large ranges use no memory.
+//:
+//: **Parameters**
+//: - `start` (Int): The start value, or the stop value when it is the only argument.
+//: - `stop` (Option<Int>): The stop value (exclusive).
+//: - `step` (Option<Int>): The step size. Defaults to 1.
+//:
+//: **Returns**
+//: - `List<Int>`
+
+//: reduce
+//: Applies a function of two arguments cumulatively to the items of an iterable.
+//:
+//: **Parameters**
+//: - `function` (Function): The function to apply.
+//: - `iterable` (Iterable): The items to reduce.
+//: - `initial` (Option<Any>): The starting value.
+//:
+//: **Returns**
+//: - `Any`
+
+//: reversed
+//: Returns a list of the elements of the sequence in reverse order.
+//:
+//: **Parameters**
+//: - `seq` (Iterable): The sequence to reverse.
+//:
+//: **Returns**
+//: - `List`
+
+//: sorted
+//: Returns a new sorted list from the items of the iterable.
+//:
+//: **Parameters**
+//: - `iterable` (Iterable): The items to sort.
+//: - `key` (Option<Function>): A function of one argument used to extract a comparison key.
+//: - `reverse` (Option<Bool>): Sort in descending order. Defaults to False.
+//:
+//: **Returns**
+//: - `List`
+
+//: zip
+//: Returns a list of tuples, where the i-th tuple contains the i-th element from each argument. The tuples are produced lazily as the result is iterated.
+//:
+//: **Parameters**
+//: - `*iterables` (Iterable): Iterables to zip together.
+//:
+//: **Returns**
+//: - `List<Tuple>`
+
+// Numbers
+// =======
+
+//: abs
+//: Returns the absolute value of a number.
+//:
+//: **Parameters**
+//: - `x` (Int | Float): The number.
+//:
+//: **Returns**
+//: - `Int | Float`
+
+// Assertions
+// ==========
+
+//: assert
+//: Aborts if the condition is false.
+//:
+//: **Parameters**
+//: - `condition` (Any): The condition to check.
+//:
+//: **Returns**
+//: - `None`
+
+//: assert_eq
+//: Aborts if `a` is not equal to `b`.
+//:
+//: **Parameters**
+//: - `a` (Any): Left operand.
+//: - `b` (Any): Right operand.
+//:
+//: **Returns**
+//: - `None`
+
+//: fail
+//: Aborts execution with an error message.
+//:
+//: **Parameters**
+//: - `message` (Option<Any>): The message to include in the error.
+//:
+//: **Returns**
+//: - `None`
+
+// Introspection
+// =============
+
+//: builtins
+//: Lists all built-in functions.
+//:
+//: **Returns**
+//: - `List<String>`
+
+//: dir
+//: Without arguments, lists the names in the current scope; otherwise lists the attributes of the object.
+//:
+//: **Parameters**
+//: - `object` (Option<Any>): The object to inspect.
+//:
+//: **Returns**
+//: - `List<String>`
+
+//: libs
+//: Lists all registered libraries.
+//:
+//: **Returns**
+//: - `List<String>`
+
+//: type
+//: Returns the name of the type of the object.
+//:
+//: **Parameters**
+//: - `object` (Any): The object to inspect.
+//:
+//: **Returns**
+//: - `String`
+
+// Evaluation
+// ==========
+
+//: eval
+//: Evaluates a string of Eldritch code in the current scope.
+//:
+//: **Parameters**
+//: - `code` (String): The code to evaluate.
+//:
+//: **Returns**
+//: - `Any`
+
+//: load
+//: Evaluates an Eldritch module read through the assets library and binds its symbols.
+//:
+//: **Parameters**
+//: - `module` (String): Asset path of the module.
+//: - `*symbols` (String): Names to bind under the same name in the current scope.
+//: - `**aliases` (String): `local_name="symbol"` pairs to bind under a different name.
+//:
+//: **Returns**
+//: - `None`
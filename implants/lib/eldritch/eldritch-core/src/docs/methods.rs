@@ -412,10 +412,10 @@
 //: str.format
 //: Perform a string formatting operation.
 //: The string on which this method is called can contain literal text or replacement fields delimited by braces {}.
+//: A field is empty or an argument index, optionally followed by a format spec, e.g. `{:>8}` or `{0:,.2f}`. Use `{{` and `}}` for literal braces.
 //:
 //: **Parameters**
 //: - `*args` (Any): Positional arguments.
-//: - `**kwargs` (Any): Keyword arguments.
 //:
 //: **Returns**
 //: - `String`
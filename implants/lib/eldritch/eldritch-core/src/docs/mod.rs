@@ -0,0 +1,145 @@
+//! Reference documentation for builtin functions and methods on builtin types.
+//!
+//! The `//:` blocks in `builtins.rs` and `methods.rs` are written for the user
+//! guide. They are also parsed at runtime so tooling such as the type checker
+//! and the language server can use the documented signatures.
+//!
+//! Library methods are described by the [`LibrarySignature`] tables that
+//! `#[eldritch_library]` generates from each library's trait instead.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+mod signature;
+
+pub use signature::{LibrarySignature, MethodSignature, ParamSignature, signatures_json};
+
+const BUILTINS: &str = include_str!("builtins.rs");
+const METHODS: &str = include_str!("methods.rs");
+
+/// One documented parameter, e.g. ``- `sep` (Option<String>): The delimiter.``
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocParam {
+    /// Parameter name including any `*`/`**` prefix.
+    pub name: String,
+    /// Type as written in the docs, e.g. `Option<Int>` or `String | Tuple<String>`.
+    pub type_name: String,
+    pub description: String,
+}
+
+impl DocParam {
+    /// Whether the parameter may be left out of a call.
+    pub fn is_optional(&self) -> bool {
+        self.type_name.starts_with("Option<")
+            || self.description.contains("Defaults to")
+            || self.description.starts_with("Optional")
+    }
+}
+
+/// A documented function or method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocEntry {
+    /// `name` for builtin functions, `type.name` for methods.
+    pub name: String,
+    pub description: String,
+    pub params: Vec<DocParam>,
+    /// Documented return type, if any.
+    pub returns: Option<String>,
+}
+
+/// Documentation of every builtin function.
+pub fn builtin_functions() -> Vec<DocEntry> {
+    parse(BUILTINS)
+}
+
+/// Documentation of every method on builtin types (`list`, `dict`, `set`, `str`).
+pub fn builtin_methods() -> Vec<DocEntry> {
+    parse(METHODS)
+}
+
+/// Parses `//:` documentation blocks. Each block starts with the entry name and
+/// ends at the first line that is not a `//:` comment.
+pub fn parse(source: &str) -> Vec<DocEntry> {
+    #[derive(PartialEq)]
+    enum Section {
+        Description,
+        Parameters,
+        Returns,
+    }
+
+    let mut entries = Vec::new();
+    let mut current: Option<DocEntry> = None;
+    let mut section = Section::Description;
+
+    for line in source.lines() {
+        let Some(text) = line.trim().strip_prefix("//:") else {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            continue;
+        };
+        let text = text.trim();
+
+        let Some(entry) = current.as_mut() else {
+            if !text.is_empty() {
+                current = Some(DocEntry {
+                    name: text.to_string(),
+                    description: String::new(),
+                    params: Vec::new(),
+                    returns: None,
+                });
+                section = Section::Description;
+            }
+            continue;
+        };
+
+        match text {
+            "**Parameters**" => section = Section::Parameters,
+            "**Returns**" => section = Section::Returns,
+            "" => {}
+            _ => match section {
+                Section::Description => {
+                    if !entry.description.is_empty() {
+                        entry.description.push(' ');
+                    }
+                    entry.description.push_str(text);
+                }
+                Section::Parameters => {
+                    if let Some(param) = parse_param(text) {
+                        entry.params.push(param);
+                    }
+                }
+                Section::Returns => {
+                    if entry.returns.is_none() {
+                        entry.returns = parse_return(text);
+                    }
+                }
+            },
+        }
+    }
+    if let Some(entry) = current {
+        entries.push(entry);
+    }
+    entries
+}
+
+// - `name` (Type): Description.
+fn parse_param(text: &str) -> Option<DocParam> {
+    let rest = text.strip_prefix("- `")?;
+    let (name, rest) = rest.split_once('`')?;
+    let rest = rest.trim_start().strip_prefix('(')?;
+    let (type_name, description) = rest.split_once(')')?;
+    let description = description.trim_start_matches(':').trim();
+    Some(DocParam {
+        name: name.to_string(),
+        type_name: type_name.trim().to_string(),
+        description: description.to_string(),
+    })
+}
+
+// - `Type`: Description.
+fn parse_return(text: &str) -> Option<String> {
+    let rest = text.strip_prefix("- `")?;
+    let (type_name, _) = rest.split_once('`')?;
+    Some(type_name.to_string())
+}
@@ -0,0 +1,130 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+/// The methods a library exposes to scripts, generated by `#[eldritch_library]` from the
+/// library's trait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibrarySignature {
+    /// The global the library is bound to, e.g. `file`.
+    pub name: &'static str,
+    /// The trait's doc comment.
+    pub doc: &'static str,
+    /// The `#[eldritch_method]` methods in declaration order, including methods behind a
+    /// `#[cfg]` only when it is enabled.
+    pub methods: &'static [MethodSignature],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodSignature {
+    /// The name scripts call, after any `#[eldritch_method("...")]` rename.
+    pub name: &'static str,
+    pub params: &'static [ParamSignature],
+    /// The Rust type the method returns, without the `Result` that errors are raised from,
+    /// e.g. `Vec<String>` or `()`.
+    pub returns: &'static str,
+    /// The method's doc comment.
+    pub doc: &'static str,
+}
+
+/// A parameter scripts pass, by position or by keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamSignature {
+    pub name: &'static str,
+    /// The Rust type of the parameter, e.g. `String` or `Option<Vec<String>>`.
+    pub type_name: &'static str,
+    /// Whether calls may leave the parameter out, which `Option` parameters allow.
+    pub optional: bool,
+}
+
+impl MethodSignature {
+    /// The method as a script calls it, e.g. `file.read(path: String) -> String`.
+    pub fn display(&self, library: &str) -> String {
+        let mut out = String::new();
+        let _ = write!(out, "{}.{}(", library, self.name);
+        for (idx, param) in self.params.iter().enumerate() {
+            if idx > 0 {
+                out.push_str(", ");
+            }
+            let _ = write!(out, "{}: {}", param.name, param.type_name);
+        }
+        let _ = write!(out, ") -> {}", self.returns);
+        out
+    }
+}
+
+/// Renders `libraries` as a JSON array, for tools outside Rust such as the docs site and
+/// the tome builder.
+pub fn signatures_json(libraries: &[&LibrarySignature]) -> String {
+    let mut out = String::from("[");
+    for (idx, library) in libraries.iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "\n  {{\n    \"name\": {},\n    \"doc\": {},\n    \"methods\": [",
+            json_string(library.name),
+            json_string(library.doc)
+        );
+        for (idx, method) in library.methods.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            let _ = write!(
+                out,
+                "\n      {{\n        \"name\": {},\n        \"params\": [",
+                json_string(method.name)
+            );
+            for (idx, param) in method.params.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                let _ = write!(
+                    out,
+                    "\n          {{ \"name\": {}, \"type\": {}, \"optional\": {} }}",
+                    json_string(param.name),
+                    json_string(param.type_name),
+                    param.optional
+                );
+            }
+            if !method.params.is_empty() {
+                out.push_str("\n        ");
+            }
+            let _ = write!(
+                out,
+                "],\n        \"returns\": {},\n        \"doc\": {}\n      }}",
+                json_string(method.returns),
+                json_string(method.doc)
+            );
+        }
+        if !library.methods.is_empty() {
+            out.push_str("\n    ");
+        }
+        out.push_str("]\n  }");
+    }
+    if !libraries.is_empty() {
+        out.push('\n');
+    }
+    out.push_str("]\n");
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,730 @@
+//! Canonical formatting for Eldritch source.
+//!
+//! The formatter works on the token stream rather than the AST so that it keeps
+//! what the AST forgets: parentheses, `elif`, the spelling of literals and the
+//! comments the lexer sets aside as trivia. It re-indents blocks with four
+//! spaces, normalizes the spacing around operators, prefers double quoted
+//! strings, clamps runs of blank lines and splits lines wider than
+//! [`MAX_WIDTH`] at their outermost brackets, one element per line with a
+//! trailing comma. A trailing comma already present keeps a bracket split.
+//!
+//! ```
+//! use eldritch_core::formatter::format;
+//!
+//! assert_eq!(format("x=[1,2]\n").unwrap(), "x = [1, 2]\n");
+//! ```
+
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::{Comment, Span, Token, TokenKind};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Lines wider than this are split at their brackets.
+pub const MAX_WIDTH: usize = 100;
+
+const INDENT: &str = "    ";
+
+/// Source that can't be formatted because it doesn't parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.span.line, self.message)
+    }
+}
+
+/// Formats `source`, which must be free of syntax errors.
+pub fn format(source: &str) -> Result<String, FormatError> {
+    let mut lexer = Lexer::new(source.to_string());
+    let tokens = lexer.scan_tokens();
+    let comments = lexer.comments().to_vec();
+
+    for token in &tokens {
+        if let TokenKind::Error(message) = &token.kind {
+            return Err(FormatError {
+                message: message.clone(),
+                span: token.span,
+            });
+        }
+    }
+    let (_, errors) = Parser::new(tokens.clone()).parse();
+    if let Some(error) = errors.into_iter().next() {
+        return Err(FormatError {
+            message: error.message,
+            span: error.span,
+        });
+    }
+
+    Ok(Formatter::new(source, &tokens, &comments).format())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bracket {
+    Call,
+    Params,
+    Subscript,
+    Paren,
+    List,
+    Brace,
+}
+
+/// The tokens of one statement or block header.
+struct Line {
+    depth: usize,
+    /// Token index range into `Formatter::tokens`.
+    start: usize,
+    end: usize,
+    /// First and last physical line, 0-based.
+    first_line: usize,
+    last_line: usize,
+    /// Original indentation.
+    column: usize,
+}
+
+/// A comment on a line of its own between statements.
+struct FreeComment {
+    line: usize,
+    column: usize,
+    text: String,
+}
+
+enum Node {
+    Token(usize),
+    Group(Group),
+}
+
+struct Group {
+    open: usize,
+    close: Option<usize>,
+    items: Vec<Item>,
+}
+
+struct Item {
+    nodes: Vec<Node>,
+    comma: Option<usize>,
+}
+
+struct Formatter {
+    line_starts: Vec<usize>,
+    tokens: Vec<Token>,
+    /// Normalized source text of each token.
+    text: Vec<String>,
+    /// Whether a token is separated from the one before it by a space.
+    space: Vec<bool>,
+    brackets: BTreeMap<usize, Bracket>,
+    lines: Vec<Line>,
+    /// Comments following a token on the same line.
+    trailing: BTreeMap<usize, Vec<String>>,
+    /// Comments on their own line inside brackets, following a token.
+    own_line: BTreeMap<usize, Vec<String>>,
+    free: Vec<FreeComment>,
+}
+
+// The token indices of `nodes` in source order
+fn flatten(nodes: &[Node], out: &mut Vec<usize>) {
+    for node in nodes {
+        match node {
+            Node::Token(i) => out.push(*i),
+            Node::Group(group) => {
+                out.push(group.open);
+                for item in &group.items {
+                    flatten(&item.nodes, out);
+                    out.extend(item.comma);
+                }
+                out.extend(group.close);
+            }
+        }
+    }
+}
+
+impl Formatter {
+    fn new(source: &str, raw: &[Token], comments: &[Comment]) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let mut line_starts = vec![0];
+        for (i, c) in chars.iter().enumerate() {
+            if *c == '\n' {
+                line_starts.push(i + 1);
+            }
+        }
+
+        let mut f = Formatter {
+            line_starts,
+            tokens: Vec::new(),
+            text: Vec::new(),
+            space: Vec::new(),
+            brackets: BTreeMap::new(),
+            lines: Vec::new(),
+            trailing: BTreeMap::new(),
+            own_line: BTreeMap::new(),
+            free: Vec::new(),
+        };
+
+        let mut depth = 0usize;
+        let mut start = 0;
+        for token in raw {
+            let is_semicolon =
+                token.kind == TokenKind::Newline && chars.get(token.span.start) == Some(&';');
+            match token.kind {
+                TokenKind::Indent => depth += 1,
+                TokenKind::Dedent => depth = depth.saturating_sub(1),
+                TokenKind::Newline | TokenKind::Eof if !is_semicolon => {
+                    f.close_line(start, depth);
+                    start = f.tokens.len();
+                }
+                _ => {
+                    let mut token = token.clone();
+                    token.span.start = literal_start(&chars, &token);
+                    f.text.push(token_text(&chars, &token));
+                    f.tokens.push(token);
+                }
+            }
+        }
+
+        for comment in comments {
+            f.attach(comment);
+        }
+        for line in 0..f.lines.len() {
+            f.compute_spacing(line);
+        }
+        f
+    }
+
+    fn close_line(&mut self, start: usize, depth: usize) {
+        let end = self.tokens.len();
+        if start == end {
+            return;
+        }
+        let first_line = self.line_of(self.tokens[start].span.start);
+        let last_line = self.line_of(self.tokens[end - 1].span.end.saturating_sub(1));
+        self.lines.push(Line {
+            depth,
+            start,
+            end,
+            first_line,
+            last_line,
+            column: self.tokens[start].span.start - self.line_starts[first_line],
+        });
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        }
+    }
+
+    fn attach(&mut self, comment: &Comment) {
+        let offset = comment.span.start;
+        let line = self.line_of(offset);
+        let text = normalize_comment(&comment.text);
+
+        let owner = self.lines.iter().rev().find(|l| {
+            l.first_line <= line && line <= l.last_line && self.tokens[l.start].span.start < offset
+        });
+        let Some(owner) = owner else {
+            self.free.push(FreeComment {
+                line,
+                column: offset - self.line_starts[line],
+                text,
+            });
+            return;
+        };
+
+        let before = (owner.start..owner.end)
+            .rev()
+            .find(|i| self.tokens[*i].span.start < offset)
+            .unwrap_or(owner.start);
+        let before_line = self.line_of(self.tokens[before].span.end.saturating_sub(1));
+        let target = if before_line == line {
+            &mut self.trailing
+        } else {
+            &mut self.own_line
+        };
+        target.entry(before).or_default().push(text);
+    }
+
+    fn compute_spacing(&mut self, line: usize) {
+        struct Level {
+            bracket: Option<Bracket>,
+            lambda: bool,
+            annotated: bool,
+        }
+
+        let (start, end) = (self.lines[line].start, self.lines[line].end);
+        let mut stack = vec![Level {
+            bracket: None,
+            lambda: false,
+            annotated: false,
+        }];
+        let mut prev_unary = false;
+
+        for i in start..end {
+            let kind = self.tokens[i].kind.clone();
+            let prev = (i > start).then(|| &self.tokens[i - 1].kind);
+            let level = stack.last().unwrap();
+
+            let space = match prev {
+                None => false,
+                Some(_) if prev_unary => false,
+                Some(prev) => spaced(prev, &kind, level.bracket, || {
+                    level.lambda
+                        || level.bracket == Some(Bracket::Call)
+                        || (level.bracket == Some(Bracket::Params) && !level.annotated)
+                }),
+            };
+            self.space.push(space);
+            prev_unary = matches!(
+                kind,
+                TokenKind::Minus
+                    | TokenKind::Plus
+                    | TokenKind::BitNot
+                    | TokenKind::Star
+                    | TokenKind::StarStar
+            ) && !prev.is_some_and(ends_operand);
+
+            let level = stack.last_mut().unwrap();
+            match kind {
+                TokenKind::LParen | TokenKind::LBracket | TokenKind::LBrace => {
+                    let bracket = match kind {
+                        TokenKind::LBrace => Bracket::Brace,
+                        TokenKind::LParen
+                            if i >= start + 2 && self.tokens[i - 2].kind == TokenKind::Def =>
+                        {
+                            Bracket::Params
+                        }
+                        TokenKind::LParen if prev.is_some_and(ends_callee) => Bracket::Call,
+                        TokenKind::LParen => Bracket::Paren,
+                        _ if prev.is_some_and(ends_callee) => Bracket::Subscript,
+                        _ => Bracket::List,
+                    };
+                    self.brackets.insert(i, bracket);
+                    stack.push(Level {
+                        bracket: Some(bracket),
+                        lambda: false,
+                        annotated: false,
+                    });
+                }
+                TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace => {
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                }
+                TokenKind::Lambda => level.lambda = true,
+                TokenKind::Colon if level.lambda => level.lambda = false,
+                TokenKind::Colon => level.annotated = true,
+                TokenKind::Comma => level.annotated = false,
+                _ => {}
+            }
+        }
+    }
+
+    fn format(&self) -> String {
+        let mut out: Vec<String> = Vec::new();
+        // Original indentation of the enclosing blocks, by depth
+        let mut columns: Vec<usize> = Vec::new();
+        // Last physical line and depth of what was emitted last
+        let mut prev: Option<(usize, usize)> = None;
+        let mut code_depth = 0;
+        let mut free = self.free.iter().peekable();
+
+        for line in &self.lines {
+            while let Some(comment) = free.next_if(|c| c.line < line.first_line) {
+                let depth = comment_depth(comment.column, code_depth, line.depth, &columns);
+                blank_lines(&mut out, prev, comment.line, depth);
+                out.push(format!("{}{}", INDENT.repeat(depth), comment.text));
+                prev = Some((comment.line, depth));
+            }
+
+            blank_lines(&mut out, prev, line.first_line, line.depth);
+            let mut start = line.start;
+            let nodes = self.parse_nodes(&mut start, line.end, false);
+            self.layout(&nodes, line.depth, "", None, &mut out);
+            prev = Some((line.last_line, line.depth));
+
+            columns.truncate(line.depth);
+            columns.resize(line.depth, 0);
+            columns.push(line.column);
+            code_depth = line.depth;
+        }
+        for comment in free {
+            let depth = comment_depth(comment.column, code_depth, 0, &columns);
+            blank_lines(&mut out, prev, comment.line, depth);
+            out.push(format!("{}{}", INDENT.repeat(depth), comment.text));
+            prev = Some((comment.line, depth));
+        }
+
+        if out.is_empty() {
+            return String::new();
+        }
+        let mut result = out.join("\n");
+        result.push('\n');
+        result
+    }
+
+    fn parse_nodes(&self, i: &mut usize, end: usize, in_group: bool) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        while *i < end {
+            match self.tokens[*i].kind {
+                TokenKind::LParen | TokenKind::LBracket | TokenKind::LBrace => {
+                    nodes.push(Node::Group(self.parse_group(i, end)));
+                }
+                TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace if in_group => break,
+                TokenKind::Comma if in_group => break,
+                _ => {
+                    nodes.push(Node::Token(*i));
+                    *i += 1;
+                }
+            }
+        }
+        nodes
+    }
+
+    fn parse_group(&self, i: &mut usize, end: usize) -> Group {
+        let open = *i;
+        *i += 1;
+        let mut items = Vec::new();
+        while *i < end {
+            if matches!(
+                self.tokens[*i].kind,
+                TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace
+            ) {
+                break;
+            }
+            let nodes = self.parse_nodes(i, end, true);
+            let comma = (*i < end && self.tokens[*i].kind == TokenKind::Comma).then(|| {
+                *i += 1;
+                *i - 1
+            });
+            items.push(Item { nodes, comma });
+        }
+        let close = (*i < end).then(|| {
+            *i += 1;
+            *i - 1
+        });
+        Group { open, close, items }
+    }
+
+    fn has_comments(&self, from: usize, to: usize) -> bool {
+        self.trailing.range(from..to).next().is_some()
+            || self.own_line.range(from..to).next().is_some()
+    }
+
+    fn must_break(&self, group: &Group) -> bool {
+        let Some(close) = group.close else {
+            return false;
+        };
+        if self.has_comments(group.open, close) {
+            return true;
+        }
+        // A trailing comma keeps a bracket split, except in a one element tuple
+        let magic_comma = group.items.last().is_some_and(|item| item.comma.is_some())
+            && !matches!(
+                (self.brackets.get(&group.open), group.items.len()),
+                (Some(Bracket::Paren), 1) | (Some(Bracket::Subscript), _)
+            );
+        magic_comma
+            || group
+                .items
+                .iter()
+                .flat_map(|item| &item.nodes)
+                .any(|node| matches!(node, Node::Group(g) if self.must_break(g)))
+    }
+
+    /// Whether a comma may follow the last element of a split bracket. One is
+    /// only added between elements, not after a lone element.
+    fn allows_trailing_comma(&self, group: &Group) -> bool {
+        let Some(last) = group.items.last() else {
+            return false;
+        };
+        last.comma.is_some()
+            || (group.items.len() > 1
+                && !matches!(
+                    self.brackets.get(&group.open),
+                    Some(Bracket::Subscript) | None
+                ))
+    }
+
+    fn take_comments<'a>(
+        &'a self,
+        i: usize,
+        trailing: &mut Vec<&'a String>,
+        after: &mut Vec<&'a String>,
+    ) {
+        trailing.extend(self.trailing.get(&i).into_iter().flatten());
+        after.extend(self.own_line.get(&i).into_iter().flatten());
+    }
+
+    fn push_token(&self, line: &mut String, i: usize, first: &mut bool) {
+        if !*first && self.space[i] {
+            line.push(' ');
+        }
+        line.push_str(&self.text[i]);
+        *first = false;
+    }
+
+    /// Lays out `nodes` starting at indentation `depth`, splitting brackets as
+    /// needed. `suffix` (a trailing comma) and the comments of token `extra` go
+    /// at the end of the last line.
+    fn layout(
+        &self,
+        nodes: &[Node],
+        depth: usize,
+        suffix: &str,
+        extra: Option<usize>,
+        out: &mut Vec<String>,
+    ) {
+        let indent = INDENT.repeat(depth);
+        let mut forced = Vec::new();
+        let mut last_group = None;
+        for node in nodes {
+            if let Node::Group(group) = node {
+                if self.must_break(group) {
+                    forced.push(group.open);
+                }
+                if !group.items.is_empty() {
+                    last_group = Some(group.open);
+                }
+            }
+        }
+
+        let mut flat = Vec::new();
+        flatten(nodes, &mut flat);
+        let mut text = String::new();
+        let mut first = true;
+        for i in &flat {
+            self.push_token(&mut text, *i, &mut first);
+        }
+        let width = indent.chars().count()
+            + text.split('\n').next().unwrap_or_default().chars().count()
+            + suffix.len();
+        let split: Vec<usize> = if !forced.is_empty() {
+            forced
+        } else if width > MAX_WIDTH {
+            last_group.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut line = indent.clone();
+        let mut first = true;
+        let mut trailing: Vec<&String> = Vec::new();
+        let mut after: Vec<&String> = Vec::new();
+
+        for node in nodes {
+            let group = match node {
+                Node::Group(group) if split.contains(&group.open) => group,
+                _ => {
+                    let mut tokens = Vec::new();
+                    flatten(core::slice::from_ref(node), &mut tokens);
+                    for i in tokens {
+                        self.push_token(&mut line, i, &mut first);
+                        self.take_comments(i, &mut trailing, &mut after);
+                    }
+                    continue;
+                }
+            };
+
+            self.push_token(&mut line, group.open, &mut first);
+            self.take_comments(group.open, &mut trailing, &mut after);
+            flush(&mut line, &mut trailing, &mut after, depth + 1, out);
+
+            let allows_comma = self.allows_trailing_comma(group);
+            for (k, item) in group.items.iter().enumerate() {
+                let comma = item.comma.is_some() || (k + 1 == group.items.len() && allows_comma);
+                self.layout(
+                    &item.nodes,
+                    depth + 1,
+                    if comma { "," } else { "" },
+                    item.comma,
+                    out,
+                );
+            }
+
+            line = indent.clone();
+            first = true;
+            if let Some(close) = group.close {
+                self.push_token(&mut line, close, &mut first);
+                self.take_comments(close, &mut trailing, &mut after);
+            }
+        }
+
+        line.push_str(suffix);
+        if let Some(extra) = extra {
+            self.take_comments(extra, &mut trailing, &mut after);
+        }
+        flush(&mut line, &mut trailing, &mut after, depth, out);
+    }
+}
+
+/// Pushes `line` with its trailing comments, then the comments that follow it.
+fn flush(
+    line: &mut String,
+    trailing: &mut Vec<&String>,
+    after: &mut Vec<&String>,
+    depth: usize,
+    out: &mut Vec<String>,
+) {
+    for comment in trailing.drain(..) {
+        line.push_str("  ");
+        line.push_str(comment);
+    }
+    out.push(core::mem::take(line));
+    for comment in after.drain(..) {
+        out.push(format!("{}{}", INDENT.repeat(depth), comment));
+    }
+}
+
+/// Keeps the blank lines before something on physical line `line`, at most two
+/// at the top level and one in a block, and none at the start of a block.
+fn blank_lines(out: &mut Vec<String>, prev: Option<(usize, usize)>, line: usize, depth: usize) {
+    let Some((prev_line, prev_depth)) = prev else {
+        return;
+    };
+    if depth > prev_depth {
+        return;
+    }
+    let max = if depth == 0 { 2 } else { 1 };
+    let blanks = line.saturating_sub(prev_line + 1).min(max);
+    for _ in 0..blanks {
+        out.push(String::new());
+    }
+}
+
+/// Chooses the depth of a comment between a statement at depth `prev` and one
+/// at depth `next`: the deepest enclosing block the comment was indented into.
+fn comment_depth(column: usize, prev: usize, next: usize, columns: &[usize]) -> usize {
+    if next > prev {
+        return next;
+    }
+    (next..=prev)
+        .rev()
+        .find(|d| columns.get(*d).is_some_and(|c| *c <= column))
+        .unwrap_or(next)
+}
+
+fn spaced(
+    prev: &TokenKind,
+    next: &TokenKind,
+    bracket: Option<Bracket>,
+    keyword_arg: impl Fn() -> bool,
+) -> bool {
+    use TokenKind as T;
+    if matches!(
+        next,
+        T::RParen | T::RBracket | T::RBrace | T::Comma | T::Colon | T::Dot | T::Newline
+    ) || matches!(prev, T::LParen | T::LBracket | T::LBrace | T::Dot)
+    {
+        return false;
+    }
+    if matches!(next, T::LParen | T::LBracket) && ends_callee(prev) {
+        return false;
+    }
+    if *prev == T::Colon {
+        return bracket != Some(Bracket::Subscript);
+    }
+    if *next == T::Assign || *prev == T::Assign {
+        return !keyword_arg();
+    }
+    true
+}
+
+fn ends_operand(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Identifier(_)
+            | TokenKind::String(_)
+            | TokenKind::Bytes(_)
+            | TokenKind::Integer(_)
+            | TokenKind::BigInteger(_)
+            | TokenKind::Float(_)
+            | TokenKind::FStringContent(_)
+            | TokenKind::True
+            | TokenKind::False
+            | TokenKind::None
+            | TokenKind::RParen
+            | TokenKind::RBracket
+            | TokenKind::RBrace
+    )
+}
+
+/// Whether a `(` or `[` after this token is a call or subscript.
+fn ends_callee(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Identifier(_)
+            | TokenKind::String(_)
+            | TokenKind::Bytes(_)
+            | TokenKind::FStringContent(_)
+            | TokenKind::RParen
+            | TokenKind::RBracket
+    )
+}
+
+fn is_string(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::String(_) | TokenKind::Bytes(_) | TokenKind::FStringContent(_)
+    )
+}
+
+/// String token spans start after the opening quote; this finds the prefix.
+fn literal_start(chars: &[char], token: &Token) -> usize {
+    let start = token.span.start;
+    if !is_string(&token.kind) || start == 0 || !matches!(chars[start - 1], '"' | '\'') {
+        return start;
+    }
+    let quote = start - 1;
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    if quote > 0
+        && matches!(chars[quote - 1], 'b' | 'f' | 'F' | 'r' | 'R')
+        && (quote < 2 || !is_ident(chars[quote - 2]))
+    {
+        quote - 1
+    } else {
+        quote
+    }
+}
+
+fn token_text(chars: &[char], token: &Token) -> String {
+    let end = token.span.end.min(chars.len());
+    let text: String = chars[token.span.start.min(end)..end].iter().collect();
+    if is_string(&token.kind) {
+        prefer_double_quotes(text)
+    } else {
+        text
+    }
+}
+
+fn prefer_double_quotes(literal: String) -> String {
+    let Some(quote) = literal.find(['"', '\'']) else {
+        return literal;
+    };
+    let (prefix, rest) = literal.split_at(quote);
+    if !rest.starts_with('\'') || rest.starts_with("'''") || rest.len() < 2 {
+        return literal;
+    }
+    let body = &rest[1..rest.len() - 1];
+    if body.contains('"') || body.contains("\\'") {
+        return literal;
+    }
+    format!("{}\"{}\"", prefix, body)
+}
+
+fn normalize_comment(comment: &str) -> String {
+    let comment = comment.trim_end();
+    let body = &comment[1..];
+    if body.is_empty() || body.starts_with([' ', '\t', '#', '!', ':']) {
+        comment.to_string()
+    } else {
+        format!("# {}", body)
+    }
+}
@@ -3,6 +3,8 @@ use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
+use num_bigint::BigInt;
+use num_traits::Signed;
 use spin::RwLock;
 
 /// `abs(x)`: Returns the absolute value of a number.
@@ -17,7 +19,10 @@ pub fn builtin_abs(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
         ));
     }
     match &args[0] {
-        Value::Int(i) => Ok(Value::Int(i.abs())),
+        Value::Int(i) => Ok(i
+            .checked_abs()
+            .map_or_else(|| Value::from_bigint(BigInt::from(*i).abs()), Value::Int)),
+        Value::BigInt(i) => Ok(Value::from_bigint(i.abs())),
         Value::Float(f) => Ok(Value::Float(f.abs())),
         _ => Err(format!(
             "bad operand type for abs(): '{}'",
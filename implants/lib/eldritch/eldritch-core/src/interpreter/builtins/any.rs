@@ -1,3 +1,4 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
 use crate::interpreter::introspection::{get_type_name, is_truthy};
 use alloc::format;
@@ -9,7 +10,7 @@ use spin::RwLock;
 ///
 /// **Parameters**
 /// - `iterable` (Iterable): The iterable to check.
-pub fn builtin_any(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_any(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
         return Err(format!(
             "any() takes exactly one argument ({} given)",
@@ -20,6 +21,7 @@ pub fn builtin_any(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
     let items = match &args[0] {
         Value::List(l) => l.read().clone(),
         Value::Tuple(t) => t.clone(),
+        Value::Iterable(it) => it.items(sequence_limit(env))?,
         Value::Set(s) => s.read().iter().cloned().collect(),
         Value::Dictionary(d) => d.read().keys().cloned().collect(),
         _ => {
@@ -1,5 +1,6 @@
 use super::get_all_builtins;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -19,5 +20,5 @@ pub fn builtin_builtins(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Resu
         .collect();
     names.sort();
     let val_list: Vec<Value> = names.into_iter().map(Value::String).collect();
-    Ok(Value::List(Arc::new(RwLock::new(val_list))))
+    Ok(Value::List(Arc::new(Freezable::new(val_list))))
 }
@@ -29,6 +29,7 @@ pub fn builtin_chr(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
                 None => Err("chr() arg not in range(0x110000)".to_string()),
             }
         }
+        Value::BigInt(_) => Err("chr() arg not in range(0x110000)".to_string()),
         _ => Err(format!(
             "TypeError: an integer is required (got type {})",
             get_type_name(&args[0])
@@ -1,4 +1,6 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use alloc::collections::BTreeMap;
 use alloc::format;
@@ -12,7 +14,7 @@ use spin::RwLock;
 /// - `iterable` (Iterable): An iterable of key-value pairs (tuples/lists of length 2).
 /// - `**kwargs` (Any): Keyword arguments to add to the dictionary.
 pub fn builtin_dict(
-    _env: &Arc<RwLock<Environment>>,
+    env: &Arc<RwLock<Environment>>,
     args: &[Value],
     kwargs: &BTreeMap<String, Value>,
 ) -> Result<Value, String> {
@@ -49,6 +51,11 @@ pub fn builtin_dict(
                     process_pair(&mut map, item, i)?;
                 }
             }
+            Value::Iterable(it) => {
+                for (i, item) in it.items(sequence_limit(env))?.iter().enumerate() {
+                    process_pair(&mut map, item, i)?;
+                }
+            }
             _ => {
                 return Err(format!(
                     "'{}' object is not iterable",
@@ -63,7 +70,7 @@ pub fn builtin_dict(
         map.insert(Value::String(k.clone()), v.clone());
     }
 
-    Ok(Value::Dictionary(Arc::new(RwLock::new(map))))
+    Ok(Value::Dictionary(Arc::new(Freezable::new(map))))
 }
 
 fn process_pair(
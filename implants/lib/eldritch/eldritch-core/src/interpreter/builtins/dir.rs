@@ -1,4 +1,5 @@
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_dir_attributes;
 use alloc::collections::BTreeSet;
 use alloc::string::String;
@@ -25,11 +26,11 @@ pub fn builtin_dir(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Val
         }
 
         let val_attrs: Vec<Value> = symbols.into_iter().map(Value::String).collect();
-        return Ok(Value::List(Arc::new(RwLock::new(val_attrs))));
+        return Ok(Value::List(Arc::new(Freezable::new(val_attrs))));
     }
 
     // Original behavior for dir(obj)
     let attrs = get_dir_attributes(&args[0]);
     let val_attrs: Vec<Value> = attrs.into_iter().map(Value::String).collect();
-    Ok(Value::List(Arc::new(RwLock::new(val_attrs))))
+    Ok(Value::List(Arc::new(Freezable::new(val_attrs))))
 }
@@ -1,15 +1,15 @@
-use crate::ast::{Environment, Value};
+use crate::ast::{Environment, LazyIterable, Value, ValueIter};
 use crate::interpreter::introspection::get_type_name;
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec;
-use alloc::vec::Vec;
 use spin::RwLock;
 
 /// `enumerate(iterable, start=0)`: Returns an enumerate object.
 ///
-/// Returns a list of tuples containing (index, value) pairs.
+/// Lazily yields tuples containing (index, value) pairs.
 ///
 /// **Parameters**
 /// - `iterable` (Iterable): The sequence to enumerate.
@@ -27,21 +27,51 @@ pub fn builtin_enumerate(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Res
     } else {
         0
     };
-    let items = match iterable {
-        Value::List(l) => l.read().clone(),
-        Value::Tuple(t) => t.clone(),
-        Value::Set(s) => s.read().iter().cloned().collect(),
-        Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
-        _ => {
-            return Err(format!(
-                "Type '{:?}' is not iterable",
-                get_type_name(iterable)
-            ));
-        }
-    };
-    let mut pairs = Vec::new();
-    for (i, item) in items.into_iter().enumerate() {
-        pairs.push(Value::Tuple(vec![Value::Int(i as i64 + start), item]));
+    if iterable.try_iter().is_none() || matches!(iterable, Value::Dictionary(_)) {
+        return Err(format!(
+            "Type '{:?}' is not iterable",
+            get_type_name(iterable)
+        ));
+    }
+    Ok(Value::Iterable(Arc::new(Enumerate {
+        source: iterable.clone(),
+        start,
+    })))
+}
+
+#[derive(Debug)]
+struct Enumerate {
+    source: Value,
+    start: i64,
+}
+
+impl LazyIterable for Enumerate {
+    // Reported as the list enumerate() returned before it became lazy.
+    fn type_name(&self) -> &str {
+        "list"
+    }
+
+    fn iter(&self) -> ValueIter {
+        let start = self.start;
+        let items = self
+            .source
+            .try_iter()
+            .unwrap_or_else(|| Box::new(core::iter::empty()));
+        Box::new(items.enumerate().map(move |(i, item)| {
+            item.map(|item| Value::Tuple(vec![Value::Int(i as i64 + start), item]))
+        }))
+    }
+
+    fn known_len(&self) -> Option<usize> {
+        self.source.known_len()
+    }
+
+    fn get(&self, index: usize) -> Option<Result<Value, String>> {
+        let i = Value::Int(index as i64 + self.start);
+        Some(
+            self.source
+                .item_at(index)?
+                .map(|item| Value::Tuple(vec![i, item])),
+        )
     }
-    Ok(Value::List(Arc::new(RwLock::new(pairs))))
 }
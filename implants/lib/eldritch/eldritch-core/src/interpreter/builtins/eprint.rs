@@ -1,3 +1,4 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
 use crate::token::Span;
 use alloc::string::{String, ToString};
@@ -10,7 +11,7 @@ pub fn builtin_eprint(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
         if i > 0 {
             out.push(' ');
         }
-        out.push_str(&arg.to_string());
+        out.push_str(&collect_lazy(env, arg)?.to_string());
     }
 
     // TODO: Pass actual span
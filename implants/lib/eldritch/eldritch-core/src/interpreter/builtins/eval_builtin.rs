@@ -1,17 +1,19 @@
-use crate::ast::{Argument, Value};
-use crate::interpreter::core::{Flow, Interpreter};
+use crate::ast::Value;
+use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
 use crate::interpreter::eval::MAX_RECURSION_DEPTH;
-use crate::interpreter::eval::functions::evaluate_arg;
 use crate::token::Span;
-use alloc::string::ToString;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
 
 pub(crate) fn builtin_eval_func(
     interp: &mut Interpreter,
-    args: &[Argument],
+    args: &[Value],
+    kwargs: &BTreeMap<String, Value>,
     span: Span,
 ) -> Result<Value, EldritchError> {
-    if args.len() != 1 {
+    if args.len() != 1 || !kwargs.is_empty() {
         return interp.error(
             EldritchErrorKind::TypeError,
             "eval() takes exactly 1 argument",
@@ -19,9 +21,8 @@ pub(crate) fn builtin_eval_func(
         );
     }
 
-    let code_val = evaluate_arg(interp, &args[0])?;
-    let code = match code_val {
-        Value::String(s) => s,
+    let code = match &args[0] {
+        Value::String(s) => s.clone(),
         _ => {
             return interp.error(
                 EldritchErrorKind::TypeError,
@@ -39,19 +40,28 @@ pub(crate) fn builtin_eval_func(
         );
     }
 
+    if let Some(max) = interp.budget.limits.max_eval_depth
+        && interp.budget.eval_depth >= max
+    {
+        return interp.error(
+            EldritchErrorKind::RecursionError,
+            &format!("eval() nesting limit of {max} exceeded"),
+            span,
+        );
+    }
+
     // Create a new interpreter instance that shares the environment
-    // We manually construct it to avoid re-loading builtins and to set depth
-    let mut temp_interp = Interpreter {
-        env: interp.env.clone(),
-        flow: Flow::Next,
-        depth: interp.depth + 1,
-        call_stack: interp.call_stack.clone(),
-        current_func_name: "<eval>".to_string(),
-        is_scope_owner: false,
-    };
+    let mut temp_interp = interp.nested(interp.env.clone(), "<eval>");
+    temp_interp.budget.eval_depth += 1;
+
+    let result = temp_interp.run(&code);
+    // Steps spent inside eval count against the caller's budget
+    interp.budget.steps = temp_interp.budget.steps;
 
-    match temp_interp.interpret(&code) {
+    match result {
         Ok(v) => Ok(v),
-        Err(e) => interp.error(EldritchErrorKind::RuntimeError, &e, span),
+        // Budget and cancellation errors must still reach the host, not an `except`
+        Err(e) if !e.kind.is_catchable() => interp.error(e.kind, &e.message, span),
+        Err(e) => interp.error(EldritchErrorKind::RuntimeError, &e.render(), span),
     }
 }
@@ -3,6 +3,7 @@ use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
+use num_traits::ToPrimitive;
 use spin::RwLock;
 
 /// `float(x)`: Converts a number or string to a floating point number.
@@ -23,6 +24,10 @@ pub fn builtin_float(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
     match &args[0] {
         Value::Float(f) => Ok(Value::Float(*f)),
         Value::Int(i) => Ok(Value::Float(*i as f64)),
+        Value::BigInt(i) => match i.to_f64() {
+            Some(f) if f.is_finite() => Ok(Value::Float(f)),
+            _ => Err("int too large to convert to float".into()),
+        },
         Value::Bool(b) => Ok(Value::Float(if *b { 1.0 } else { 0.0 })),
         Value::String(s) => {
             let s_trimmed = s.trim();
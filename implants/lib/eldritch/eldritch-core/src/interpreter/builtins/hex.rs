@@ -3,6 +3,7 @@ use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
+use num_bigint::Sign;
 use spin::RwLock;
 
 /// `hex(x)`: Return the hexadecimal representation of an integer.
@@ -24,6 +25,10 @@ pub fn builtin_hex(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
                 Ok(Value::String(format!("0x{:x}", i)))
             }
         }
+        Value::BigInt(i) => {
+            let sign = if i.sign() == Sign::Minus { "-" } else { "" };
+            Ok(Value::String(format!("{sign}0x{:x}", i.magnitude())))
+        }
         _ => Err(format!(
             "hex() argument must be an integer, not '{}'",
             get_type_name(&args[0])
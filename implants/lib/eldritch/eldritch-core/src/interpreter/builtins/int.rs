@@ -3,6 +3,9 @@ use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
+use core::num::IntErrorKind;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{FromPrimitive, Num};
 use spin::RwLock;
 
 /// `int(x)`: Converts a number or string to an integer.
@@ -39,8 +42,8 @@ pub fn builtin_int(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
     } else {
         // No base provided
         match x {
-            Value::Int(i) => Ok(Value::Int(*i)),
-            Value::Float(f) => Ok(Value::Int(*f as i64)), // Truncate
+            Value::Int(_) | Value::BigInt(_) => Ok(x.clone()),
+            Value::Float(f) => float_to_int(*f),
             Value::Bool(b) => Ok(Value::Int(if *b { 1 } else { 0 })),
             Value::String(s) => parse_int_string(s, 10),
             _ => Err(format!(
@@ -89,31 +92,43 @@ fn parse_int_string(s: &str, base: i64) -> Result<Value, String> {
         clean_s
     };
 
-    let uval = u64::from_str_radix(clean_s_no_prefix, radix).map_err(|_| {
+    let invalid = || {
         if base == 0 || base == 10 {
             format!("invalid literal for int() with base {radix}: '{s}'")
         } else {
             format!("invalid literal for int() with base {base}: '{s}'")
         }
-    })?;
+    };
 
-    if is_neg {
-        // For negative, absolute value can be at most i64::MAX + 1 (which is abs(i64::MIN))
-        // i64::MIN is -9223372036854775808
-        // i64::MAX is  9223372036854775807
-        // abs(i64::MIN) is 9223372036854775808 (u64)
-        if uval > i64::MAX as u64 + 1 {
-            return Err(format!(
-                "int() literal too large: '{s}'" // Python behavior for overflow in fixed-width types? Python ints are arbitrary precision.
-                                                 // But eldritch uses i64. We should error on overflow.
-            ));
-        }
-        // Wrapping negation for i64::MIN case
-        Ok(Value::Int((uval as i64).wrapping_neg()))
-    } else {
-        if uval > i64::MAX as u64 {
-            return Err(format!("int() literal too large: '{s}'"));
+    let uval = match u64::from_str_radix(clean_s_no_prefix, radix) {
+        Ok(uval) => BigUint::from(uval),
+        // Literals beyond 64 bits become big integers
+        Err(e)
+            if *e.kind() == IntErrorKind::PosOverflow
+                && clean_s_no_prefix.bytes().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            BigUint::from_str_radix(clean_s_no_prefix, radix).map_err(|_| invalid())?
         }
-        Ok(Value::Int(uval as i64))
+        Err(_) => return Err(invalid()),
+    };
+
+    let val = BigInt::from(uval);
+    Ok(Value::from_bigint(if is_neg { -val } else { val }))
+}
+
+// Truncates a float towards zero
+fn float_to_int(f: f64) -> Result<Value, String> {
+    if f.is_nan() {
+        return Err("cannot convert float NaN to integer".into());
+    }
+    if f.is_infinite() {
+        return Err("cannot convert float infinity to integer".into());
+    }
+    let truncated = f.trunc();
+    if truncated >= i64::MIN as f64 && truncated < i64::MAX as f64 {
+        return Ok(Value::Int(truncated as i64));
     }
+    BigInt::from_f64(truncated)
+        .map(Value::from_bigint)
+        .ok_or_else(|| format!("cannot convert float {f} to integer"))
 }
@@ -22,6 +22,12 @@ pub fn builtin_len(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
         Value::Dictionary(d) => Ok(Value::Int(d.read().len() as i64)),
         Value::Tuple(t) => Ok(Value::Int(t.len() as i64)),
         Value::Set(s) => Ok(Value::Int(s.read().len() as i64)),
+        Value::Iterable(it) => match it.known_len() {
+            Some(len) => Ok(Value::Int(len as i64)),
+            None => Ok(Value::Int(
+                it.iter().try_fold(0, |n, item| item.map(|_| n + 1))?,
+            )),
+        },
         _ => Err(format!(
             "TypeError: object of type '{}' has no len()",
             crate::interpreter::introspection::get_type_name(&args[0])
@@ -1,4 +1,5 @@
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use alloc::collections::BTreeSet;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
@@ -26,5 +27,5 @@ pub fn builtin_libs(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
     }
 
     let val_list: Vec<Value> = names.into_iter().map(Value::String).collect();
-    Ok(Value::List(Arc::new(RwLock::new(val_list))))
+    Ok(Value::List(Arc::new(Freezable::new(val_list))))
 }
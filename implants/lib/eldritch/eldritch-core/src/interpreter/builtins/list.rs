@@ -1,4 +1,6 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
@@ -11,9 +13,9 @@ use spin::RwLock;
 ///
 /// If no argument is given, the constructor creates a new empty list.
 /// The argument must be an iterable if specified.
-pub fn builtin_list(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_list(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
-        return Ok(Value::List(Arc::new(RwLock::new(Vec::new()))));
+        return Ok(Value::List(Arc::new(Freezable::new(Vec::new()))));
     }
     if args.len() != 1 {
         return Err(format!(
@@ -25,6 +27,7 @@ pub fn builtin_list(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<V
     let items = match &args[0] {
         Value::List(l) => l.read().clone(),
         Value::Tuple(t) => t.clone(),
+        Value::Iterable(it) => it.items(sequence_limit(env))?,
         Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
         Value::Set(s) => s.read().iter().cloned().collect(),
         Value::Dictionary(d) => d.read().keys().cloned().collect(),
@@ -36,5 +39,5 @@ pub fn builtin_list(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<V
         }
     };
 
-    Ok(Value::List(Arc::new(RwLock::new(items))))
+    Ok(Value::List(Arc::new(Freezable::new(items))))
 }
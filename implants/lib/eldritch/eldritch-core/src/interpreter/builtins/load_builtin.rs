@@ -0,0 +1,235 @@
+use crate::ast::{Environment, Value};
+use crate::interpreter::core::Interpreter;
+use crate::interpreter::error::{EldritchError, EldritchErrorKind};
+use crate::interpreter::eval::MAX_RECURSION_DEPTH;
+use crate::token::Span;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use spin::RwLock;
+
+/// Modules evaluated by `load()`, shared by an interpreter and everything it loads.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleCache {
+    /// Globals of every module that finished loading, keyed by asset path.
+    pub(crate) loaded: BTreeMap<String, Arc<RwLock<Environment>>>,
+    /// Paths currently being evaluated, used to detect load cycles.
+    pub(crate) loading: Vec<String>,
+}
+
+/// `load(module, *symbols, **aliases)`: Evaluates an Eldritch module and binds its symbols.
+///
+/// The module is read through the `assets` library, so it may come from any registered asset
+/// backend. Each module is evaluated once per interpreter and then served from a cache.
+///
+/// **Parameters**
+/// - `module` (str): Asset path of the module, e.g. `"lib/util.eldritch"`.
+/// - `*symbols` (str): Names to bind under the same name in the current scope.
+/// - `**aliases` (str): `local_name="symbol"` pairs to bind under a different name.
+pub(crate) fn builtin_load_func(
+    interp: &mut Interpreter,
+    args: &[Value],
+    kwargs: &BTreeMap<String, Value>,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    let mut path = None;
+    let mut bindings: Vec<(String, String)> = Vec::new();
+    let positional = args.iter().map(|v| (None, v));
+    let keywords = kwargs.iter().map(|(k, v)| (Some(k.clone()), v));
+    for (local, value) in positional.chain(keywords) {
+        let name = match value {
+            Value::String(s) => s.clone(),
+            _ => {
+                return interp.error(
+                    EldritchErrorKind::TypeError,
+                    "load() arguments must be strings",
+                    span,
+                );
+            }
+        };
+        if local.is_none() && path.is_none() {
+            path = Some(name);
+        } else {
+            bindings.push((local.unwrap_or_else(|| name.clone()), name));
+        }
+    }
+
+    let Some(path) = path else {
+        return interp.error(
+            EldritchErrorKind::TypeError,
+            "load() missing required argument: 'module'",
+            span,
+        );
+    };
+    if bindings.is_empty() {
+        return interp.error(
+            EldritchErrorKind::TypeError,
+            "load() requires at least one symbol to import",
+            span,
+        );
+    }
+
+    let module = load_module(interp, &path, span)?;
+    for (local, symbol) in bindings {
+        if symbol.starts_with('_') {
+            return interp.error(
+                EldritchErrorKind::ImportError,
+                &format!("cannot load private symbol '{symbol}' from '{path}'"),
+                span,
+            );
+        }
+        let value = module.read().values.get(&symbol).cloned();
+        match value {
+            Some(v) => interp.define_variable(&local, v),
+            None => {
+                return interp.error(
+                    EldritchErrorKind::ImportError,
+                    &format!("module '{path}' has no symbol '{symbol}'"),
+                    span,
+                );
+            }
+        }
+    }
+
+    Ok(Value::None)
+}
+
+fn load_module(
+    interp: &mut Interpreter,
+    path: &str,
+    span: Span,
+) -> Result<Arc<RwLock<Environment>>, EldritchError> {
+    if let Some(env) = interp.modules.read().loaded.get(path) {
+        return Ok(env.clone());
+    }
+
+    let cycle = {
+        let cache = interp.modules.read();
+        cache.loading.iter().any(|p| p == path).then(|| {
+            let mut chain = cache.loading.clone();
+            chain.push(path.to_string());
+            chain.join(" -> ")
+        })
+    };
+    if let Some(chain) = cycle {
+        return interp.error(
+            EldritchErrorKind::ImportError,
+            &format!("load cycle detected: {chain}"),
+            span,
+        );
+    }
+
+    if interp.depth >= MAX_RECURSION_DEPTH {
+        return interp.error(
+            EldritchErrorKind::RecursionError,
+            "Recursion limit exceeded",
+            span,
+        );
+    }
+
+    let source = read_module_source(interp, path, span)?;
+
+    // Modules run in their own globals with the builtins and the importer's libraries,
+    // so they can't observe or clobber the importing script's variables.
+    let printer = interp.env.read().printer.clone();
+    let mut module_interp = Interpreter::new_with_printer(printer);
+    module_interp.is_scope_owner = false;
+    module_interp.depth = interp.depth + 1;
+    module_interp.budget = interp.budget.clone();
+    module_interp.modules = interp.modules.clone();
+    {
+        let root = root_env(interp);
+        let root = root.read();
+        let mut module_env = module_interp.env.write();
+        for lib in &root.libraries {
+            if let Some(val) = root.values.get(lib) {
+                module_env.values.insert(lib.clone(), val.clone());
+                module_env.libraries.insert(lib.clone());
+            }
+        }
+    }
+
+    interp.modules.write().loading.push(path.to_string());
+    let result = module_interp.run(&source);
+    interp.modules.write().loading.pop();
+    // Steps spent inside the module count against the importer's budget
+    interp.budget.steps = module_interp.budget.steps;
+
+    if let Err(e) = result {
+        // Budget and cancellation errors must still reach the host, not an `except`
+        if !e.kind.is_catchable() {
+            return interp.error(e.kind, &e.message, span);
+        }
+        return interp.error(
+            EldritchErrorKind::ImportError,
+            &format!("failed to load '{path}': {}", e.render()),
+            span,
+        );
+    }
+
+    // Like Starlark, a loaded module's globals are frozen so every importer sees the same values
+    let env = module_interp.env.clone();
+    env.read().values.values().for_each(Value::freeze);
+    interp
+        .modules
+        .write()
+        .loaded
+        .insert(path.to_string(), env.clone());
+    Ok(env)
+}
+
+fn read_module_source(
+    interp: &mut Interpreter,
+    path: &str,
+    span: Span,
+) -> Result<String, EldritchError> {
+    let assets = {
+        let root = root_env(interp);
+        let root = root.read();
+        if root.libraries.contains("assets") {
+            root.values.get("assets").cloned()
+        } else {
+            None
+        }
+    };
+    let Some(Value::Foreign(assets)) = assets else {
+        return interp.error(
+            EldritchErrorKind::ImportError,
+            "load() requires the assets library to be registered",
+            span,
+        );
+    };
+
+    match assets.call_method(
+        interp,
+        "read",
+        &[Value::String(path.to_string())],
+        &BTreeMap::new(),
+    ) {
+        Ok(Value::String(s)) => Ok(s),
+        Ok(_) => interp.error(
+            EldritchErrorKind::ImportError,
+            &format!("module '{path}' is not valid UTF-8 source"),
+            span,
+        ),
+        Err(e) => interp.error(
+            EldritchErrorKind::ImportError,
+            &format!("cannot load '{path}': {e}"),
+            span,
+        ),
+    }
+}
+
+/// Libraries are registered on the interpreter's outermost environment.
+fn root_env(interp: &Interpreter) -> Arc<RwLock<Environment>> {
+    let mut env = interp.env.clone();
+    loop {
+        let parent = env.read().parent.clone();
+        match parent {
+            Some(p) => env = p,
+            None => return env,
+        }
+    }
+}
@@ -1,18 +1,23 @@
-use crate::ast::{Argument, Value};
+use crate::ast::Value;
+use crate::interpreter::Freezable;
 use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
-use crate::interpreter::eval::functions::{call_value, evaluate_arg};
-use crate::interpreter::eval::utils::to_iterable;
+use crate::interpreter::eval::functions::{call_value, reject_hof_kwargs};
+use crate::interpreter::eval::utils::check_collected_len;
+use crate::interpreter::eval::{iterate, iteration_error};
 use crate::token::Span;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::RwLock;
 
 pub(crate) fn builtin_map(
     interp: &mut Interpreter,
-    args: &[Argument],
+    args: &[Value],
+    kwargs: &BTreeMap<String, Value>,
     span: Span,
 ) -> Result<Value, EldritchError> {
+    reject_hof_kwargs(interp, kwargs, span)?;
     if args.len() != 2 {
         return interp.error(
             EldritchErrorKind::TypeError,
@@ -20,16 +25,16 @@ pub(crate) fn builtin_map(
             span,
         );
     }
-    let func_val = evaluate_arg(interp, &args[0])?;
-    let iterable_val = evaluate_arg(interp, &args[1])?;
+    let func_val = &args[0];
+    let iterable_val = &args[1];
 
-    let items = to_iterable(interp, &iterable_val, span)?;
     let mut results = Vec::new();
-
-    for item in items {
-        let res = call_value(interp, &func_val, core::slice::from_ref(&item), span)?;
+    for item in iterate(interp, iterable_val, span)? {
+        let item = item.or_else(|msg| iteration_error(interp, msg, span))?;
+        let res = call_value(interp, func_val, core::slice::from_ref(&item), span)?;
         results.push(res);
+        check_collected_len(interp, results.len(), span)?;
     }
 
-    Ok(Value::List(Arc::new(RwLock::new(results))))
+    Ok(Value::List(Arc::new(Freezable::new(results))))
 }
@@ -1,3 +1,4 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
 use crate::interpreter::introspection::get_type_name;
 use alloc::format;
@@ -12,7 +13,7 @@ use spin::RwLock;
 /// **Parameters**
 /// - `iterable` (Iterable): An iterable to search.
 /// - `arg1, arg2, *args` (Any): Two or more arguments to compare.
-pub fn builtin_max(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_max(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Err("max expected at least 1 argument, got 0".to_string());
     }
@@ -21,6 +22,7 @@ pub fn builtin_max(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
         match &args[0] {
             Value::List(l) => l.read().clone(),
             Value::Tuple(t) => t.clone(),
+            Value::Iterable(it) => it.items(sequence_limit(env))?,
             Value::Set(s) => s.read().iter().cloned().collect(),
             Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
             Value::Dictionary(d) => d.read().keys().cloned().collect(),
@@ -1,7 +1,10 @@
-use crate::ast::{BuiltinFn, BuiltinFnWithKwargs, Value};
-use alloc::string::ToString;
+use crate::ast::{BuiltinFn, BuiltinFnWithKwargs, Environment, Value};
+use crate::interpreter::Freezable;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
+use spin::RwLock;
 
 mod assert;
 mod assert_eq;
@@ -36,6 +39,7 @@ mod min;
 mod repr;
 mod reversed;
 mod set;
+mod struct_;
 pub mod tprint;
 mod tuple;
 mod zip;
@@ -43,6 +47,7 @@ mod zip;
 // Moved from eval/
 pub mod eval_builtin;
 pub mod filter;
+pub mod load_builtin;
 pub mod map;
 pub mod reduce;
 pub mod sorted;
@@ -87,9 +92,28 @@ pub fn get_all_builtins() -> Vec<(&'static str, BuiltinFn)> {
     ]
 }
 
+// The sequence limit of the running script, for builtins that collect an iterable
+pub(crate) fn sequence_limit(env: &Arc<RwLock<Environment>>) -> Option<usize> {
+    env.read().max_sequence_len
+}
+
+// `val` with a lazy iterable collected into a list within the sequence limit, so that
+// formatting it raises an iteration error instead of cutting the output short
+pub(crate) fn collect_lazy(env: &Arc<RwLock<Environment>>, val: &Value) -> Result<Value, String> {
+    match val {
+        Value::Iterable(it) => Ok(Value::List(Arc::new(Freezable::new(
+            it.items(sequence_limit(env))?,
+        )))),
+        val => Ok(val.clone()),
+    }
+}
+
 // Separate function for kwargs builtins
 pub fn get_all_builtins_with_kwargs() -> Vec<(&'static str, BuiltinFnWithKwargs)> {
-    vec![("dict", dict::builtin_dict as BuiltinFnWithKwargs)]
+    vec![
+        ("dict", dict::builtin_dict as BuiltinFnWithKwargs),
+        ("struct", struct_::builtin_struct as BuiltinFnWithKwargs),
+    ]
 }
 
 // I need to handle stubs.
@@ -107,5 +131,6 @@ pub fn get_stubs() -> Vec<(&'static str, BuiltinFn)> {
         ("reduce", builtin_stub as BuiltinFn),
         ("sorted", builtin_stub as BuiltinFn),
         ("eval", builtin_stub as BuiltinFn),
+        ("load", builtin_stub as BuiltinFn),
     ]
 }
@@ -1,4 +1,6 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::token::Span;
 use alloc::format;
 use alloc::string::{String, ToString};
@@ -24,7 +26,7 @@ pub fn builtin_pprint(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
     }
 
     let mut output = String::new();
-    pretty_format(&args[0], 0, indent_width, &mut output);
+    pretty_format(&collect_lazy(env, &args[0])?, 0, indent_width, &mut output);
 
     // TODO: Pass actual span
     env.read().printer.print_out(&Span::new(0, 0, 0), &output);
@@ -129,7 +131,38 @@ pub fn pretty_format(val: &Value, current_indent: usize, indent_width: usize, bu
             buf.push_str(&indent_str);
             buf.push('}');
         }
+        Value::Struct(fields) => {
+            if fields.is_empty() {
+                buf.push_str("struct()");
+                return;
+            }
+            buf.push_str("struct(\n");
+
+            let next_indent = current_indent + indent_width;
+            let next_indent_str = " ".repeat(next_indent);
+
+            for (i, (name, value)) in fields.iter().enumerate() {
+                buf.push_str(&next_indent_str);
+                buf.push_str(name);
+                buf.push('=');
+                pretty_format(value, next_indent, indent_width, buf);
+                if i < fields.len() - 1 {
+                    buf.push(',');
+                }
+                buf.push('\n');
+            }
+            let indent_str = " ".repeat(current_indent);
+            buf.push_str(&indent_str);
+            buf.push(')');
+        }
         Value::String(s) => buf.push_str(&format!("{s:?}")),
+        Value::Iterable(it) => match it.items(None) {
+            Ok(items) => {
+                let list = Value::List(Arc::new(Freezable::new(items)));
+                pretty_format(&list, current_indent, indent_width, buf)
+            }
+            Err(_) => buf.push_str(&format!("{val}")),
+        },
         _ => buf.push_str(&format!("{val}")),
     }
 }
@@ -1,3 +1,4 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
 use crate::token::Span;
 use alloc::string::{String, ToString};
@@ -14,7 +15,7 @@ pub fn builtin_print(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<V
         if i > 0 {
             out.push(' ');
         }
-        out.push_str(&arg.to_string());
+        out.push_str(&collect_lazy(env, arg)?.to_string());
     }
 
     // TODO: Pass actual span
@@ -1,16 +1,23 @@
-use crate::ast::{Environment, Value};
+use crate::ast::{Environment, LazyIterable, Value, ValueIter};
+use alloc::boxed::Box;
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
-use alloc::vec::Vec;
 use spin::RwLock;
 
 /// `range(stop)` or `range(start, stop[, step])`: Returns a sequence of numbers.
 ///
+/// The numbers are produced lazily, so large ranges cost no memory until they are
+/// converted with `list()`.
+///
 /// **Parameters**
 /// - `start` (Int): The start value (inclusive). Defaults to 0.
 /// - `stop` (Int): The stop value (exclusive).
 /// - `step` (Int): The step size. Defaults to 1.
 pub fn builtin_range(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+    if let Some(Value::BigInt(i)) = args.iter().find(|arg| matches!(arg, Value::BigInt(_))) {
+        return Err(format!("ValueError: int {i} out of range for i64"));
+    }
     let (start, end, step) = match args {
         [Value::Int(end)] => (0, *end, 1),
         [Value::Int(start), Value::Int(end)] => (*start, *end, 1),
@@ -20,19 +27,45 @@ pub fn builtin_range(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
     if step == 0 {
         return Err("ValueError: range() arg 3 must not be zero".to_string());
     }
+    Ok(Value::Iterable(Arc::new(Range { start, end, step })))
+}
+
+#[derive(Debug)]
+struct Range {
+    start: i64,
+    end: i64,
+    step: i64,
+}
+
+impl Range {
+    fn len(&self) -> usize {
+        let (start, end, step) = (self.start as i128, self.end as i128, self.step as i128);
+        let len = if step > 0 {
+            (end - start + step - 1) / step
+        } else {
+            (start - end - step - 1) / -step
+        };
+        len.max(0) as usize
+    }
+}
+
+impl LazyIterable for Range {
+    // Reported as the list range() returned before it became lazy.
+    fn type_name(&self) -> &str {
+        "list"
+    }
+
+    fn iter(&self) -> ValueIter {
+        let (start, step) = (self.start as i128, self.step as i128);
+        Box::new((0..self.len() as i128).map(move |i| Ok(Value::Int((start + i * step) as i64))))
+    }
+
+    fn known_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
 
-    let mut list = Vec::new();
-    let mut curr = start;
-    if step > 0 {
-        while curr < end {
-            list.push(Value::Int(curr));
-            curr += step;
-        }
-    } else {
-        while curr > end {
-            list.push(Value::Int(curr));
-            curr += step;
-        }
+    fn get(&self, index: usize) -> Option<Result<Value, String>> {
+        let value = self.start as i128 + index as i128 * self.step as i128;
+        (index < self.len()).then_some(Ok(Value::Int(value as i64)))
     }
-    Ok(Value::List(Arc::new(RwLock::new(list))))
 }
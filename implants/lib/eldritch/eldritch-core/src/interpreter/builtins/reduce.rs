@@ -1,15 +1,19 @@
-use crate::ast::Argument;
+use crate::ast::Value;
 use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
-use crate::interpreter::eval::functions::{call_value, evaluate_arg};
-use crate::interpreter::eval::utils::to_iterable;
+use crate::interpreter::eval::functions::{call_value, reject_hof_kwargs};
+use crate::interpreter::eval::{iterate, iteration_error};
 use crate::token::Span;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
 
 pub(crate) fn builtin_reduce(
     interp: &mut Interpreter,
-    args: &[Argument],
+    args: &[Value],
+    kwargs: &BTreeMap<String, Value>,
     span: Span,
-) -> Result<crate::ast::Value, EldritchError> {
+) -> Result<Value, EldritchError> {
+    reject_hof_kwargs(interp, kwargs, span)?;
     if args.len() < 2 || args.len() > 3 {
         return interp.error(
             EldritchErrorKind::TypeError,
@@ -17,15 +21,14 @@ pub(crate) fn builtin_reduce(
             span,
         );
     }
-    let func_val = evaluate_arg(interp, &args[0])?;
-    let iterable_val = evaluate_arg(interp, &args[1])?;
-    let mut items = to_iterable(interp, &iterable_val, span)?.into_iter();
+    let func_val = &args[0];
+    let mut items = iterate(interp, &args[1], span)?;
 
     let mut acc = if args.len() == 3 {
-        evaluate_arg(interp, &args[2])?
+        args[2].clone()
     } else {
         match items.next() {
-            Some(v) => v,
+            Some(v) => v.or_else(|msg| iteration_error(interp, msg, span))?,
             None => {
                 return interp.error(
                     EldritchErrorKind::TypeError,
@@ -37,7 +40,8 @@ pub(crate) fn builtin_reduce(
     };
 
     for item in items {
-        acc = call_value(interp, &func_val, &[acc, item], span)?;
+        let item = item.or_else(|msg| iteration_error(interp, msg, span))?;
+        acc = call_value(interp, func_val, &[acc, item], span)?;
     }
     Ok(acc)
 }
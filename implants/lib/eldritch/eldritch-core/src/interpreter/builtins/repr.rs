@@ -1,10 +1,11 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
 use spin::RwLock;
 
-pub fn builtin_repr(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_repr(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
         return Err(format!(
             "repr() takes exactly one argument ({} given)",
@@ -25,6 +26,6 @@ pub fn builtin_repr(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<V
 
     match &args[0] {
         Value::String(s) => Ok(Value::String(format!("{s:?}"))),
-        _ => Ok(Value::String(format!("{:?}", args[0]))),
+        val => Ok(Value::String(format!("{:?}", collect_lazy(env, val)?))),
     }
 }
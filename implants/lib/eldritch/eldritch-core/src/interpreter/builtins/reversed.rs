@@ -1,4 +1,6 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use alloc::format;
 use alloc::string::String;
@@ -12,7 +14,7 @@ use spin::RwLock;
 ///
 /// **Parameters**
 /// - `seq` (Sequence): The sequence to reverse (List, Tuple, String).
-pub fn builtin_reversed(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_reversed(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
         return Err(format!(
             "reversed() takes exactly one argument ({} given)",
@@ -23,6 +25,7 @@ pub fn builtin_reversed(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Resu
     let items = match &args[0] {
         Value::List(l) => l.read().clone(),
         Value::Tuple(t) => t.clone(),
+        Value::Iterable(it) => it.items(sequence_limit(env))?,
         Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
         // Dictionary and Set are not reversible in Python (TypeError)
         _ => {
@@ -37,5 +40,5 @@ pub fn builtin_reversed(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Resu
     rev_items.reverse();
 
     // Python reversed() returns an iterator. Here we return a List (per prompt: "returns a new list").
-    Ok(Value::List(Arc::new(RwLock::new(rev_items))))
+    Ok(Value::List(Arc::new(Freezable::new(rev_items))))
 }
@@ -1,4 +1,6 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use alloc::collections::BTreeSet;
 use alloc::format;
@@ -12,9 +14,9 @@ use spin::RwLock;
 ///
 /// If no argument is given, the constructor creates a new empty set.
 /// The argument must be an iterable if specified.
-pub fn builtin_set(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_set(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
-        return Ok(Value::Set(Arc::new(RwLock::new(BTreeSet::new()))));
+        return Ok(Value::Set(Arc::new(Freezable::new(BTreeSet::new()))));
     }
     if args.len() != 1 {
         return Err(format!(
@@ -26,6 +28,7 @@ pub fn builtin_set(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
     let items = match &args[0] {
         Value::List(l) => l.read().clone(),
         Value::Tuple(t) => t.clone(),
+        Value::Iterable(it) => it.items(sequence_limit(env))?,
         Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
         Value::Set(s) => s.read().iter().cloned().collect(),
         Value::Dictionary(d) => d.read().keys().cloned().collect(),
@@ -46,5 +49,5 @@ pub fn builtin_set(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
         set.insert(item);
     }
 
-    Ok(Value::Set(Arc::new(RwLock::new(set))))
+    Ok(Value::Set(Arc::new(Freezable::new(set))))
 }
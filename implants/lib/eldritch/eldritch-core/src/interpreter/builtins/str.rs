@@ -1,3 +1,4 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
 use alloc::format;
 use alloc::string::{String, ToString};
@@ -8,7 +9,7 @@ use spin::RwLock;
 ///
 /// **Parameters**
 /// - `object` (Any): The object to convert.
-pub fn builtin_str(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_str(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Ok(Value::String(String::new()));
     }
@@ -18,6 +19,6 @@ pub fn builtin_str(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Va
             Err(_) => Ok(Value::String(format!("{b:?}"))), // Fallback
         }
     } else {
-        Ok(Value::String(args[0].to_string()))
+        Ok(Value::String(collect_lazy(env, &args[0])?.to_string()))
     }
 }
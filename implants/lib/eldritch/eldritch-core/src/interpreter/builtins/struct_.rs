@@ -0,0 +1,24 @@
+use crate::ast::{Environment, Value};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use spin::RwLock;
+
+/// `struct(**kwargs)`: Creates an immutable record whose fields are the keyword arguments.
+///
+/// **Parameters**
+/// - `**kwargs` (Any): The fields of the struct.
+pub fn builtin_struct(
+    _env: &Arc<RwLock<Environment>>,
+    args: &[Value],
+    kwargs: &BTreeMap<String, Value>,
+) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!(
+            "struct() takes no positional arguments ({} given)",
+            args.len()
+        ));
+    }
+    Ok(Value::Struct(Arc::new(kwargs.clone())))
+}
@@ -1,3 +1,4 @@
+use super::collect_lazy;
 use crate::ast::{Environment, Value};
 use crate::token::Span;
 use alloc::collections::{BTreeMap, BTreeSet};
@@ -13,7 +14,7 @@ pub fn builtin_tprint(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
         return Err("tprint() takes at least 1 argument".to_string());
     }
 
-    let output = format_tprint(&args[0])?;
+    let output = format_tprint(&collect_lazy(env, &args[0])?)?;
 
     if let Some(text) = output {
         env.read().printer.print_out(&Span::new(0, 0, 0), &text);
@@ -28,6 +29,7 @@ pub fn builtin_tprint(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
 pub fn format_tprint(list_val: &Value) -> Result<Option<String>, String> {
     let items_snapshot: Vec<Value> = match list_val {
         Value::List(l) => l.read().clone(),
+        Value::Iterable(it) => it.items(None)?,
         _ => return Err("tprint() argument must be a list of dictionaries".to_string()),
     };
 
@@ -1,3 +1,4 @@
+use super::sequence_limit;
 use crate::ast::{Environment, Value};
 use crate::interpreter::introspection::get_type_name;
 use alloc::format;
@@ -11,7 +12,7 @@ use spin::RwLock;
 ///
 /// If no argument is given, the constructor creates a new empty tuple.
 /// The argument must be an iterable if specified.
-pub fn builtin_tuple(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
+pub fn builtin_tuple(env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Ok(Value::Tuple(Vec::new()));
     }
@@ -25,6 +26,7 @@ pub fn builtin_tuple(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<
     let items = match &args[0] {
         Value::List(l) => l.read().clone(),
         Value::Tuple(t) => t.clone(),
+        Value::Iterable(it) => it.items(sequence_limit(env))?,
         Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
         Value::Set(s) => s.read().iter().cloned().collect(),
         Value::Dictionary(d) => d.read().keys().cloned().collect(),
@@ -1,47 +1,75 @@
-use crate::ast::{Environment, Value};
+use crate::ast::{Environment, LazyIterable, Value, ValueIter};
 use crate::interpreter::introspection::get_type_name;
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::String;
-use alloc::string::ToString;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use spin::RwLock;
 
 /// `zip(*iterables)`: Returns an iterator of tuples.
 ///
-/// Returns a list of tuples, where the i-th tuple contains the i-th element from each of the argument sequences or iterables.
-/// The returned list is truncated to the length of the shortest argument sequence.
+/// Lazily yields tuples, where the i-th tuple contains the i-th element from each of the argument sequences or iterables.
+/// The result is truncated to the length of the shortest argument sequence.
 ///
 /// **Parameters**
 /// - `*iterables` (Iterable): Iterables to zip together.
 pub fn builtin_zip(_env: &Arc<RwLock<Environment>>, args: &[Value]) -> Result<Value, String> {
-    if args.is_empty() {
-        return Ok(Value::List(Arc::new(RwLock::new(Vec::new()))));
-    }
-
-    let mut iterators: Vec<Vec<Value>> = Vec::new();
     for arg in args {
-        let items = match arg {
-            Value::List(l) => l.read().clone(),
-            Value::Tuple(t) => t.clone(),
-            Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
-            Value::Set(s) => s.read().iter().cloned().collect(),
-            Value::Dictionary(d) => d.read().keys().cloned().collect(),
-            _ => return Err(format!("'{}' object is not iterable", get_type_name(arg))),
-        };
-        iterators.push(items);
+        if arg.try_iter().is_none() {
+            return Err(format!("'{}' object is not iterable", get_type_name(arg)));
+        }
     }
+    Ok(Value::Iterable(Arc::new(Zip {
+        sources: args.to_vec(),
+    })))
+}
 
-    let min_len = iterators.iter().map(|v| v.len()).min().unwrap_or(0);
-    let mut result = Vec::with_capacity(min_len);
+#[derive(Debug)]
+struct Zip {
+    sources: Vec<Value>,
+}
 
-    for i in 0..min_len {
-        let mut tuple_items = Vec::with_capacity(iterators.len());
-        for iter in &iterators {
-            tuple_items.push(iter[i].clone());
+impl LazyIterable for Zip {
+    // Reported as the list zip() returned before it became lazy.
+    fn type_name(&self) -> &str {
+        "list"
+    }
+
+    fn iter(&self) -> ValueIter {
+        let mut iters: Vec<ValueIter> = self.sources.iter().filter_map(Value::try_iter).collect();
+        if iters.is_empty() {
+            return Box::new(core::iter::empty());
         }
-        result.push(Value::Tuple(tuple_items));
+        Box::new(core::iter::from_fn(move || {
+            let mut items = Vec::with_capacity(iters.len());
+            for iter in iters.iter_mut() {
+                match iter.next()? {
+                    Ok(item) => items.push(item),
+                    Err(msg) => return Some(Err(msg)),
+                }
+            }
+            Some(Ok(Value::Tuple(items)))
+        }))
+    }
+
+    fn known_len(&self) -> Option<usize> {
+        self.sources
+            .iter()
+            .map(Value::known_len)
+            .try_fold(usize::MAX, |min, len| Some(min.min(len?)))
+            .map(|len| if self.sources.is_empty() { 0 } else { len })
     }
 
-    Ok(Value::List(Arc::new(RwLock::new(result))))
+    fn get(&self, index: usize) -> Option<Result<Value, String>> {
+        if self.sources.is_empty() {
+            return None;
+        }
+        let items = self
+            .sources
+            .iter()
+            .map(|source| source.item_at(index))
+            .collect::<Option<Result<Vec<_>, _>>>()?;
+        Some(items.map(Value::Tuple))
+    }
 }
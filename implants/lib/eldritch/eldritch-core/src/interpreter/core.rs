@@ -2,6 +2,8 @@ use super::super::ast::{BuiltinFn, Environment, Value};
 use super::super::lexer::Lexer;
 use super::super::parser::Parser;
 use super::super::token::{Span, TokenKind};
+use super::Freezable;
+use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
 use alloc::string::{String, ToString};
@@ -10,13 +12,20 @@ use alloc::vec;
 use alloc::vec::Vec;
 use spin::RwLock;
 
+use super::builtins::load_builtin::ModuleCache;
 use super::builtins::{get_all_builtins, get_all_builtins_with_kwargs, get_stubs};
+use super::coverage::CoverageRecorder;
+use super::debug::DebugHook;
+use super::diagnostic::ScriptError;
 use super::error::{EldritchError, EldritchErrorKind, StackFrame};
-use super::eval;
-use super::exec;
+use super::eval::utils::materialize;
 use super::introspection::find_best_match;
+use super::limits::{Budget, CancellationToken, ExecutionLimits};
 use super::methods::get_native_methods;
 use super::printer::{Printer, StdoutPrinter};
+use super::profiler::{Profile, ProfileClock, Profiler};
+#[cfg(not(feature = "bytecode"))]
+use super::{eval, exec};
 use crate::ast::ForeignValue;
 
 #[derive(Clone, PartialEq)]
@@ -34,6 +43,11 @@ pub struct Interpreter {
     pub call_stack: Vec<StackFrame>,
     pub current_func_name: String,
     pub is_scope_owner: bool,
+    pub(crate) budget: Budget,
+    pub(crate) modules: Arc<RwLock<ModuleCache>>,
+    pub(crate) profiler: Option<Box<Profiler>>,
+    pub(crate) debug_hook: Option<Arc<dyn DebugHook>>,
+    pub(crate) coverage: Option<CoverageRecorder>,
 }
 
 impl Drop for Interpreter {
@@ -43,6 +57,10 @@ impl Drop for Interpreter {
             // This drops all variables including functions, which may hold references back to the environment.
             self.env.write().values.clear();
             self.env.write().parent = None;
+            // Modules loaded by this interpreter are kept alive by the cache alone
+            for module in self.modules.read().loaded.values() {
+                module.write().values.clear();
+            }
         }
     }
 }
@@ -64,6 +82,7 @@ impl Interpreter {
             values: BTreeMap::new(),
             printer,
             libraries: BTreeSet::new(),
+            max_sequence_len: None,
         }));
 
         let mut interpreter = Interpreter {
@@ -73,6 +92,11 @@ impl Interpreter {
             call_stack: Vec::new(),
             current_func_name: "<module>".to_string(),
             is_scope_owner: true,
+            budget: Budget::default(),
+            modules: Arc::new(RwLock::new(ModuleCache::default())),
+            profiler: None,
+            debug_hook: None,
+            coverage: None,
         };
 
         interpreter.load_builtins();
@@ -106,9 +130,12 @@ impl Interpreter {
         );
     }
 
+    /// Registers a host-provided value under `name`. The value is frozen, so tomes can
+    /// read it but not modify it.
     pub fn register_module(&mut self, name: &str, module: Value) {
         // Ensure the value is actually a dictionary or structurally appropriate for a module
         // We accept any Value, but practically it should be a Dictionary of functions
+        module.freeze();
         self.env.write().values.insert(name.to_string(), module);
     }
 
@@ -121,6 +148,110 @@ impl Interpreter {
             .insert(name, Value::Foreign(Arc::new(val)));
     }
 
+    /// Returns a handle that can be used to cancel this interpreter from another thread.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.budget.token.clone()
+    }
+
+    /// Replace the cancellation handle, e.g. with one owned by a task registry.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.budget.token = token;
+    }
+
+    /// Set the step and wall-clock budgets enforced for each `interpret` call.
+    pub fn set_limits(&mut self, limits: ExecutionLimits) {
+        self.budget.limits = limits;
+    }
+
+    pub fn limits(&self) -> ExecutionLimits {
+        self.budget.limits
+    }
+
+    /// Records where the time goes in every following run, using the system's monotonic
+    /// clock. Profiling slows scripts down, so it is off unless enabled.
+    #[cfg(feature = "std")]
+    pub fn with_profiler(self) -> Self {
+        self.with_profiler_clock(super::profiler::std_clock)
+    }
+
+    /// Like [`Interpreter::with_profiler`], but timed with a host-provided clock.
+    pub fn with_profiler_clock(mut self, clock: ProfileClock) -> Self {
+        self.profiler = Some(Box::new(Profiler::new(clock)));
+        self
+    }
+
+    /// Returns what the profiler recorded since profiling was enabled or the profile was last
+    /// taken, or `None` if profiling is off.
+    pub fn take_profile(&mut self) -> Option<Profile> {
+        self.profiler.as_deref_mut().map(Profiler::take)
+    }
+
+    /// Lets a debugger pause every following run before each line; see [`DebugHook`].
+    pub fn set_debug_hook(&mut self, hook: Arc<dyn DebugHook>) {
+        self.debug_hook = Some(hook);
+    }
+
+    /// Counts the statements of every following run in `recorder`; see
+    /// [`CoverageRecorder::report`].
+    pub fn set_coverage(&mut self, recorder: CoverageRecorder) {
+        self.coverage = Some(recorder);
+    }
+
+    /// Runs `code` inside `env`, such as a scope of a script paused by a [`DebugHook`].
+    /// Like `eval()`, the code shares this interpreter's modules and budget, and can
+    /// assign variables in `env`.
+    pub fn evaluate_in(
+        &mut self,
+        env: Arc<RwLock<Environment>>,
+        code: &str,
+    ) -> Result<Value, String> {
+        let mut nested = self.nested(env, "<debug>");
+        let result = nested.interpret(code);
+        self.budget.steps = nested.budget.steps;
+        result
+    }
+
+    // An interpreter for code run inside `env` on behalf of this one, e.g. by `eval()`.
+    // Nested runs aren't profiled, debugged or covered on their own.
+    pub(crate) fn nested(&self, env: Arc<RwLock<Environment>>, name: &str) -> Interpreter {
+        Interpreter {
+            env,
+            flow: Flow::Next,
+            depth: self.depth + 1,
+            call_stack: self.call_stack.clone(),
+            current_func_name: name.to_string(),
+            is_scope_owner: false,
+            budget: self.budget.clone(),
+            modules: self.modules.clone(),
+            profiler: None,
+            debug_hook: None,
+            coverage: None,
+        }
+    }
+
+    /// The checkpoint before each statement, which also records it for coverage.
+    pub(crate) fn enter_statement(&mut self, span: Span) -> Result<(), EldritchError> {
+        if let Some(coverage) = &self.coverage {
+            coverage.hit(span);
+        }
+        self.check_budget(span)
+    }
+
+    /// Cooperative checkpoint: counts a step and aborts if the interpreter was
+    /// cancelled or a budget has been exhausted.
+    pub(crate) fn check_budget(&mut self, span: Span) -> Result<(), EldritchError> {
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.line(span.line);
+        }
+        if let Some(hook) = self.debug_hook.clone() {
+            hook.on_line(self, span.line);
+        }
+        match self.budget.tick() {
+            Some((kind, msg)) => self.error(kind, &msg, span),
+            None => Ok(()),
+        }
+    }
+
     // Helper to create errors from interpreter context
     pub fn error<T>(
         &self,
@@ -164,9 +295,22 @@ impl Interpreter {
             line: span.line,
         });
         self.current_func_name = name.to_string();
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.enter(name);
+        }
+    }
+
+    /// Attributes the call entered by the last [`Interpreter::push_frame`] to a library.
+    pub(crate) fn mark_native_frame(&mut self, library: &str) {
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.mark_native(library);
+        }
     }
 
     pub fn pop_frame(&mut self) {
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.exit();
+        }
         if let Some(frame) = self.call_stack.pop() {
             self.current_func_name = frame.name;
         } else {
@@ -176,13 +320,21 @@ impl Interpreter {
     }
 
     pub fn interpret(&mut self, input: &str) -> Result<Value, String> {
+        self.run(input).map_err(|e| e.render())
+    }
+
+    /// Like [`Interpreter::interpret`], but reports failures as a [`ScriptError`] with the
+    /// error kind, traceback and source snippets instead of pre-rendered text.
+    #[allow(clippy::result_large_err)]
+    pub fn run(&mut self, input: &str) -> Result<Value, ScriptError> {
         let mut lexer = Lexer::new(input.to_string());
         let tokens = lexer.scan_tokens();
 
         // Check for lexer errors first to maintain behavior
         for token in &tokens {
             if let TokenKind::Error(msg) = &token.kind {
-                return Err(format!("Lexer Error: {}", msg));
+                let err = EldritchError::new(EldritchErrorKind::SyntaxError, msg, token.span);
+                return Err(ScriptError::from_error(input, err));
             }
         }
 
@@ -193,111 +345,89 @@ impl Interpreter {
             // If we have parsing errors, we return the first one formatted
             // Or maybe a combined list? Usually first is enough to abort execution.
             // The prompt says "interpreter does not attempt to evaluate / execute statements from ASTs with error tokens"
-            return Err(self.format_error(input, errors[0].clone()));
+            return Err(ScriptError::from_error(input, errors[0].clone()));
         }
 
-        let mut last_val = Value::None;
-
         // Reset state for fresh run
         self.call_stack.clear();
         self.current_func_name = "<module>".to_string();
+        self.env.write().max_sequence_len = self.budget.limits.max_sequence_len;
+        // Nested `eval` runs share the budget of the outermost run
+        if self.depth == 0 {
+            self.budget.start();
+            if let Some(profiler) = self.profiler.as_deref_mut() {
+                profiler.start();
+            }
+        }
 
-        if let Err(e) = exec::hoist_functions(self, &stmts) {
-            return Err(self.format_error(input, e));
+        #[cfg(not(feature = "bytecode"))]
+        let result = self.execute_module(&stmts);
+        #[cfg(feature = "bytecode")]
+        let result = super::vm::execute_module(self, &stmts);
+
+        if self.depth == 0
+            && let Some(profiler) = self.profiler.as_deref_mut()
+        {
+            profiler.finish();
+        }
+
+        match result.map_err(|e| ScriptError::from_error(input, e))? {
+            // Hosts receive the list a lazy result stands for
+            Value::Iterable(it) => match materialize(self, &it, Span::new(0, 0, 0)) {
+                Ok(items) => Ok(Value::List(Arc::new(Freezable::new(items)))),
+                Err(err) => Err(ScriptError::from_error(input, err)),
+            },
+            value => Ok(value),
         }
+    }
+
+    #[cfg(not(feature = "bytecode"))]
+    fn execute_module(
+        &mut self,
+        stmts: &[super::super::ast::Stmt],
+    ) -> Result<Value, EldritchError> {
+        exec::hoist_functions(self, stmts)?;
 
+        let mut last_val = Value::None;
         for stmt in stmts {
             match &stmt.kind {
                 // Special case: if top-level statement is an expression, return its value
                 // This matches behavior of typical REPLs / starlark-like exec
                 super::super::ast::StmtKind::Expression(expr) => {
-                    let res = eval::evaluate(self, expr);
-                    match res {
-                        Ok(v) => last_val = v,
-                        Err(e) => {
-                            return Err(self.format_error(input, e));
-                        }
-                    }
+                    self.enter_statement(stmt.span)?;
+                    last_val = eval::evaluate(self, expr)?;
                 }
                 _ => {
-                    let res = exec::execute(self, &stmt);
-                    match res {
-                        Ok(_) => {
-                            if let Flow::Return(v) = &self.flow {
-                                let ret = v.clone();
-                                self.flow = Flow::Next;
-                                return Ok(ret);
-                            }
-                            last_val = Value::None;
-                        }
-                        Err(e) => {
-                            return Err(self.format_error(input, e));
-                        }
+                    exec::execute(self, stmt)?;
+                    if let Flow::Return(v) = &self.flow {
+                        let ret = v.clone();
+                        self.flow = Flow::Next;
+                        return Ok(ret);
                     }
+                    last_val = Value::None;
                 }
             }
         }
         Ok(last_val)
     }
 
-    pub(crate) fn format_error(&self, source: &str, error: EldritchError) -> String {
-        // combine formatted traceback with source snippet
-        let mut output = error.to_string();
-
-        let lines: Vec<&str> = source.lines().collect();
-        if error.span.line > 0 && error.span.line <= lines.len() {
-            let line_idx = error.span.line - 1;
-            let line_content = lines[line_idx];
-
-            // Calculate column offset relative to trimmed line
-            let leading_whitespace = line_content.len() - line_content.trim_start().len();
-
-            // Calculate line start byte offset
-            let mut line_start = error.span.start;
-            let source_bytes = source.as_bytes();
-            // Walk backwards from error start to find newline
-            // If error.span.start is beyond source len (shouldn't happen for valid error), clamp it.
-            if line_start > source_bytes.len() {
-                line_start = source_bytes.len();
-            }
-            while line_start > 0 && source_bytes[line_start - 1] != b'\n' {
-                line_start -= 1;
-            }
-
-            // Calculate raw column (byte offset from start of line)
-            let raw_col = error.span.start.saturating_sub(line_start);
-
-            // Calculate display column relative to trimmed string
-            let display_col = raw_col.saturating_sub(leading_whitespace);
-
-            // Create dynamic padding
-            let padding = format!("{:>width$}", "", width = display_col);
-
-            output.push_str(&format!(
-                "\n\nError location:\n  at line {}:\n    {}\n    {}^-- here",
-                error.span.line,
-                line_content.trim(),
-                padding
-            ));
+    pub(crate) fn assign_variable(&mut self, name: &str, value: Value) {
+        if let Err(value) = self.update_variable(name, value) {
+            self.env.write().values.insert(name.to_string(), value);
         }
-        output
     }
 
-    pub(crate) fn assign_variable(&mut self, name: &str, value: Value) {
+    /// Update the nearest existing binding of `name`, handing the value back if there is none.
+    pub(crate) fn update_variable(&self, name: &str, value: Value) -> Result<(), Value> {
         let mut env_opt = Some(self.env.clone());
-        let mut target_env = None;
         while let Some(env) = env_opt {
             if env.read().values.contains_key(name) {
-                target_env = Some(env.clone());
-                break;
+                env.write().values.insert(name.to_string(), value);
+                return Ok(());
             }
             env_opt = env.read().parent.clone();
         }
-        if let Some(env) = target_env {
-            env.write().values.insert(name.to_string(), value);
-        } else {
-            self.env.write().values.insert(name.to_string(), value);
-        }
+        Err(value)
     }
 
     pub fn define_variable(&mut self, name: &str, value: Value) {
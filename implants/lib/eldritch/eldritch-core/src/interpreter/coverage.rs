@@ -0,0 +1,174 @@
+use crate::ast::{Stmt, StmtKind};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::Span;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use spin::Mutex;
+
+/// A cloneable handle that counts how often each statement of a script runs, keyed by the
+/// statement's span. Interpreters that run the same source can share one to combine their
+/// counts, e.g. across every test of a tome.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageRecorder {
+    hits: Arc<Mutex<BTreeMap<(usize, usize), u64>>>,
+}
+
+/// Which lines, `if` branches and functions of a script ran, and how often.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Runs of each line that starts a statement. Lines that never ran are included with
+    /// a count of zero.
+    pub lines: BTreeMap<usize, u64>,
+    /// The branches of every `if` and `elif`, in source order.
+    pub branches: Vec<BranchCoverage>,
+    /// Every `def`, in source order.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+/// One side of an `if` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCoverage {
+    pub line: usize,
+    /// Numbers the `if` statements of the script, starting from 0.
+    pub block: usize,
+    /// 0 for the body of the `if`, 1 for its `else`, which is taken when the condition is
+    /// false even if no `else` is written.
+    pub branch: usize,
+    /// How often the branch was taken, or `None` if the `if` never ran.
+    pub taken: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCoverage {
+    pub name: String,
+    pub line: usize,
+    pub calls: u64,
+}
+
+impl CoverageRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn hit(&self, span: Span) {
+        *self.hits.lock().entry((span.start, span.end)).or_default() += 1;
+    }
+
+    fn count(&self, stmt: &Stmt) -> u64 {
+        let hits = self.hits.lock();
+        hits.get(&(stmt.span.start, stmt.span.end))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Matches the counts recorded so far against the statements of `source`, which must be
+    /// the script the recording interpreters ran.
+    pub fn report(&self, source: &str) -> CoverageReport {
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (stmts, _errors) = Parser::new(tokens).parse();
+        let mut report = CoverageReport::default();
+        self.visit(&stmts, &mut report);
+        report
+    }
+
+    fn visit(&self, stmts: &[Stmt], report: &mut CoverageReport) {
+        for stmt in stmts {
+            let count = self.count(stmt);
+            let line = report.lines.entry(stmt.span.line).or_default();
+            *line = (*line).max(count);
+
+            match &stmt.kind {
+                StmtKind::If(_, then_branch, else_branch) => {
+                    // Each block runs its first statement exactly once per entry
+                    let then_count = then_branch.first().map_or(0, |s| self.count(s));
+                    let else_count = match else_branch.as_ref().and_then(|b| b.first()) {
+                        Some(first) => self.count(first),
+                        None => count.saturating_sub(then_count),
+                    };
+                    let block = report.branches.len() / 2;
+                    for (branch, taken) in [then_count, else_count].into_iter().enumerate() {
+                        report.branches.push(BranchCoverage {
+                            line: stmt.span.line,
+                            block,
+                            branch,
+                            taken: (count > 0).then_some(taken),
+                        });
+                    }
+                    self.visit(then_branch, report);
+                    if let Some(else_branch) = else_branch {
+                        self.visit(else_branch, report);
+                    }
+                }
+                StmtKind::Def(name, _, _, body) => {
+                    report.functions.push(FunctionCoverage {
+                        name: name.clone(),
+                        line: stmt.span.line,
+                        calls: body.first().map_or(0, |s| self.count(s)),
+                    });
+                    self.visit(body, report);
+                }
+                StmtKind::For(_, _, body) => self.visit(body, report),
+                StmtKind::While(_, body, else_branch) => {
+                    self.visit(body, report);
+                    if let Some(else_branch) = else_branch {
+                        self.visit(else_branch, report);
+                    }
+                }
+                StmtKind::Try(body, handlers, finally) => {
+                    self.visit(body, report);
+                    for handler in handlers {
+                        self.visit(&handler.body, report);
+                    }
+                    if let Some(finally) = finally {
+                        self.visit(finally, report);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Renders the report as an lcov tracefile record for the script at `path`, as read by
+    /// `genhtml` and editor coverage extensions.
+    pub fn lcov(&self, path: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "TN:");
+        let _ = writeln!(out, "SF:{path}");
+        for function in &self.functions {
+            let _ = writeln!(out, "FN:{},{}", function.line, function.name);
+        }
+        for function in &self.functions {
+            let _ = writeln!(out, "FNDA:{},{}", function.calls, function.name);
+        }
+        let _ = writeln!(out, "FNF:{}", self.functions.len());
+        let hit = self.functions.iter().filter(|f| f.calls > 0).count();
+        let _ = writeln!(out, "FNH:{hit}");
+
+        for b in &self.branches {
+            let taken = b.taken.map_or("-".to_string(), |n| n.to_string());
+            let _ = writeln!(out, "BRDA:{},{},{},{}", b.line, b.block, b.branch, taken);
+        }
+        let _ = writeln!(out, "BRF:{}", self.branches.len());
+        let hit = self
+            .branches
+            .iter()
+            .filter(|b| b.taken.is_some_and(|n| n > 0))
+            .count();
+        let _ = writeln!(out, "BRH:{hit}");
+
+        for (line, count) in &self.lines {
+            let _ = writeln!(out, "DA:{line},{count}");
+        }
+        let _ = writeln!(out, "LF:{}", self.lines.len());
+        let hit = self.lines.values().filter(|&&n| n > 0).count();
+        let _ = writeln!(out, "LH:{hit}");
+        let _ = writeln!(out, "end_of_record");
+        out
+    }
+}
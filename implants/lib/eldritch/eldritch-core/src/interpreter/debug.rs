@@ -0,0 +1,13 @@
+use super::core::Interpreter;
+
+/// Lets a debugger pause a running script. Once set with
+/// [`Interpreter::set_debug_hook`], the hook is called before every statement, loop
+/// iteration and call, with the line about to run. The script stays paused for as long as
+/// the hook blocks, during which the hook can inspect `interp.call_stack` and
+/// `interp.env`, or run code with [`Interpreter::evaluate_in`].
+///
+/// To stop the script instead of resuming it, cancel its
+/// [`CancellationToken`](super::CancellationToken) before returning.
+pub trait DebugHook: Send + Sync {
+    fn on_line(&self, interp: &mut Interpreter, line: usize);
+}
@@ -0,0 +1,154 @@
+use super::error::{EldritchError, EldritchErrorKind};
+use crate::token::Span;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A script failure returned by [`Interpreter::run`](super::Interpreter::run), with enough
+/// context for hosts to render or forward it without parsing text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub kind: EldritchErrorKind,
+    /// The stable identifier of `kind`; see [`EldritchErrorKind::code`].
+    pub code: &'static str,
+    pub message: String,
+    /// Where the error was raised.
+    pub span: Span,
+    /// The source line `span` points at.
+    pub snippet: Option<SourceSnippet>,
+    /// The calls that led to the error, outermost first. The last frame is the function
+    /// that raised it.
+    pub traceback: Vec<TracebackFrame>,
+    /// The library whose method raised the error, e.g. `"file"`.
+    pub library: Option<String>,
+    /// Advice for common mistakes, such as a misspelled variable.
+    pub hint: Option<String>,
+}
+
+/// One call in a [`ScriptError`] traceback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracebackFrame {
+    /// The function that was running, or `<module>` at the top level.
+    pub function: String,
+    pub filename: String,
+    pub line: usize,
+    /// The trimmed source of `line`, when it is known.
+    pub source: Option<String>,
+}
+
+/// A line of source code and the column an error points at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSnippet {
+    pub line: usize,
+    /// The line with leading and trailing whitespace removed.
+    pub text: String,
+    /// The offset of the error within `text`.
+    pub column: usize,
+}
+
+impl ScriptError {
+    /// Attaches the source of `input` to an error raised while running it.
+    pub(crate) fn from_error(input: &str, error: EldritchError) -> Self {
+        let lines: Vec<&str> = input.lines().collect();
+        let source_of = |line: usize| {
+            line.checked_sub(1)
+                .and_then(|idx| lines.get(idx))
+                .map(|text| text.trim().to_string())
+        };
+
+        let traceback = error
+            .stack
+            .iter()
+            .map(|frame| TracebackFrame {
+                function: frame.name.clone(),
+                filename: frame.filename.clone(),
+                line: frame.line,
+                source: source_of(frame.line),
+            })
+            .collect();
+
+        ScriptError {
+            hint: error.hint(),
+            snippet: snippet(input, &lines, error.span),
+            code: error.kind.code(),
+            kind: error.kind,
+            message: error.message,
+            span: error.span,
+            traceback,
+            library: error.library,
+        }
+    }
+
+    /// Names the script in the traceback, e.g. with the path of the tome that failed.
+    pub fn with_filename(mut self, filename: &str) -> Self {
+        for frame in &mut self.traceback {
+            if frame.filename == "<script>" {
+                frame.filename = filename.to_string();
+            }
+        }
+        self
+    }
+
+    /// Renders the error as a traceback followed by the offending line with a caret under
+    /// the error, as shown by golem and reported to Tavern.
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+// The line containing `span`, with the caret column relative to the trimmed text.
+fn snippet(input: &str, lines: &[&str], span: Span) -> Option<SourceSnippet> {
+    let content = span.line.checked_sub(1).and_then(|idx| lines.get(idx))?;
+    let leading_whitespace = content.len() - content.trim_start().len();
+
+    // Walk back from the error to the start of its line
+    let bytes = input.as_bytes();
+    let mut line_start = span.start.min(bytes.len());
+    while line_start > 0 && bytes[line_start - 1] != b'\n' {
+        line_start -= 1;
+    }
+    let raw_col = span.start.saturating_sub(line_start);
+
+    Some(SourceSnippet {
+        line: span.line,
+        text: content.trim().to_string(),
+        column: raw_col.saturating_sub(leading_whitespace),
+    })
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.traceback.is_empty() {
+            writeln!(f, "Traceback (most recent call last):")?;
+            for frame in &self.traceback {
+                writeln!(
+                    f,
+                    "  File \"{}\", line {}, in {}",
+                    frame.filename, frame.line, frame.function
+                )?;
+                if let Some(source) = frame.source.as_deref().filter(|s| !s.is_empty()) {
+                    writeln!(f, "    {}", source)?;
+                }
+            }
+        }
+
+        write!(f, "{}: {}", self.kind, self.message)?;
+        if let Some(library) = &self.library {
+            write!(f, "\n(raised by the '{}' library)", library)?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\n{}", hint)?;
+        }
+
+        if let Some(snippet) = &self.snippet {
+            let padding = format!("{:>width$}", "", width = snippet.column);
+            write!(
+                f,
+                "\n\nError location:\n  at line {}:\n    {}\n    {}^-- here",
+                snippet.line, snippet.text, padding
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use super::super::token::Span;
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
@@ -18,6 +19,11 @@ pub enum EldritchErrorKind {
     ZeroDivisionError,
     ImportError,
     AssertionError,
+    CancelledError,
+    StepLimitError,
+    TimeoutError,
+    LoopLimitError,
+    MemoryLimitError,
 }
 
 impl fmt::Display for EldritchErrorKind {
@@ -35,10 +41,80 @@ impl fmt::Display for EldritchErrorKind {
             EldritchErrorKind::ZeroDivisionError => write!(f, "ZeroDivisionError"),
             EldritchErrorKind::ImportError => write!(f, "ImportError"),
             EldritchErrorKind::AssertionError => write!(f, "AssertionError"),
+            EldritchErrorKind::CancelledError => write!(f, "CancelledError"),
+            EldritchErrorKind::StepLimitError => write!(f, "StepLimitError"),
+            EldritchErrorKind::TimeoutError => write!(f, "TimeoutError"),
+            EldritchErrorKind::LoopLimitError => write!(f, "LoopLimitError"),
+            EldritchErrorKind::MemoryLimitError => write!(f, "MemoryLimitError"),
         }
     }
 }
 
+impl EldritchErrorKind {
+    /// Look up an error kind by the name shown in tracebacks (e.g. `"KeyError"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SyntaxError" => Some(EldritchErrorKind::SyntaxError),
+            "TypeError" => Some(EldritchErrorKind::TypeError),
+            "NameError" => Some(EldritchErrorKind::NameError),
+            "IndexError" => Some(EldritchErrorKind::IndexError),
+            "KeyError" => Some(EldritchErrorKind::KeyError),
+            "AttributeError" => Some(EldritchErrorKind::AttributeError),
+            "ValueError" => Some(EldritchErrorKind::ValueError),
+            "RuntimeError" => Some(EldritchErrorKind::RuntimeError),
+            "RecursionError" => Some(EldritchErrorKind::RecursionError),
+            "ZeroDivisionError" => Some(EldritchErrorKind::ZeroDivisionError),
+            "ImportError" => Some(EldritchErrorKind::ImportError),
+            "AssertionError" => Some(EldritchErrorKind::AssertionError),
+            "CancelledError" => Some(EldritchErrorKind::CancelledError),
+            "StepLimitError" => Some(EldritchErrorKind::StepLimitError),
+            "TimeoutError" => Some(EldritchErrorKind::TimeoutError),
+            "LoopLimitError" => Some(EldritchErrorKind::LoopLimitError),
+            "MemoryLimitError" => Some(EldritchErrorKind::MemoryLimitError),
+            _ => None,
+        }
+    }
+
+    /// A stable identifier for this kind, e.g. `"E0002"` for `TypeError`, for hosts that
+    /// match on errors. Codes are never reused or renumbered; new kinds take the next one.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EldritchErrorKind::SyntaxError => "E0001",
+            EldritchErrorKind::TypeError => "E0002",
+            EldritchErrorKind::NameError => "E0003",
+            EldritchErrorKind::IndexError => "E0004",
+            EldritchErrorKind::KeyError => "E0005",
+            EldritchErrorKind::AttributeError => "E0006",
+            EldritchErrorKind::ValueError => "E0007",
+            EldritchErrorKind::RuntimeError => "E0008",
+            EldritchErrorKind::RecursionError => "E0009",
+            EldritchErrorKind::ZeroDivisionError => "E0010",
+            EldritchErrorKind::ImportError => "E0011",
+            EldritchErrorKind::AssertionError => "E0012",
+            EldritchErrorKind::CancelledError => "E0013",
+            EldritchErrorKind::StepLimitError => "E0014",
+            EldritchErrorKind::TimeoutError => "E0015",
+            EldritchErrorKind::LoopLimitError => "E0016",
+            EldritchErrorKind::MemoryLimitError => "E0017",
+        }
+    }
+
+    /// Whether a `try`/`except` block may handle this error.
+    ///
+    /// Cancellation and execution budget errors always unwind to the host so a
+    /// script cannot swallow its own shutdown.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(
+            self,
+            EldritchErrorKind::CancelledError
+                | EldritchErrorKind::StepLimitError
+                | EldritchErrorKind::TimeoutError
+                | EldritchErrorKind::LoopLimitError
+                | EldritchErrorKind::MemoryLimitError
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StackFrame {
     pub name: String,
@@ -52,6 +128,8 @@ pub struct EldritchError {
     pub message: String,
     pub span: Span,
     pub stack: Vec<StackFrame>,
+    /// The library whose method raised the error, if any.
+    pub library: Option<String>,
 }
 
 impl EldritchError {
@@ -61,6 +139,7 @@ impl EldritchError {
             message: message.to_string(),
             span,
             stack: Vec::new(),
+            library: None,
         }
     }
 
@@ -68,6 +147,34 @@ impl EldritchError {
         self.stack = stack;
         self
     }
+
+    pub fn with_library(mut self, library: &str) -> Self {
+        self.library = Some(library.to_string());
+        self
+    }
+
+    /// Advice shown below the message for common mistakes.
+    pub(crate) fn hint(&self) -> Option<String> {
+        match self.kind {
+            EldritchErrorKind::NameError => self
+                .message
+                .strip_prefix("Undefined variable: '")
+                .and_then(|s| s.strip_suffix("'"))
+                .map(|var_name| format!("Did you mean to define '{}' or import it?", var_name)),
+            EldritchErrorKind::TypeError if self.message.contains("not iterable") => Some(
+                "Ensure you are iterating over a List, Tuple, Set, Dictionary, or String."
+                    .to_string(),
+            ),
+            EldritchErrorKind::TypeError if self.message.contains("not subscriptable") => Some(
+                "Ensure you are accessing an index on a List, Tuple, or Dictionary.".to_string(),
+            ),
+            EldritchErrorKind::KeyError => Some(
+                "The key does not exist in the dictionary. Use .get() to avoid this error."
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for EldritchError {
@@ -92,41 +199,9 @@ impl fmt::Display for EldritchError {
         // as we don't have source code access here easily.
 
         write!(f, "{}: {}", self.kind, self.message)?;
-
-        // Add helpful advice based on error kind
-        match self.kind {
-            EldritchErrorKind::NameError => {
-                if let Some(var_name) = self
-                    .message
-                    .strip_prefix("Undefined variable: '")
-                    .and_then(|s| s.strip_suffix("'"))
-                {
-                    // Simple heuristic for advice
-                    write!(f, "\nDid you mean to define '{}' or import it?", var_name)?;
-                }
-            }
-            EldritchErrorKind::TypeError => {
-                if self.message.contains("not iterable") {
-                    write!(
-                        f,
-                        "\nEnsure you are iterating over a List, Tuple, Set, Dictionary, or String."
-                    )?;
-                } else if self.message.contains("not subscriptable") {
-                    write!(
-                        f,
-                        "\nEnsure you are accessing an index on a List, Tuple, or Dictionary."
-                    )?;
-                }
-            }
-            EldritchErrorKind::KeyError => {
-                write!(
-                    f,
-                    "\nThe key does not exist in the dictionary. Use .get() to avoid this error."
-                )?;
-            }
-            _ => {}
+        if let Some(hint) = self.hint() {
+            write!(f, "\n{}", hint)?;
         }
-
         Ok(())
     }
 }
@@ -1,16 +1,17 @@
 use super::super::super::ast::{Expr, Value};
 use super::super::super::token::Span;
+use super::super::Freezable;
 use super::super::core::Interpreter;
 use super::super::error::{EldritchError, EldritchErrorKind};
 use super::super::introspection::get_type_name;
 use super::super::operations::adjust_slice_indices;
-use super::evaluate;
+use super::utils::check_collected_len;
+use super::{evaluate, iteration_error, materialize};
 use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::RwLock;
 
 pub(crate) fn evaluate_index(
     interp: &mut Interpreter,
@@ -20,8 +21,67 @@ pub(crate) fn evaluate_index(
 ) -> Result<Value, EldritchError> {
     let obj_val = evaluate(interp, obj)?;
     let idx_val = evaluate(interp, index)?;
+    index_value(interp, obj_val, idx_val, span, obj.span, index.span)
+}
 
+pub(crate) fn index_value(
+    interp: &mut Interpreter,
+    obj_val: Value,
+    idx_val: Value,
+    span: Span,
+    obj_span: Span,
+    index_span: Span,
+) -> Result<Value, EldritchError> {
+    // No sequence is long enough for an index past i64
+    if let Value::BigInt(i) = &idx_val
+        && matches!(
+            obj_val,
+            Value::Iterable(_)
+                | Value::List(_)
+                | Value::Tuple(_)
+                | Value::String(_)
+                | Value::Bytes(_)
+        )
+    {
+        return interp.error(
+            EldritchErrorKind::IndexError,
+            &format!("int {i} out of range for i64"),
+            index_span,
+        );
+    }
     match obj_val {
+        Value::Iterable(it) => {
+            let idx_int = match idx_val {
+                Value::Int(i) => i,
+                _ => {
+                    return interp.error(
+                        EldritchErrorKind::TypeError,
+                        "list indices must be integers",
+                        index_span,
+                    );
+                }
+            };
+            // Only a negative index of an iterable of unknown length needs every item
+            let true_idx = match (idx_int < 0, it.known_len()) {
+                (false, _) => idx_int,
+                (true, Some(len)) => len as i64 + idx_int,
+                (true, None) => {
+                    let items = materialize(interp, &it, obj_span)?;
+                    let list = Value::List(Arc::new(Freezable::new(items)));
+                    return index_value(interp, list, idx_val, span, obj_span, index_span);
+                }
+            };
+            let item = usize::try_from(true_idx).ok().and_then(|i| it.get(i));
+            match item {
+                Some(Ok(item)) => Ok(item),
+                Some(Err(msg)) => iteration_error(interp, msg, obj_span),
+                None => interp.error(
+                    EldritchErrorKind::IndexError,
+                    "List index out of range",
+                    span,
+                ),
+            }
+        }
         Value::List(l) => {
             let idx_int = match idx_val {
                 Value::Int(i) => i,
@@ -29,7 +89,7 @@ pub(crate) fn evaluate_index(
                     return interp.error(
                         EldritchErrorKind::TypeError,
                         "list indices must be integers",
-                        index.span,
+                        index_span,
                     );
                 }
             };
@@ -55,7 +115,7 @@ pub(crate) fn evaluate_index(
                     return interp.error(
                         EldritchErrorKind::TypeError,
                         "tuple indices must be integers",
-                        index.span,
+                        index_span,
                     );
                 }
             };
@@ -91,7 +151,7 @@ pub(crate) fn evaluate_index(
                     return interp.error(
                         EldritchErrorKind::TypeError,
                         "string indices must be integers",
-                        index.span,
+                        index_span,
                     );
                 }
             };
@@ -114,7 +174,7 @@ pub(crate) fn evaluate_index(
                     return interp.error(
                         EldritchErrorKind::TypeError,
                         "bytes indices must be integers",
-                        index.span,
+                        index_span,
                     );
                 }
             };
@@ -132,7 +192,7 @@ pub(crate) fn evaluate_index(
         _ => interp.error(
             EldritchErrorKind::TypeError,
             &format!("'{}' object is not subscriptable", get_type_name(&obj_val)),
-            obj.span,
+            obj_span,
         ),
     }
 }
@@ -147,19 +207,54 @@ pub(crate) fn evaluate_slice(
 ) -> Result<Value, EldritchError> {
     let obj_val = evaluate(interp, obj)?;
 
-    let step_val = if let Some(s) = step {
-        match evaluate(interp, s)? {
-            Value::Int(i) => i,
-            _ => {
-                return interp.error(
-                    EldritchErrorKind::TypeError,
-                    "slice step must be an integer",
-                    s.span,
-                );
-            }
+    let step_val = match step {
+        Some(s) => {
+            let val = evaluate(interp, s)?;
+            slice_step(interp, Some((val, s.span)), span)?
+        }
+        None => 1,
+    };
+    let start_val_opt = match start {
+        Some(s) => {
+            let val = evaluate(interp, s)?;
+            slice_bound(interp, Some((val, s.span)), "start")?
+        }
+        None => None,
+    };
+    let stop_val_opt = match stop {
+        Some(s) => {
+            let val = evaluate(interp, s)?;
+            slice_bound(interp, Some((val, s.span)), "stop")?
+        }
+        None => None,
+    };
+
+    slice_value(
+        interp,
+        obj_val,
+        start_val_opt,
+        stop_val_opt,
+        step_val,
+        obj.span,
+    )
+}
+
+/// Validate an optional slice step, defaulting to 1.
+pub(crate) fn slice_step(
+    interp: &Interpreter,
+    step: Option<(Value, Span)>,
+    span: Span,
+) -> Result<i64, EldritchError> {
+    let step_val = match step {
+        Some((Value::Int(i), _)) => i,
+        Some((_, s)) => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                "slice step must be an integer",
+                s,
+            );
         }
-    } else {
-        1
+        None => 1,
     };
 
     if step_val == 0 {
@@ -169,38 +264,68 @@ pub(crate) fn evaluate_slice(
             span,
         );
     }
+    Ok(step_val)
+}
 
-    let start_val_opt = if let Some(s) = start {
-        match evaluate(interp, s)? {
-            Value::Int(i) => Some(i),
-            _ => {
-                return interp.error(
-                    EldritchErrorKind::TypeError,
-                    "slice start must be an integer",
-                    s.span,
-                );
-            }
-        }
-    } else {
-        None
-    };
+/// Validate an optional slice `start` or `stop` bound.
+pub(crate) fn slice_bound(
+    interp: &Interpreter,
+    bound: Option<(Value, Span)>,
+    which: &str,
+) -> Result<Option<i64>, EldritchError> {
+    match bound {
+        Some((Value::Int(i), _)) => Ok(Some(i)),
+        Some((_, s)) => interp.error(
+            EldritchErrorKind::TypeError,
+            &format!("slice {which} must be an integer"),
+            s,
+        ),
+        None => Ok(None),
+    }
+}
 
-    let stop_val_opt = if let Some(s) = stop {
-        match evaluate(interp, s)? {
-            Value::Int(i) => Some(i),
-            _ => {
-                return interp.error(
-                    EldritchErrorKind::TypeError,
-                    "slice stop must be an integer",
-                    s.span,
-                );
+pub(crate) fn slice_value(
+    interp: &mut Interpreter,
+    obj_val: Value,
+    start_val_opt: Option<i64>,
+    stop_val_opt: Option<i64>,
+    step_val: i64,
+    obj_span: Span,
+) -> Result<Value, EldritchError> {
+    match obj_val {
+        // With a known length only the selected items are produced
+        Value::Iterable(it) if it.known_len().is_some() => {
+            let len = it.known_len().unwrap_or(0) as i64;
+            let (i, j) = adjust_slice_indices(len, &start_val_opt, &stop_val_opt, step_val);
+            let count = if step_val > 0 {
+                (j - i + step_val - 1) / step_val
+            } else {
+                (i - j - step_val - 1) / -step_val
+            }
+            .max(0) as usize;
+            check_collected_len(interp, count, obj_span)?;
+            let mut result = Vec::new();
+            for n in 0..count as i64 {
+                match it.get((i + n * step_val) as usize) {
+                    Some(Ok(item)) => result.push(item),
+                    Some(Err(msg)) => return iteration_error(interp, msg, obj_span),
+                    None => break,
+                }
             }
+            Ok(Value::List(Arc::new(Freezable::new(result))))
+        }
+        Value::Iterable(it) => {
+            let items = materialize(interp, &it, obj_span)?;
+            let list = Value::List(Arc::new(Freezable::new(items)));
+            slice_value(
+                interp,
+                list,
+                start_val_opt,
+                stop_val_opt,
+                step_val,
+                obj_span,
+            )
         }
-    } else {
-        None
-    };
-
-    match obj_val {
         Value::List(l) => {
             let list = l.read();
             let len = list.len() as i64;
@@ -224,7 +349,7 @@ pub(crate) fn evaluate_slice(
                     curr += step_val;
                 }
             }
-            Ok(Value::List(Arc::new(RwLock::new(result))))
+            Ok(Value::List(Arc::new(Freezable::new(result))))
         }
         Value::Tuple(t) => {
             let len = t.len() as i64;
@@ -296,7 +421,7 @@ pub(crate) fn evaluate_slice(
         _ => interp.error(
             EldritchErrorKind::TypeError,
             &format!("'{}' object is not subscriptable", get_type_name(&obj_val)),
-            obj.span,
+            obj_span,
         ),
     }
 }
@@ -305,9 +430,14 @@ pub(crate) fn evaluate_getattr(
     interp: &mut Interpreter,
     obj: &Expr,
     name: String,
+    span: Span,
 ) -> Result<Value, EldritchError> {
     let obj_val = evaluate(interp, obj)?;
+    getattr_value(obj_val, name)
+        .or_else(|msg| interp.error(EldritchErrorKind::AttributeError, &msg, span))
+}
 
+pub(crate) fn getattr_value(obj_val: Value, name: String) -> Result<Value, String> {
     // Support dot access for dictionary keys (useful for modules)
     if let Value::Dictionary(d) = &obj_val {
         #[allow(clippy::collapsible_if)]
@@ -316,6 +446,14 @@ pub(crate) fn evaluate_getattr(
         }
     }
 
+    // Structs only have fields, so a missing one is an error rather than a method
+    if let Value::Struct(fields) = &obj_val {
+        return fields
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| format!("'struct' object has no attribute '{name}'"));
+    }
+
     // Support Foreign Objects
     if let Value::Foreign(_) = &obj_val {
         // Return a bound method where the receiver is the foreign object
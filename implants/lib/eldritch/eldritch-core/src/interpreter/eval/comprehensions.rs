@@ -1,4 +1,5 @@
 use super::super::super::ast::{Expr, Value};
+use super::super::Freezable;
 use super::super::core::Interpreter;
 use super::super::error::EldritchError;
 use super::super::operations::evaluate_comprehension_generic;
@@ -7,7 +8,6 @@ use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::RwLock;
 
 pub(crate) fn evaluate_list_comp(
     interp: &mut Interpreter,
@@ -21,7 +21,7 @@ pub(crate) fn evaluate_list_comp(
         results.push(evaluate(i, body)?);
         Ok(())
     })?;
-    Ok(Value::List(Arc::new(RwLock::new(results))))
+    Ok(Value::List(Arc::new(Freezable::new(results))))
 }
 
 pub(crate) fn evaluate_dict_comp(
@@ -39,7 +39,7 @@ pub(crate) fn evaluate_dict_comp(
         results.insert(k, v);
         Ok(())
     })?;
-    Ok(Value::Dictionary(Arc::new(RwLock::new(results))))
+    Ok(Value::Dictionary(Arc::new(Freezable::new(results))))
 }
 
 pub(crate) fn evaluate_set_comp(
@@ -55,5 +55,5 @@ pub(crate) fn evaluate_set_comp(
         results.insert(evaluate(i, body)?);
         Ok(())
     })?;
-    Ok(Value::Set(Arc::new(RwLock::new(results))))
+    Ok(Value::Set(Arc::new(Freezable::new(results))))
 }
@@ -2,16 +2,17 @@ use super::super::super::ast::{
     Argument, Environment, Expr, ExprKind, Function, Param, RuntimeParam, Stmt, StmtKind, Value,
 };
 use super::super::super::token::Span;
+use super::super::Freezable;
 use super::super::builtins::{
-    eval_builtin::builtin_eval_func, filter::builtin_filter, map::builtin_map,
-    reduce::builtin_reduce, sorted::builtin_sorted,
+    eval_builtin::builtin_eval_func, filter::builtin_filter, load_builtin::builtin_load_func,
+    map::builtin_map, reduce::builtin_reduce, sorted::builtin_sorted,
 };
 use super::super::core::{Flow, Interpreter};
 use super::super::error::{EldritchError, EldritchErrorKind};
-use super::super::exec::execute_stmts;
 use super::super::introspection::get_type_name;
-use super::super::methods::call_bound_method;
-use super::utils::parse_error_kind;
+use super::super::methods::{MUTATING_LIST_METHODS, call_bound_method};
+use super::strings::format_template;
+use super::utils::{check_collected_len, materialize, parse_error_kind};
 use super::{MAX_RECURSION_DEPTH, evaluate};
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
@@ -47,6 +48,8 @@ pub(crate) fn evaluate_lambda(
         params: runtime_params,
         body: alloc::vec![ret_stmt],
         closure: interp.env.clone(),
+        #[cfg(feature = "bytecode")]
+        code: None,
     });
     Ok(func)
 }
@@ -57,24 +60,27 @@ pub(crate) fn call_function(
     args: &[Argument],
     span: Span,
 ) -> Result<Value, EldritchError> {
+    interp.check_budget(span)?;
     let callee_val = evaluate(interp, callee)?;
+    let (pos_args_val, kw_args_val) = evaluate_args(interp, args)?;
+    let callee_name = match &callee.kind {
+        ExprKind::Identifier(name) => Some(name.as_str()),
+        _ => None,
+    };
+    call_value_with_args(
+        interp,
+        callee_val,
+        callee_name,
+        pos_args_val,
+        kw_args_val,
+        span,
+    )
+}
 
-    // Special handling for map/filter/reduce which take functions
-    if let Value::NativeFunction(name, _) = &callee_val {
-        if name == "map" {
-            return builtin_map(interp, args, span);
-        } else if name == "filter" {
-            return builtin_filter(interp, args, span);
-        } else if name == "reduce" {
-            return builtin_reduce(interp, args, span);
-        } else if name == "sorted" {
-            return builtin_sorted(interp, args, span);
-        } else if name == "eval" {
-            return builtin_eval_func(interp, args, span);
-        }
-    }
-
-    // Standard call
+fn evaluate_args(
+    interp: &mut Interpreter,
+    args: &[Argument],
+) -> Result<(Vec<Value>, BTreeMap<String, Value>), EldritchError> {
     let mut pos_args_val = Vec::new();
     let mut kw_args_val = BTreeMap::new();
 
@@ -87,53 +93,99 @@ pub(crate) fn call_function(
             }
             Argument::StarArgs(expr) => {
                 let val = evaluate(interp, expr)?;
-                match val {
-                    Value::List(l) => pos_args_val.extend(l.read().clone()),
-                    Value::Tuple(t) => pos_args_val.extend(t.clone()),
-                    _ => {
-                        return interp.error(
-                            EldritchErrorKind::TypeError,
-                            &format!(
-                                "*args argument must be iterable, got {:?}",
-                                get_type_name(&val)
-                            ),
-                            expr.span,
-                        );
-                    }
-                }
+                extend_star_args(interp, &mut pos_args_val, val, expr.span)?;
             }
             Argument::KwArgs(expr) => {
                 let val = evaluate(interp, expr)?;
-                match val {
-                    Value::Dictionary(d) => {
-                        let dict = d.read();
-                        for (k, v) in dict.iter() {
-                            match k {
-                                Value::String(s) => {
-                                    kw_args_val.insert(s.clone(), v.clone());
-                                }
-                                _ => {
-                                    return interp.error(
-                                        EldritchErrorKind::TypeError,
-                                        "Keywords must be strings",
-                                        expr.span,
-                                    );
-                                }
-                            }
-                        }
+                extend_kwargs(interp, &mut kw_args_val, val, expr.span)?;
+            }
+        }
+    }
+    Ok((pos_args_val, kw_args_val))
+}
+
+/// Append the values of a `*args` argument to the positional arguments.
+pub(crate) fn extend_star_args(
+    interp: &Interpreter,
+    pos_args_val: &mut Vec<Value>,
+    val: Value,
+    span: Span,
+) -> Result<(), EldritchError> {
+    match val {
+        Value::List(l) => pos_args_val.extend(l.read().clone()),
+        Value::Tuple(t) => pos_args_val.extend(t),
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                &format!(
+                    "*args argument must be iterable, got {:?}",
+                    get_type_name(&val)
+                ),
+                span,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Merge the entries of a `**kwargs` argument into the keyword arguments.
+pub(crate) fn extend_kwargs(
+    interp: &Interpreter,
+    kw_args_val: &mut BTreeMap<String, Value>,
+    val: Value,
+    span: Span,
+) -> Result<(), EldritchError> {
+    match val {
+        Value::Dictionary(d) => {
+            let dict = d.read();
+            for (k, v) in dict.iter() {
+                match k {
+                    Value::String(s) => {
+                        kw_args_val.insert(s.clone(), v.clone());
                     }
                     _ => {
                         return interp.error(
                             EldritchErrorKind::TypeError,
-                            &format!(
-                                "**kwargs argument must be a dict, got {:?}",
-                                get_type_name(&val)
-                            ),
-                            expr.span,
+                            "Keywords must be strings",
+                            span,
                         );
                     }
                 }
             }
+            Ok(())
+        }
+        _ => interp.error(
+            EldritchErrorKind::TypeError,
+            &format!(
+                "**kwargs argument must be a dict, got {:?}",
+                get_type_name(&val)
+            ),
+            span,
+        ),
+    }
+}
+
+/// Call an already evaluated callee. `callee_name` is the identifier it was called
+/// through, if any, and names the stack frame of native calls.
+pub(crate) fn call_value_with_args(
+    interp: &mut Interpreter,
+    callee_val: Value,
+    callee_name: Option<&str>,
+    pos_args_val: Vec<Value>,
+    kw_args_val: BTreeMap<String, Value>,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    // Special handling for map/filter/reduce which take functions
+    if let Value::NativeFunction(name, _) = &callee_val {
+        let args = pos_args_val.as_slice();
+        match name.as_str() {
+            "map" => return builtin_map(interp, args, &kw_args_val, span),
+            "filter" => return builtin_filter(interp, args, &kw_args_val, span),
+            "reduce" => return builtin_reduce(interp, args, &kw_args_val, span),
+            "sorted" => return builtin_sorted(interp, args, &kw_args_val, span),
+            "eval" => return builtin_eval_func(interp, args, &kw_args_val, span),
+            "load" => return builtin_load_func(interp, args, &kw_args_val, span),
+            _ => {}
         }
     }
 
@@ -149,12 +201,7 @@ pub(crate) fn call_function(
                 );
             }
             // Ensure stack frame for native call
-            // Native function name?
-            if let ExprKind::Identifier(name) = &callee.kind {
-                interp.push_frame(name, span);
-            } else {
-                interp.push_frame("<native>", span);
-            }
+            interp.push_frame(callee_name.unwrap_or("<native>"), span);
 
             let res = f(&interp.env, args_slice).map_err(|e| {
                 let (kind, msg) = parse_error_kind(&e);
@@ -165,11 +212,7 @@ pub(crate) fn call_function(
         }
         Value::NativeFunctionWithKwargs(_, f) => {
             // Ensure stack frame for native call
-            if let ExprKind::Identifier(name) = &callee.kind {
-                interp.push_frame(name, span);
-            } else {
-                interp.push_frame("<native>", span);
-            }
+            interp.push_frame(callee_name.unwrap_or("<native>"), span);
             let res = f(&interp.env, args_slice, &kw_args_val).map_err(|e| {
                 let (kind, msg) = parse_error_kind(&e);
                 EldritchError::new(kind, msg, span).with_stack(interp.call_stack.clone())
@@ -177,15 +220,7 @@ pub(crate) fn call_function(
             interp.pop_frame();
             res
         }
-        Value::Function(Function {
-            name,
-            params,
-            body,
-            closure,
-        }) => {
-            #[allow(unused_variables)]
-            let _ = name; // Silence unused name warning if any
-
+        Value::Function(func) => {
             if interp.depth >= MAX_RECURSION_DEPTH {
                 return interp.error(
                     EldritchErrorKind::RecursionError,
@@ -196,119 +231,9 @@ pub(crate) fn call_function(
             interp.depth += 1;
 
             // Push stack frame
-            interp.push_frame(&name, span);
-
-            let result = (|| {
-                let printer = interp.env.read().printer.clone();
-                let function_env = Arc::new(RwLock::new(Environment {
-                    parent: Some(closure),
-                    values: BTreeMap::new(),
-                    printer,
-                    libraries: BTreeSet::new(),
-                }));
-                let mut pos_idx = 0;
-                for param in params {
-                    match param {
-                        RuntimeParam::Normal(param_name) => {
-                            if pos_idx < pos_args_val.len() {
-                                function_env
-                                    .write()
-                                    .values
-                                    .insert(param_name.clone(), pos_args_val[pos_idx].clone());
-                                pos_idx += 1;
-                            } else if let Some(val) = kw_args_val.remove(&param_name) {
-                                function_env.write().values.insert(param_name.clone(), val);
-                            } else {
-                                return interp.error(
-                                    EldritchErrorKind::TypeError,
-                                    &format!("Missing required argument: '{param_name}'"),
-                                    span,
-                                );
-                            }
-                        }
-                        RuntimeParam::WithDefault(param_name, default_val) => {
-                            if pos_idx < pos_args_val.len() {
-                                function_env
-                                    .write()
-                                    .values
-                                    .insert(param_name.clone(), pos_args_val[pos_idx].clone());
-                                pos_idx += 1;
-                            } else if let Some(val) = kw_args_val.remove(&param_name) {
-                                function_env.write().values.insert(param_name.clone(), val);
-                            } else {
-                                function_env
-                                    .write()
-                                    .values
-                                    .insert(param_name.clone(), default_val.clone());
-                            }
-                        }
-                        RuntimeParam::Star(param_name) => {
-                            let remaining = if pos_idx < pos_args_val.len() {
-                                pos_args_val[pos_idx..].to_vec()
-                            } else {
-                                Vec::new()
-                            };
-                            pos_idx = pos_args_val.len();
-                            function_env
-                                .write()
-                                .values
-                                .insert(param_name.clone(), Value::Tuple(remaining));
-                        }
-                        RuntimeParam::StarStar(param_name) => {
-                            let mut dict = BTreeMap::new();
-                            let keys_to_move: Vec<String> = kw_args_val.keys().cloned().collect();
-                            for k in keys_to_move {
-                                if let Some(v) = kw_args_val.remove(&k) {
-                                    dict.insert(Value::String(k), v);
-                                }
-                            }
-                            function_env.write().values.insert(
-                                param_name.clone(),
-                                Value::Dictionary(Arc::new(RwLock::new(dict))),
-                            );
-                        }
-                    }
-                }
+            interp.push_frame(&func.name, span);
 
-                if pos_idx < pos_args_val.len() {
-                    return interp.error(
-                        EldritchErrorKind::TypeError,
-                        "Function got too many positional arguments.",
-                        span,
-                    );
-                }
-
-                if !kw_args_val.is_empty() {
-                    let mut keys: Vec<&String> = kw_args_val.keys().collect();
-                    keys.sort();
-                    return interp.error(
-                        EldritchErrorKind::TypeError,
-                        &format!(
-                            "{}() got an unexpected keyword argument '{}'",
-                            name, keys[0]
-                        ),
-                        span,
-                    );
-                }
-
-                let original_env = interp.env.clone();
-                interp.env = function_env;
-                let old_flow = interp.flow.clone();
-                interp.flow = Flow::Next;
-
-                let exec_result = execute_stmts(interp, &body);
-
-                let ret_val = if let Flow::Return(v) = &interp.flow {
-                    v.clone()
-                } else {
-                    Value::None
-                };
-                interp.env = original_env;
-                interp.flow = old_flow;
-
-                exec_result?;
-                Ok(ret_val)
-            })();
+            let result = call_user_function(interp, &func, pos_args_val, kw_args_val, span);
             interp.depth -= 1;
             interp.pop_frame();
             result
@@ -319,12 +244,14 @@ pub(crate) fn call_function(
             let res = {
                 // Check if receiver is Foreign
                 if let Value::Foreign(foreign) = receiver.as_ref() {
+                    interp.mark_native_frame(foreign.type_name());
                     foreign
                         .call_method(interp, &method_name, args_slice, &kw_args_val)
                         .map_err(|e| {
                             let (kind, msg) = parse_error_kind(&e);
                             EldritchError::new(kind, msg, span)
                                 .with_stack(interp.call_stack.clone())
+                                .with_library(foreign.type_name())
                         })
                 } else if !kw_args_val.is_empty() {
                     Err(EldritchError::new(
@@ -334,10 +261,7 @@ pub(crate) fn call_function(
                     )
                     .with_stack(interp.call_stack.clone()))
                 } else {
-                    call_bound_method(&receiver, &method_name, args_slice).map_err(|e| {
-                        let (kind, msg) = parse_error_kind(&e);
-                        EldritchError::new(kind, msg, span).with_stack(interp.call_stack.clone())
-                    })
+                    call_builtin_method(interp, &receiver, &method_name, args_slice, span)
                 }
             };
             interp.pop_frame();
@@ -351,6 +275,114 @@ pub(crate) fn call_function(
     }
 }
 
+fn call_user_function(
+    interp: &mut Interpreter,
+    func: &Function,
+    pos_args_val: Vec<Value>,
+    mut kw_args_val: BTreeMap<String, Value>,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    let mut locals = BTreeMap::new();
+    let mut pos_idx = 0;
+    for param in &func.params {
+        match param {
+            RuntimeParam::Normal(param_name) => {
+                if pos_idx < pos_args_val.len() {
+                    locals.insert(param_name.clone(), pos_args_val[pos_idx].clone());
+                    pos_idx += 1;
+                } else if let Some(val) = kw_args_val.remove(param_name) {
+                    locals.insert(param_name.clone(), val);
+                } else {
+                    return interp.error(
+                        EldritchErrorKind::TypeError,
+                        &format!("Missing required argument: '{param_name}'"),
+                        span,
+                    );
+                }
+            }
+            RuntimeParam::WithDefault(param_name, default_val) => {
+                if pos_idx < pos_args_val.len() {
+                    locals.insert(param_name.clone(), pos_args_val[pos_idx].clone());
+                    pos_idx += 1;
+                } else if let Some(val) = kw_args_val.remove(param_name) {
+                    locals.insert(param_name.clone(), val);
+                } else {
+                    locals.insert(param_name.clone(), default_val.clone());
+                }
+            }
+            RuntimeParam::Star(param_name) => {
+                let remaining = if pos_idx < pos_args_val.len() {
+                    pos_args_val[pos_idx..].to_vec()
+                } else {
+                    Vec::new()
+                };
+                pos_idx = pos_args_val.len();
+                locals.insert(param_name.clone(), Value::Tuple(remaining));
+            }
+            RuntimeParam::StarStar(param_name) => {
+                let mut dict = BTreeMap::new();
+                let keys_to_move: Vec<String> = kw_args_val.keys().cloned().collect();
+                for k in keys_to_move {
+                    if let Some(v) = kw_args_val.remove(&k) {
+                        dict.insert(Value::String(k), v);
+                    }
+                }
+                locals.insert(
+                    param_name.clone(),
+                    Value::Dictionary(Arc::new(Freezable::new(dict))),
+                );
+            }
+        }
+    }
+
+    if pos_idx < pos_args_val.len() {
+        return interp.error(
+            EldritchErrorKind::TypeError,
+            "Function got too many positional arguments.",
+            span,
+        );
+    }
+
+    if !kw_args_val.is_empty() {
+        let mut keys: Vec<&String> = kw_args_val.keys().collect();
+        keys.sort();
+        return interp.error(
+            EldritchErrorKind::TypeError,
+            &format!(
+                "{}() got an unexpected keyword argument '{}'",
+                func.name, keys[0]
+            ),
+            span,
+        );
+    }
+
+    let printer = interp.env.read().printer.clone();
+    let function_env = Arc::new(RwLock::new(Environment {
+        parent: Some(func.closure.clone()),
+        values: locals,
+        printer,
+        libraries: BTreeSet::new(),
+        max_sequence_len: interp.budget.limits.max_sequence_len,
+    }));
+
+    let original_env = interp.env.clone();
+    interp.env = function_env;
+    let old_flow = core::mem::replace(&mut interp.flow, Flow::Next);
+
+    #[cfg(not(feature = "bytecode"))]
+    let exec_result =
+        super::super::exec::execute_stmts(interp, &func.body).map(|_| match &interp.flow {
+            Flow::Return(v) => v.clone(),
+            _ => Value::None,
+        });
+    #[cfg(feature = "bytecode")]
+    let exec_result = super::super::vm::execute_function(interp, func);
+
+    interp.env = original_env;
+    interp.flow = old_flow;
+    exec_result
+}
+
 pub(crate) fn call_value(
     interp: &mut Interpreter,
     func: &Value,
@@ -369,12 +401,7 @@ pub(crate) fn call_value(
             interp.pop_frame();
             res
         }
-        Value::Function(Function {
-            name: _,
-            params: _,
-            body: _,
-            closure: _,
-        }) => {
+        Value::Function(_) => {
             if interp.depth >= MAX_RECURSION_DEPTH {
                 return interp.error(
                     EldritchErrorKind::RecursionError,
@@ -383,33 +410,22 @@ pub(crate) fn call_value(
                 );
             }
             interp.depth += 1;
-
-            let expr_args: Vec<Argument> = args
-                .iter()
-                .map(|v| {
-                    Argument::Positional(Expr {
-                        kind: ExprKind::Literal(v.clone()),
-                        span,
-                    })
-                })
-                .collect();
-
-            // Construct minimal callee expr for recursion call
-            let callee_expr = Expr {
-                kind: ExprKind::Literal(func.clone()),
-                span,
-            };
-
-            let res = call_function(interp, &callee_expr, &expr_args, span);
+            let res = interp.check_budget(span).and_then(|_| {
+                call_value_with_args(
+                    interp,
+                    func.clone(),
+                    None,
+                    args.to_vec(),
+                    BTreeMap::new(),
+                    span,
+                )
+            });
             interp.depth -= 1;
             res
         }
         Value::BoundMethod(receiver, method_name) => {
             interp.push_frame(method_name, span);
-            let res = call_bound_method(receiver, method_name, args).map_err(|e| {
-                let (kind, msg) = parse_error_kind(&e);
-                EldritchError::new(kind, msg, span).with_stack(interp.call_stack.clone())
-            });
+            let res = call_builtin_method(interp, receiver, method_name, args, span);
             interp.pop_frame();
             res
         }
@@ -421,18 +437,80 @@ pub(crate) fn call_value(
     }
 }
 
-pub(crate) fn evaluate_arg(
-    interp: &mut Interpreter,
-    arg: &Argument,
+// `args` with each lazy iterable collected into a list, within the sequence limit
+fn collect_lazy_args(
+    interp: &Interpreter,
+    args: &[Value],
+    span: Span,
+) -> Result<Vec<Value>, EldritchError> {
+    args.iter()
+        .map(|arg| match arg {
+            Value::Iterable(it) => Ok(Value::List(Arc::new(Freezable::new(materialize(
+                interp, it, span,
+            )?)))),
+            arg => Ok(arg.clone()),
+        })
+        .collect()
+}
+
+/// Calls a method of a builtin type. `str.format` and `list.extend` are held to the
+/// sequence limit, and lazy arguments to set methods, which collect them, are collected
+/// within it.
+fn call_builtin_method(
+    interp: &Interpreter,
+    receiver: &Value,
+    method: &str,
+    args: &[Value],
+    span: Span,
 ) -> Result<Value, EldritchError> {
-    match arg {
-        Argument::Positional(e) => evaluate(interp, e),
-        // Just return a dummy span here for the error, or match e.span if available.
-        // Since we don't have easy access to a span here without unpacking, use a dummy one.
-        _ => interp.error(
+    let max_len = interp.budget.limits.max_sequence_len;
+    let res = match receiver {
+        Value::String(s) if method == "format" => {
+            format_template(s, args, max_len).map(Value::String)
+        }
+        Value::List(l) if method == "extend" => {
+            let len = l.read().len();
+            check_collected_len(
+                interp,
+                len + args.first().and_then(Value::known_len).unwrap_or(0),
+                span,
+            )?;
+            call_bound_method(receiver, method, args)
+        }
+        // A lazy sequence answers list methods as an immutable list
+        Value::Iterable(_) if MUTATING_LIST_METHODS.contains(&method) => Err(format!(
+            "TypeError: cannot mutate lazy {}; use list() to get a mutable copy",
+            get_type_name(receiver)
+        )),
+        Value::Iterable(it) => {
+            let list = Value::List(Arc::new(Freezable::new(materialize(interp, it, span)?)));
+            call_bound_method(&list, method, args)
+        }
+        Value::Set(_) if max_len.is_some() => {
+            let args = collect_lazy_args(interp, args, span)?;
+            call_bound_method(receiver, method, &args)
+        }
+        _ => call_bound_method(receiver, method, args),
+    };
+    res.map_err(|e| {
+        let (kind, msg) = parse_error_kind(&e);
+        EldritchError::new(kind, msg, span).with_stack(interp.call_stack.clone())
+    })
+}
+
+/// `map`, `filter` and `reduce` only take their arguments positionally.
+pub(crate) fn reject_hof_kwargs(
+    interp: &Interpreter,
+    kwargs: &BTreeMap<String, Value>,
+    span: Span,
+) -> Result<(), EldritchError> {
+    if kwargs.is_empty() {
+        Ok(())
+    } else {
+        interp.error(
             EldritchErrorKind::TypeError,
             "HOFs currently only support positional arguments",
-            Span::new(0, 0, 0),
-        ),
+            span,
+        )
     }
 }
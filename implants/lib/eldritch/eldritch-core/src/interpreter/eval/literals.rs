@@ -1,11 +1,11 @@
 use super::super::super::ast::{Expr, Value};
+use super::super::Freezable;
 use super::super::core::Interpreter;
 use super::super::error::EldritchError;
 use super::evaluate;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::RwLock;
 
 pub(crate) fn evaluate_list_literal(
     interp: &mut Interpreter,
@@ -15,7 +15,7 @@ pub(crate) fn evaluate_list_literal(
     for expr in elements {
         vals.push(evaluate(interp, expr)?);
     }
-    Ok(Value::List(Arc::new(RwLock::new(vals))))
+    Ok(Value::List(Arc::new(Freezable::new(vals))))
 }
 
 pub(crate) fn evaluate_tuple_literal(
@@ -39,7 +39,7 @@ pub(crate) fn evaluate_dict_literal(
         let value_val = evaluate(interp, value_expr)?;
         map.insert(key_val, value_val);
     }
-    Ok(Value::Dictionary(Arc::new(RwLock::new(map))))
+    Ok(Value::Dictionary(Arc::new(Freezable::new(map))))
 }
 
 pub(crate) fn evaluate_set_literal(
@@ -52,5 +52,5 @@ pub(crate) fn evaluate_set_literal(
         let val = evaluate(interp, expr)?;
         set.insert(val);
     }
-    Ok(Value::Set(Arc::new(RwLock::new(set))))
+    Ok(Value::Set(Arc::new(Freezable::new(set))))
 }
@@ -22,8 +22,7 @@ use self::strings::evaluate_fstring;
 use alloc::format;
 
 // Re-export for exec.rs
-pub(crate) use self::ops::apply_binary_op as apply_binary_op_pub;
-pub(crate) use self::utils::to_iterable;
+pub(crate) use self::utils::{iterate, iteration_error, materialize};
 
 pub(crate) const MAX_RECURSION_DEPTH: usize = 64;
 
@@ -41,7 +40,7 @@ pub fn evaluate(interp: &mut Interpreter, expr: &Expr) -> Result<Value, Eldritch
         ExprKind::Dictionary(entries) => evaluate_dict_literal(interp, entries),
         ExprKind::Set(elements) => evaluate_set_literal(interp, elements),
         ExprKind::Index(obj, index) => evaluate_index(interp, obj, index, span),
-        ExprKind::GetAttr(obj, name) => evaluate_getattr(interp, obj, name.clone()),
+        ExprKind::GetAttr(obj, name) => evaluate_getattr(interp, obj, name.clone(), span),
         ExprKind::Slice(obj, start, stop, step) => {
             evaluate_slice(interp, obj, start, stop, step, span)
         }
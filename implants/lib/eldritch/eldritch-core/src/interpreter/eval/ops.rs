@@ -1,21 +1,21 @@
 use super::super::super::ast::{Expr, Value};
 use super::super::super::token::{Span, TokenKind};
+use super::super::Freezable;
 use super::super::core::Interpreter;
 use super::super::error::{EldritchError, EldritchErrorKind, runtime_error};
 use super::super::introspection::{get_type_name, is_truthy};
 use super::super::operations::{
     apply_arithmetic_op, apply_bitwise_op, apply_comparison_op, compare_values, values_equal,
 };
-use super::evaluate;
+use super::{evaluate, iteration_error, materialize};
 use alloc::collections::BTreeSet;
 use alloc::format;
-use alloc::string::{String, ToString};
+use alloc::string::String;
 use alloc::sync::Arc;
-use alloc::vec;
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cmp::Ordering;
-use core::fmt::Write;
-use spin::RwLock;
+use num_bigint::{BigInt, Sign};
 
 pub(crate) fn apply_unary_op(
     interp: &mut Interpreter,
@@ -24,9 +24,21 @@ pub(crate) fn apply_unary_op(
     span: Span,
 ) -> Result<Value, EldritchError> {
     let val = evaluate(interp, right)?;
+    unary_op(interp, op, val, span)
+}
+
+pub(crate) fn unary_op(
+    interp: &mut Interpreter,
+    op: &TokenKind,
+    val: Value,
+    span: Span,
+) -> Result<Value, EldritchError> {
     match op {
         TokenKind::Minus => match val {
-            Value::Int(i) => Ok(Value::Int(-i)),
+            Value::Int(i) => Ok(i
+                .checked_neg()
+                .map_or_else(|| Value::from_bigint(-BigInt::from(i)), Value::Int)),
+            Value::BigInt(i) => Ok(Value::from_bigint(-i)),
             Value::Float(f) => Ok(Value::Float(-f)),
             _ => interp.error(
                 EldritchErrorKind::TypeError,
@@ -37,6 +49,7 @@ pub(crate) fn apply_unary_op(
         TokenKind::Not => Ok(Value::Bool(!is_truthy(&val))),
         TokenKind::BitNot => match val {
             Value::Int(i) => Ok(Value::Int(!i)),
+            Value::BigInt(i) => Ok(Value::from_bigint(!i)),
             _ => interp.error(
                 EldritchErrorKind::TypeError,
                 "Bitwise '~' only valid for integers",
@@ -143,6 +156,16 @@ fn evaluate_in(
             };
             Ok(Value::Bool(contains))
         }
+        Value::Iterable(it) => {
+            for x in it.iter() {
+                match x {
+                    Ok(x) if values_equal(&x, item) => return Ok(Value::Bool(true)),
+                    Ok(_) => {}
+                    Err(msg) => return iteration_error(interp, msg, span),
+                }
+            }
+            Ok(Value::Bool(false))
+        }
         _ => interp.error(
             EldritchErrorKind::TypeError,
             &format!(
@@ -163,6 +186,25 @@ pub(crate) fn apply_binary_op(
 ) -> Result<Value, EldritchError> {
     let a = evaluate(interp, left)?;
     let b = evaluate(interp, right)?;
+    binary_op(interp, a, op, b, span)
+}
+
+pub(crate) fn binary_op(
+    interp: &mut Interpreter,
+    a: Value,
+    op: &TokenKind,
+    b: Value,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    let (a, b) = if matches!(op, TokenKind::Plus | TokenKind::Star) {
+        (
+            sequence_operand(interp, a, span)?,
+            sequence_operand(interp, b, span)?,
+        )
+    } else {
+        (a, b)
+    };
+    check_sequence_len(interp, &a, op, &b, span)?;
 
     // Handle operations that are fully delegated
     if matches!(
@@ -179,11 +221,15 @@ pub(crate) fn apply_binary_op(
         // Let's check which are generic.
 
         // Only numbers are fully handled in apply_arithmetic_op
-        if (matches!(a, Value::Int(_) | Value::Float(_))
-            && matches!(b, Value::Int(_) | Value::Float(_)))
+        if (matches!(a, Value::Int(_) | Value::BigInt(_) | Value::Float(_))
+            && matches!(b, Value::Int(_) | Value::BigInt(_) | Value::Float(_)))
         {
             return apply_arithmetic_op(interp, &a, op, &b, span);
         }
+        // As is printf-style string formatting
+        if matches!((&a, op), (Value::String(_), TokenKind::Percent)) {
+            return apply_arithmetic_op(interp, &a, op, &b, span);
+        }
     }
 
     // Comparisons
@@ -202,10 +248,22 @@ pub(crate) fn apply_binary_op(
             (Value::List(la), Value::List(lb)) => {
                 let list_a = la.read();
                 let list_b = lb.read();
-                return compare_sequences(&list_a, &list_b, op.clone(), span);
+                let (seq_a, seq_b) = (list_a.iter().map(Ok), list_b.iter().map(Ok));
+                return compare_sequences(interp, seq_a, seq_b, op.clone(), span);
             }
             (Value::Tuple(ta), Value::Tuple(tb)) => {
-                return compare_sequences(ta, tb, op.clone(), span);
+                let (seq_a, seq_b) = (ta.iter().map(Ok), tb.iter().map(Ok));
+                return compare_sequences(interp, seq_a, seq_b, op.clone(), span);
+            }
+            // Lazy iterables compare as the lists they stand for, one item at a time
+            (Value::Iterable(_), Value::Iterable(_) | Value::List(_))
+            | (Value::List(_), Value::Iterable(_)) => {
+                return match (a.try_iter(), b.try_iter()) {
+                    (Some(seq_a), Some(seq_b)) => {
+                        compare_sequences(interp, seq_a, seq_b, op.clone(), span)
+                    }
+                    _ => apply_comparison_op(interp, &a, op, &b, span),
+                };
             }
             _ => return apply_comparison_op(interp, &a, op, &b, span),
         }
@@ -240,14 +298,10 @@ pub(crate) fn apply_binary_op(
         (Value::Set(a), TokenKind::Minus, Value::Set(b)) => {
             #[allow(clippy::mutable_key_type)]
             let difference: BTreeSet<Value> = a.read().difference(&b.read()).cloned().collect();
-            Ok(Value::Set(Arc::new(RwLock::new(difference))))
+            Ok(Value::Set(Arc::new(Freezable::new(difference))))
         }
 
         (Value::String(a), TokenKind::Plus, Value::String(b)) => Ok(Value::String(a + &b)),
-        (Value::String(a), TokenKind::Percent, b_val) => {
-            // String formatting
-            string_modulo_format(interp, &a, &b_val, span)
-        }
 
         (Value::Bytes(a), TokenKind::Plus, Value::Bytes(b)) => {
             let mut new_bytes = a.clone();
@@ -281,14 +335,16 @@ pub(crate) fn apply_binary_op(
         // List concatenation (new list)
         (Value::List(mut a), TokenKind::Plus, Value::List(b)) => {
             // Optimization: If `a` is a temporary (unique), mutate in place
-            if let Some(rw_lock) = Arc::get_mut(&mut a) {
+            if let Some(rw_lock) = Arc::get_mut(&mut a)
+                && !rw_lock.is_frozen()
+            {
                 let list = rw_lock.get_mut();
                 list.extend(b.read().clone());
                 Ok(Value::List(a))
             } else {
                 let mut new_list = a.read().clone();
                 new_list.extend(b.read().clone());
-                Ok(Value::List(Arc::new(RwLock::new(new_list))))
+                Ok(Value::List(Arc::new(Freezable::new(new_list))))
             }
         }
 
@@ -301,7 +357,7 @@ pub(crate) fn apply_binary_op(
                     new_list.extend(list_ref.clone());
                 }
             }
-            Ok(Value::List(Arc::new(RwLock::new(new_list))))
+            Ok(Value::List(Arc::new(Freezable::new(new_list))))
         }
         (Value::Int(n), TokenKind::Star, Value::List(a)) => {
             let mut new_list = Vec::new();
@@ -311,7 +367,7 @@ pub(crate) fn apply_binary_op(
                     new_list.extend(list_ref.clone());
                 }
             }
-            Ok(Value::List(Arc::new(RwLock::new(new_list))))
+            Ok(Value::List(Arc::new(Freezable::new(new_list))))
         }
 
         // Tuple concatenation (new tuple)
@@ -357,9 +413,26 @@ pub(crate) fn apply_binary_op(
             }
         }
 
+        // Repetition by a count past i64 is empty when negative and too long to build otherwise
+        (seq, TokenKind::Star, Value::BigInt(n)) | (Value::BigInt(n), TokenKind::Star, seq)
+            if sequence_len(&seq).is_some() =>
+        {
+            if n.sign() == Sign::Minus {
+                binary_op(interp, seq, &TokenKind::Star, Value::Int(0), span)
+            } else {
+                interp.error(
+                    EldritchErrorKind::ValueError,
+                    &format!("int {n} out of range for i64"),
+                    span,
+                )
+            }
+        }
+
         // Dict merge (new dict)
         (Value::Dictionary(mut a), TokenKind::Plus, Value::Dictionary(b)) => {
-            if let Some(rw_lock) = Arc::get_mut(&mut a) {
+            if let Some(rw_lock) = Arc::get_mut(&mut a)
+                && !rw_lock.is_frozen()
+            {
                 let dict = rw_lock.get_mut();
                 for (k, v) in b.read().iter() {
                     dict.insert(k.clone(), v.clone());
@@ -370,13 +443,15 @@ pub(crate) fn apply_binary_op(
                 for (k, v) in b.read().iter() {
                     new_dict.insert(k.clone(), v.clone());
                 }
-                Ok(Value::Dictionary(Arc::new(RwLock::new(new_dict))))
+                Ok(Value::Dictionary(Arc::new(Freezable::new(new_dict))))
             }
         }
 
         // Set union (new set) - Plus is deprecated for sets in favor of |
         (Value::Set(mut a), TokenKind::Plus, Value::Set(b)) => {
-            if let Some(rw_lock) = Arc::get_mut(&mut a) {
+            if let Some(rw_lock) = Arc::get_mut(&mut a)
+                && !rw_lock.is_frozen()
+            {
                 #[allow(clippy::mutable_key_type)]
                 let set = rw_lock.get_mut();
                 for item in b.read().iter() {
@@ -389,7 +464,7 @@ pub(crate) fn apply_binary_op(
                 for item in b.read().iter() {
                     new_set.insert(item.clone());
                 }
-                Ok(Value::Set(Arc::new(RwLock::new(new_set))))
+                Ok(Value::Set(Arc::new(Freezable::new(new_set))))
             }
         }
 
@@ -419,172 +494,82 @@ pub(crate) fn apply_binary_op(
     }
 }
 
-fn string_modulo_format(
-    interp: &mut Interpreter,
-    fmt_str: &str,
-    val: &Value,
+/// Collects a lazy iterable operand of `+` or `*` into a list, so `range(3) + [3]`
+/// and `range(2) * 2` keep working as they did when those builtins returned lists.
+pub(crate) fn sequence_operand(
+    interp: &Interpreter,
+    value: Value,
     span: Span,
 ) -> Result<Value, EldritchError> {
-    let mut result = String::new();
-    let mut chars = fmt_str.chars().peekable();
-    let mut val_idx = 0;
-    let vals: Vec<Value> = match val {
-        Value::Tuple(t) => t.clone(),
-        _ => vec![val.clone()],
-    };
-
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            if let Some(&next) = chars.peek() {
-                // If it is '%%', handle immediately
-                if next == '%' {
-                    chars.next();
-                    result.push('%');
-                    continue;
-                }
-
-                chars.next(); // Consume specifier
-
-                if val_idx >= vals.len() {
-                    return interp.error(
-                        EldritchErrorKind::TypeError,
-                        "not enough arguments for format string",
-                        span,
-                    );
-                }
-                let v = &vals[val_idx];
-                val_idx += 1;
+    match value {
+        Value::Iterable(it) => Ok(Value::List(Arc::new(Freezable::new(materialize(
+            interp, &it, span,
+        )?)))),
+        other => Ok(other),
+    }
+}
 
-                match next {
-                    's' => {
-                        result.push_str(&v.to_string());
-                    }
-                    'd' | 'i' | 'u' => {
-                        let i_val = to_int_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{i_val}");
-                    }
-                    'o' => {
-                        let i_val = to_int_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{i_val:o}");
-                    }
-                    'x' => {
-                        let i_val = to_int_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{i_val:x}");
-                    }
-                    'X' => {
-                        let i_val = to_int_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{i_val:X}");
-                    }
-                    'e' => {
-                        let f_val = to_float_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{f_val:e}");
-                    }
-                    'E' => {
-                        let f_val = to_float_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{f_val:E}");
-                    }
-                    'f' | 'F' => {
-                        let f_val = to_float_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{f_val:.6}",);
-                    }
-                    'g' | 'G' => {
-                        let f_val = to_float_or_error(interp, v, next, span)?;
-                        let _ = write!(result, "{f_val:?}");
-                    }
-                    'r' => match v {
-                        Value::String(s) => {
-                            let _ = write!(result, "\"{s}\"");
-                        }
-                        _ => result.push_str(&v.to_string()),
-                    },
-                    _ => {
-                        return interp.error(
-                            EldritchErrorKind::ValueError,
-                            &format!(
-                                "unsupported format character '{}' (0x{:x})",
-                                next, next as u32
-                            ),
-                            span,
-                        );
-                    }
-                }
-            } else {
-                return interp.error(EldritchErrorKind::ValueError, "incomplete format key", span);
+/// Refuses a `+` or `*` whose result would be longer than
+/// `ExecutionLimits::max_sequence_len`, before it is allocated.
+pub(crate) fn check_sequence_len(
+    interp: &Interpreter,
+    a: &Value,
+    op: &TokenKind,
+    b: &Value,
+    span: Span,
+) -> Result<(), EldritchError> {
+    let Some(max) = interp.budget.limits.max_sequence_len else {
+        return Ok(());
+    };
+    let len = match (a, op, b) {
+        (_, TokenKind::Plus, _) => match (sequence_len(a), sequence_len(b)) {
+            (Some(x), Some(y)) => x.saturating_add(y),
+            _ => return Ok(()),
+        },
+        (seq, TokenKind::Star, Value::Int(n)) | (Value::Int(n), TokenKind::Star, seq) => {
+            match sequence_len(seq) {
+                Some(len) => len.saturating_mul(usize::try_from(*n).unwrap_or(0)),
+                None => return Ok(()),
             }
-        } else {
-            result.push(c);
         }
-    }
-
-    if val_idx < vals.len() {
+        _ => return Ok(()),
+    };
+    if len > max {
         return interp.error(
-            EldritchErrorKind::TypeError,
-            "not all arguments converted during string formatting",
+            EldritchErrorKind::MemoryLimitError,
+            &format!("result of length {len} exceeds the maximum of {max}"),
             span,
         );
     }
-
-    Ok(Value::String(result))
+    Ok(())
 }
 
-fn to_int_or_error(
-    interp: &Interpreter,
-    v: &Value,
-    spec: char,
-    span: Span,
-) -> Result<i64, EldritchError> {
-    match v {
-        Value::Int(i) => Ok(*i),
-        Value::Float(f) => Ok(*f as i64),
-        Value::Bool(b) => Ok(if *b { 1 } else { 0 }),
-        _ => interp.error(
-            EldritchErrorKind::TypeError,
-            &format!(
-                "%{} format: a number is required, not {}",
-                spec,
-                get_type_name(v)
-            ),
-            span,
-        ),
+fn sequence_len(value: &Value) -> Option<usize> {
+    match value {
+        Value::String(s) => Some(s.len()),
+        Value::Bytes(b) => Some(b.len()),
+        Value::List(l) => Some(l.read().len()),
+        Value::Tuple(t) => Some(t.len()),
+        _ => None,
     }
 }
 
-fn to_float_or_error(
+// Lexicographical comparison of two sequences, raising the first iteration error of a lazy one.
+fn compare_sequences<A: Borrow<Value>, B: Borrow<Value>>(
     interp: &Interpreter,
-    v: &Value,
-    spec: char,
-    span: Span,
-) -> Result<f64, EldritchError> {
-    match v {
-        Value::Int(i) => Ok(*i as f64),
-        Value::Float(f) => Ok(*f),
-        Value::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
-        _ => interp.error(
-            EldritchErrorKind::TypeError,
-            &format!(
-                "%{} format: a number is required, not {}",
-                spec,
-                get_type_name(v)
-            ),
-            span,
-        ),
-    }
-}
-
-fn compare_sequences(
-    seq_a: &[Value],
-    seq_b: &[Value],
+    mut seq_a: impl Iterator<Item = Result<A, String>>,
+    mut seq_b: impl Iterator<Item = Result<B, String>>,
     op: TokenKind,
     span: Span,
 ) -> Result<Value, EldritchError> {
-    // Lexicographical comparison
-    let len_a = seq_a.len();
-    let len_b = seq_b.len();
-    let len = len_a.min(len_b);
-
-    for i in 0..len {
-        let val_a = &seq_a[i];
-        let val_b = &seq_b[i];
+    let (len_a, len_b) = loop {
+        let (val_a, val_b) = match (seq_a.next(), seq_b.next()) {
+            (Some(Err(msg)), _) | (_, Some(Err(msg))) => return iteration_error(interp, msg, span),
+            (Some(Ok(a)), Some(Ok(b))) => (a, b),
+            // If prefix matches, compare lengths
+            (a, b) => break (a.is_some() as usize, b.is_some() as usize),
+        };
+        let (val_a, val_b) = (val_a.borrow(), val_b.borrow());
 
         if val_a != val_b {
             return match op {
@@ -612,9 +597,8 @@ fn compare_sequences(
                 _ => runtime_error(span, "Invalid comparison operator for sequences"),
             };
         }
-    }
+    };
 
-    // If prefix matches, compare lengths
     match op {
         TokenKind::Eq => Ok(Value::Bool(len_a == len_b)),
         TokenKind::NotEq => Ok(Value::Bool(len_a != len_b)),
@@ -1,9 +1,15 @@
 use super::super::super::ast::{FStringSegment, Value};
+use super::super::super::token::Span;
 use super::super::core::Interpreter;
 use super::super::error::EldritchError;
+use super::super::introspection::get_type_name;
 use super::evaluate;
-use alloc::string::ToString;
+use super::utils::parse_error_kind;
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use num_bigint::{BigInt, Sign};
+use num_traits::ToPrimitive;
 
 pub(crate) fn evaluate_fstring(
     interp: &mut Interpreter,
@@ -17,7 +23,443 @@ pub(crate) fn evaluate_fstring(
                 let val = evaluate(interp, expr)?;
                 parts.push(val.to_string());
             }
+            FStringSegment::Formatted(expr, spec) => {
+                let val = evaluate(interp, expr)?;
+                let spec = evaluate(interp, spec)?.to_string();
+                parts.push(format_value_or_error(interp, &val, &spec, expr.span)?);
+            }
         }
     }
     Ok(Value::String(parts.join("")))
 }
+
+/// Like [`format_value`], but raises a bad spec as an error at `span`.
+pub(crate) fn format_value_or_error(
+    interp: &Interpreter,
+    value: &Value,
+    spec: &str,
+    span: Span,
+) -> Result<String, EldritchError> {
+    let max_len = interp.budget.limits.max_sequence_len;
+    format_value(value, spec, max_len).or_else(|msg| {
+        let (kind, msg) = parse_error_kind(&msg);
+        interp.error(kind, msg, span)
+    })
+}
+
+/// Formats `value` with a format spec such as `>10`, `08.3f` or `,d`, as in `f"{x:>10}"`.
+/// A width or precision over `max_len` is refused before the text is built.
+pub(crate) fn format_value(
+    value: &Value,
+    spec: &str,
+    max_len: Option<usize>,
+) -> Result<String, String> {
+    if spec.is_empty() {
+        return Ok(value.to_string());
+    }
+    let spec = FormatSpec::parse(spec)?;
+    spec.check_len(max_len)?;
+    spec.apply(value)
+}
+
+/// Substitutes positional arguments into a `str.format` template. Fields are empty (`{}`) or
+/// an argument index (`{1}`), optionally followed by a spec (`{:>8}`, `{0:.2f}`) that may
+/// take parts of itself from other arguments (`{:>{}}`); `{{` and `}}` are literal braces.
+/// Any other text in braces is kept as written.
+pub(crate) fn format_template(
+    template: &str,
+    args: &[Value],
+    max_len: Option<usize>,
+) -> Result<String, String> {
+    let mut result = String::new();
+    let mut auto_index = 0;
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        result.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            result.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        let field = tail
+            .strip_prefix('{')
+            .and_then(|t| field_len(t).map(|end| &t[..end]));
+        match field.and_then(|f| parse_field(f, &mut auto_index).map(|parsed| (f, parsed))) {
+            Some((field, (index, spec))) => {
+                let value = args
+                    .get(index)
+                    .ok_or("IndexError: tuple index out of range")?;
+                let spec = expand_spec(spec, args, &mut auto_index, max_len)?;
+                result.push_str(&format_value(value, &spec, max_len)?);
+                rest = &tail[field.len() + 2..];
+            }
+            None => {
+                result.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Replaces the fields nested in a format spec, as in `{:>{}}`, with their arguments.
+fn expand_spec(
+    spec: &str,
+    args: &[Value],
+    auto_index: &mut usize,
+    max_len: Option<usize>,
+) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = spec;
+    while let Some(pos) = rest.find('{') {
+        result.push_str(&rest[..pos]);
+        let tail = &rest[pos + 1..];
+        let Some(len) = field_len(tail) else {
+            rest = &rest[pos..];
+            break;
+        };
+        let field = &tail[..len];
+        match parse_field(field, auto_index) {
+            Some((index, spec)) => {
+                let value = args
+                    .get(index)
+                    .ok_or("IndexError: tuple index out of range")?;
+                result.push_str(&format_value(value, spec, max_len)?);
+            }
+            None => {
+                result.push('{');
+                result.push_str(field);
+                result.push('}');
+            }
+        }
+        rest = &tail[len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+// The length of a replacement field's text, up to the `}` that closes it.
+fn field_len(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, b) in text.bytes().enumerate() {
+        match b {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+// The argument index and spec of a replacement field, or None if it isn't positional.
+fn parse_field<'a>(field: &'a str, auto_index: &mut usize) -> Option<(usize, &'a str)> {
+    let (name, spec) = field.split_once(':').unwrap_or((field, ""));
+    if name.is_empty() {
+        let index = *auto_index;
+        *auto_index += 1;
+        Some((index, spec))
+    } else if name.bytes().all(|b| b.is_ascii_digit()) {
+        name.parse().ok().map(|index| (index, spec))
+    } else {
+        None
+    }
+}
+
+/// A parsed format spec: `[[fill]align][sign][#][0][width][grouping][.precision][type]`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FormatSpec {
+    pub(crate) fill: Option<char>,
+    /// One of `<`, `>`, `^`, or `=` to pad between the sign and the digits.
+    pub(crate) align: Option<char>,
+    /// One of `+`, `-` or ` `.
+    pub(crate) sign: Option<char>,
+    /// `#`: prefix `0b`, `0o` and `0x`, and keep trailing zeros for `g`.
+    pub(crate) alternate: bool,
+    /// `0`: pad numbers with zeros after the sign.
+    pub(crate) zero: bool,
+    pub(crate) width: usize,
+    /// `,` or `_` between thousands.
+    pub(crate) grouping: Option<char>,
+    pub(crate) precision: Option<usize>,
+    pub(crate) ty: Option<char>,
+}
+
+impl FormatSpec {
+    pub(crate) fn parse(spec: &str) -> Result<Self, String> {
+        let chars: Vec<char> = spec.chars().collect();
+        let is_align = |c: &char| matches!(c, '<' | '>' | '^' | '=');
+        let mut out = FormatSpec::default();
+        let mut i = 0;
+
+        if chars.get(1).is_some_and(is_align) {
+            out.fill = Some(chars[0]);
+            out.align = Some(chars[1]);
+            i = 2;
+        } else if chars.first().is_some_and(is_align) {
+            out.align = Some(chars[0]);
+            i = 1;
+        }
+        if let Some(&c @ ('+' | '-' | ' ')) = chars.get(i) {
+            out.sign = Some(c);
+            i += 1;
+        }
+        if chars.get(i) == Some(&'#') {
+            out.alternate = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            out.zero = true;
+            i += 1;
+        }
+        out.width = parse_digits(&chars, &mut i)?.unwrap_or(0);
+        if let Some(&c @ (',' | '_')) = chars.get(i) {
+            out.grouping = Some(c);
+            i += 1;
+        }
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            let precision = parse_digits(&chars, &mut i)?;
+            out.precision =
+                Some(precision.ok_or("ValueError: Format specifier missing precision")?);
+        }
+        if let Some(&c) = chars.get(i)
+            && (c.is_ascii_alphabetic() || c == '%')
+        {
+            out.ty = Some(c);
+            i += 1;
+        }
+
+        if i != chars.len() {
+            return Err(format!("ValueError: Invalid format specifier '{spec}'"));
+        }
+        Ok(out)
+    }
+
+    /// Refuses a width or precision over `max`, which would pad the text past it.
+    pub(crate) fn check_len(&self, max: Option<usize>) -> Result<(), String> {
+        let len = self.width.max(self.precision.unwrap_or(0));
+        match max {
+            Some(max) if len > max => Err(format!(
+                "MemoryLimitError: format width of {len} exceeds the maximum of {max}"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn apply(&self, value: &Value) -> Result<String, String> {
+        match value {
+            Value::Int(i) => self.format_int(&BigInt::from(*i)),
+            Value::BigInt(i) => self.format_int(i),
+            Value::Bool(b) if self.ty.is_some() => self.format_int(&BigInt::from(*b as i64)),
+            Value::Float(f) => self.format_float(*f),
+            _ => {
+                let type_name = get_type_name(value);
+                if let Some(c) = self.ty.filter(|&c| c != 's') {
+                    return Err(unknown_code(c, &type_name));
+                }
+                if self.sign.is_some() {
+                    return Err("ValueError: Sign not allowed in string format specifier".into());
+                }
+                if self.align == Some('=') {
+                    return Err(
+                        "ValueError: '=' alignment not allowed in string format specifier".into(),
+                    );
+                }
+                Ok(self.format_text(&value.to_string()))
+            }
+        }
+    }
+
+    /// Pads `text`, truncated to the precision, as a string. Strings align left by default.
+    pub(crate) fn format_text(&self, text: &str) -> String {
+        let text: String = match self.precision {
+            Some(p) => text.chars().take(p).collect(),
+            None => text.to_string(),
+        };
+        let fill = self.fill.unwrap_or(if self.zero { '0' } else { ' ' });
+        self.pad(&text, fill, self.align.unwrap_or('<'))
+    }
+
+    pub(crate) fn format_int(&self, n: &BigInt) -> Result<String, String> {
+        let (radix, prefix) = match self.ty {
+            None | Some('d' | 'n') => (10, ""),
+            Some('b') => (2, "0b"),
+            Some('o') => (8, "0o"),
+            Some('x') => (16, "0x"),
+            Some('X') => (16, "0X"),
+            Some('c') => {
+                let c = n
+                    .to_u32()
+                    .and_then(char::from_u32)
+                    .ok_or("ValueError: %c arg not in range(0x110000)")?;
+                return Ok(self.pad(&c.to_string(), self.fill.unwrap_or(' '), '>'));
+            }
+            Some('e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%') => {
+                return self.format_float(n.to_f64().unwrap_or(f64::NAN));
+            }
+            Some(c) => return Err(unknown_code(c, "int")),
+        };
+        if self.precision.is_some() {
+            return Err("ValueError: Precision not allowed in integer format specifier".into());
+        }
+
+        let mut digits = n.magnitude().to_str_radix(radix);
+        if self.ty == Some('X') {
+            digits.make_ascii_uppercase();
+        }
+        if let Some(sep) = self.grouping {
+            digits = group_digits(&digits, sep, if radix == 10 { 3 } else { 4 });
+        }
+        let prefix = if self.alternate { prefix } else { "" };
+        Ok(self.pad_number(n.sign() == Sign::Minus, prefix, &digits))
+    }
+
+    pub(crate) fn format_float(&self, f: f64) -> Result<String, String> {
+        let x = f.abs();
+        let mut body = if x.is_nan() {
+            "nan".to_string()
+        } else if x.is_infinite() {
+            "inf".to_string()
+        } else {
+            match self.ty {
+                Some('f' | 'F') => format!("{:.*}", self.precision.unwrap_or(6), x),
+                Some('e' | 'E') => exponent(x, self.precision.unwrap_or(6)),
+                Some('g' | 'G' | 'n') => general(x, self.precision.unwrap_or(6), self.alternate),
+                Some('%') => format!("{:.*}", self.precision.unwrap_or(6), x * 100.0),
+                None => match self.precision {
+                    Some(p) => general(x, p, self.alternate),
+                    None => Value::Float(x).to_string(),
+                },
+                Some(c) => return Err(unknown_code(c, "float")),
+            }
+        };
+        if matches!(self.ty, Some('E' | 'F' | 'G')) {
+            body.make_ascii_uppercase();
+        }
+        if self.ty == Some('%') {
+            body.push('%');
+        }
+        if let Some(sep) = self.grouping {
+            let int_len = body
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(body.len());
+            body = group_digits(&body[..int_len], sep, 3) + &body[int_len..];
+        }
+        let negative = f.is_sign_negative() && !f.is_nan();
+        Ok(self.pad_number(negative, "", &body))
+    }
+
+    // Numbers align right by default, and `0` pads between the sign and the digits.
+    fn pad_number(&self, negative: bool, prefix: &str, digits: &str) -> String {
+        let sign = match (negative, self.sign) {
+            (true, _) => "-",
+            (false, Some('+')) => "+",
+            (false, Some(' ')) => " ",
+            _ => "",
+        };
+        let fill = self.fill.unwrap_or(if self.zero { '0' } else { ' ' });
+        let align = self.align.unwrap_or(if self.zero { '=' } else { '>' });
+        if align == '=' {
+            let head = format!("{sign}{prefix}");
+            let used = head.chars().count() + digits.chars().count();
+            let padding: String =
+                core::iter::repeat_n(fill, self.width.saturating_sub(used)).collect();
+            format!("{head}{padding}{digits}")
+        } else {
+            self.pad(&format!("{sign}{prefix}{digits}"), fill, align)
+        }
+    }
+
+    fn pad(&self, text: &str, fill: char, align: char) -> String {
+        let total = self.width.saturating_sub(text.chars().count());
+        let (left, right) = match align {
+            '<' => (0, total),
+            '^' => (total / 2, total - total / 2),
+            _ => (total, 0),
+        };
+        let mut out = String::with_capacity(text.len() + total);
+        out.extend(core::iter::repeat_n(fill, left));
+        out.push_str(text);
+        out.extend(core::iter::repeat_n(fill, right));
+        out
+    }
+}
+
+fn parse_digits(chars: &[char], i: &mut usize) -> Result<Option<usize>, String> {
+    let start = *i;
+    while chars.get(*i).is_some_and(char::is_ascii_digit) {
+        *i += 1;
+    }
+    if start == *i {
+        return Ok(None);
+    }
+    let digits: String = chars[start..*i].iter().collect();
+    digits
+        .parse()
+        .map(Some)
+        .map_err(|_| "ValueError: Too many decimal digits in format string".to_string())
+}
+
+fn unknown_code(code: char, type_name: &str) -> String {
+    format!("ValueError: Unknown format code '{code}' for object of type '{type_name}'")
+}
+
+// Inserts `sep` between every `size` digits, counting from the right.
+fn group_digits(digits: &str, sep: char, size: usize) -> String {
+    let len = digits.chars().count();
+    let mut out = String::with_capacity(digits.len() + len / size);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(size) {
+            out.push(sep);
+        }
+        out.push(c);
+    }
+    out
+}
+
+// Scientific notation with a signed, two digit exponent: `1.500000e+02`.
+fn exponent(x: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, x);
+    let (mantissa, exp) = text.split_once('e').unwrap_or((&text, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let sign = if exp < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exp.abs())
+}
+
+// `g`: fixed or scientific notation depending on the exponent, without trailing zeros.
+fn general(x: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    let exp = if x == 0.0 {
+        0
+    } else {
+        let text = format!("{:.*e}", precision - 1, x);
+        text.split_once('e')
+            .and_then(|(_, e)| e.parse::<i32>().ok())
+            .unwrap_or(0)
+    };
+
+    let text = if -4 <= exp && exp < precision as i32 {
+        format!("{:.*}", (precision as i32 - 1 - exp) as usize, x)
+    } else {
+        exponent(x, precision - 1)
+    };
+    if alternate {
+        return text;
+    }
+
+    let (mantissa, exp) = match text.find('e') {
+        Some(pos) => text.split_at(pos),
+        None => (text.as_str(), ""),
+    };
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    format!("{mantissa}{exp}")
+}
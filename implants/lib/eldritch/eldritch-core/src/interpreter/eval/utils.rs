@@ -1,10 +1,11 @@
-use super::super::super::ast::Value;
+use super::super::super::ast::{LazyIterable, Value, ValueIter};
 use super::super::super::token::Span;
 use super::super::core::Interpreter;
 use super::super::error::{EldritchError, EldritchErrorKind};
 use super::super::introspection::get_type_name;
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 pub(crate) fn to_iterable(
@@ -18,6 +19,7 @@ pub(crate) fn to_iterable(
         Value::Set(s) => Ok(s.read().iter().cloned().collect()),
         Value::Dictionary(d) => Ok(d.read().keys().cloned().collect()),
         Value::String(s) => Ok(s.chars().map(|c| Value::String(c.to_string())).collect()),
+        Value::Iterable(it) => materialize(interp, it, span),
         _ => interp.error(
             EldritchErrorKind::TypeError,
             &format!("Type '{:?}' is not iterable", get_type_name(val)),
@@ -26,6 +28,60 @@ pub(crate) fn to_iterable(
     }
 }
 
+/// Like `to_iterable`, but yields the items one at a time so lazy iterables are never
+/// materialized. Containers are snapshotted, so the body may modify them.
+pub(crate) fn iterate(
+    interp: &Interpreter,
+    val: &Value,
+    span: Span,
+) -> Result<ValueIter, EldritchError> {
+    match val.try_iter() {
+        Some(items) => Ok(items),
+        None => interp.error(
+            EldritchErrorKind::TypeError,
+            &format!("Type '{:?}' is not iterable", get_type_name(val)),
+            span,
+        ),
+    }
+}
+
+/// Collects the items of a lazy iterable, raising a `MemoryLimitError` as soon as there
+/// are more than `ExecutionLimits::max_sequence_len` of them.
+pub(crate) fn materialize(
+    interp: &Interpreter,
+    it: &Arc<dyn LazyIterable>,
+    span: Span,
+) -> Result<Vec<Value>, EldritchError> {
+    it.items(interp.budget.limits.max_sequence_len)
+        .or_else(|msg| iteration_error(interp, msg, span))
+}
+
+/// Refuses to hold more than `ExecutionLimits::max_sequence_len` collected items.
+pub(crate) fn check_collected_len(
+    interp: &Interpreter,
+    len: usize,
+    span: Span,
+) -> Result<(), EldritchError> {
+    match interp.budget.limits.max_sequence_len {
+        Some(max) if len > max => interp.error(
+            EldritchErrorKind::MemoryLimitError,
+            &format!("result of length {len} exceeds the maximum of {max}"),
+            span,
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Raises an error produced while iterating, keeping its kind if the message names one.
+pub(crate) fn iteration_error<T>(
+    interp: &Interpreter,
+    msg: String,
+    span: Span,
+) -> Result<T, EldritchError> {
+    let (kind, msg) = parse_error_kind(&msg);
+    interp.error(kind, msg, span)
+}
+
 pub(crate) fn parse_error_kind(msg: &str) -> (EldritchErrorKind, &str) {
     if let Some(rest) = msg.strip_prefix("TypeError: ") {
         (EldritchErrorKind::TypeError, rest)
@@ -39,6 +95,8 @@ pub(crate) fn parse_error_kind(msg: &str) -> (EldritchErrorKind, &str) {
         (EldritchErrorKind::AttributeError, rest)
     } else if let Some(rest) = msg.strip_prefix("NameError: ") {
         (EldritchErrorKind::NameError, rest)
+    } else if let Some(rest) = msg.strip_prefix("MemoryLimitError: ") {
+        (EldritchErrorKind::MemoryLimitError, rest)
     } else {
         (EldritchErrorKind::RuntimeError, msg)
     }
@@ -1,11 +1,15 @@
 use super::super::ast::{
-    Environment, Expr, ExprKind, Function, Param, RuntimeParam, Stmt, StmtKind, Value,
+    Environment, ExceptHandler, Expr, ExprKind, Function, Param, RuntimeParam, Stmt, StmtKind,
+    Value, ValueIter,
 };
-use super::super::token::TokenKind;
+use super::super::token::{Span, TokenKind};
+use super::Freezable;
 use super::core::{Flow, Interpreter};
 use super::error::{EldritchError, EldritchErrorKind};
-use super::eval::{apply_binary_op_pub, evaluate};
+use super::eval::ops::{binary_op, check_sequence_len, sequence_operand};
+use super::eval::{evaluate, iteration_error, materialize};
 use super::introspection::{get_type_name, is_truthy};
+use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
 use alloc::string::ToString;
@@ -17,6 +21,7 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
     if interp.flow != Flow::Next {
         return Ok(());
     }
+    interp.enter_statement(stmt.span)?;
 
     match &stmt.kind {
         StmtKind::Expression(expr) => {
@@ -64,28 +69,19 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
                 params: runtime_params,
                 body: body.clone(),
                 closure: interp.env.clone(),
+                #[cfg(feature = "bytecode")]
+                code: None,
             });
             interp.env.write().values.insert(name.clone(), func);
         }
         StmtKind::For(idents, iterable, body) => {
             let iterable_val = evaluate(interp, iterable)?;
-            let items: Vec<Value> = match iterable_val {
-                Value::List(l) => l.read().clone(),
-                Value::Tuple(t) => t.clone(),
-                Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
-                Value::Bytes(b) => b.iter().map(|&byte| Value::Int(byte as i64)).collect(),
-                Value::Dictionary(d) => d.read().keys().cloned().collect(),
-                Value::Set(s) => s.read().iter().cloned().collect(),
-                _ => return interp.error(
-                    EldritchErrorKind::TypeError,
-                    &format!(
-                        "'for' loop can only iterate over lists/iterables. Found {iterable_val:?}"
-                    ),
-                    iterable.span,
-                ),
-            };
+            let items = for_loop_iter(interp, iterable_val, iterable.span)?;
 
             for item in items {
+                let item = item.or_else(|msg| iteration_error(interp, msg, iterable.span))?;
+                interp.check_budget(stmt.span)?;
+
                 // Scope per iteration to prevent leaking variables
                 let parent_env = interp.env.clone();
                 let printer = parent_env.read().printer.clone();
@@ -94,6 +90,7 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
                     values: BTreeMap::new(),
                     printer,
                     libraries: BTreeSet::new(),
+                    max_sequence_len: interp.budget.limits.max_sequence_len,
                 }));
                 interp.env = new_env;
 
@@ -101,24 +98,14 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
                 if idents.len() == 1 {
                     interp.define_variable(&idents[0], item);
                 } else {
-                    let parts = match item {
-                        Value::List(l) => l.read().clone(),
-                        Value::Tuple(t) => t.clone(),
-                        _ => {
+                    let parts = match unpack_loop_item(interp, item, idents.len(), stmt.span) {
+                        Ok(parts) => parts,
+                        Err(e) => {
                             interp.env = parent_env;
-                            return interp.error(
-                                EldritchErrorKind::TypeError,
-                                "Cannot unpack non-iterable",
-                                stmt.span,
-                            );
+                            return Err(e);
                         }
                     };
 
-                    if parts.len() != idents.len() {
-                        interp.env = parent_env;
-                        return interp.error(EldritchErrorKind::ValueError, &format!("ValueError: too many/not enough values to unpack (expected {}, got {})", idents.len(), parts.len()), stmt.span);
-                    }
-
                     for (var, val) in idents.iter().zip(parts.into_iter()) {
                         interp.define_variable(var, val);
                     }
@@ -142,6 +129,48 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
                 }
             }
         }
+        StmtKind::While(condition, body, else_branch) => {
+            let mut iterations: u64 = 0;
+            loop {
+                let eval_cond = &evaluate(interp, condition)?;
+                if !is_truthy(eval_cond) {
+                    // Like Python, `else` only runs when the loop was not broken out of
+                    if let Some(else_stmts) = else_branch {
+                        execute_stmts(interp, else_stmts)?;
+                    }
+                    break;
+                }
+
+                iterations += 1;
+                if let Some(max) = interp.budget.limits.max_loop_iterations
+                    && iterations > max
+                {
+                    return interp.error(
+                        EldritchErrorKind::LoopLimitError,
+                        &format!("while loop exceeded the maximum of {max} iterations"),
+                        stmt.span,
+                    );
+                }
+                interp.check_budget(stmt.span)?;
+
+                execute_stmts(interp, body)?;
+
+                match &interp.flow {
+                    Flow::Break => {
+                        interp.flow = Flow::Next;
+                        break;
+                    }
+                    Flow::Continue => {
+                        interp.flow = Flow::Next;
+                    }
+                    Flow::Return(_) => return Ok(()),
+                    Flow::Next => {}
+                }
+            }
+        }
+        StmtKind::Try(body, handlers, finally_body) => {
+            execute_try(interp, body, handlers, finally_body.as_deref())?;
+        }
         StmtKind::Break => interp.flow = Flow::Break,
         StmtKind::Continue => interp.flow = Flow::Continue,
         StmtKind::Pass => {} // Do nothing
@@ -155,6 +184,139 @@ pub fn execute(interp: &mut Interpreter, stmt: &Stmt) -> Result<(), EldritchErro
     Ok(())
 }
 
+fn execute_try(
+    interp: &mut Interpreter,
+    body: &[Stmt],
+    handlers: &[ExceptHandler],
+    finally_body: Option<&[Stmt]>,
+) -> Result<(), EldritchError> {
+    let result = match execute_stmts(interp, body) {
+        Err(err) => handle_error(interp, handlers, err),
+        ok => ok,
+    };
+
+    if let Some(finally_stmts) = finally_body {
+        // The finally block runs with a clean flow; a break/continue/return from the
+        // try or except blocks is resumed afterwards unless finally overrides it.
+        let pending_flow = core::mem::replace(&mut interp.flow, Flow::Next);
+        execute_stmts(interp, finally_stmts)?;
+        if interp.flow != Flow::Next {
+            // Like Python, leaving a finally block early discards any pending error,
+            // except one the script isn't allowed to handle.
+            return match result {
+                Err(err) if !err.kind.is_catchable() => {
+                    interp.flow = Flow::Next;
+                    Err(err)
+                }
+                _ => Ok(()),
+            };
+        }
+        interp.flow = pending_flow;
+    }
+
+    result
+}
+
+fn handle_error(
+    interp: &mut Interpreter,
+    handlers: &[ExceptHandler],
+    err: EldritchError,
+) -> Result<(), EldritchError> {
+    if !err.kind.is_catchable() {
+        return Err(err);
+    }
+
+    for handler in handlers {
+        if let Some(kind_expr) = &handler.kind {
+            let kinds = evaluate(interp, kind_expr)?;
+            if !error_kind_matches(interp, &kinds, &err, kind_expr.span)? {
+                continue;
+            }
+        }
+        if let Some(name) = &handler.name {
+            interp.assign_variable(name, error_to_value(&err));
+        }
+        return execute_stmts(interp, &handler.body);
+    }
+
+    Err(err)
+}
+
+pub(crate) fn error_kind_matches(
+    interp: &Interpreter,
+    kinds: &Value,
+    err: &EldritchError,
+    kind_span: Span,
+) -> Result<bool, EldritchError> {
+    match kinds {
+        Value::String(name) => match EldritchErrorKind::from_name(name) {
+            Some(kind) => Ok(kind == err.kind),
+            None => interp.error(
+                EldritchErrorKind::ValueError,
+                &format!("Unknown error kind '{name}' in except clause"),
+                kind_span,
+            ),
+        },
+        Value::List(l) => {
+            let items = l.read().clone();
+            for item in items {
+                if error_kind_matches(interp, &item, err, kind_span)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        Value::Tuple(items) => {
+            for item in items {
+                if error_kind_matches(interp, item, err, kind_span)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        _ => interp.error(
+            EldritchErrorKind::TypeError,
+            &format!(
+                "except clause expects an error kind string or a list/tuple of them, got '{}'",
+                get_type_name(kinds)
+            ),
+            kind_span,
+        ),
+    }
+}
+
+/// Expose a caught error to the script as `{"kind", "message", "span": {"start", "end", "line"}}`.
+pub(crate) fn error_to_value(err: &EldritchError) -> Value {
+    let mut span = BTreeMap::new();
+    span.insert(
+        Value::String("start".to_string()),
+        Value::Int(err.span.start as i64),
+    );
+    span.insert(
+        Value::String("end".to_string()),
+        Value::Int(err.span.end as i64),
+    );
+    span.insert(
+        Value::String("line".to_string()),
+        Value::Int(err.span.line as i64),
+    );
+
+    let mut dict = BTreeMap::new();
+    dict.insert(
+        Value::String("kind".to_string()),
+        Value::String(err.kind.to_string()),
+    );
+    dict.insert(
+        Value::String("message".to_string()),
+        Value::String(err.message.clone()),
+    );
+    dict.insert(
+        Value::String("span".to_string()),
+        Value::Dictionary(Arc::new(Freezable::new(span))),
+    );
+    Value::Dictionary(Arc::new(Freezable::new(dict)))
+}
+
 pub fn hoist_functions(interp: &mut Interpreter, stmts: &[Stmt]) -> Result<(), EldritchError> {
     // Collect functions to hoist so we don't hold read locks while evaluating default params
     // Only hoist the *first* definition of a given name in this block, to allow forward references,
@@ -189,6 +351,8 @@ pub fn hoist_functions(interp: &mut Interpreter, stmts: &[Stmt]) -> Result<(), E
             params: runtime_params,
             body: body.clone(),
             closure: interp.env.clone(),
+            #[cfg(feature = "bytecode")]
+            code: None,
         });
         interp.env.write().values.insert(name.clone(), func);
     }
@@ -205,6 +369,68 @@ pub fn execute_stmts(interp: &mut Interpreter, stmts: &[Stmt]) -> Result<(), Eld
     Ok(())
 }
 
+/// Start iterating the items a `for` loop runs over. Containers are snapshotted first,
+/// while lazy iterables produce their items as the loop advances.
+pub(crate) fn for_loop_iter(
+    interp: &Interpreter,
+    iterable_val: Value,
+    span: Span,
+) -> Result<ValueIter, EldritchError> {
+    let items: Vec<Value> = match iterable_val {
+        Value::Iterable(it) => return Ok(it.iter()),
+        Value::List(l) => l.read().clone(),
+        Value::Tuple(t) => t,
+        Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
+        Value::Bytes(b) => b.iter().map(|&byte| Value::Int(byte as i64)).collect(),
+        Value::Dictionary(d) => d.read().keys().cloned().collect(),
+        Value::Set(s) => s.read().iter().cloned().collect(),
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                &format!(
+                    "'for' loop can only iterate over lists/iterables. Found {iterable_val:?}"
+                ),
+                span,
+            );
+        }
+    };
+    Ok(Box::new(items.into_iter().map(Ok)))
+}
+
+/// Split a `for` loop item across multiple loop variables.
+pub(crate) fn unpack_loop_item(
+    interp: &Interpreter,
+    item: Value,
+    count: usize,
+    span: Span,
+) -> Result<Vec<Value>, EldritchError> {
+    let parts = match item {
+        Value::List(l) => l.read().clone(),
+        Value::Tuple(t) => t,
+        Value::Iterable(it) => materialize(interp, &it, span)?,
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                "Cannot unpack non-iterable",
+                span,
+            );
+        }
+    };
+
+    if parts.len() != count {
+        return interp.error(
+            EldritchErrorKind::ValueError,
+            &format!(
+                "ValueError: too many/not enough values to unpack (expected {}, got {})",
+                count,
+                parts.len()
+            ),
+            span,
+        );
+    }
+    Ok(parts)
+}
+
 fn assign(interp: &mut Interpreter, target: &Expr, value: Value) -> Result<(), EldritchError> {
     match &target.kind {
         ExprKind::Identifier(name) => {
@@ -212,31 +438,7 @@ fn assign(interp: &mut Interpreter, target: &Expr, value: Value) -> Result<(), E
             Ok(())
         }
         ExprKind::List(elements) | ExprKind::Tuple(elements) => {
-            // Unpacking
-            let values = match value {
-                Value::List(l) => l.read().clone(),
-                Value::Tuple(t) => t.clone(),
-                _ => {
-                    return interp.error(
-                        EldritchErrorKind::TypeError,
-                        &format!("cannot unpack non-iterable {:?}", get_type_name(&value)),
-                        target.span,
-                    );
-                }
-            };
-
-            if elements.len() != values.len() {
-                return interp.error(
-                    EldritchErrorKind::ValueError,
-                    &format!(
-                        "ValueError: too many/not enough values to unpack (expected {}, got {})",
-                        elements.len(),
-                        values.len()
-                    ),
-                    target.span,
-                );
-            }
-
+            let values = unpack_assignment(interp, value, elements.len(), target.span)?;
             for (target_elem, val_elem) in elements.iter().zip(values.into_iter()) {
                 assign(interp, target_elem, val_elem)?;
             }
@@ -245,44 +447,7 @@ fn assign(interp: &mut Interpreter, target: &Expr, value: Value) -> Result<(), E
         ExprKind::Index(obj_expr, index_expr) => {
             let obj = evaluate(interp, obj_expr)?;
             let index = evaluate(interp, index_expr)?;
-            match obj {
-                Value::List(l) => {
-                    let idx_int = match index {
-                        Value::Int(i) => i,
-                        _ => {
-                            return interp.error(
-                                EldritchErrorKind::TypeError,
-                                "List indices must be integers",
-                                index_expr.span,
-                            );
-                        }
-                    };
-                    let mut list = l.write();
-                    let true_idx = if idx_int < 0 {
-                        list.len() as i64 + idx_int
-                    } else {
-                        idx_int
-                    };
-                    if true_idx < 0 || true_idx as usize >= list.len() {
-                        return interp.error(
-                            EldritchErrorKind::IndexError,
-                            "List assignment index out of range",
-                            target.span,
-                        );
-                    }
-                    list[true_idx as usize] = value;
-                    Ok(())
-                }
-                Value::Dictionary(d) => {
-                    d.write().insert(index, value);
-                    Ok(())
-                }
-                _ => interp.error(
-                    EldritchErrorKind::TypeError,
-                    "Object does not support item assignment",
-                    target.span,
-                ),
-            }
+            assign_index(interp, obj, index, value, target.span, index_expr.span)
         }
         _ => interp.error(
             EldritchErrorKind::SyntaxError,
@@ -292,6 +457,90 @@ fn assign(interp: &mut Interpreter, target: &Expr, value: Value) -> Result<(), E
     }
 }
 
+/// Unpack the right-hand side of `a, b = value`.
+pub(crate) fn unpack_assignment(
+    interp: &Interpreter,
+    value: Value,
+    count: usize,
+    span: Span,
+) -> Result<Vec<Value>, EldritchError> {
+    let values = match value {
+        Value::List(l) => l.read().clone(),
+        Value::Tuple(t) => t,
+        Value::Iterable(it) => materialize(interp, &it, span)?,
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                &format!("cannot unpack non-iterable {:?}", get_type_name(&value)),
+                span,
+            );
+        }
+    };
+
+    if count != values.len() {
+        return interp.error(
+            EldritchErrorKind::ValueError,
+            &format!(
+                "ValueError: too many/not enough values to unpack (expected {}, got {})",
+                count,
+                values.len()
+            ),
+            span,
+        );
+    }
+    Ok(values)
+}
+
+/// Store `value` into `obj[index]`.
+pub(crate) fn assign_index(
+    interp: &Interpreter,
+    obj: Value,
+    index: Value,
+    value: Value,
+    span: Span,
+    index_span: Span,
+) -> Result<(), EldritchError> {
+    check_not_frozen(interp, &obj, span)?;
+    match obj {
+        Value::List(l) => {
+            let idx_int = match index {
+                Value::Int(i) => i,
+                _ => {
+                    return interp.error(
+                        EldritchErrorKind::TypeError,
+                        "List indices must be integers",
+                        index_span,
+                    );
+                }
+            };
+            let mut list = l.write();
+            let true_idx = if idx_int < 0 {
+                list.len() as i64 + idx_int
+            } else {
+                idx_int
+            };
+            if true_idx < 0 || true_idx as usize >= list.len() {
+                return interp.error(
+                    EldritchErrorKind::IndexError,
+                    "List assignment index out of range",
+                    span,
+                );
+            }
+            list[true_idx as usize] = value;
+            Ok(())
+        }
+        Value::Dictionary(d) => {
+            d.write().insert(index, value);
+            Ok(())
+        }
+        _ => interp.error(
+            EldritchErrorKind::TypeError,
+            "Object does not support item assignment",
+            span,
+        ),
+    }
+}
+
 fn execute_augmented_assignment(
     interp: &mut Interpreter,
     target: &Expr,
@@ -305,133 +554,151 @@ fn execute_augmented_assignment(
     match &target.kind {
         ExprKind::Identifier(name) => {
             let left = interp.lookup_variable(name, span)?;
-
-            if matches!(op, TokenKind::PlusAssign) && try_inplace_add(&left, &right) {
-                return Ok(());
+            if let Some(new_val) = augmented_value(interp, left, op, right, span)? {
+                interp.assign_variable(name, new_val);
             }
-
-            let bin_op = augmented_op_to_binary(op).ok_or_else(|| EldritchError {
-                span,
-                message: "Unknown augmented assignment operator".to_string(),
-                kind: EldritchErrorKind::SyntaxError,
-                stack: Vec::new(),
-            })?;
-
-            // Construct dummy expressions for apply_binary_op call to reuse logic
-            let left_expr = Expr {
-                kind: ExprKind::Literal(left),
-                span,
-            };
-            let right_expr = Expr {
-                kind: ExprKind::Literal(right),
-                span,
-            };
-
-            let new_val = apply_binary_op_pub(interp, &left_expr, &bin_op, &right_expr, span)?;
-            interp.assign_variable(name, new_val);
             Ok(())
         }
         ExprKind::Index(obj_expr, index_expr) => {
             let obj = evaluate(interp, obj_expr)?;
             let index = evaluate(interp, index_expr)?;
+            augmented_index(interp, obj, index, op, right, span, index_expr.span)
+        }
+        _ => interp.error(
+            EldritchErrorKind::SyntaxError,
+            "Illegal target for augmented assignment",
+            span,
+        ),
+    }
+}
 
-            // This is tricky: we need to get the item, op it, and set it back.
-            // For mutable objects (List, Dict), we can modify in place or set item.
-
-            let current_val = match &obj {
-                Value::List(l) => {
-                    let idx_int = match index {
-                        Value::Int(i) => i,
-                        _ => {
-                            return interp.error(
-                                EldritchErrorKind::TypeError,
-                                "List indices must be integers",
-                                index_expr.span,
-                            );
-                        }
-                    };
-                    let list = l.read();
-                    let true_idx = if idx_int < 0 {
-                        list.len() as i64 + idx_int
-                    } else {
-                        idx_int
-                    };
-                    if true_idx < 0 || true_idx as usize >= list.len() {
-                        return interp.error(
-                            EldritchErrorKind::IndexError,
-                            "List index out of range",
-                            span,
-                        );
-                    }
-                    list[true_idx as usize].clone()
-                }
-                Value::Dictionary(d) => {
-                    let dict = d.read();
-                    match dict.get(&index) {
-                        Some(v) => v.clone(),
-                        None => return interp.error(EldritchErrorKind::KeyError, "KeyError", span),
-                    }
-                }
+/// Compute `left <op>= right`.
+///
+/// Returns `None` when the update happened in place (e.g. `list += list`) and
+/// nothing needs to be stored back.
+pub(crate) fn augmented_value(
+    interp: &mut Interpreter,
+    left: Value,
+    op: &TokenKind,
+    mut right: Value,
+    span: Span,
+) -> Result<Option<Value>, EldritchError> {
+    if matches!(op, TokenKind::PlusAssign) {
+        check_not_frozen(interp, &left, span)?;
+        right = sequence_operand(interp, right, span)?;
+        check_sequence_len(interp, &left, &TokenKind::Plus, &right, span)?;
+        if try_inplace_add(&left, &right) {
+            return Ok(None);
+        }
+    }
+
+    let bin_op = augmented_op_to_binary(op).ok_or_else(|| EldritchError {
+        span,
+        message: "Unknown augmented assignment operator".to_string(),
+        kind: EldritchErrorKind::SyntaxError,
+        stack: Vec::new(),
+        library: None,
+    })?;
+
+    binary_op(interp, left, &bin_op, right, span).map(Some)
+}
+
+/// Apply `obj[index] <op>= right`.
+pub(crate) fn augmented_index(
+    interp: &mut Interpreter,
+    obj: Value,
+    index: Value,
+    op: &TokenKind,
+    right: Value,
+    span: Span,
+    index_span: Span,
+) -> Result<(), EldritchError> {
+    check_not_frozen(interp, &obj, span)?;
+    // This is tricky: we need to get the item, op it, and set it back.
+    // For mutable objects (List, Dict), we can modify in place or set item.
+
+    let current_val = match &obj {
+        Value::List(l) => {
+            let idx_int = match index {
+                Value::Int(i) => i,
                 _ => {
                     return interp.error(
                         EldritchErrorKind::TypeError,
-                        "Object does not support item assignment",
-                        span,
+                        "List indices must be integers",
+                        index_span,
                     );
                 }
             };
-
-            if matches!(op, TokenKind::PlusAssign) && try_inplace_add(&current_val, &right) {
-                return Ok(());
+            let list = l.read();
+            let true_idx = if idx_int < 0 {
+                list.len() as i64 + idx_int
+            } else {
+                idx_int
+            };
+            if true_idx < 0 || true_idx as usize >= list.len() {
+                return interp.error(
+                    EldritchErrorKind::IndexError,
+                    "List index out of range",
+                    span,
+                );
             }
-
-            let bin_op = augmented_op_to_binary(op).ok_or_else(|| EldritchError {
+            list[true_idx as usize].clone()
+        }
+        Value::Dictionary(d) => {
+            let dict = d.read();
+            match dict.get(&index) {
+                Some(v) => v.clone(),
+                None => return interp.error(EldritchErrorKind::KeyError, "KeyError", span),
+            }
+        }
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                "Object does not support item assignment",
                 span,
-                message: "Unknown augmented assignment operator".to_string(),
-                kind: EldritchErrorKind::SyntaxError,
-                stack: Vec::new(),
-            })?;
+            );
+        }
+    };
 
-            let left_expr = Expr {
-                kind: ExprKind::Literal(current_val),
-                span,
+    let Some(new_val) = augmented_value(interp, current_val, op, right, span)? else {
+        return Ok(());
+    };
+
+    // Set back
+    match obj {
+        Value::List(l) => {
+            // Need to re-calculate index as borrow ends
+            let idx_int = match index {
+                Value::Int(i) => i,
+                _ => unreachable!(),
             };
-            let right_expr = Expr {
-                kind: ExprKind::Literal(right),
-                span,
+            let mut list = l.write();
+            let true_idx = if idx_int < 0 {
+                list.len() as i64 + idx_int
+            } else {
+                idx_int
             };
-            let new_val = apply_binary_op_pub(interp, &left_expr, &bin_op, &right_expr, span)?;
-
-            // Set back
-            match obj {
-                Value::List(l) => {
-                    // Need to re-calculate index as borrow ends
-                    let idx_int = match index {
-                        Value::Int(i) => i,
-                        _ => unreachable!(),
-                    };
-                    let mut list = l.write();
-                    let true_idx = if idx_int < 0 {
-                        list.len() as i64 + idx_int
-                    } else {
-                        idx_int
-                    };
-                    list[true_idx as usize] = new_val;
-                    Ok(())
-                }
-                Value::Dictionary(d) => {
-                    d.write().insert(index, new_val);
-                    Ok(())
-                }
-                _ => unreachable!(),
-            }
+            list[true_idx as usize] = new_val;
+            Ok(())
         }
-        _ => interp.error(
-            EldritchErrorKind::SyntaxError,
-            "Illegal target for augmented assignment",
+        Value::Dictionary(d) => {
+            d.write().insert(index, new_val);
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+// Lists, dicts and sets frozen by `Value::freeze` reject every in-place update.
+fn check_not_frozen(interp: &Interpreter, value: &Value, span: Span) -> Result<(), EldritchError> {
+    if matches!(value, Value::List(_) | Value::Dictionary(_) | Value::Set(_)) && value.is_frozen() {
+        return interp.error(
+            EldritchErrorKind::TypeError,
+            &format!("cannot mutate frozen {}", get_type_name(value)),
             span,
-        ),
+        );
     }
+    Ok(())
 }
 
 fn augmented_op_to_binary(op: &TokenKind) -> Option<TokenKind> {
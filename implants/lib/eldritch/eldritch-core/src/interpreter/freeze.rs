@@ -0,0 +1,115 @@
+use crate::ast::Value;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+use spin::RwLock;
+
+/// The shared storage behind a list, dict or set: its lock, plus the flag set by
+/// [`Value::freeze`]. It derefs to the lock, so `read()` and `write()` work as usual.
+pub struct Freezable<T> {
+    lock: RwLock<T>,
+    frozen: AtomicBool,
+}
+
+impl<T> Freezable<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            lock: RwLock::new(value),
+            frozen: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether [`Value::freeze`] has reached this container.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::Acquire)
+    }
+
+    // Marks the container as frozen, returning false if it already was.
+    fn mark(&self) -> bool {
+        !self.frozen.swap(true, Ordering::AcqRel)
+    }
+
+    /// Fails with a `TypeError` message if the container is frozen.
+    pub(crate) fn check_mutable(&self, type_name: &str) -> Result<(), String> {
+        if self.is_frozen() {
+            Err(format!("TypeError: cannot mutate frozen {type_name}"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T> Deref for Freezable<T> {
+    type Target = RwLock<T>;
+
+    fn deref(&self) -> &RwLock<T> {
+        &self.lock
+    }
+}
+
+impl<T> DerefMut for Freezable<T> {
+    fn deref_mut(&mut self) -> &mut RwLock<T> {
+        &mut self.lock
+    }
+}
+
+impl<T: Default> Default for Freezable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Freezable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.lock.fmt(f)
+    }
+}
+
+impl Value {
+    /// Makes this value and everything reachable from it immutable, as Starlark does for the
+    /// globals of a loaded module. Mutating a frozen list, dict or set raises a `TypeError`.
+    /// Freezing can't be undone; copies such as `list(x)` are mutable again.
+    pub fn freeze(&self) {
+        match self {
+            Value::List(l) => {
+                if l.mark() {
+                    l.read().iter().for_each(Value::freeze);
+                }
+            }
+            Value::Dictionary(d) => {
+                if d.mark() {
+                    for (k, v) in d.read().iter() {
+                        k.freeze();
+                        v.freeze();
+                    }
+                }
+            }
+            Value::Set(s) => {
+                if s.mark() {
+                    s.read().iter().for_each(Value::freeze);
+                }
+            }
+            Value::Tuple(items) => items.iter().for_each(Value::freeze),
+            Value::Struct(fields) => fields.values().for_each(Value::freeze),
+            Value::BoundMethod(receiver, _) => receiver.freeze(),
+            _ => {}
+        }
+    }
+
+    /// Whether this value can't be mutated. Lists, dicts and sets are frozen by
+    /// [`Value::freeze`]; tuples and structs are frozen when everything they hold is; all
+    /// other values are immutable and always frozen.
+    pub fn is_frozen(&self) -> bool {
+        match self {
+            Value::List(l) => l.is_frozen(),
+            Value::Dictionary(d) => d.is_frozen(),
+            Value::Set(s) => s.is_frozen(),
+            Value::Tuple(items) => items.iter().all(Value::is_frozen),
+            Value::Struct(fields) => fields.values().all(Value::is_frozen),
+            Value::BoundMethod(receiver, _) => receiver.is_frozen(),
+            _ => true,
+        }
+    }
+}
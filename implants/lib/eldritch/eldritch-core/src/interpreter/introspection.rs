@@ -15,7 +15,12 @@ pub fn is_truthy(value: &Value) -> bool {
         Value::Dictionary(d) => !d.read().is_empty(),
         Value::Set(s) => !s.read().is_empty(),
         Value::Tuple(t) => !t.is_empty(),
-        Value::Function(_)
+        Value::Iterable(it) => it
+            .known_len()
+            .map_or_else(|| it.iter().next().is_some(), |n| n > 0),
+        Value::BigInt(_)
+        | Value::Struct(_)
+        | Value::Function(_)
         | Value::NativeFunction(_, _)
         | Value::NativeFunctionWithKwargs(_, _)
         | Value::BoundMethod(_, _)
@@ -27,7 +32,7 @@ pub fn get_type_name(value: &Value) -> String {
     match value {
         Value::None => "NoneType".to_string(),
         Value::Bool(_) => "bool".to_string(),
-        Value::Int(_) => "int".to_string(),
+        Value::Int(_) | Value::BigInt(_) => "int".to_string(),
         Value::Float(_) => "float".to_string(),
         Value::String(_) => "string".to_string(),
         Value::Bytes(_) => "bytes".to_string(),
@@ -35,6 +40,8 @@ pub fn get_type_name(value: &Value) -> String {
         Value::Dictionary(_) => "dict".to_string(),
         Value::Set(_) => "set".to_string(),
         Value::Tuple(_) => "tuple".to_string(),
+        Value::Iterable(it) => it.type_name().to_string(),
+        Value::Struct(_) => "struct".to_string(),
         Value::Function(_)
         | Value::NativeFunction(_, _)
         | Value::NativeFunctionWithKwargs(_, _)
@@ -45,7 +52,7 @@ pub fn get_type_name(value: &Value) -> String {
 
 pub fn get_dir_attributes(value: &Value) -> Vec<String> {
     let mut attrs = match value {
-        Value::List(_) => vec![
+        Value::List(_) | Value::Iterable(_) => vec![
             "append".to_string(),
             "extend".to_string(),
             "index".to_string(),
@@ -80,6 +87,7 @@ pub fn get_dir_attributes(value: &Value) -> Vec<String> {
         ],
         Value::String(s) => super::methods::get_native_methods(&Value::String(s.clone())),
         Value::Foreign(f) => f.method_names(),
+        Value::Struct(fields) => fields.keys().cloned().collect(),
         _ => Vec::new(),
     };
     attrs.sort();
@@ -0,0 +1,146 @@
+use super::error::EldritchErrorKind;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// A cloneable handle used to cooperatively stop a running interpreter.
+///
+/// Cancellation is checked before every statement, loop iteration and function call,
+/// so a script stops at the next checkpoint rather than immediately. Native library
+/// calls that are already running are allowed to finish.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that every interpreter holding this token stops at its next checkpoint.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Clear a previous cancellation so the token can be reused (e.g. by a REPL).
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Optional budgets enforced for each call to `Interpreter::interpret`.
+///
+/// `timeout` is only enforced when eldritch-core is built with the `std` feature,
+/// since `no_std` targets have no monotonic clock available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Maximum number of interpreter steps (statements, loop iterations and calls).
+    pub max_steps: Option<u64>,
+    /// Maximum wall-clock time a single `interpret` call may run for.
+    pub timeout: Option<Duration>,
+    /// Maximum number of iterations any single `while` loop may run for.
+    pub max_loop_iterations: Option<u64>,
+    /// Maximum length of a string, bytes, list or tuple built with `+` or `*`, of a lazy
+    /// iterable collected into memory, and of a format width or precision, checked before
+    /// the result is allocated.
+    pub max_sequence_len: Option<usize>,
+    /// Maximum number of `eval()` calls that may be nested inside each other.
+    pub max_eval_depth: Option<usize>,
+}
+
+impl ExecutionLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_steps(mut self, steps: u64) -> Self {
+        self.max_steps = Some(steps);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_max_loop_iterations(mut self, iterations: u64) -> Self {
+        self.max_loop_iterations = Some(iterations);
+        self
+    }
+
+    pub fn with_max_sequence_len(mut self, len: usize) -> Self {
+        self.max_sequence_len = Some(len);
+        self
+    }
+
+    pub fn with_max_eval_depth(mut self, depth: usize) -> Self {
+        self.max_eval_depth = Some(depth);
+        self
+    }
+}
+
+/// Per-run accounting for cancellation and `ExecutionLimits`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Budget {
+    pub(crate) token: CancellationToken,
+    pub(crate) limits: ExecutionLimits,
+    pub(crate) steps: u64,
+    /// How many `eval()` calls enclose the code being run.
+    pub(crate) eval_depth: usize,
+    #[cfg(feature = "std")]
+    pub(crate) started: Option<std::time::Instant>,
+}
+
+impl Budget {
+    /// Reset the step counter and clock at the start of a top-level run.
+    pub(crate) fn start(&mut self) {
+        self.steps = 0;
+        self.eval_depth = 0;
+        #[cfg(feature = "std")]
+        {
+            self.started = self.limits.timeout.map(|_| std::time::Instant::now());
+        }
+    }
+
+    /// Count one step and report which limit, if any, has been exceeded.
+    pub(crate) fn tick(&mut self) -> Option<(EldritchErrorKind, String)> {
+        if self.token.is_cancelled() {
+            return Some((
+                EldritchErrorKind::CancelledError,
+                "Execution was cancelled".into(),
+            ));
+        }
+
+        self.steps += 1;
+        if let Some(max) = self.limits.max_steps
+            && self.steps > max
+        {
+            return Some((
+                EldritchErrorKind::StepLimitError,
+                format!("Step limit of {max} exceeded"),
+            ));
+        }
+
+        #[cfg(feature = "std")]
+        if let (Some(timeout), Some(started)) = (self.limits.timeout, self.started)
+            && started.elapsed() > timeout
+        {
+            return Some((
+                EldritchErrorKind::TimeoutError,
+                format!("Execution timed out after {}ms", timeout.as_millis()),
+            ));
+        }
+
+        None
+    }
+}
@@ -1,27 +1,34 @@
 use super::ArgCheck;
 use crate::ast::Value;
+use crate::interpreter::Freezable;
 use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
-use spin::RwLock;
+
+const MUTATING_METHODS: &[&str] = &["update", "popitem", "clear", "pop", "setdefault"];
 
 pub fn handle_dict_methods(
-    d: &Arc<RwLock<BTreeMap<Value, Value>>>,
+    d: &Arc<Freezable<BTreeMap<Value, Value>>>,
     method: &str,
     args: &[Value],
 ) -> Option<Result<Value, alloc::string::String>> {
+    if MUTATING_METHODS.contains(&method)
+        && let Err(e) = d.check_mutable("dict")
+    {
+        return Some(Err(e));
+    }
     match method {
         "keys" => Some((|| {
             args.require(0, "keys")?;
             let keys: Vec<Value> = d.read().keys().cloned().collect();
-            Ok(Value::List(Arc::new(RwLock::new(keys))))
+            Ok(Value::List(Arc::new(Freezable::new(keys))))
         })()),
         "values" => Some((|| {
             args.require(0, "values")?;
             let values: Vec<Value> = d.read().values().cloned().collect();
-            Ok(Value::List(Arc::new(RwLock::new(values))))
+            Ok(Value::List(Arc::new(Freezable::new(values))))
         })()),
         "items" => Some((|| {
             args.require(0, "items")?;
@@ -30,7 +37,7 @@ pub fn handle_dict_methods(
                 .iter()
                 .map(|(k, v)| Value::Tuple(vec![k.clone(), v.clone()]))
                 .collect();
-            Ok(Value::List(Arc::new(RwLock::new(items))))
+            Ok(Value::List(Arc::new(Freezable::new(items))))
         })()),
         "get" => Some((|| {
             args.require_range(1, 2, "get")?;
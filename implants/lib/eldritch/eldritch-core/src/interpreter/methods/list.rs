@@ -1,18 +1,26 @@
 use super::ArgCheck;
 use crate::ast::Value;
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use crate::interpreter::operations::{compare_values, values_equal};
 use alloc::format;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
-use spin::RwLock;
+
+pub(crate) const MUTATING_METHODS: &[&str] =
+    &["append", "extend", "insert", "remove", "pop", "sort"];
 
 pub fn handle_list_methods(
-    l: &Arc<RwLock<Vec<Value>>>,
+    l: &Arc<Freezable<Vec<Value>>>,
     method: &str,
     args: &[Value],
 ) -> Option<Result<Value, alloc::string::String>> {
+    if MUTATING_METHODS.contains(&method)
+        && let Err(e) = l.check_mutable("list")
+    {
+        return Some(Err(e));
+    }
     match method {
         "append" => Some((|| {
             args.require(1, "append")?;
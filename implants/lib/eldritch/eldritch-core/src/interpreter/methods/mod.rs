@@ -13,6 +13,7 @@ mod str;
 
 use bytes::handle_bytes_methods;
 use dict::handle_dict_methods;
+pub(crate) use list::MUTATING_METHODS as MUTATING_LIST_METHODS;
 use list::handle_list_methods;
 use set::handle_set_methods;
 use str::handle_string_methods;
@@ -50,7 +51,7 @@ impl ArgCheck for [Value] {
 
 pub fn get_native_methods(value: &Value) -> Vec<String> {
     match value {
-        Value::List(_) => vec![
+        Value::List(_) | Value::Iterable(_) => vec![
             "append".to_string(),
             "extend".to_string(),
             "insert".to_string(),
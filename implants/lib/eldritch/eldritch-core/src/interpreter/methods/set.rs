@@ -1,11 +1,11 @@
 use super::ArgCheck;
 use crate::ast::Value;
+use crate::interpreter::Freezable;
 use crate::interpreter::introspection::get_type_name;
 use alloc::collections::BTreeSet;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
-use spin::RwLock;
 
 // Helper to convert any iterable Value into a BTreeSet<Value> for set operations.
 fn get_set_elements(v: &Value) -> Result<BTreeSet<Value>, String> {
@@ -15,6 +15,7 @@ fn get_set_elements(v: &Value) -> Result<BTreeSet<Value>, String> {
         Value::Tuple(t) => Ok(t.iter().cloned().collect()),
         Value::Dictionary(d) => Ok(d.read().keys().cloned().collect()),
         Value::String(s) => Ok(s.chars().map(|c| Value::String(c.to_string())).collect()),
+        Value::Iterable(it) => it.iter().collect(),
         _ => Err(format!(
             "TypeError: '{}' object is not iterable",
             get_type_name(v)
@@ -22,11 +23,18 @@ fn get_set_elements(v: &Value) -> Result<BTreeSet<Value>, String> {
     }
 }
 
+const MUTATING_METHODS: &[&str] = &["add", "clear", "discard", "pop", "remove", "update"];
+
 pub fn handle_set_methods(
-    s: &Arc<RwLock<BTreeSet<Value>>>,
+    s: &Arc<Freezable<BTreeSet<Value>>>,
     method: &str,
     args: &[Value],
 ) -> Option<Result<Value, String>> {
+    if MUTATING_METHODS.contains(&method)
+        && let Err(e) = s.check_mutable("set")
+    {
+        return Some(Err(e));
+    }
     match method {
         "add" => Some((|| {
             args.require(1, "add")?;
@@ -46,7 +54,7 @@ pub fn handle_set_methods(
             args.require(1, "difference")?;
             let other_set = get_set_elements(&args[0])?;
             let diff: BTreeSet<Value> = s.read().difference(&other_set).cloned().collect();
-            Ok(Value::Set(Arc::new(RwLock::new(diff))))
+            Ok(Value::Set(Arc::new(Freezable::new(diff))))
         })()),
         "discard" => Some((|| {
             args.require(1, "discard")?;
@@ -57,7 +65,7 @@ pub fn handle_set_methods(
             args.require(1, "intersection")?;
             let other_set = get_set_elements(&args[0])?;
             let inter: BTreeSet<Value> = s.read().intersection(&other_set).cloned().collect();
-            Ok(Value::Set(Arc::new(RwLock::new(inter))))
+            Ok(Value::Set(Arc::new(Freezable::new(inter))))
         })()),
         "isdisjoint" => Some((|| {
             args.require(1, "isdisjoint")?;
@@ -99,13 +107,13 @@ pub fn handle_set_methods(
             args.require(1, "symmetric_difference")?;
             let other_set = get_set_elements(&args[0])?;
             let sym: BTreeSet<Value> = s.read().symmetric_difference(&other_set).cloned().collect();
-            Ok(Value::Set(Arc::new(RwLock::new(sym))))
+            Ok(Value::Set(Arc::new(Freezable::new(sym))))
         })()),
         "union" => Some((|| {
             args.require(1, "union")?;
             let other_set = get_set_elements(&args[0])?;
             let u: BTreeSet<Value> = s.read().union(&other_set).cloned().collect();
-            Ok(Value::Set(Arc::new(RwLock::new(u))))
+            Ok(Value::Set(Arc::new(Freezable::new(u))))
         })()),
         "update" => Some((|| {
             args.require(1, "update")?;
@@ -1,11 +1,12 @@
 use super::ArgCheck;
 use crate::ast::Value;
+use crate::interpreter::Freezable;
+use crate::interpreter::eval::strings::format_template;
 use crate::interpreter::introspection::is_truthy;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
-use spin::RwLock;
 
 pub fn handle_string_methods(
     s: &str,
@@ -24,7 +25,7 @@ pub fn handle_string_methods(
                     .map(|p| Value::String(p.to_string()))
                     .collect()
             };
-            Ok(Value::List(Arc::new(RwLock::new(parts))))
+            Ok(Value::List(Arc::new(Freezable::new(parts))))
         }),
         "splitlines" => Some({
             let keepends = if !args.is_empty() {
@@ -39,7 +40,7 @@ pub fn handle_string_methods(
             } else {
                 s.lines().map(|p| Value::String(p.to_string())).collect()
             };
-            Ok(Value::List(Arc::new(RwLock::new(lines))))
+            Ok(Value::List(Arc::new(Freezable::new(lines))))
         }),
         "strip" => Some((|| {
             args.require_range(0, 1, "strip")?;
@@ -201,26 +202,8 @@ pub fn handle_string_methods(
                 _ => Err("TypeError: join() expects a list".into()),
             }
         })()),
-        "format" => Some((|| {
-            let mut result = String::new();
-            let mut arg_idx = 0;
-            let chars: Vec<char> = s.chars().collect();
-            let mut i = 0;
-            while i < chars.len() {
-                if chars[i] == '{' && i + 1 < chars.len() && chars[i + 1] == '}' {
-                    if arg_idx >= args.len() {
-                        return Err("IndexError: tuple index out of range".into());
-                    }
-                    result.push_str(&args[arg_idx].to_string());
-                    arg_idx += 1;
-                    i += 2;
-                } else {
-                    result.push(chars[i]);
-                    i += 1;
-                }
-            }
-            Ok(Value::String(result))
-        })()),
+        // Scripts reach this through `call_builtin_method`, which applies the sequence limit
+        "format" => Some(format_template(s, args, None).map(Value::String)),
         "partition" => Some((|| {
             args.require(1, "partition")?;
             let sep = args[0].to_string();
@@ -268,17 +251,17 @@ pub fn handle_string_methods(
                 .map(|p| Value::String(p.to_string()))
                 .collect();
             parts.reverse();
-            Ok(Value::List(Arc::new(RwLock::new(parts))))
+            Ok(Value::List(Arc::new(Freezable::new(parts))))
         }),
         "codepoints" => Some((|| {
             args.require(0, "codepoints")?;
             let points: Vec<Value> = s.chars().map(|c| Value::Int(c as i64)).collect();
-            Ok(Value::List(Arc::new(RwLock::new(points))))
+            Ok(Value::List(Arc::new(Freezable::new(points))))
         })()),
         "elems" => Some((|| {
             args.require(0, "elems")?;
             let chars: Vec<Value> = s.chars().map(|c| Value::String(c.to_string())).collect();
-            Ok(Value::List(Arc::new(RwLock::new(chars))))
+            Ok(Value::List(Arc::new(Freezable::new(chars))))
         })()),
         "encode" => Some((|| {
             args.require(0, "encode")?;
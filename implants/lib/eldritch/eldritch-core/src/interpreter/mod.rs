@@ -1,16 +1,33 @@
 pub mod builtins;
 mod core;
+mod coverage;
+mod debug;
+mod diagnostic;
 pub mod error;
+mod freeze;
+// With the bytecode VM the AST walkers are unused, but their value-level helpers are shared
+#[cfg_attr(feature = "bytecode", allow(dead_code))]
 mod eval;
+#[cfg_attr(feature = "bytecode", allow(dead_code))]
 mod exec;
 pub mod introspection;
-mod methods;
+mod limits;
+pub(crate) mod methods;
 pub mod operations;
 pub mod printer;
+mod profiler;
+#[cfg(feature = "bytecode")]
+pub(crate) mod vm;
 
 pub use self::core::Interpreter;
+pub use self::coverage::{BranchCoverage, CoverageRecorder, CoverageReport, FunctionCoverage};
+pub use self::debug::DebugHook;
+pub use self::diagnostic::{ScriptError, SourceSnippet, TracebackFrame};
 #[allow(unused_imports)]
 pub use self::error::EldritchError;
 #[allow(unused_imports)]
 pub use self::error::EldritchErrorKind;
+pub use self::freeze::Freezable;
+pub use self::limits::{CancellationToken, ExecutionLimits};
 pub use self::printer::{BufferPrinter, NoopPrinter, Printer, StdoutPrinter};
+pub use self::profiler::{Profile, ProfileClock, ProfileStats};
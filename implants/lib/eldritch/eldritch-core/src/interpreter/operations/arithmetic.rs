@@ -1,9 +1,17 @@
 use crate::ast::Value;
 use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
+use crate::interpreter::eval::strings::FormatSpec;
+use crate::interpreter::eval::utils::parse_error_kind;
 use crate::interpreter::introspection::get_type_name;
 use crate::token::{Span, TokenKind};
 use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
 
 #[cfg(feature = "std")]
 extern crate std;
@@ -15,10 +23,32 @@ pub(crate) fn apply_arithmetic_op(
     b: &Value,
     span: Span,
 ) -> Result<Value, EldritchError> {
+    // Big integers mixed with floats behave like floats
+    if let (Value::BigInt(_), Value::Float(_)) | (Value::Float(_), Value::BigInt(_)) = (a, b) {
+        let a = Value::Float(a.to_f64().unwrap_or(f64::NAN));
+        let b = Value::Float(b.to_f64().unwrap_or(f64::NAN));
+        return apply_arithmetic_op(interp, &a, op, &b, span);
+    }
+    if let (Value::BigInt(_), Value::Int(_) | Value::BigInt(_))
+    | (Value::Int(_), Value::BigInt(_)) = (a, b)
+    {
+        return apply_bigint_op(interp, a, op, b, span);
+    }
+
     match (a, op, b) {
-        (Value::Int(a), TokenKind::Plus, Value::Int(b)) => Ok(Value::Int(a + b)),
-        (Value::Int(a), TokenKind::Minus, Value::Int(b)) => Ok(Value::Int(a - b)),
-        (Value::Int(a), TokenKind::Star, Value::Int(b)) => Ok(Value::Int(a * b)),
+        // Results that overflow i64 are promoted to big integers
+        (Value::Int(x), TokenKind::Plus, Value::Int(y)) => match x.checked_add(*y) {
+            Some(r) => Ok(Value::Int(r)),
+            None => apply_bigint_op(interp, a, op, b, span),
+        },
+        (Value::Int(x), TokenKind::Minus, Value::Int(y)) => match x.checked_sub(*y) {
+            Some(r) => Ok(Value::Int(r)),
+            None => apply_bigint_op(interp, a, op, b, span),
+        },
+        (Value::Int(x), TokenKind::Star, Value::Int(y)) => match x.checked_mul(*y) {
+            Some(r) => Ok(Value::Int(r)),
+            None => apply_bigint_op(interp, a, op, b, span),
+        },
         (Value::Int(a), TokenKind::Slash, Value::Int(b)) => {
             if *b == 0 {
                 return interp.error(EldritchErrorKind::ZeroDivisionError, "divide by zero", span);
@@ -142,45 +172,324 @@ pub(crate) fn apply_arithmetic_op(
             }
         }
 
-        (Value::Int(a), TokenKind::SlashSlash, Value::Int(b)) => {
-            if *b == 0 {
+        (Value::Int(x), TokenKind::SlashSlash, Value::Int(y)) => {
+            if *y == 0 {
                 return interp.error(EldritchErrorKind::ZeroDivisionError, "divide by zero", span);
             }
-            let mut res = a / b;
-            if (a % b != 0) && ((*a < 0) ^ (*b < 0)) {
-                res -= 1;
+            // Only i64::MIN // -1 overflows
+            match x.checked_div(*y) {
+                Some(mut res) => {
+                    if (x % y != 0) && ((*x < 0) ^ (*y < 0)) {
+                        res -= 1;
+                    }
+                    Ok(Value::Int(res))
+                }
+                None => apply_bigint_op(interp, a, op, b, span),
             }
-            Ok(Value::Int(res))
         }
-        (Value::Int(a), TokenKind::Percent, Value::Int(b)) => {
-            if *b == 0 {
+        (Value::Int(x), TokenKind::Percent, Value::Int(y)) => {
+            if *y == 0 {
                 return interp.error(EldritchErrorKind::ZeroDivisionError, "modulo by zero", span);
             }
-            let res = ((a % b) + b) % b;
-            Ok(Value::Int(res))
+            // The result takes the sign of the divisor
+            let res = x.checked_rem(*y).unwrap_or(0);
+            if res != 0 && ((res < 0) ^ (*y < 0)) {
+                Ok(Value::Int(res + y))
+            } else {
+                Ok(Value::Int(res))
+            }
+        }
+
+        (Value::String(fmt), TokenKind::Percent, args) => {
+            percent_format(interp, fmt, args, span).map(Value::String)
+        }
+
+        _ => unsupported_operands(interp, a, op, b, span),
+    }
+}
+
+// printf-style formatting: `"%-12s %5.1f" % (name, size)`. A dict on the right supplies
+// `%(key)s` fields; any other value that isn't a tuple is the only argument.
+fn percent_format(
+    interp: &Interpreter,
+    fmt: &str,
+    args: &Value,
+    span: Span,
+) -> Result<String, EldritchError> {
+    let mapping = match args {
+        Value::Dictionary(d) => Some(d),
+        _ => None,
+    };
+    let vals: Vec<Value> = match args {
+        Value::Tuple(t) => t.clone(),
+        _ => vec![args.clone()],
+    };
+    let mut val_idx = 0;
+    let mut next_arg = || {
+        let v = vals.get(val_idx).cloned();
+        val_idx += 1;
+        v.ok_or(())
+    };
+
+    let mut result = String::new();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            result.push('%');
+            continue;
+        }
+
+        let mut keyed = None;
+        if chars.next_if_eq(&'(').is_some() {
+            let key: String = chars.by_ref().take_while(|&c| c != ')').collect();
+            let Some(d) = mapping else {
+                return interp.error(
+                    EldritchErrorKind::TypeError,
+                    "format requires a mapping",
+                    span,
+                );
+            };
+            match d.read().get(&Value::String(key.clone())) {
+                Some(v) => keyed = Some(v.clone()),
+                None => {
+                    return interp.error(EldritchErrorKind::KeyError, &format!("'{key}'"), span);
+                }
+            }
+        }
+
+        let mut spec = FormatSpec::default();
+        while let Some(flag) = chars.next_if(|c| matches!(c, '-' | '0' | '+' | ' ' | '#')) {
+            match flag {
+                '-' => spec.align = Some('<'),
+                '0' => spec.zero = true,
+                '+' => spec.sign = Some('+'),
+                ' ' => spec.sign = spec.sign.or(Some(' ')),
+                _ => spec.alternate = true,
+            }
+        }
+        if spec.align.is_some() {
+            spec.zero = false;
+        }
+
+        // `*` takes the width or precision from the arguments
+        let mut count = |chars: &mut core::iter::Peekable<core::str::Chars>| {
+            if chars.next_if_eq(&'*').is_some() {
+                let Ok(v) = next_arg() else {
+                    return not_enough(interp, span);
+                };
+                return match v {
+                    Value::Int(n) => Ok(Some(n.max(0) as usize)),
+                    _ => interp.error(EldritchErrorKind::TypeError, "* wants int", span),
+                };
+            }
+            let mut digits = String::new();
+            while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                digits.push(d);
+            }
+            Ok(digits.parse().ok())
+        };
+        spec.width = count(&mut chars)?.unwrap_or(0);
+        if chars.next_if_eq(&'.').is_some() {
+            spec.precision = Some(count(&mut chars)?.unwrap_or(0));
+        }
+        if let Err(msg) = spec.check_len(interp.budget.limits.max_sequence_len) {
+            let (kind, msg) = parse_error_kind(&msg);
+            return interp.error(kind, msg, span);
         }
 
-        _ => interp.error(
+        let Some(conversion) = chars.next() else {
+            return interp.error(EldritchErrorKind::ValueError, "incomplete format", span);
+        };
+        let v = match keyed {
+            Some(v) => v,
+            None => match next_arg() {
+                Ok(v) => v,
+                Err(()) => return not_enough(interp, span),
+            },
+        };
+
+        // Unlike format specs, strings align right and are never zero padded
+        let mut text_spec = spec.clone();
+        text_spec.align = text_spec.align.or(Some('>'));
+        text_spec.zero = false;
+
+        let formatted = match conversion {
+            's' => Ok(text_spec.format_text(&v.to_string())),
+            'r' => Ok(text_spec.format_text(&match &v {
+                Value::String(s) => format!("\"{s}\""),
+                _ => v.to_string(),
+            })),
+            'd' | 'i' | 'u' | 'o' | 'x' | 'X' => {
+                spec.precision = None;
+                spec.ty = Some(if matches!(conversion, 'i' | 'u') {
+                    'd'
+                } else {
+                    conversion
+                });
+                spec.format_int(&percent_int(interp, &v, conversion, span)?)
+            }
+            'e' | 'E' | 'f' | 'F' | 'g' | 'G' => {
+                spec.ty = Some(conversion);
+                spec.format_float(percent_float(interp, &v, conversion, span)?)
+            }
+            'c' => match &v {
+                Value::String(s) if s.chars().count() == 1 => Ok(text_spec.format_text(s)),
+                _ => {
+                    spec.ty = Some('c');
+                    spec.format_int(&percent_int(interp, &v, conversion, span)?)
+                }
+            },
+            _ => {
+                return interp.error(
+                    EldritchErrorKind::ValueError,
+                    &format!(
+                        "unsupported format character '{}' (0x{:x})",
+                        conversion, conversion as u32
+                    ),
+                    span,
+                );
+            }
+        };
+        match formatted {
+            Ok(text) => result.push_str(&text),
+            Err(msg) => {
+                let (kind, msg) = parse_error_kind(&msg);
+                return interp.error(kind, msg, span);
+            }
+        }
+    }
+
+    if mapping.is_none() && val_idx < vals.len() {
+        return interp.error(
             EldritchErrorKind::TypeError,
-            &format!(
-                "unsupported operand type(s) for {}: '{}' and '{}'",
-                match op {
-                    TokenKind::Plus => "+",
-                    TokenKind::Minus => "-",
-                    TokenKind::Star => "*",
-                    TokenKind::Slash => "/",
-                    TokenKind::SlashSlash => "//",
-                    TokenKind::Percent => "%",
-                    _ => "?",
-                },
-                get_type_name(a),
-                get_type_name(b)
-            ),
+            "not all arguments converted during string formatting",
             span,
+        );
+    }
+    Ok(result)
+}
+
+fn not_enough<T>(interp: &Interpreter, span: Span) -> Result<T, EldritchError> {
+    interp.error(
+        EldritchErrorKind::TypeError,
+        "not enough arguments for format string",
+        span,
+    )
+}
+
+fn percent_int(
+    interp: &Interpreter,
+    v: &Value,
+    conversion: char,
+    span: Span,
+) -> Result<BigInt, EldritchError> {
+    match v {
+        Value::Int(i) => Ok(BigInt::from(*i)),
+        Value::BigInt(i) => Ok(i.clone()),
+        Value::Float(f) => Ok(BigInt::from(*f as i64)),
+        Value::Bool(b) => Ok(BigInt::from(*b as i64)),
+        _ => number_required(interp, v, conversion, span),
+    }
+}
+
+fn percent_float(
+    interp: &Interpreter,
+    v: &Value,
+    conversion: char,
+    span: Span,
+) -> Result<f64, EldritchError> {
+    match v {
+        Value::Int(i) => Ok(*i as f64),
+        Value::BigInt(i) => Ok(bigint_to_f64(i)),
+        Value::Float(f) => Ok(*f),
+        Value::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+        _ => number_required(interp, v, conversion, span),
+    }
+}
+
+fn number_required<T>(
+    interp: &Interpreter,
+    v: &Value,
+    conversion: char,
+    span: Span,
+) -> Result<T, EldritchError> {
+    interp.error(
+        EldritchErrorKind::TypeError,
+        &format!(
+            "%{} format: a number is required, not {}",
+            conversion,
+            get_type_name(v)
         ),
+        span,
+    )
+}
+
+// Arithmetic on two integers at arbitrary precision, narrowing the result back to `Int` when it fits.
+fn apply_bigint_op(
+    interp: &Interpreter,
+    a: &Value,
+    op: &TokenKind,
+    b: &Value,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    let (Some(x), Some(y)) = (a.to_bigint(), b.to_bigint()) else {
+        return unsupported_operands(interp, a, op, b, span);
+    };
+    match op {
+        TokenKind::Plus => Ok(Value::from_bigint(x + y)),
+        TokenKind::Minus => Ok(Value::from_bigint(x - y)),
+        TokenKind::Star => Ok(Value::from_bigint(x * y)),
+        TokenKind::Slash | TokenKind::SlashSlash | TokenKind::Percent if y.is_zero() => {
+            let msg = if matches!(op, TokenKind::Percent) {
+                "modulo by zero"
+            } else {
+                "divide by zero"
+            };
+            interp.error(EldritchErrorKind::ZeroDivisionError, msg, span)
+        }
+        TokenKind::Slash => Ok(Value::Float(bigint_to_f64(&x) / bigint_to_f64(&y))),
+        TokenKind::SlashSlash => Ok(Value::from_bigint(x.div_floor(&y))),
+        TokenKind::Percent => Ok(Value::from_bigint(x.mod_floor(&y))),
+        _ => unsupported_operands(interp, a, op, b, span),
     }
 }
 
+fn bigint_to_f64(i: &BigInt) -> f64 {
+    i.to_f64().unwrap_or(f64::NAN)
+}
+
+fn unsupported_operands(
+    interp: &Interpreter,
+    a: &Value,
+    op: &TokenKind,
+    b: &Value,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    interp.error(
+        EldritchErrorKind::TypeError,
+        &format!(
+            "unsupported operand type(s) for {}: '{}' and '{}'",
+            match op {
+                TokenKind::Plus => "+",
+                TokenKind::Minus => "-",
+                TokenKind::Star => "*",
+                TokenKind::Slash => "/",
+                TokenKind::SlashSlash => "//",
+                TokenKind::Percent => "%",
+                _ => "?",
+            },
+            get_type_name(a),
+            get_type_name(b)
+        ),
+        span,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,5 @@
 use crate::ast::Value;
+use crate::interpreter::Freezable;
 use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
 use crate::interpreter::introspection::get_type_name;
@@ -6,7 +7,11 @@ use crate::token::{Span, TokenKind};
 use alloc::collections::BTreeSet;
 use alloc::format;
 use alloc::sync::Arc;
-use spin::RwLock;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+
+// Shifting further than this is rejected rather than allocating huge integers
+const MAX_SHIFT: i64 = 512;
 
 pub(crate) fn apply_bitwise_op(
     interp: &Interpreter,
@@ -16,31 +21,42 @@ pub(crate) fn apply_bitwise_op(
     span: Span,
 ) -> Result<Value, EldritchError> {
     match (a, b) {
-        (Value::Int(a), Value::Int(b)) => match op {
-            TokenKind::BitAnd => Ok(Value::Int(a & b)),
-            TokenKind::BitOr => Ok(Value::Int(a | b)),
-            TokenKind::BitXor => Ok(Value::Int(a ^ b)),
-            TokenKind::LShift => Ok(Value::Int(a << b)),
-            TokenKind::RShift => Ok(Value::Int(a >> b)),
+        (Value::Int(x), Value::Int(y)) => match op {
+            TokenKind::BitAnd => Ok(Value::Int(x & y)),
+            TokenKind::BitOr => Ok(Value::Int(x | y)),
+            TokenKind::BitXor => Ok(Value::Int(x ^ y)),
+            TokenKind::LShift | TokenKind::RShift => shift(interp, a, op, b, span),
             _ => unreachable!(),
         },
+        (Value::Int(_) | Value::BigInt(_), Value::Int(_) | Value::BigInt(_)) => {
+            let (Some(x), Some(y)) = (a.to_bigint(), b.to_bigint()) else {
+                unreachable!()
+            };
+            match op {
+                TokenKind::BitAnd => Ok(Value::from_bigint(x & y)),
+                TokenKind::BitOr => Ok(Value::from_bigint(x | y)),
+                TokenKind::BitXor => Ok(Value::from_bigint(x ^ y)),
+                TokenKind::LShift | TokenKind::RShift => shift(interp, a, op, b, span),
+                _ => unreachable!(),
+            }
+        }
         (Value::Set(a), Value::Set(b)) => match op {
             TokenKind::BitAnd => {
                 #[allow(clippy::mutable_key_type)]
                 let intersection: BTreeSet<Value> =
                     a.read().intersection(&b.read()).cloned().collect();
-                Ok(Value::Set(Arc::new(RwLock::new(intersection))))
+                Ok(Value::Set(Arc::new(Freezable::new(intersection))))
             }
             TokenKind::BitOr => {
                 #[allow(clippy::mutable_key_type)]
                 let union: BTreeSet<Value> = a.read().union(&b.read()).cloned().collect();
-                Ok(Value::Set(Arc::new(RwLock::new(union))))
+                Ok(Value::Set(Arc::new(Freezable::new(union))))
             }
             TokenKind::BitXor => {
                 #[allow(clippy::mutable_key_type)]
                 let symmetric_difference: BTreeSet<Value> =
                     a.read().symmetric_difference(&b.read()).cloned().collect();
-                Ok(Value::Set(Arc::new(RwLock::new(symmetric_difference))))
+                Ok(Value::Set(Arc::new(Freezable::new(symmetric_difference))))
             }
             // Note: Minus is not bitwise, handled in arithmetic/sets
             _ => interp.error(
@@ -55,7 +71,7 @@ pub(crate) fn apply_bitwise_op(
             for (k, v) in b.read().iter() {
                 new_dict.insert(k.clone(), v.clone());
             }
-            Ok(Value::Dictionary(Arc::new(RwLock::new(new_dict))))
+            Ok(Value::Dictionary(Arc::new(Freezable::new(new_dict))))
         }
         _ => interp.error(
             EldritchErrorKind::TypeError,
@@ -76,3 +92,51 @@ pub(crate) fn apply_bitwise_op(
         ),
     }
 }
+// `a << b` and `a >> b` on integers, promoting to a big integer when bits would be lost
+fn shift(
+    interp: &Interpreter,
+    a: &Value,
+    op: &TokenKind,
+    b: &Value,
+    span: Span,
+) -> Result<Value, EldritchError> {
+    let count = match b {
+        Value::Int(n) => *n,
+        Value::BigInt(n) if n.is_negative() => -1,
+        _ => i64::MAX,
+    };
+    if count < 0 {
+        return interp.error(EldritchErrorKind::ValueError, "negative shift count", span);
+    }
+    if let Value::Int(x) = a {
+        match op {
+            TokenKind::RShift => return Ok(Value::Int(x >> count.min(63))),
+            _ if count < 64 && (x << count) >> count == *x => return Ok(Value::Int(x << count)),
+            _ => {}
+        }
+    }
+    let Some(x) = a.to_bigint() else {
+        unreachable!()
+    };
+    if matches!(op, TokenKind::RShift) {
+        // Shifting past the last bit leaves only the sign
+        let count = count.to_usize().unwrap_or(usize::MAX);
+        return Ok(Value::from_bigint(if count >= x.bits() as usize {
+            if x.is_negative() {
+                BigInt::from(-1)
+            } else {
+                BigInt::from(0)
+            }
+        } else {
+            x >> count
+        }));
+    }
+    if count >= MAX_SHIFT {
+        return interp.error(
+            EldritchErrorKind::ValueError,
+            &format!("shift count too large: {b}"),
+            span,
+        );
+    }
+    Ok(Value::from_bigint(x << count as usize))
+}
@@ -21,6 +21,9 @@ pub fn compare_values(a: &Value, b: &Value) -> Result<Ordering, String> {
     match (a, b) {
         (Value::Int(i1), Value::Float(f2)) => Ok((*i1 as f64).total_cmp(f2)),
         (Value::Float(f1), Value::Int(i2)) => Ok(f1.total_cmp(&(*i2 as f64))),
+        // Value's ordering already compares big integers numerically
+        (Value::BigInt(_), Value::Int(_) | Value::BigInt(_) | Value::Float(_))
+        | (Value::Int(_) | Value::Float(_), Value::BigInt(_)) => Ok(a.cmp(b)),
         _ => {
             if core::mem::discriminant(a) == core::mem::discriminant(b) {
                 Ok(a.cmp(b))
@@ -47,6 +50,9 @@ pub(crate) fn apply_comparison_op(
         match (a, b) {
             (Value::Int(i), Value::Float(f)) => return Ok(Value::Bool(*i as f64 == *f)),
             (Value::Float(f), Value::Int(i)) => return Ok(Value::Bool(*f == *i as f64)),
+            (Value::BigInt(_), Value::Float(_)) | (Value::Float(_), Value::BigInt(_)) => {
+                return Ok(Value::Bool(a.cmp(b) == Ordering::Equal));
+            }
             _ => return Ok(Value::Bool(a == b)),
         }
     }
@@ -54,6 +60,9 @@ pub(crate) fn apply_comparison_op(
         match (a, b) {
             (Value::Int(i), Value::Float(f)) => return Ok(Value::Bool(*i as f64 != *f)),
             (Value::Float(f), Value::Int(i)) => return Ok(Value::Bool(*f != *i as f64)),
+            (Value::BigInt(_), Value::Float(_)) | (Value::Float(_), Value::BigInt(_)) => {
+                return Ok(Value::Bool(a.cmp(b) != Ordering::Equal));
+            }
             _ => return Ok(Value::Bool(a != b)),
         }
     }
@@ -86,8 +95,12 @@ pub(crate) fn apply_comparison_op(
                     }
                 }
                 _ => {
-                    // Mismatched types
-                    if core::mem::discriminant(a) != core::mem::discriminant(b) {
+                    // Mismatched types; big integers compare with ints and floats
+                    let numeric =
+                        |v: &Value| matches!(v, Value::Int(_) | Value::BigInt(_) | Value::Float(_));
+                    if core::mem::discriminant(a) != core::mem::discriminant(b)
+                        && !(numeric(a) && numeric(b))
+                    {
                         return interp.error(
                             EldritchErrorKind::TypeError,
                             &format!(
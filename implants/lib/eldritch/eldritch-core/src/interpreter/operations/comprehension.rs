@@ -3,13 +3,16 @@ use crate::interpreter::core::Interpreter;
 use crate::interpreter::error::{EldritchError, EldritchErrorKind};
 use crate::interpreter::eval::evaluate;
 use crate::interpreter::introspection::is_truthy;
+use crate::token::Span;
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use spin::RwLock;
 
 use crate::ast::Value;
 
+#[cfg_attr(feature = "bytecode", allow(dead_code))]
 pub(crate) fn evaluate_comprehension_generic<F>(
     interp: &mut Interpreter,
     vars: &[alloc::string::String],
@@ -21,8 +24,7 @@ where
     F: FnMut(&mut Interpreter) -> Result<(), EldritchError>,
 {
     let iterable_val = evaluate(interp, iterable)?;
-    // Use crate::interpreter::eval::to_iterable
-    let items = crate::interpreter::eval::to_iterable(interp, &iterable_val, iterable.span)?;
+    let items = crate::interpreter::eval::iterate(interp, &iterable_val, iterable.span)?;
 
     let printer = interp.env.read().printer.clone();
     let comp_env = Arc::new(RwLock::new(Environment {
@@ -30,42 +32,35 @@ where
         values: BTreeMap::new(),
         printer,
         libraries: BTreeSet::new(),
+        max_sequence_len: interp.budget.limits.max_sequence_len,
     }));
     let original_env = interp.env.clone();
     interp.env = comp_env;
 
     for item in items {
+        if let Err(e) = interp.check_budget(iterable.span) {
+            interp.env = original_env;
+            return Err(e);
+        }
+        let item = match item {
+            Ok(item) => item,
+            Err(msg) => {
+                interp.env = original_env;
+                return crate::interpreter::eval::iteration_error(interp, msg, iterable.span);
+            }
+        };
         if vars.len() == 1 {
             interp.define_variable(&vars[0], item);
         } else {
-            // Unpack
-            let elements = match item {
-                Value::List(l) => l.read().clone(),
-                Value::Tuple(t) => t.clone(),
-                Value::Set(s) => s.read().iter().cloned().collect(),
-                _ => {
+            let elements = match unpack_comprehension_item(interp, item, vars.len(), iterable.span)
+            {
+                Ok(elements) => elements,
+                Err(e) => {
                     interp.env = original_env;
-                    return interp.error(
-                        EldritchErrorKind::TypeError,
-                        &alloc::format!("Cannot unpack non-iterable object of type {}", item),
-                        iterable.span,
-                    );
+                    return Err(e);
                 }
             };
 
-            if elements.len() != vars.len() {
-                interp.env = original_env;
-                return interp.error(
-                    EldritchErrorKind::ValueError,
-                    &alloc::format!(
-                        "Too many (or not enough) values to unpack (expected {}, got {})",
-                        vars.len(),
-                        elements.len()
-                    ),
-                    iterable.span,
-                );
-            }
-
             for (var, val) in vars.iter().zip(elements.into_iter()) {
                 interp.define_variable(var, val);
             }
@@ -89,3 +84,38 @@ where
     interp.env = original_env;
     Ok(())
 }
+
+/// Split a comprehension item across multiple loop variables.
+pub(crate) fn unpack_comprehension_item(
+    interp: &Interpreter,
+    item: Value,
+    count: usize,
+    span: Span,
+) -> Result<Vec<Value>, EldritchError> {
+    let elements = match item {
+        Value::List(l) => l.read().clone(),
+        Value::Tuple(t) => t,
+        Value::Set(s) => s.read().iter().cloned().collect(),
+        Value::Iterable(it) => crate::interpreter::eval::materialize(interp, &it, span)?,
+        _ => {
+            return interp.error(
+                EldritchErrorKind::TypeError,
+                &alloc::format!("Cannot unpack non-iterable object of type {}", item),
+                span,
+            );
+        }
+    };
+
+    if elements.len() != count {
+        return interp.error(
+            EldritchErrorKind::ValueError,
+            &alloc::format!(
+                "Too many (or not enough) values to unpack (expected {}, got {})",
+                count,
+                elements.len()
+            ),
+            span,
+        );
+    }
+    Ok(elements)
+}
@@ -0,0 +1,197 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// A monotonic clock for the profiler, returning the time elapsed since an arbitrary fixed
+/// point. `no_std` hosts that have a timer can provide their own.
+pub type ProfileClock = fn() -> Duration;
+
+/// The clock used by [`Interpreter::with_profiler`](super::Interpreter::with_profiler).
+#[cfg(feature = "std")]
+pub fn std_clock() -> Duration {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    EPOCH.get_or_init(std::time::Instant::now).elapsed()
+}
+
+/// Call counts and timings for one function, native method or line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileStats {
+    /// How many times the function was called, or the line was reached.
+    pub calls: u64,
+    /// Time spent including everything called from it.
+    pub inclusive: Duration,
+    /// Time spent in the function or line itself, excluding other calls.
+    pub exclusive: Duration,
+}
+
+/// Where a script spent its time, as recorded by an interpreter with profiling enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// Script functions and builtins, by name.
+    pub functions: BTreeMap<String, ProfileStats>,
+    /// Library methods, by `library.method`, e.g. `file.read`.
+    pub natives: BTreeMap<String, ProfileStats>,
+    /// Statements, by line number.
+    pub lines: BTreeMap<usize, ProfileStats>,
+    /// Exclusive time spent in each call stack, outermost frame first.
+    pub stacks: BTreeMap<Vec<String>, Duration>,
+}
+
+impl Profile {
+    /// Renders the stacks in the folded format read by `flamegraph.pl` and `inferno`: one
+    /// line per stack, frames joined by `;`, followed by the time spent in nanoseconds.
+    pub fn folded(&self) -> String {
+        let mut out = String::new();
+        for (stack, time) in &self.stacks {
+            let nanos = time.as_nanos();
+            if nanos > 0 {
+                let _ = writeln!(out, "{} {}", stack.join(";"), nanos);
+            }
+        }
+        out
+    }
+}
+
+struct Frame {
+    name: String,
+    native: bool,
+    start: Duration,
+    // Inclusive time of the calls made from this frame
+    child: Duration,
+    line: Option<LineMark>,
+}
+
+struct LineMark {
+    line: usize,
+    start: Duration,
+    child: Duration,
+}
+
+/// Records a [`Profile`] from the interpreter's frame and statement hooks.
+pub(crate) struct Profiler {
+    clock: ProfileClock,
+    profile: Profile,
+    frames: Vec<Frame>,
+}
+
+impl Profiler {
+    pub(crate) fn new(clock: ProfileClock) -> Self {
+        Self {
+            clock,
+            profile: Profile::default(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Opens the `<module>` frame at the start of a top-level run.
+    pub(crate) fn start(&mut self) {
+        self.frames.clear();
+        self.enter("<module>");
+    }
+
+    /// Closes every frame still open, e.g. when a run ends or fails.
+    pub(crate) fn finish(&mut self) {
+        while !self.frames.is_empty() {
+            self.exit();
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> Profile {
+        core::mem::take(&mut self.profile)
+    }
+
+    pub(crate) fn enter(&mut self, name: &str) {
+        let now = (self.clock)();
+        self.frames.push(Frame {
+            name: name.to_string(),
+            native: false,
+            start: now,
+            child: Duration::ZERO,
+            line: None,
+        });
+    }
+
+    /// Marks the innermost frame as a call to a method of `library`.
+    pub(crate) fn mark_native(&mut self, library: &str) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.name = format!("{library}.{}", frame.name);
+            frame.native = true;
+        }
+    }
+
+    pub(crate) fn exit(&mut self) {
+        let now = (self.clock)();
+        let Some(mut frame) = self.frames.pop() else {
+            return;
+        };
+        close_line(&mut self.profile, &self.frames, &mut frame, now);
+
+        let inclusive = now.saturating_sub(frame.start);
+        let exclusive = inclusive.saturating_sub(frame.child);
+
+        let mut stack: Vec<String> = self.frames.iter().map(|f| f.name.clone()).collect();
+        // Recursive calls are already timed by the outermost call
+        let recursive = stack.contains(&frame.name);
+        stack.push(frame.name.clone());
+        *self.profile.stacks.entry(stack).or_default() += exclusive;
+
+        let table = if frame.native {
+            &mut self.profile.natives
+        } else {
+            &mut self.profile.functions
+        };
+        let stats = table.entry(frame.name).or_default();
+        stats.calls += 1;
+        stats.exclusive += exclusive;
+        if !recursive {
+            stats.inclusive += inclusive;
+        }
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.child += inclusive;
+            if let Some(mark) = &mut parent.line {
+                mark.child += inclusive;
+            }
+        }
+    }
+
+    /// Starts timing `line` in the innermost frame, ending the line that ran before it.
+    pub(crate) fn line(&mut self, line: usize) {
+        let now = (self.clock)();
+        let Some((frame, outer)) = self.frames.split_last_mut() else {
+            return;
+        };
+        if frame.line.as_ref().is_some_and(|mark| mark.line == line) {
+            return;
+        }
+        close_line(&mut self.profile, outer, frame, now);
+        self.profile.lines.entry(line).or_default().calls += 1;
+        frame.line = Some(LineMark {
+            line,
+            start: now,
+            child: Duration::ZERO,
+        });
+    }
+}
+
+// Ends the line running in `frame`, which is called from the `outer` frames
+fn close_line(profile: &mut Profile, outer: &[Frame], frame: &mut Frame, now: Duration) {
+    if let Some(mark) = frame.line.take() {
+        let inclusive = now.saturating_sub(mark.start);
+        let stats = profile.lines.entry(mark.line).or_default();
+        stats.exclusive += inclusive.saturating_sub(mark.child);
+        // As with functions, a line that recursed is timed by its outermost run
+        let recursive = outer
+            .iter()
+            .any(|f| f.line.as_ref().is_some_and(|m| m.line == mark.line));
+        if !recursive {
+            stats.inclusive += inclusive;
+        }
+    }
+}
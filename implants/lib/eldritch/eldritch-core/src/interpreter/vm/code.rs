@@ -0,0 +1,181 @@
+use crate::ast::{Param, Stmt, Value};
+use crate::interpreter::error::EldritchErrorKind;
+use crate::token::{Span, TokenKind};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Index into one of the tables of a [`Code`] object, or a jump target.
+pub(crate) type Idx = u32;
+
+/// A single VM instruction.
+///
+/// Operands are indexes into the tables of the owning [`Code`], which keeps
+/// instructions small and `Copy`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Op {
+    /// Push `consts[c]`.
+    Const(Idx),
+    Pop,
+
+    /// Push the variable `names[n]`, looked up through the environment chain.
+    LoadName(Idx, Idx),
+    /// Assign `names[n]` where it is already bound, else in the current scope.
+    StoreName(Idx),
+    /// Bind `names[n]` in the current scope, shadowing outer bindings.
+    DefineName(Idx),
+    /// Push local slot `s`, falling back to the environment chain while it is unbound.
+    LoadLocal(Idx, Idx),
+    /// Assign local slot `s`, or the enclosing binding of the same name if the slot is unbound.
+    StoreLocal(Idx),
+    /// Bind local slot `s` in the current block scope, shadowing any outer value.
+    DefineLocal(Idx),
+
+    /// Apply `tokens[t]` to the top of the stack.
+    Unary(Idx, Idx),
+    /// Pop the right then the left operand and apply `tokens[t]`.
+    Binary(Idx, Idx),
+    /// Pop the current value then the right operand and apply the augmented operator `tokens[t]`.
+    AugAssign(Idx, Idx),
+    /// Pop index, object and right operand and apply `obj[index] <tokens[t]>= right`.
+    AugIndex(Idx, Idx, Idx),
+
+    Jump(Idx),
+    /// Pop the condition and jump if it is falsy.
+    JumpIfFalse(Idx),
+    /// Jump keeping the top of the stack if it is falsy, else pop it (`and`).
+    JumpIfFalseOrPop(Idx),
+    /// Jump keeping the top of the stack if it is truthy, else pop it (`or`).
+    JumpIfTrueOrPop(Idx),
+
+    BuildList(Idx),
+    BuildTuple(Idx),
+    /// Build a dict from `n` key/value pairs.
+    BuildDict(Idx),
+    BuildSet(Idx),
+    /// Concatenate the string form of the top `n` values (f-strings).
+    BuildString(Idx),
+    /// Pop a format spec and replace the value below it with the value formatted by it.
+    FormatValue(Idx),
+    /// Pop a value and add it to the comprehension accumulator below it.
+    ListAppend,
+    SetAdd,
+    /// Pop a value then a key and insert them into the dict accumulator below.
+    DictInsert,
+
+    /// Pop index and object and push `obj[index]`.
+    Index(Idx, Idx, Idx),
+    /// Slice the object below the bounds described by `slices[i]`.
+    Slice(Idx),
+    /// Pop an object and push its attribute `names[i]`.
+    GetAttr(Idx, Idx),
+    /// Pop index, object and value and store `obj[index] = value`.
+    StoreIndex(Idx, Idx),
+    /// Unpack the top of the stack into `n` values for an assignment, first value on top.
+    UnpackAssign(Idx, Idx),
+    /// Unpack a `for` loop item into `n` values, first value on top.
+    UnpackLoop(Idx, Idx),
+    /// Unpack a comprehension item into `n` values, first value on top.
+    UnpackComp(Idx, Idx),
+
+    /// Count a step against the interpreter budget.
+    Tick(Idx),
+    /// Start a statement: count a step and record the statement for coverage.
+    Stmt(Idx),
+    /// Call with `argc` positional arguments; the optional name labels native stack frames.
+    Call(Idx, Idx, Option<Idx>),
+    /// Call with the arguments described by `calls[i]`.
+    CallEx(Idx, Idx, Option<Idx>),
+    /// Pop the default values of `protos[p]` and push a new function closing over the current scope.
+    MakeFunction(Idx),
+    Return,
+
+    /// Pop an iterable and start a `for` loop over it.
+    GetIter(Idx),
+    /// Pop an iterable and start a comprehension over it.
+    CompIter(Idx),
+    /// Push the next item of the innermost iterator, or jump once it is exhausted.
+    ForIter(Idx),
+    PopIter,
+    PushScope,
+    PopScope,
+
+    /// Enter a loop whose `break` jumps to the first target and `continue` to the second.
+    SetupLoop(Idx, Idx),
+    /// Count one iteration of the innermost `while` loop.
+    LoopIter(Idx),
+    PopBlock,
+    Break,
+    Continue,
+
+    /// Route catchable errors raised until the matching `PopBlock` to the handlers.
+    SetupExcept(Idx),
+    /// Route every way out of the block until `EnterFinally` through the finally body.
+    SetupFinally(Idx),
+    /// Start running a finally body after its try statement completed normally.
+    EnterFinally,
+    /// Resume whatever was pending when the finally body started.
+    EndFinally,
+    /// Pop an except clause kind and jump if it doesn't match the error being handled.
+    MatchError(Idx, Idx),
+    /// Push the error being handled as a dict.
+    LoadError,
+    /// Finish dispatching the error being handled.
+    PopHandler,
+    /// Raise the error being handled again since no clause matched.
+    Reraise,
+
+    /// Pop the value of a top-level expression statement as the result of the run.
+    SetResult,
+    ClearResult,
+    /// Raise `failures[f]`.
+    Fail(Idx, Idx),
+}
+
+/// Shape of one argument of a `CallEx` call.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ArgKind {
+    Positional,
+    Keyword(Idx),
+    StarArgs(Idx),
+    KwArgs(Idx),
+}
+
+/// Which parts of a slice expression are present, and their spans.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SliceInfo {
+    pub(crate) start: Option<Idx>,
+    pub(crate) stop: Option<Idx>,
+    pub(crate) step: Option<Idx>,
+    pub(crate) span: Idx,
+    pub(crate) obj_span: Idx,
+}
+
+/// Everything needed to create a function value at runtime.
+#[derive(Debug)]
+pub(crate) struct FunctionProto {
+    pub(crate) name: String,
+    pub(crate) params: Vec<Param>,
+    pub(crate) body: Vec<Stmt>,
+    pub(crate) code: Arc<Code>,
+}
+
+/// A compiled module or function body.
+#[derive(Debug, Default)]
+pub(crate) struct Code {
+    pub(crate) ops: Vec<Op>,
+    pub(crate) consts: Vec<Value>,
+    pub(crate) names: Vec<String>,
+    pub(crate) spans: Vec<Span>,
+    pub(crate) tokens: Vec<TokenKind>,
+    pub(crate) calls: Vec<Vec<ArgKind>>,
+    pub(crate) slices: Vec<SliceInfo>,
+    pub(crate) protos: Vec<Arc<FunctionProto>>,
+    pub(crate) failures: Vec<(EldritchErrorKind, String)>,
+    /// Names of the local slots. Empty when the body keeps its variables in environments.
+    pub(crate) locals: Vec<String>,
+    /// Whether variables are resolved to local slots rather than environment lookups.
+    pub(crate) uses_slots: bool,
+    /// Functions defined at the top level of a module, bound before it runs.
+    pub(crate) hoisted: Vec<Idx>,
+}
@@ -0,0 +1,873 @@
+use super::code::{ArgKind, Code, FunctionProto, Idx, Op, SliceInfo};
+use crate::ast::{Argument, Expr, ExprKind, FStringSegment, Param, Stmt, StmtKind, Value};
+use crate::interpreter::error::EldritchErrorKind;
+use crate::token::{Span, TokenKind};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Builtins that read or write the caller's scope by name. Functions referring to
+/// them keep their variables in environments so those builtins can see them.
+const SCOPE_BUILTINS: &[&str] = &["eval", "load", "dir"];
+
+/// Compile the top-level statements of a script.
+pub(crate) fn compile_module(stmts: &[Stmt]) -> Code {
+    let mut c = Compiler::new(None);
+    let mut hoisted = Vec::new();
+    for stmt in stmts {
+        match &stmt.kind {
+            // The value of a top-level expression statement is the result of the run
+            StmtKind::Expression(expr) => {
+                let span = c.span(stmt.span);
+                c.emit(Op::Stmt(span));
+                c.expr(expr);
+                c.emit(Op::SetResult);
+            }
+            _ => {
+                c.stmt(stmt);
+                c.emit(Op::ClearResult);
+            }
+        }
+        // Only the first definition of a name is hoisted, matching the tree-walker
+        if let StmtKind::Def(name, ..) = &stmt.kind
+            && !hoisted.contains(name)
+        {
+            hoisted.push(name.clone());
+            c.code.hoisted.push(c.code.protos.len() as Idx - 1);
+        }
+    }
+    c.code
+}
+
+/// Compile the body of a function taking `params`.
+///
+/// Bodies that define closures or use scope-inspecting builtins keep their variables
+/// in environments; all others resolve every variable they assign to a local slot.
+pub(crate) fn compile_function(params: &[String], body: &[Stmt]) -> Code {
+    let mut scan = Scan::default();
+    for param in params {
+        scan.bind(param);
+    }
+    scan.stmts(body);
+
+    let slots = (!scan.dynamic).then(|| {
+        scan.assigned
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i as Idx))
+            .collect::<BTreeMap<_, _>>()
+    });
+    let mut c = Compiler::new(slots);
+    if c.slots.is_some() {
+        c.code.uses_slots = true;
+        c.code.locals = scan.assigned;
+    }
+    c.stmts(body);
+    c.code
+}
+
+fn param_name(param: &Param) -> &String {
+    match param {
+        Param::Normal(n, _)
+        | Param::WithDefault(n, _, _)
+        | Param::Star(n, _)
+        | Param::StarStar(n, _) => n,
+    }
+}
+
+enum CompKind<'a> {
+    List(&'a Expr),
+    Set(&'a Expr),
+    Dict(&'a Expr, &'a Expr),
+}
+
+struct Compiler {
+    code: Code,
+    /// Slot of every local variable, or `None` when variables live in environments.
+    slots: Option<BTreeMap<String, Idx>>,
+    name_ids: BTreeMap<String, Idx>,
+}
+
+impl Compiler {
+    fn new(slots: Option<BTreeMap<String, Idx>>) -> Self {
+        Self {
+            code: Code::default(),
+            slots,
+            name_ids: BTreeMap::new(),
+        }
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        self.code.ops.push(op);
+        self.code.ops.len() - 1
+    }
+
+    fn here(&self) -> Idx {
+        self.code.ops.len() as Idx
+    }
+
+    /// Point the jump emitted at `at` to the next instruction.
+    fn patch(&mut self, at: usize) {
+        let target = self.here();
+        match &mut self.code.ops[at] {
+            Op::Jump(t)
+            | Op::JumpIfFalse(t)
+            | Op::JumpIfFalseOrPop(t)
+            | Op::JumpIfTrueOrPop(t)
+            | Op::ForIter(t)
+            | Op::SetupLoop(t, _)
+            | Op::SetupExcept(t)
+            | Op::SetupFinally(t)
+            | Op::MatchError(t, _) => *t = target,
+            op => unreachable!("cannot patch {op:?}"),
+        }
+    }
+
+    fn span(&mut self, span: Span) -> Idx {
+        self.code.spans.push(span);
+        self.code.spans.len() as Idx - 1
+    }
+
+    fn constant(&mut self, value: Value) -> Idx {
+        self.code.consts.push(value);
+        self.code.consts.len() as Idx - 1
+    }
+
+    fn name(&mut self, name: &str) -> Idx {
+        if let Some(id) = self.name_ids.get(name) {
+            return *id;
+        }
+        let id = self.code.names.len() as Idx;
+        self.code.names.push(name.to_string());
+        self.name_ids.insert(name.to_string(), id);
+        id
+    }
+
+    fn token(&mut self, token: &TokenKind) -> Idx {
+        self.code.tokens.push(token.clone());
+        self.code.tokens.len() as Idx - 1
+    }
+
+    fn fail(&mut self, kind: EldritchErrorKind, msg: String, span: Span) {
+        self.code.failures.push((kind, msg));
+        let failure = self.code.failures.len() as Idx - 1;
+        let span = self.span(span);
+        self.emit(Op::Fail(failure, span));
+    }
+
+    fn slot(&self, name: &str) -> Option<Idx> {
+        self.slots.as_ref().and_then(|s| s.get(name).copied())
+    }
+
+    fn load(&mut self, name: &str, span: Span) {
+        let span = self.span(span);
+        match self.slot(name) {
+            Some(slot) => self.emit(Op::LoadLocal(slot, span)),
+            None => {
+                let name = self.name(name);
+                self.emit(Op::LoadName(name, span))
+            }
+        };
+    }
+
+    fn store(&mut self, name: &str) {
+        match self.slot(name) {
+            Some(slot) => self.emit(Op::StoreLocal(slot)),
+            None => {
+                let name = self.name(name);
+                self.emit(Op::StoreName(name))
+            }
+        };
+    }
+
+    fn define(&mut self, name: &str) {
+        match self.slot(name) {
+            Some(slot) => self.emit(Op::DefineLocal(slot)),
+            None => {
+                let name = self.name(name);
+                self.emit(Op::DefineName(name))
+            }
+        };
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        let span = self.span(stmt.span);
+        self.emit(Op::Stmt(span));
+
+        match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                self.expr(expr);
+                self.emit(Op::Pop);
+            }
+            StmtKind::Assignment(target, _annotation, value) => {
+                self.expr(value);
+                self.assign(target);
+            }
+            StmtKind::AugmentedAssignment(target, op, value) => {
+                self.augmented_assign(target, op, value);
+            }
+            StmtKind::If(cond, then_branch, else_branch) => {
+                self.expr(cond);
+                let to_else = self.emit(Op::JumpIfFalse(0));
+                self.stmts(then_branch);
+                match else_branch {
+                    Some(else_stmts) => {
+                        let to_end = self.emit(Op::Jump(0));
+                        self.patch(to_else);
+                        self.stmts(else_stmts);
+                        self.patch(to_end);
+                    }
+                    None => self.patch(to_else),
+                }
+            }
+            StmtKind::Return(expr) => {
+                match expr {
+                    Some(e) => self.expr(e),
+                    None => {
+                        let none = self.constant(Value::None);
+                        self.emit(Op::Const(none));
+                    }
+                }
+                self.emit(Op::Return);
+            }
+            StmtKind::Def(name, params, _return_annotation, body) => {
+                self.function(name, params, body.clone());
+                self.define(name);
+            }
+            StmtKind::For(idents, iterable, body) => {
+                self.expr(iterable);
+                let iter_span = self.span(iterable.span);
+                self.emit(Op::GetIter(iter_span));
+                let setup = self.emit(Op::SetupLoop(0, self.here() + 1));
+                let head = self.here();
+                let to_exit = self.emit(Op::ForIter(0));
+                self.emit(Op::Tick(span));
+                // Each iteration gets its own scope so loop variables don't leak
+                self.emit(Op::PushScope);
+                if let [ident] = idents.as_slice() {
+                    self.define(ident);
+                } else {
+                    self.emit(Op::UnpackLoop(idents.len() as Idx, span));
+                    for ident in idents {
+                        self.define(ident);
+                    }
+                }
+                self.stmts(body);
+                self.emit(Op::PopScope);
+                self.emit(Op::Jump(head));
+                self.patch(to_exit);
+                self.emit(Op::PopBlock);
+                self.patch(setup);
+                self.emit(Op::PopIter);
+            }
+            StmtKind::While(cond, body, else_branch) => {
+                let setup = self.emit(Op::SetupLoop(0, self.here() + 1));
+                let head = self.here();
+                self.expr(cond);
+                let to_else = self.emit(Op::JumpIfFalse(0));
+                self.emit(Op::LoopIter(span));
+                self.stmts(body);
+                self.emit(Op::Jump(head));
+                self.patch(to_else);
+                self.emit(Op::PopBlock);
+                // `else` runs outside the loop, so `break` there targets an enclosing loop
+                if let Some(else_stmts) = else_branch {
+                    self.stmts(else_stmts);
+                }
+                self.patch(setup);
+            }
+            StmtKind::Try(body, handlers, finally_body) => {
+                let finally_setup = finally_body
+                    .as_ref()
+                    .map(|_| self.emit(Op::SetupFinally(0)));
+                let except_setup = (!handlers.is_empty()).then(|| self.emit(Op::SetupExcept(0)));
+                self.stmts(body);
+
+                if let Some(except_setup) = except_setup {
+                    self.emit(Op::PopBlock);
+                    let mut to_end = vec![self.emit(Op::Jump(0))];
+                    self.patch(except_setup);
+                    for handler in handlers {
+                        let to_next = handler.kind.as_ref().map(|kind| {
+                            self.expr(kind);
+                            let kind_span = self.span(kind.span);
+                            self.emit(Op::MatchError(0, kind_span))
+                        });
+                        if let Some(name) = &handler.name {
+                            self.emit(Op::LoadError);
+                            self.store(name);
+                        }
+                        self.emit(Op::PopHandler);
+                        self.stmts(&handler.body);
+                        to_end.push(self.emit(Op::Jump(0)));
+                        if let Some(to_next) = to_next {
+                            self.patch(to_next);
+                        }
+                    }
+                    self.emit(Op::Reraise);
+                    for jump in to_end {
+                        self.patch(jump);
+                    }
+                }
+
+                if let (Some(finally_setup), Some(finally_stmts)) = (finally_setup, finally_body) {
+                    self.emit(Op::EnterFinally);
+                    self.patch(finally_setup);
+                    self.stmts(finally_stmts);
+                    self.emit(Op::EndFinally);
+                }
+            }
+            StmtKind::Break => {
+                self.emit(Op::Break);
+            }
+            StmtKind::Continue => {
+                self.emit(Op::Continue);
+            }
+            StmtKind::Pass => {}
+            StmtKind::Error(msg) => self.fail(
+                EldritchErrorKind::RuntimeError,
+                format!("Runtime encountered syntax error node: {msg}"),
+                stmt.span,
+            ),
+        }
+    }
+
+    fn assign(&mut self, target: &Expr) {
+        match &target.kind {
+            ExprKind::Identifier(name) => self.store(name),
+            ExprKind::List(elements) | ExprKind::Tuple(elements) => {
+                let span = self.span(target.span);
+                self.emit(Op::UnpackAssign(elements.len() as Idx, span));
+                for element in elements {
+                    self.assign(element);
+                }
+            }
+            ExprKind::Index(obj, index) => {
+                self.expr(obj);
+                self.expr(index);
+                let span = self.span(target.span);
+                let index_span = self.span(index.span);
+                self.emit(Op::StoreIndex(span, index_span));
+            }
+            _ => {
+                self.emit(Op::Pop);
+                self.fail(
+                    EldritchErrorKind::SyntaxError,
+                    "cannot assign to this expression".to_string(),
+                    target.span,
+                );
+            }
+        }
+    }
+
+    fn augmented_assign(&mut self, target: &Expr, op: &TokenKind, value: &Expr) {
+        self.expr(value);
+        match &target.kind {
+            ExprKind::Identifier(name) => {
+                self.load(name, target.span);
+                let op = self.token(op);
+                let span = self.span(target.span);
+                self.emit(Op::AugAssign(op, span));
+                self.store(name);
+            }
+            ExprKind::Index(obj, index) => {
+                self.expr(obj);
+                self.expr(index);
+                let op = self.token(op);
+                let span = self.span(target.span);
+                let index_span = self.span(index.span);
+                self.emit(Op::AugIndex(op, span, index_span));
+            }
+            _ => {
+                self.emit(Op::Pop);
+                self.fail(
+                    EldritchErrorKind::SyntaxError,
+                    "Illegal target for augmented assignment".to_string(),
+                    target.span,
+                );
+            }
+        }
+    }
+
+    /// Emit the default values and `MakeFunction` for a `def` or lambda.
+    fn function(&mut self, name: &str, params: &[Param], body: Vec<Stmt>) {
+        for param in params {
+            if let Param::WithDefault(_, _, default) = param {
+                self.expr(default);
+            }
+        }
+        let names: Vec<String> = params.iter().map(|p| param_name(p).clone()).collect();
+        let code = compile_function(&names, &body);
+        self.code.protos.push(Arc::new(FunctionProto {
+            name: name.to_string(),
+            params: params.to_vec(),
+            body,
+            code: Arc::new(code),
+        }));
+        self.emit(Op::MakeFunction(self.code.protos.len() as Idx - 1));
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        let span = expr.span;
+        match &expr.kind {
+            ExprKind::Literal(value) => {
+                let c = self.constant(value.clone());
+                self.emit(Op::Const(c));
+            }
+            ExprKind::Identifier(name) => self.load(name, span),
+            ExprKind::BinaryOp(left, op, right) => {
+                self.expr(left);
+                self.expr(right);
+                let op = self.token(op);
+                let span = self.span(span);
+                self.emit(Op::Binary(op, span));
+            }
+            ExprKind::UnaryOp(op, right) => {
+                self.expr(right);
+                let op = self.token(op);
+                let span = self.span(span);
+                self.emit(Op::Unary(op, span));
+            }
+            ExprKind::LogicalOp(left, op, right) => {
+                self.expr(left);
+                let jump = match op {
+                    TokenKind::Or => Op::JumpIfTrueOrPop(0),
+                    TokenKind::And => Op::JumpIfFalseOrPop(0),
+                    _ => {
+                        self.emit(Op::Pop);
+                        return self.fail(
+                            EldritchErrorKind::SyntaxError,
+                            "Invalid logical operator".to_string(),
+                            span,
+                        );
+                    }
+                };
+                let to_end = self.emit(jump);
+                self.expr(right);
+                self.patch(to_end);
+            }
+            ExprKind::Call(callee, args) => self.call(callee, args, span),
+            ExprKind::List(elements) => {
+                for e in elements {
+                    self.expr(e);
+                }
+                self.emit(Op::BuildList(elements.len() as Idx));
+            }
+            ExprKind::Tuple(elements) => {
+                for e in elements {
+                    self.expr(e);
+                }
+                self.emit(Op::BuildTuple(elements.len() as Idx));
+            }
+            ExprKind::Set(elements) => {
+                for e in elements {
+                    self.expr(e);
+                }
+                self.emit(Op::BuildSet(elements.len() as Idx));
+            }
+            ExprKind::Dictionary(entries) => {
+                for (k, v) in entries {
+                    self.expr(k);
+                    self.expr(v);
+                }
+                self.emit(Op::BuildDict(entries.len() as Idx));
+            }
+            ExprKind::Index(obj, index) => {
+                self.expr(obj);
+                self.expr(index);
+                let span = self.span(span);
+                let obj_span = self.span(obj.span);
+                let index_span = self.span(index.span);
+                self.emit(Op::Index(span, obj_span, index_span));
+            }
+            ExprKind::GetAttr(obj, name) => {
+                self.expr(obj);
+                let name = self.name(name);
+                let span = self.span(span);
+                self.emit(Op::GetAttr(name, span));
+            }
+            ExprKind::Slice(obj, start, stop, step) => {
+                self.expr(obj);
+                // Same evaluation order as the tree-walker: step before the bounds
+                let bound = |c: &mut Self, e: &Option<Box<Expr>>| {
+                    e.as_ref().map(|e| {
+                        c.expr(e);
+                        c.span(e.span)
+                    })
+                };
+                let step = bound(self, step);
+                let start = bound(self, start);
+                let stop = bound(self, stop);
+                let info = SliceInfo {
+                    start,
+                    stop,
+                    step,
+                    span: self.span(span),
+                    obj_span: self.span(obj.span),
+                };
+                self.code.slices.push(info);
+                self.emit(Op::Slice(self.code.slices.len() as Idx - 1));
+            }
+            ExprKind::FString(segments) => {
+                for segment in segments {
+                    match segment {
+                        FStringSegment::Literal(s) => {
+                            let c = self.constant(Value::String(s.clone()));
+                            self.emit(Op::Const(c));
+                        }
+                        FStringSegment::Expression(e) => self.expr(e),
+                        FStringSegment::Formatted(e, spec) => {
+                            self.expr(e);
+                            self.expr(spec);
+                            let sp = self.span(e.span);
+                            self.emit(Op::FormatValue(sp));
+                        }
+                    }
+                }
+                self.emit(Op::BuildString(segments.len() as Idx));
+            }
+            ExprKind::ListComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => self.comprehension(CompKind::List(body), vars, iterable, cond),
+            ExprKind::SetComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => self.comprehension(CompKind::Set(body), vars, iterable, cond),
+            ExprKind::DictComp {
+                key,
+                value,
+                vars,
+                iterable,
+                cond,
+            } => self.comprehension(CompKind::Dict(key, value), vars, iterable, cond),
+            ExprKind::Lambda { params, body } => {
+                let ret = Stmt {
+                    kind: StmtKind::Return(Some((**body).clone())),
+                    span: body.span,
+                };
+                self.function("<lambda>", params, vec![ret]);
+            }
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                let to_else = self.emit(Op::JumpIfFalse(0));
+                self.expr(then_branch);
+                let to_end = self.emit(Op::Jump(0));
+                self.patch(to_else);
+                self.expr(else_branch);
+                self.patch(to_end);
+            }
+            ExprKind::Error(msg) => self.fail(
+                EldritchErrorKind::RuntimeError,
+                format!("Runtime encountered syntax error node: {msg}"),
+                span,
+            ),
+        }
+    }
+
+    fn call(&mut self, callee: &Expr, args: &[Argument], span: Span) {
+        let span = self.span(span);
+        self.emit(Op::Tick(span));
+        self.expr(callee);
+        let name = match &callee.kind {
+            ExprKind::Identifier(name) => Some(self.name(name)),
+            _ => None,
+        };
+
+        if args.iter().all(|a| matches!(a, Argument::Positional(_))) {
+            for arg in args {
+                if let Argument::Positional(e) = arg {
+                    self.expr(e);
+                }
+            }
+            self.emit(Op::Call(args.len() as Idx, span, name));
+            return;
+        }
+
+        let mut shape = Vec::with_capacity(args.len());
+        for arg in args {
+            let kind = match arg {
+                Argument::Positional(e) => {
+                    self.expr(e);
+                    ArgKind::Positional
+                }
+                Argument::Keyword(n, e) => {
+                    self.expr(e);
+                    ArgKind::Keyword(self.name(n))
+                }
+                Argument::StarArgs(e) => {
+                    self.expr(e);
+                    ArgKind::StarArgs(self.span(e.span))
+                }
+                Argument::KwArgs(e) => {
+                    self.expr(e);
+                    ArgKind::KwArgs(self.span(e.span))
+                }
+            };
+            shape.push(kind);
+        }
+        self.code.calls.push(shape);
+        self.emit(Op::CallEx(self.code.calls.len() as Idx - 1, span, name));
+    }
+
+    fn comprehension(
+        &mut self,
+        kind: CompKind,
+        vars: &[String],
+        iterable: &Expr,
+        cond: &Option<Box<Expr>>,
+    ) {
+        self.emit(match kind {
+            CompKind::List(_) => Op::BuildList(0),
+            CompKind::Set(_) => Op::BuildSet(0),
+            CompKind::Dict(..) => Op::BuildDict(0),
+        });
+        self.expr(iterable);
+        let iter_span = self.span(iterable.span);
+        self.emit(Op::CompIter(iter_span));
+        // One scope for the whole comprehension, like the tree-walker
+        self.emit(Op::PushScope);
+        let head = self.here();
+        let to_exit = self.emit(Op::ForIter(0));
+        self.emit(Op::Tick(iter_span));
+        if let [var] = vars {
+            self.define(var);
+        } else {
+            self.emit(Op::UnpackComp(vars.len() as Idx, iter_span));
+            for var in vars {
+                self.define(var);
+            }
+        }
+        if let Some(cond) = cond {
+            self.expr(cond);
+            self.emit(Op::JumpIfFalse(head));
+        }
+        match kind {
+            CompKind::List(body) => {
+                self.expr(body);
+                self.emit(Op::ListAppend);
+            }
+            CompKind::Set(body) => {
+                self.expr(body);
+                self.emit(Op::SetAdd);
+            }
+            CompKind::Dict(key, value) => {
+                self.expr(key);
+                self.expr(value);
+                self.emit(Op::DictInsert);
+            }
+        }
+        self.emit(Op::Jump(head));
+        self.patch(to_exit);
+        self.emit(Op::PopScope);
+        self.emit(Op::PopIter);
+    }
+}
+
+/// Finds the variables a function body assigns, and whether it needs environments.
+#[derive(Default)]
+struct Scan {
+    assigned: Vec<String>,
+    dynamic: bool,
+}
+
+impl Scan {
+    fn bind(&mut self, name: &str) {
+        if !self.assigned.iter().any(|n| n == name) {
+            self.assigned.push(name.to_string());
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Expression(e) => self.expr(e),
+            StmtKind::Assignment(target, _, value) => {
+                self.expr(value);
+                self.target(target);
+            }
+            StmtKind::AugmentedAssignment(target, _, value) => {
+                self.expr(value);
+                self.target(target);
+            }
+            StmtKind::If(cond, then_branch, else_branch) => {
+                self.expr(cond);
+                self.stmts(then_branch);
+                if let Some(else_stmts) = else_branch {
+                    self.stmts(else_stmts);
+                }
+            }
+            StmtKind::Return(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+            }
+            // Nested functions close over the current environment
+            StmtKind::Def(..) => self.dynamic = true,
+            StmtKind::For(idents, iterable, body) => {
+                self.expr(iterable);
+                for ident in idents {
+                    self.bind(ident);
+                }
+                self.stmts(body);
+            }
+            StmtKind::While(cond, body, else_branch) => {
+                self.expr(cond);
+                self.stmts(body);
+                if let Some(else_stmts) = else_branch {
+                    self.stmts(else_stmts);
+                }
+            }
+            StmtKind::Try(body, handlers, finally_body) => {
+                self.stmts(body);
+                for handler in handlers {
+                    if let Some(kind) = &handler.kind {
+                        self.expr(kind);
+                    }
+                    if let Some(name) = &handler.name {
+                        self.bind(name);
+                    }
+                    self.stmts(&handler.body);
+                }
+                if let Some(finally_stmts) = finally_body {
+                    self.stmts(finally_stmts);
+                }
+            }
+            StmtKind::Break | StmtKind::Continue | StmtKind::Pass | StmtKind::Error(_) => {}
+        }
+    }
+
+    fn target(&mut self, target: &Expr) {
+        match &target.kind {
+            ExprKind::Identifier(name) => self.bind(name),
+            ExprKind::List(elements) | ExprKind::Tuple(elements) => {
+                for e in elements {
+                    self.target(e);
+                }
+            }
+            _ => self.expr(target),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Literal(_) | ExprKind::Error(_) => {}
+            ExprKind::Identifier(name) => {
+                if SCOPE_BUILTINS.contains(&name.as_str()) {
+                    self.dynamic = true;
+                }
+            }
+            ExprKind::BinaryOp(l, _, r) | ExprKind::LogicalOp(l, _, r) => {
+                self.expr(l);
+                self.expr(r);
+            }
+            ExprKind::UnaryOp(_, e) | ExprKind::GetAttr(e, _) => self.expr(e),
+            ExprKind::Call(callee, args) => {
+                self.expr(callee);
+                for arg in args {
+                    match arg {
+                        Argument::Positional(e)
+                        | Argument::Keyword(_, e)
+                        | Argument::StarArgs(e)
+                        | Argument::KwArgs(e) => self.expr(e),
+                    }
+                }
+            }
+            ExprKind::List(elements) | ExprKind::Tuple(elements) | ExprKind::Set(elements) => {
+                for e in elements {
+                    self.expr(e);
+                }
+            }
+            ExprKind::Dictionary(entries) => {
+                for (k, v) in entries {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Index(obj, index) => {
+                self.expr(obj);
+                self.expr(index);
+            }
+            ExprKind::Slice(obj, start, stop, step) => {
+                self.expr(obj);
+                for e in [start, stop, step].into_iter().flatten() {
+                    self.expr(e);
+                }
+            }
+            ExprKind::FString(segments) => {
+                for e in segments.iter().flat_map(FStringSegment::exprs) {
+                    self.expr(e);
+                }
+            }
+            ExprKind::ListComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            }
+            | ExprKind::SetComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => {
+                self.comprehension(vars, iterable, cond);
+                self.expr(body);
+            }
+            ExprKind::DictComp {
+                key,
+                value,
+                vars,
+                iterable,
+                cond,
+            } => {
+                self.comprehension(vars, iterable, cond);
+                self.expr(key);
+                self.expr(value);
+            }
+            ExprKind::Lambda { .. } => self.dynamic = true,
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.expr(then_branch);
+                self.expr(else_branch);
+            }
+        }
+    }
+
+    fn comprehension(&mut self, vars: &[String], iterable: &Expr, cond: &Option<Box<Expr>>) {
+        self.expr(iterable);
+        for var in vars {
+            self.bind(var);
+        }
+        if let Some(cond) = cond {
+            self.expr(cond);
+        }
+    }
+}
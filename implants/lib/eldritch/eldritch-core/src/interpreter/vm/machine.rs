@@ -0,0 +1,654 @@
+use super::code::{ArgKind, Code, Idx, Op};
+use crate::ast::{Environment, Function, Param, RuntimeParam, Value, ValueIter};
+use crate::interpreter::Freezable;
+use crate::interpreter::core::Interpreter;
+use crate::interpreter::error::{EldritchError, EldritchErrorKind};
+use crate::interpreter::eval::access::{
+    getattr_value, index_value, slice_bound, slice_step, slice_value,
+};
+use crate::interpreter::eval::functions::{call_value_with_args, extend_kwargs, extend_star_args};
+use crate::interpreter::eval::ops::{binary_op, unary_op};
+use crate::interpreter::eval::strings::format_value_or_error;
+use crate::interpreter::eval::{iterate, iteration_error};
+use crate::interpreter::exec::{
+    assign_index, augmented_index, augmented_value, error_kind_matches, error_to_value,
+    for_loop_iter, unpack_assignment, unpack_loop_item,
+};
+use crate::interpreter::introspection::is_truthy;
+use crate::interpreter::operations::unpack_comprehension_item;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use spin::RwLock;
+
+/// Turn a declared parameter into its runtime form, taking the default from `default`.
+pub(super) fn runtime_param(param: &Param, default: impl FnOnce() -> Value) -> RuntimeParam {
+    match param {
+        Param::Normal(n, _) => RuntimeParam::Normal(n.clone()),
+        Param::WithDefault(n, _, _) => RuntimeParam::WithDefault(n.clone(), default()),
+        Param::Star(n, _) => RuntimeParam::Star(n.clone()),
+        Param::StarStar(n, _) => RuntimeParam::StarStar(n.clone()),
+    }
+}
+
+/// A way of leaving the current instruction other than falling through to the next one.
+enum Unwind {
+    Error(EldritchError),
+    Break,
+    Continue,
+    Return(Value),
+}
+
+impl From<EldritchError> for Unwind {
+    fn from(err: EldritchError) -> Self {
+        Unwind::Error(err)
+    }
+}
+
+/// Scope pushed by a loop iteration or comprehension.
+enum Scope {
+    /// The environment to restore when the scope ends.
+    Env(Arc<RwLock<Environment>>),
+    /// Local slots bound in the scope and the values they had before it.
+    Slots(Vec<(Idx, Option<Value>)>),
+}
+
+/// Frame sizes to cut back to when control jumps out of a block.
+#[derive(Clone, Copy)]
+struct Depth {
+    stack: usize,
+    scopes: usize,
+    iters: usize,
+}
+
+enum Block {
+    Loop {
+        break_to: Idx,
+        continue_to: Idx,
+        depth: Depth,
+        iterations: u64,
+    },
+    Except {
+        handler: Idx,
+        depth: Depth,
+    },
+    Finally {
+        finally: Idx,
+        depth: Depth,
+    },
+    /// A finally body is running; what to resume once it ends, if anything.
+    Finalizing(Option<Unwind>),
+    /// The except clauses of a try statement are looking for a match for this error.
+    Handling(EldritchError),
+}
+
+/// Execution state of one module or function body.
+pub(super) struct Frame {
+    code: Arc<Code>,
+    pc: usize,
+    stack: Vec<Value>,
+    locals: Vec<Option<Value>>,
+    scopes: Vec<Scope>,
+    // Active loops, with the span of the iterable for errors raised while iterating
+    iters: Vec<(ValueIter, Idx)>,
+    blocks: Vec<Block>,
+    result: Value,
+}
+
+impl Frame {
+    pub(super) fn new(code: Arc<Code>, locals: Vec<Option<Value>>) -> Self {
+        Self {
+            code,
+            pc: 0,
+            stack: Vec::new(),
+            locals,
+            scopes: Vec::new(),
+            iters: Vec::new(),
+            blocks: Vec::new(),
+            result: Value::None,
+        }
+    }
+
+    /// Run to completion, returning the frame's return value or the module result.
+    pub(super) fn run(mut self, interp: &mut Interpreter) -> Result<Value, EldritchError> {
+        let result = self.run_loop(interp);
+        self.pop_scopes(interp, 0);
+        result
+    }
+
+    fn run_loop(&mut self, interp: &mut Interpreter) -> Result<Value, EldritchError> {
+        while let Some(&op) = self.code.ops.get(self.pc) {
+            self.pc += 1;
+            let outcome = match op {
+                Op::Call(..) | Op::CallEx(..) => self.call(interp, op).map_err(Unwind::from),
+                _ => self.step(interp, op),
+            };
+            if let Err(unwind) = outcome
+                && let Some(value) = self.unwind(interp, unwind)?
+            {
+                return Ok(value);
+            }
+        }
+        Ok(core::mem::replace(&mut self.result, Value::None))
+    }
+
+    fn depth(&self) -> Depth {
+        Depth {
+            stack: self.stack.len(),
+            scopes: self.scopes.len(),
+            iters: self.iters.len(),
+        }
+    }
+
+    fn restore(&mut self, interp: &mut Interpreter, depth: Depth) {
+        self.stack.truncate(depth.stack);
+        self.pop_scopes(interp, depth.scopes);
+        self.iters.truncate(depth.iters);
+    }
+
+    fn pop_scopes(&mut self, interp: &mut Interpreter, len: usize) {
+        while self.scopes.len() > len {
+            match self.scopes.pop() {
+                Some(Scope::Env(env)) => interp.env = env,
+                Some(Scope::Slots(saved)) => {
+                    for (slot, value) in saved.into_iter().rev() {
+                        self.locals[slot as usize] = value;
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Remember the value `slot` had before the innermost scope, once per scope.
+    fn save_slot(&mut self, slot: Idx) {
+        if let Some(Scope::Slots(saved)) = self.scopes.last_mut()
+            && !saved.iter().any(|(s, _)| *s == slot)
+        {
+            saved.push((slot, self.locals[slot as usize].clone()));
+        }
+    }
+
+    /// Route `unwind` to the innermost block that handles it.
+    ///
+    /// Returns `Some` when the frame is finished with that value.
+    fn unwind(
+        &mut self,
+        interp: &mut Interpreter,
+        mut unwind: Unwind,
+    ) -> Result<Option<Value>, EldritchError> {
+        while let Some(block) = self.blocks.pop() {
+            match block {
+                Block::Loop {
+                    break_to,
+                    continue_to,
+                    depth,
+                    iterations,
+                } => match unwind {
+                    Unwind::Break => {
+                        self.restore(interp, depth);
+                        self.pc = break_to as usize;
+                        return Ok(None);
+                    }
+                    Unwind::Continue => {
+                        self.restore(interp, depth);
+                        self.blocks.push(Block::Loop {
+                            break_to,
+                            continue_to,
+                            depth,
+                            iterations,
+                        });
+                        self.pc = continue_to as usize;
+                        return Ok(None);
+                    }
+                    _ => {}
+                },
+                Block::Except { handler, depth } => {
+                    unwind = match unwind {
+                        Unwind::Error(err) if err.kind.is_catchable() => {
+                            self.restore(interp, depth);
+                            self.blocks.push(Block::Handling(err));
+                            self.pc = handler as usize;
+                            return Ok(None);
+                        }
+                        other => other,
+                    };
+                }
+                Block::Finally { finally, depth } => {
+                    self.restore(interp, depth);
+                    self.blocks.push(Block::Finalizing(Some(unwind)));
+                    self.pc = finally as usize;
+                    return Ok(None);
+                }
+                // Leaving a finally body early resumes an error the script can't handle
+                Block::Finalizing(Some(Unwind::Error(err))) if !err.kind.is_catchable() => {
+                    unwind = Unwind::Error(err);
+                }
+                // and discards anything else it was going to resume
+                Block::Finalizing(_) | Block::Handling(_) => {}
+            }
+        }
+
+        match unwind {
+            Unwind::Error(err) => Err(err),
+            Unwind::Return(value) => Ok(Some(value)),
+            Unwind::Break | Unwind::Continue => Ok(Some(Value::None)),
+        }
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().unwrap_or(Value::None)
+    }
+
+    fn pop_n(&mut self, n: Idx) -> Vec<Value> {
+        let at = self.stack.len().saturating_sub(n as usize);
+        self.stack.split_off(at)
+    }
+
+    /// Push unpacked values so that the first one ends up on top.
+    fn push_unpacked(&mut self, values: Vec<Value>) {
+        self.stack.extend(values.into_iter().rev());
+    }
+
+    fn handling(&self) -> &EldritchError {
+        match self.blocks.last() {
+            Some(Block::Handling(err)) => err,
+            _ => unreachable!("except clause outside of error handling"),
+        }
+    }
+
+    /// Run a call instruction.
+    ///
+    /// Kept out of [`Frame::step`] so that recursive calls only stack up the
+    /// small frames on the call path.
+    #[inline(never)]
+    fn call(&mut self, interp: &mut Interpreter, op: Op) -> Result<(), EldritchError> {
+        let code = self.code.clone();
+        let span = |i: Idx| code.spans[i as usize];
+        let (args, kwargs, sp, name) = match op {
+            Op::Call(argc, sp, name) => (self.pop_n(argc), BTreeMap::new(), sp, name),
+            Op::CallEx(i, sp, name) => {
+                let shape = &code.calls[i as usize];
+                let values = self.pop_n(shape.len() as Idx);
+                let mut args = Vec::new();
+                let mut kwargs = BTreeMap::new();
+                for (kind, value) in shape.iter().zip(values) {
+                    match kind {
+                        ArgKind::Positional => args.push(value),
+                        ArgKind::Keyword(n) => {
+                            kwargs.insert(code.names[*n as usize].clone(), value);
+                        }
+                        ArgKind::StarArgs(sp) => {
+                            extend_star_args(interp, &mut args, value, span(*sp))?
+                        }
+                        ArgKind::KwArgs(sp) => {
+                            extend_kwargs(interp, &mut kwargs, value, span(*sp))?
+                        }
+                    }
+                }
+                (args, kwargs, sp, name)
+            }
+            _ => unreachable!("not a call instruction"),
+        };
+        let callee = self.pop();
+        let name = name.map(|n| code.names[n as usize].as_str());
+        let value = call_value_with_args(interp, callee, name, args, kwargs, span(sp))?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, op: Op) -> Result<(), Unwind> {
+        let code = self.code.clone();
+        let span = |i: Idx| code.spans[i as usize];
+
+        match op {
+            Op::Const(c) => self.stack.push(code.consts[c as usize].clone()),
+            Op::Pop => {
+                self.pop();
+            }
+
+            Op::LoadName(n, sp) => {
+                let value = interp.lookup_variable(&code.names[n as usize], span(sp))?;
+                self.stack.push(value);
+            }
+            Op::StoreName(n) => {
+                let value = self.pop();
+                interp.assign_variable(&code.names[n as usize], value);
+            }
+            Op::DefineName(n) => {
+                let value = self.pop();
+                interp.define_variable(&code.names[n as usize], value);
+            }
+            Op::LoadLocal(slot, sp) => {
+                let value = match &self.locals[slot as usize] {
+                    Some(value) => value.clone(),
+                    None => interp.lookup_variable(&code.locals[slot as usize], span(sp))?,
+                };
+                self.stack.push(value);
+            }
+            Op::StoreLocal(slot) => {
+                let value = self.pop();
+                if self.locals[slot as usize].is_some() {
+                    self.locals[slot as usize] = Some(value);
+                } else if let Err(value) =
+                    interp.update_variable(&code.locals[slot as usize], value)
+                {
+                    self.save_slot(slot);
+                    self.locals[slot as usize] = Some(value);
+                }
+            }
+            Op::DefineLocal(slot) => {
+                let value = self.pop();
+                self.save_slot(slot);
+                self.locals[slot as usize] = Some(value);
+            }
+
+            Op::Unary(t, sp) => {
+                let value = self.pop();
+                let value = unary_op(interp, &code.tokens[t as usize], value, span(sp))?;
+                self.stack.push(value);
+            }
+            Op::Binary(t, sp) => {
+                let right = self.pop();
+                let left = self.pop();
+                let value = binary_op(interp, left, &code.tokens[t as usize], right, span(sp))?;
+                self.stack.push(value);
+            }
+            Op::AugAssign(t, sp) => {
+                let left = self.pop();
+                let right = self.pop();
+                // In-place updates of containers store the same container back
+                let container =
+                    matches!(left, Value::List(_) | Value::Dictionary(_) | Value::Set(_))
+                        .then(|| left.clone());
+                let value =
+                    augmented_value(interp, left, &code.tokens[t as usize], right, span(sp))?;
+                self.stack.push(value.or(container).unwrap_or(Value::None));
+            }
+            Op::AugIndex(t, sp, index_sp) => {
+                let index = self.pop();
+                let obj = self.pop();
+                let right = self.pop();
+                augmented_index(
+                    interp,
+                    obj,
+                    index,
+                    &code.tokens[t as usize],
+                    right,
+                    span(sp),
+                    span(index_sp),
+                )?;
+            }
+
+            Op::Jump(target) => self.pc = target as usize,
+            Op::JumpIfFalse(target) => {
+                if !is_truthy(&self.pop()) {
+                    self.pc = target as usize;
+                }
+            }
+            Op::JumpIfFalseOrPop(target) => {
+                if self.stack.last().is_some_and(|v| !is_truthy(v)) {
+                    self.pc = target as usize;
+                } else {
+                    self.pop();
+                }
+            }
+            Op::JumpIfTrueOrPop(target) => {
+                if self.stack.last().is_some_and(is_truthy) {
+                    self.pc = target as usize;
+                } else {
+                    self.pop();
+                }
+            }
+
+            Op::BuildList(n) => {
+                let values = self.pop_n(n);
+                self.stack
+                    .push(Value::List(Arc::new(Freezable::new(values))));
+            }
+            Op::BuildTuple(n) => {
+                let values = self.pop_n(n);
+                self.stack.push(Value::Tuple(values));
+            }
+            Op::BuildDict(n) => {
+                let values = self.pop_n(n * 2);
+                let mut map = BTreeMap::new();
+                let mut values = values.into_iter();
+                while let (Some(k), Some(v)) = (values.next(), values.next()) {
+                    map.insert(k, v);
+                }
+                self.stack
+                    .push(Value::Dictionary(Arc::new(Freezable::new(map))));
+            }
+            Op::BuildSet(n) => {
+                #[allow(clippy::mutable_key_type)]
+                let set: BTreeSet<Value> = self.pop_n(n).into_iter().collect();
+                self.stack.push(Value::Set(Arc::new(Freezable::new(set))));
+            }
+            Op::BuildString(n) => {
+                let parts: Vec<String> = self.pop_n(n).iter().map(|v| v.to_string()).collect();
+                self.stack.push(Value::String(parts.join("")));
+            }
+            Op::FormatValue(sp) => {
+                let spec = self.pop().to_string();
+                let value = self.pop();
+                let text = format_value_or_error(interp, &value, &spec, span(sp))?;
+                self.stack.push(Value::String(text));
+            }
+            Op::ListAppend => {
+                let value = self.pop();
+                if let Some(Value::List(l)) = self.stack.last() {
+                    l.write().push(value);
+                }
+            }
+            Op::SetAdd => {
+                let value = self.pop();
+                if let Some(Value::Set(s)) = self.stack.last() {
+                    s.write().insert(value);
+                }
+            }
+            Op::DictInsert => {
+                let value = self.pop();
+                let key = self.pop();
+                if let Some(Value::Dictionary(d)) = self.stack.last() {
+                    d.write().insert(key, value);
+                }
+            }
+
+            Op::Index(sp, obj_sp, index_sp) => {
+                let index = self.pop();
+                let obj = self.pop();
+                let value =
+                    index_value(interp, obj, index, span(sp), span(obj_sp), span(index_sp))?;
+                self.stack.push(value);
+            }
+            Op::Slice(i) => {
+                let info = code.slices[i as usize];
+                let stop = info.stop.map(|sp| (self.pop(), span(sp)));
+                let start = info.start.map(|sp| (self.pop(), span(sp)));
+                let step = info.step.map(|sp| (self.pop(), span(sp)));
+                let obj = self.pop();
+                let step = slice_step(interp, step, span(info.span))?;
+                let start = slice_bound(interp, start, "start")?;
+                let stop = slice_bound(interp, stop, "stop")?;
+                let value = slice_value(interp, obj, start, stop, step, span(info.obj_span))?;
+                self.stack.push(value);
+            }
+            Op::GetAttr(n, sp) => {
+                let obj = self.pop();
+                let value = getattr_value(obj, code.names[n as usize].clone()).or_else(|msg| {
+                    interp.error(EldritchErrorKind::AttributeError, &msg, span(sp))
+                })?;
+                self.stack.push(value);
+            }
+            Op::StoreIndex(sp, index_sp) => {
+                let index = self.pop();
+                let obj = self.pop();
+                let value = self.pop();
+                assign_index(interp, obj, index, value, span(sp), span(index_sp))?;
+            }
+            Op::UnpackAssign(n, sp) => {
+                let value = self.pop();
+                let values = unpack_assignment(interp, value, n as usize, span(sp))?;
+                self.push_unpacked(values);
+            }
+            Op::UnpackLoop(n, sp) => {
+                let value = self.pop();
+                let values = unpack_loop_item(interp, value, n as usize, span(sp))?;
+                self.push_unpacked(values);
+            }
+            Op::UnpackComp(n, sp) => {
+                let value = self.pop();
+                let values = unpack_comprehension_item(interp, value, n as usize, span(sp))?;
+                self.push_unpacked(values);
+            }
+
+            Op::Tick(sp) => interp.check_budget(span(sp))?,
+            Op::Stmt(sp) => interp.enter_statement(span(sp))?,
+            Op::Call(..) | Op::CallEx(..) => self.call(interp, op)?,
+            Op::MakeFunction(p) => {
+                let proto = &code.protos[p as usize];
+                let count = proto
+                    .params
+                    .iter()
+                    .filter(|p| matches!(p, Param::WithDefault(..)))
+                    .count();
+                let mut defaults = self.pop_n(count as Idx).into_iter();
+                let params = proto
+                    .params
+                    .iter()
+                    .map(|p| runtime_param(p, || defaults.next().unwrap_or(Value::None)))
+                    .collect();
+                self.stack.push(Value::Function(Function {
+                    name: proto.name.clone(),
+                    params,
+                    body: proto.body.clone(),
+                    closure: interp.env.clone(),
+                    code: Some(proto.code.clone()),
+                }));
+            }
+            Op::Return => return Err(Unwind::Return(self.pop())),
+
+            Op::GetIter(sp) => {
+                let iterable = self.pop();
+                let items = for_loop_iter(interp, iterable, span(sp))?;
+                self.iters.push((items, sp));
+            }
+            Op::CompIter(sp) => {
+                let iterable = self.pop();
+                let items = iterate(interp, &iterable, span(sp))?;
+                self.iters.push((items, sp));
+            }
+            Op::ForIter(exit) => match self.iters.last_mut() {
+                Some((items, sp)) => match items.next() {
+                    Some(Ok(item)) => self.stack.push(item),
+                    Some(Err(msg)) => iteration_error(interp, msg, span(*sp))?,
+                    None => self.pc = exit as usize,
+                },
+                None => self.pc = exit as usize,
+            },
+            Op::PopIter => {
+                self.iters.pop();
+            }
+            Op::PushScope => {
+                if code.uses_slots {
+                    self.scopes.push(Scope::Slots(Vec::new()));
+                } else {
+                    let parent = interp.env.clone();
+                    let printer = parent.read().printer.clone();
+                    interp.env = Arc::new(RwLock::new(Environment {
+                        parent: Some(parent.clone()),
+                        values: BTreeMap::new(),
+                        printer,
+                        libraries: BTreeSet::new(),
+                        max_sequence_len: interp.budget.limits.max_sequence_len,
+                    }));
+                    self.scopes.push(Scope::Env(parent));
+                }
+            }
+            Op::PopScope => {
+                let len = self.scopes.len().saturating_sub(1);
+                self.pop_scopes(interp, len);
+            }
+
+            Op::SetupLoop(break_to, continue_to) => {
+                self.blocks.push(Block::Loop {
+                    break_to,
+                    continue_to,
+                    depth: self.depth(),
+                    iterations: 0,
+                });
+            }
+            Op::LoopIter(sp) => {
+                if let Some(Block::Loop { iterations, .. }) = self.blocks.last_mut() {
+                    *iterations += 1;
+                    if let Some(max) = interp.budget.limits.max_loop_iterations
+                        && *iterations > max
+                    {
+                        interp.error::<()>(
+                            EldritchErrorKind::LoopLimitError,
+                            &format!("while loop exceeded the maximum of {max} iterations"),
+                            span(sp),
+                        )?;
+                    }
+                }
+                interp.check_budget(span(sp))?;
+            }
+            Op::PopBlock => {
+                self.blocks.pop();
+            }
+            Op::Break => return Err(Unwind::Break),
+            Op::Continue => return Err(Unwind::Continue),
+
+            Op::SetupExcept(handler) => {
+                self.blocks.push(Block::Except {
+                    handler,
+                    depth: self.depth(),
+                });
+            }
+            Op::SetupFinally(finally) => {
+                self.blocks.push(Block::Finally {
+                    finally,
+                    depth: self.depth(),
+                });
+            }
+            Op::EnterFinally => {
+                self.blocks.pop();
+                self.blocks.push(Block::Finalizing(None));
+            }
+            Op::EndFinally => {
+                if let Some(Block::Finalizing(Some(pending))) = self.blocks.pop() {
+                    return Err(pending);
+                }
+            }
+            Op::MatchError(next, sp) => {
+                let kinds = self.pop();
+                if !error_kind_matches(interp, &kinds, self.handling(), span(sp))? {
+                    self.pc = next as usize;
+                }
+            }
+            Op::LoadError => {
+                let value = error_to_value(self.handling());
+                self.stack.push(value);
+            }
+            Op::PopHandler => {
+                self.blocks.pop();
+            }
+            Op::Reraise => {
+                if let Some(Block::Handling(err)) = self.blocks.pop() {
+                    return Err(Unwind::Error(err));
+                }
+            }
+
+            Op::SetResult => self.result = self.pop(),
+            Op::ClearResult => self.result = Value::None,
+            Op::Fail(f, sp) => {
+                let (kind, msg) = &code.failures[f as usize];
+                interp.error::<()>(kind.clone(), msg, span(sp))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Bytecode backend, enabled by the `bytecode` feature.
+//!
+//! Scripts are compiled to a flat instruction list per module and function body
+//! and run on a small stack machine. Values, builtins, libraries and error
+//! reporting are shared with the tree-walking interpreter, so both backends
+//! behave the same from a script's point of view.
+
+mod code;
+mod compiler;
+mod machine;
+
+pub(crate) use self::code::Code;
+
+use self::compiler::{compile_function, compile_module};
+use self::machine::Frame;
+use super::core::Interpreter;
+use super::error::EldritchError;
+use crate::ast::{Function, RuntimeParam, Stmt, Value};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Run the top-level statements of a script in the interpreter's current environment.
+pub(crate) fn execute_module(
+    interp: &mut Interpreter,
+    stmts: &[Stmt],
+) -> Result<Value, EldritchError> {
+    let code = Arc::new(compile_module(stmts));
+
+    // Functions are callable before their `def` runs; defaults are filled in then
+    for &proto in &code.hoisted {
+        let proto = &code.protos[proto as usize];
+        let params = proto
+            .params
+            .iter()
+            .map(|p| machine::runtime_param(p, || Value::None))
+            .collect();
+        let func = Value::Function(Function {
+            name: proto.name.clone(),
+            params,
+            body: proto.body.clone(),
+            closure: interp.env.clone(),
+            code: Some(proto.code.clone()),
+        });
+        interp.define_variable(&proto.name, func);
+    }
+
+    Frame::new(code, Vec::new()).run(interp)
+}
+
+/// Run the body of `func`. The caller has already bound the arguments in `interp.env`.
+pub(crate) fn execute_function(
+    interp: &mut Interpreter,
+    func: &Function,
+) -> Result<Value, EldritchError> {
+    let code = match &func.code {
+        Some(code) => code.clone(),
+        None => {
+            let params: Vec<String> = func
+                .params
+                .iter()
+                .map(|p| match p {
+                    RuntimeParam::Normal(n)
+                    | RuntimeParam::WithDefault(n, _)
+                    | RuntimeParam::Star(n)
+                    | RuntimeParam::StarStar(n) => n.clone(),
+                })
+                .collect();
+            Arc::new(compile_function(&params, &func.body))
+        }
+    };
+
+    let mut locals = Vec::new();
+    if code.uses_slots {
+        let mut env = interp.env.write();
+        locals = code
+            .locals
+            .iter()
+            .map(|name| env.values.remove(name))
+            .collect();
+    }
+
+    Frame::new(code, locals).run(interp)
+}
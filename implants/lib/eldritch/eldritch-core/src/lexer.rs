@@ -1,9 +1,10 @@
-use super::token::{Span, Token, TokenKind};
+use super::token::{Comment, Span, Token, TokenKind};
 use alloc::collections::VecDeque;
 use alloc::format;
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
+use num_bigint::BigInt;
 
 pub struct Lexer {
     source: Vec<char>,
@@ -13,6 +14,7 @@ pub struct Lexer {
     indent_stack: Vec<usize>,
     nesting: usize,
     pending_tokens: VecDeque<Token>,
+    comments: Vec<Comment>,
 }
 
 impl Lexer {
@@ -28,6 +30,7 @@ impl Lexer {
             indent_stack: vec![0],
             nesting: 0,
             pending_tokens: VecDeque::new(),
+            comments: Vec::new(),
         }
     }
 
@@ -77,10 +80,20 @@ impl Lexer {
         }
     }
 
+    /// The comments skipped so far, in source order.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
     fn skip_comment(&mut self) {
+        let start = self.current;
         while self.peek() != '\n' && !self.is_at_end() {
             self.advance();
         }
+        self.comments.push(Comment {
+            text: self.source[start..self.current].iter().collect(),
+            span: Span::new(start, self.current, self.line),
+        });
     }
 
     fn identifier(&mut self) -> Token {
@@ -114,8 +127,13 @@ impl Lexer {
         }
 
         let value: String = self.source[self.start..self.current].iter().collect();
-        let number: i64 = value.parse().unwrap_or(0);
-        self.add_token(TokenKind::Integer(number))
+        match value.parse::<i64>() {
+            Ok(number) => self.add_token(TokenKind::Integer(number)),
+            Err(_) => {
+                let number: BigInt = value.parse().unwrap_or_default();
+                self.add_token(TokenKind::BigInteger(number))
+            }
+        }
     }
 
     fn string(
@@ -275,7 +293,6 @@ impl Lexer {
     }
 
     fn tokenize_fstring_expression(&mut self) -> Vec<Token> {
-        let mut tokens = Vec::new();
         let initial_start = self.current;
         let mut nesting_level = 1;
 
@@ -298,7 +315,21 @@ impl Lexer {
         }
 
         let end_of_expr = self.current;
-        let expr_source: String = self.source[initial_start..end_of_expr].iter().collect();
+        let field = self.source[initial_start..end_of_expr].to_vec();
+        self.advance();
+        self.fstring_field_tokens(&field)
+    }
+
+    // `(`, the tokens of a replacement field's expression and `)`, then `:` and the format
+    // spec if there is one. A spec with nested fields, as in `{x:>{width}}`, is itself
+    // f-string content.
+    fn fstring_field_tokens(&mut self, field: &[char]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let (expr_chars, spec) = match format_spec_start(field) {
+            Some(colon) => (&field[..colon], Some(&field[colon + 1..])),
+            None => (field, None),
+        };
+        let expr_source: String = expr_chars.iter().collect();
         let mut expr_lexer = Lexer::new(expr_source);
 
         // Recursively tokenize the expression inside the f-string
@@ -315,14 +346,48 @@ impl Lexer {
             }
         }
 
-        self.advance();
-
         let mut final_tokens = vec![self.add_token(TokenKind::LParen)];
         final_tokens.extend(tokens);
         final_tokens.push(self.add_token(TokenKind::RParen));
+        if let Some(spec) = spec {
+            final_tokens.push(self.add_token(TokenKind::Colon));
+            if spec.contains(&'{') {
+                let spec_tokens = self.fstring_spec_tokens(spec);
+                final_tokens.push(self.add_token(TokenKind::FStringContent(spec_tokens)));
+            } else {
+                final_tokens.push(self.create_string_token(spec.iter().collect()));
+            }
+        }
         final_tokens
     }
 
+    // The literal text and replacement fields of a format spec with nested fields
+    fn fstring_spec_tokens(&mut self, spec: &[char]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut i = 0;
+        while i < spec.len() {
+            if spec[i] != '{' {
+                literal.push(spec[i]);
+                i += 1;
+                continue;
+            }
+            let Some(end) = matching_brace(spec, i) else {
+                literal.extend(&spec[i..]);
+                break;
+            };
+            if !literal.is_empty() {
+                tokens.push(self.create_string_token(core::mem::take(&mut literal)));
+            }
+            tokens.extend(self.fstring_field_tokens(&spec[i + 1..end]));
+            i = end + 1;
+        }
+        if !literal.is_empty() {
+            tokens.push(self.create_string_token(literal));
+        }
+        tokens
+    }
+
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         let mut tokens = Vec::new();
         loop {
@@ -363,6 +428,11 @@ impl Lexer {
                 return self.add_token(TokenKind::Eof);
             }
 
+            // A line holding only a comment is blank: it doesn't open or close a block
+            if self.peek() == '#' {
+                self.skip_comment();
+            }
+
             if self.peek() != '\n' {
                 let current_indent = *self.indent_stack.last().unwrap();
                 if indent_count > current_indent {
@@ -539,6 +609,7 @@ impl Lexer {
                 }
             }
             '#' => {
+                self.current = self.start;
                 self.skip_comment();
                 self.next_token()
             }
@@ -589,3 +660,40 @@ impl Lexer {
         }
     }
 }
+
+// The index of the `:` that starts the format spec of an f-string field, skipping colons
+// inside brackets and string literals such as `{d["a:b"]:>8}`.
+// The index of the `}` that closes the `{` at `open`.
+fn matching_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn format_spec_start(field: &[char]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, &c) in field.iter().enumerate() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ':') if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
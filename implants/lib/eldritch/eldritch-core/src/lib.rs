@@ -7,24 +7,34 @@ extern crate self as eldritch_core;
 // Internal
 pub mod analysis;
 mod ast;
+pub mod docs;
+pub mod formatter;
 mod interpreter;
 mod lexer;
 mod parser;
 mod token;
 
 // Re-export core types
-pub use analysis::find_node_at_offset;
+pub use analysis::{find_node_at_offset, shift_spans, walk_exprs};
 pub use ast::{
-    Argument, Environment, ExprKind, FStringSegment, ForeignValue, Param, Stmt, StmtKind, Value,
+    Argument, Environment, ExceptHandler, Expr, ExprKind, FStringSegment, ForeignValue,
+    LazyIterable, Param, Stmt, StmtKind, Value, ValueIter,
 };
 pub use interpreter::builtins::pprint::pretty_format;
 pub use interpreter::builtins::tprint::format_tprint;
-pub use interpreter::{BufferPrinter, Interpreter, NoopPrinter, Printer, StdoutPrinter};
+pub use interpreter::{
+    BranchCoverage, BufferPrinter, CancellationToken, CoverageRecorder, CoverageReport, DebugHook,
+    EldritchErrorKind, ExecutionLimits, Freezable, FunctionCoverage, Interpreter, NoopPrinter,
+    Printer, Profile, ProfileClock, ProfileStats, ScriptError, SourceSnippet, StdoutPrinter,
+    TracebackFrame,
+};
 pub use lexer::Lexer;
-pub use token::{Span, TokenKind};
+pub use num_bigint::BigInt;
+pub use token::{Comment, Span, TokenKind};
 
 // Public API exports
 pub mod conversion;
+pub mod typecheck;
 // Re-export introspection for macros and tests
 pub use interpreter::introspection;
 
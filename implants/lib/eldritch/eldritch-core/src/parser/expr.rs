@@ -564,6 +564,12 @@ impl Parser {
             return Ok(self.make_expr(ExprKind::Literal(Value::Int(i)), span, span));
         }
 
+        if let TokenKind::BigInteger(i) = &token.kind {
+            let value = Value::BigInt(i.clone());
+            self.advance();
+            return Ok(self.make_expr(ExprKind::Literal(value), span, span));
+        }
+
         if let TokenKind::Float(f) = token.kind {
             self.advance();
             return Ok(self.make_expr(ExprKind::Literal(Value::Float(f)), span, span));
@@ -934,11 +940,28 @@ impl Parser {
                 internal_parser.advance();
             } else if internal_parser.match_token(&[TokenKind::LParen]) {
                 let expr = internal_parser.expression()?;
-                segments.push(FStringSegment::Expression(expr));
                 internal_parser.consume(
                     |t| matches!(t, TokenKind::RParen),
                     "Expected ')' to close f-string embedded expression.",
                 )?;
+                if internal_parser.match_token(&[TokenKind::Colon]) {
+                    let spec_token = internal_parser.peek().clone();
+                    let spec = match spec_token.kind {
+                        TokenKind::String(spec) => {
+                            internal_parser.advance();
+                            let kind = ExprKind::Literal(Value::String(spec));
+                            self.make_expr(kind, spec_token.span, spec_token.span)
+                        }
+                        TokenKind::FStringContent(spec_tokens) => {
+                            internal_parser.advance();
+                            internal_parser.parse_fstring_content(spec_tokens, spec_token.span)?
+                        }
+                        _ => return internal_parser.error("Expected a format spec after ':'."),
+                    };
+                    segments.push(FStringSegment::Formatted(expr, spec));
+                } else {
+                    segments.push(FStringSegment::Expression(expr));
+                }
             } else {
                 return internal_parser.error(&format!(
                     "Unexpected token in f-string content: {:?}",
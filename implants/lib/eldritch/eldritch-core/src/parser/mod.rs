@@ -87,6 +87,8 @@ impl Parser {
                 TokenKind::Def
                 | TokenKind::If
                 | TokenKind::For
+                | TokenKind::While
+                | TokenKind::Try
                 | TokenKind::Return
                 | TokenKind::Pass
                 | TokenKind::Break
@@ -1,4 +1,4 @@
-use super::super::ast::{ExprKind, Stmt, StmtKind};
+use super::super::ast::{ExceptHandler, ExprKind, Stmt, StmtKind};
 use super::super::interpreter::error::EldritchError;
 use super::super::token::{Span, TokenKind};
 use super::Parser;
@@ -109,6 +109,10 @@ impl Parser {
             self.return_statement(start)
         } else if self.match_token(&[TokenKind::For]) {
             self.for_statement(start)
+        } else if self.match_token(&[TokenKind::While]) {
+            self.while_statement(start)
+        } else if self.match_token(&[TokenKind::Try]) {
+            self.try_statement(start)
         } else if self.match_token(&[TokenKind::Break]) {
             if !self.is_at_end() && !matches!(self.peek().kind, TokenKind::Dedent) {
                 self.consume(
@@ -178,6 +182,103 @@ impl Parser {
         Ok(self.make_stmt(StmtKind::For(vars, iterable, body), start, end))
     }
 
+    fn while_statement(&mut self, start: Span) -> Result<Stmt, EldritchError> {
+        let condition = self.expression()?;
+        self.consume(
+            |t| matches!(t, TokenKind::Colon),
+            "Expected ':' after while condition.",
+        )?;
+        let body = self.parse_block_or_statement()?;
+        let mut end = if let Some(last) = body.last() {
+            last.span
+        } else {
+            condition.span
+        };
+
+        let mut else_branch = None;
+        if self.match_token(&[TokenKind::Else]) {
+            self.consume(
+                |t| matches!(t, TokenKind::Colon),
+                "Expected ':' after else.",
+            )?;
+            let else_stmts = self.parse_block_or_statement()?;
+            if let Some(last) = else_stmts.last() {
+                end = last.span;
+            }
+            else_branch = Some(else_stmts);
+        }
+        Ok(self.make_stmt(StmtKind::While(condition, body, else_branch), start, end))
+    }
+
+    fn try_statement(&mut self, start: Span) -> Result<Stmt, EldritchError> {
+        self.consume(|t| matches!(t, TokenKind::Colon), "Expected ':' after try.")?;
+        let body = self.parse_block_or_statement()?;
+        let mut end = if let Some(last) = body.last() {
+            last.span
+        } else {
+            start
+        };
+
+        let mut handlers = Vec::new();
+        while self.match_token(&[TokenKind::Except]) {
+            let handler_start = self.tokens[self.current - 1].span;
+            let kind = if !self.check(&TokenKind::Colon) && !self.check(&TokenKind::As) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            let name = if self.match_token(&[TokenKind::As]) {
+                let token = self.consume(
+                    |t| matches!(t, TokenKind::Identifier(_)),
+                    "Expected name after 'as'.",
+                )?;
+                match &token.kind {
+                    TokenKind::Identifier(s) => Some(s.clone()),
+                    _ => unreachable!(),
+                }
+            } else {
+                None
+            };
+            self.consume(
+                |t| matches!(t, TokenKind::Colon),
+                "Expected ':' after except clause.",
+            )?;
+            let handler_body = self.parse_block_or_statement()?;
+            let handler_end = if let Some(last) = handler_body.last() {
+                last.span
+            } else {
+                handler_start
+            };
+            end = handler_end;
+            handlers.push(ExceptHandler {
+                kind,
+                name,
+                body: handler_body,
+                span: Span::new(handler_start.start, handler_end.end, handler_start.line),
+            });
+        }
+
+        let finally_body = if self.match_token(&[TokenKind::Finally]) {
+            self.consume(
+                |t| matches!(t, TokenKind::Colon),
+                "Expected ':' after finally.",
+            )?;
+            let stmts = self.parse_block_or_statement()?;
+            if let Some(last) = stmts.last() {
+                end = last.span;
+            }
+            Some(stmts)
+        } else {
+            None
+        };
+
+        if handlers.is_empty() && finally_body.is_none() {
+            return self.error("Expected 'except' or 'finally' after try block.");
+        }
+
+        Ok(self.make_stmt(StmtKind::Try(body, handlers, finally_body), start, end))
+    }
+
     fn if_statement(&mut self, start: Span) -> Result<Stmt, EldritchError> {
         let condition = self.expression()?;
         self.consume(
@@ -1,6 +1,7 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
+use num_bigint::BigInt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Span {
@@ -15,6 +16,15 @@ impl Span {
     }
 }
 
+/// A `#` comment. Comments are not part of the token stream; the lexer keeps them
+/// aside as trivia for tools that need to reproduce the source, such as the formatter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The comment text, including the leading `#`.
+    pub text: String,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub kind: TokenKind,
@@ -75,6 +85,8 @@ pub enum TokenKind {
     String(String),
     Bytes(Vec<u8>),
     Integer(i64),
+    /// An integer literal too large for `i64`.
+    BigInteger(BigInt),
     Float(f64),
     FStringContent(Vec<Token>),
 
@@ -85,6 +97,7 @@ pub enum TokenKind {
     Else,
     Return,
     For,
+    While,
     In,
     NotIn,
     True,
@@ -97,6 +110,10 @@ pub enum TokenKind {
     Continue,
     Pass,
     Lambda,
+    Try,
+    Except,
+    Finally,
+    As,
 
     // Structural
     Indent,
@@ -156,6 +173,7 @@ impl fmt::Display for TokenKind {
             TokenKind::String(s) => write!(f, "string {:?}", s),
             TokenKind::Bytes(_) => write!(f, "bytes"),
             TokenKind::Integer(i) => write!(f, "{}", i),
+            TokenKind::BigInteger(i) => write!(f, "{}", i),
             TokenKind::Float(v) => write!(f, "{}", v),
             TokenKind::FStringContent(_) => write!(f, "f-string"),
 
@@ -165,6 +183,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Else => write!(f, "else"),
             TokenKind::Return => write!(f, "return"),
             TokenKind::For => write!(f, "for"),
+            TokenKind::While => write!(f, "while"),
             TokenKind::In => write!(f, "in"),
             TokenKind::NotIn => write!(f, "not in"),
             TokenKind::True => write!(f, "True"),
@@ -177,6 +196,10 @@ impl fmt::Display for TokenKind {
             TokenKind::Continue => write!(f, "continue"),
             TokenKind::Pass => write!(f, "pass"),
             TokenKind::Lambda => write!(f, "lambda"),
+            TokenKind::Try => write!(f, "try"),
+            TokenKind::Except => write!(f, "except"),
+            TokenKind::Finally => write!(f, "finally"),
+            TokenKind::As => write!(f, "as"),
 
             TokenKind::Indent => write!(f, "indent"),
             TokenKind::Dedent => write!(f, "dedent"),
@@ -195,6 +218,7 @@ impl TokenKind {
             "else" => Some(TokenKind::Else),
             "return" => Some(TokenKind::Return),
             "for" => Some(TokenKind::For),
+            "while" => Some(TokenKind::While),
             "in" => Some(TokenKind::In),
             "True" => Some(TokenKind::True),
             "False" => Some(TokenKind::False),
@@ -206,6 +230,10 @@ impl TokenKind {
             "continue" => Some(TokenKind::Continue),
             "pass" => Some(TokenKind::Pass),
             "lambda" => Some(TokenKind::Lambda),
+            "try" => Some(TokenKind::Try),
+            "except" => Some(TokenKind::Except),
+            "finally" => Some(TokenKind::Finally),
+            "as" => Some(TokenKind::As),
             _ => None,
         }
     }
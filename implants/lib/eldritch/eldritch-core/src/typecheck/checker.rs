@@ -0,0 +1,1125 @@
+use super::types::Type;
+use super::{Diagnostic, Severity, TypeChecker, always_returns, augmented_op};
+use crate::ast::{Argument, Expr, ExprKind, FStringSegment, Param, Stmt, StmtKind};
+use crate::docs::{DocEntry, MethodSignature};
+use crate::interpreter::introspection::find_best_match;
+use crate::token::{Span, TokenKind};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Rounds of return type inference; enough for chains of helpers calling helpers.
+const INFERENCE_PASSES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParamKind {
+    Required,
+    Optional,
+    Star,
+    StarStar,
+}
+
+#[derive(Debug, Clone)]
+struct SigParam {
+    name: String,
+    ty: Type,
+    kind: ParamKind,
+}
+
+#[derive(Debug, Clone)]
+struct Signature {
+    name: String,
+    params: Vec<SigParam>,
+    ret: Type,
+    /// The return type comes from an annotation rather than inference.
+    declared_ret: bool,
+    /// Missing, surplus and unknown arguments are errors. Builtin methods only
+    /// have their argument types checked, as their documented arity is looser
+    /// than the runtime's.
+    check_arity: bool,
+}
+
+impl Signature {
+    fn from_doc(entry: &DocEntry, check_arity: bool) -> Self {
+        let params = entry
+            .params
+            .iter()
+            .map(|p| {
+                let (name, kind) = if let Some(name) = p.name.strip_prefix("**") {
+                    (name, ParamKind::StarStar)
+                } else if let Some(name) = p.name.strip_prefix('*') {
+                    (name, ParamKind::Star)
+                } else if p.is_optional() {
+                    (p.name.as_str(), ParamKind::Optional)
+                } else {
+                    (p.name.as_str(), ParamKind::Required)
+                };
+                SigParam {
+                    name: name.to_string(),
+                    ty: Type::from_doc(&p.type_name),
+                    kind,
+                }
+            })
+            .collect();
+        let name = entry.name.rsplit('.').next().unwrap_or(&entry.name);
+        Self {
+            name: name.to_string(),
+            params,
+            ret: entry.returns.as_deref().map_or(Type::Any, Type::from_doc),
+            declared_ret: true,
+            check_arity,
+        }
+    }
+
+    fn from_library_method(method: &MethodSignature) -> Self {
+        let params = method
+            .params
+            .iter()
+            .map(|p| {
+                let ty = Type::from_rust(p.type_name);
+                SigParam {
+                    name: p.name.to_string(),
+                    // A `Vec` parameter converts from a tuple as well as a list
+                    ty: if ty.members().iter().any(|t| matches!(t, Type::List(_))) {
+                        ty.union(Type::Tuple)
+                    } else {
+                        ty
+                    },
+                    kind: if p.optional {
+                        ParamKind::Optional
+                    } else {
+                        ParamKind::Required
+                    },
+                }
+            })
+            .collect();
+        Self {
+            name: method.name.to_string(),
+            params,
+            ret: Type::from_rust(method.returns),
+            declared_ret: true,
+            check_arity: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Var {
+    ty: Type,
+    declared: Option<Type>,
+    /// Index into `Checker::funcs` when the variable holds a `def`.
+    func: Option<usize>,
+}
+
+type Scope = BTreeMap<String, Var>;
+
+enum Callee {
+    User(usize),
+    Doc(Signature),
+    Unknown,
+}
+
+pub(super) struct Checker<'a> {
+    ctx: &'a TypeChecker,
+    funcs: Vec<Signature>,
+    /// `def` statements by span start.
+    def_ids: BTreeMap<usize, usize>,
+    /// Module level functions, callable before their `def` runs.
+    hoisted: BTreeMap<String, usize>,
+    scopes: Vec<Scope>,
+    /// Index of the innermost function scope. Undeclared variables below it
+    /// belong to enclosing code and may be reassigned before the function runs.
+    local_base: usize,
+    current_fn: Option<usize>,
+    returns: Vec<Type>,
+    report: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    pub(super) fn new(ctx: &'a TypeChecker, stmts: &[Stmt]) -> Self {
+        let mut checker = Self {
+            ctx,
+            funcs: Vec::new(),
+            def_ids: BTreeMap::new(),
+            hoisted: BTreeMap::new(),
+            scopes: alloc::vec![Scope::new()],
+            local_base: 0,
+            current_fn: None,
+            returns: Vec::new(),
+            report: true,
+            diagnostics: Vec::new(),
+        };
+        checker.collect(stmts, true);
+        checker
+    }
+
+    pub(super) fn run(mut self, stmts: &[Stmt]) -> Vec<Diagnostic> {
+        self.infer_returns(stmts);
+        self.report = true;
+        self.scopes = alloc::vec![Scope::new()];
+        self.block(stmts);
+        self.diagnostics
+    }
+
+    fn error(&mut self, span: Span, code: &'static str, message: String) {
+        self.emit(span, Severity::Error, code, message);
+    }
+
+    fn emit(&mut self, span: Span, severity: Severity, code: &'static str, message: String) {
+        if self.report {
+            self.diagnostics.push(Diagnostic {
+                span,
+                severity,
+                code,
+                message,
+            });
+        }
+    }
+
+    fn annotation(&mut self, expr: &Expr) -> Type {
+        match Type::from_annotation(expr) {
+            Ok(ty) => ty,
+            Err((span, name)) => {
+                self.emit(
+                    span,
+                    Severity::Warning,
+                    "unknown-type",
+                    format!("unknown type '{}' in annotation", name),
+                );
+                Type::Any
+            }
+        }
+    }
+
+    // Registers every `def`, resolving parameter and return annotations.
+    fn collect(&mut self, stmts: &[Stmt], top_level: bool) {
+        for stmt in stmts {
+            match &stmt.kind {
+                StmtKind::Def(name, params, ret, body) => {
+                    let params = params
+                        .iter()
+                        .map(|p| {
+                            let (name, annotation, kind) = match p {
+                                Param::Normal(n, a) => (n, a, ParamKind::Required),
+                                Param::WithDefault(n, a, _) => (n, a, ParamKind::Optional),
+                                Param::Star(n, a) => (n, a, ParamKind::Star),
+                                Param::StarStar(n, a) => (n, a, ParamKind::StarStar),
+                            };
+                            SigParam {
+                                name: name.clone(),
+                                ty: annotation
+                                    .as_ref()
+                                    .map_or(Type::Any, |a| self.annotation(a)),
+                                kind,
+                            }
+                        })
+                        .collect();
+                    let declared = ret.as_ref().map(|r| self.annotation(r));
+                    let id = self.funcs.len();
+                    self.funcs.push(Signature {
+                        name: name.clone(),
+                        params,
+                        declared_ret: declared.is_some(),
+                        ret: declared.unwrap_or(Type::Any),
+                        check_arity: true,
+                    });
+                    self.def_ids.insert(stmt.span.start, id);
+                    if top_level {
+                        self.hoisted.insert(name.clone(), id);
+                    }
+                    self.collect(body, false);
+                }
+                StmtKind::If(_, then_branch, else_branch) => {
+                    self.collect(then_branch, top_level);
+                    if let Some(else_branch) = else_branch {
+                        self.collect(else_branch, top_level);
+                    }
+                }
+                StmtKind::For(_, _, body) => self.collect(body, top_level),
+                StmtKind::While(_, body, else_branch) => {
+                    self.collect(body, top_level);
+                    if let Some(else_branch) = else_branch {
+                        self.collect(else_branch, top_level);
+                    }
+                }
+                StmtKind::Try(body, handlers, finally) => {
+                    self.collect(body, top_level);
+                    for handler in handlers {
+                        self.collect(&handler.body, top_level);
+                    }
+                    if let Some(finally) = finally {
+                        self.collect(finally, top_level);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Infers the result of every unannotated function from its `return`
+    // statements, repeating while the results keep changing.
+    fn infer_returns(&mut self, stmts: &[Stmt]) {
+        self.report = false;
+        let mut bodies = BTreeMap::new();
+        find_defs(stmts, &mut bodies);
+
+        for _ in 0..INFERENCE_PASSES {
+            let mut changed = false;
+            for (&start, &(params, body)) in &bodies {
+                let id = self.def_ids[&start];
+                if self.funcs[id].declared_ret {
+                    continue;
+                }
+                self.scopes = alloc::vec![Scope::new()];
+                let ret = self.function_body(id, params, body);
+                if ret != self.funcs[id].ret {
+                    self.funcs[id].ret = ret;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    // Checks a function body in a new scope and returns the joined type of its results.
+    fn function_body(&mut self, id: usize, params: &[Param], body: &[Stmt]) -> Type {
+        let mut scope = Scope::new();
+        for (param, sig) in params.iter().zip(self.funcs[id].params.clone()) {
+            let ty = match sig.kind {
+                ParamKind::Star => Type::Tuple,
+                ParamKind::StarStar => Type::dict(Type::Str, sig.ty.clone()),
+                _ => sig.ty.clone(),
+            };
+            if let Param::WithDefault(_, _, default) = param {
+                let default_ty = self.expr(default);
+                if !default_ty.is_assignable_to(&sig.ty) {
+                    self.error(
+                        default.span,
+                        "type-mismatch",
+                        format!(
+                            "default value of parameter '{}' is {}, expected {}",
+                            sig.name, default_ty, sig.ty
+                        ),
+                    );
+                }
+            }
+            let declared = (sig.ty != Type::Any).then(|| ty.clone());
+            scope.insert(
+                sig.name,
+                Var {
+                    ty,
+                    declared,
+                    func: None,
+                },
+            );
+        }
+
+        let saved_fn = self.current_fn.replace(id);
+        let saved_returns = core::mem::take(&mut self.returns);
+        let saved_base = core::mem::replace(&mut self.local_base, self.scopes.len());
+        self.scopes.push(scope);
+        self.block(body);
+        self.scopes.pop();
+        let mut returns = core::mem::replace(&mut self.returns, saved_returns);
+        self.local_base = saved_base;
+        self.current_fn = saved_fn;
+
+        if !always_returns(body) {
+            returns.push(Type::None);
+        }
+        returns
+            .into_iter()
+            .reduce(Type::union)
+            .unwrap_or(Type::None)
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    // Runs `f` on a copy of the scopes and joins the result with the original,
+    // for code that may or may not run.
+    fn branch(&mut self, f: impl FnOnce(&mut Self)) {
+        let before = self.scopes.clone();
+        f(self);
+        let after = core::mem::replace(&mut self.scopes, before);
+        self.merge(after);
+    }
+
+    fn merge(&mut self, other: Vec<Scope>) {
+        for (scope, other) in self.scopes.iter_mut().zip(other) {
+            for (name, var) in other {
+                match scope.get_mut(&name) {
+                    Some(existing) => {
+                        existing.ty = existing.ty.clone().union(var.ty);
+                        if existing.func != var.func {
+                            existing.func = None;
+                        }
+                    }
+                    None => {
+                        scope.insert(name, var);
+                    }
+                }
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                self.expr(expr);
+            }
+            StmtKind::Assignment(target, annotation, value) => {
+                let ty = self.expr(value);
+                match annotation {
+                    Some(annotation) => {
+                        let declared = self.annotation(annotation);
+                        if !ty.is_assignable_to(&declared) {
+                            self.error(
+                                value.span,
+                                "type-mismatch",
+                                format!("expected {}, got {}", declared, ty),
+                            );
+                        }
+                        match &target.kind {
+                            ExprKind::Identifier(name) => {
+                                self.scopes.last_mut().unwrap().insert(
+                                    name.clone(),
+                                    Var {
+                                        ty: declared.clone(),
+                                        declared: Some(declared),
+                                        func: None,
+                                    },
+                                );
+                            }
+                            _ => self.assign(target, ty, value.span),
+                        }
+                    }
+                    None => self.assign(target, ty, value.span),
+                }
+            }
+            StmtKind::AugmentedAssignment(target, op, value) => {
+                let current = self.expr(target);
+                let rhs = self.expr(value);
+                let ty = self.binary(&current, &augmented_op(op), &rhs, stmt.span);
+                self.assign(target, ty, value.span);
+            }
+            StmtKind::If(cond, then_branch, else_branch) => {
+                self.expr(cond);
+                let before = self.scopes.clone();
+                self.block(then_branch);
+                let after_then = core::mem::replace(&mut self.scopes, before);
+                if let Some(else_branch) = else_branch {
+                    self.block(else_branch);
+                }
+                self.merge(after_then);
+            }
+            StmtKind::Return(value) => {
+                let ty = match value {
+                    Some(value) => self.expr(value),
+                    None => Type::None,
+                };
+                if let Some(id) = self.current_fn {
+                    let sig = &self.funcs[id];
+                    if sig.declared_ret && !ty.is_assignable_to(&sig.ret) {
+                        let message = format!(
+                            "function '{}' is declared to return {}, got {}",
+                            sig.name, sig.ret, ty
+                        );
+                        let span = value.as_ref().map_or(stmt.span, |v| v.span);
+                        self.error(span, "return-type", message);
+                    }
+                    self.returns.push(ty);
+                }
+            }
+            StmtKind::Def(name, params, _, body) => {
+                let id = self.def_ids[&stmt.span.start];
+                self.bind(
+                    name,
+                    Var {
+                        ty: Type::Function,
+                        declared: None,
+                        func: Some(id),
+                    },
+                );
+                // Bodies are inferred on their own; only the final pass checks nested ones
+                if self.report {
+                    let depth = self.scopes.len();
+                    self.function_body(id, params, body);
+                    self.scopes.truncate(depth);
+                }
+            }
+            StmtKind::For(vars, iterable, body) => {
+                let elem = self.expr(iterable).element();
+                self.branch(|this| {
+                    for var in vars {
+                        let ty = if vars.len() == 1 {
+                            elem.clone()
+                        } else {
+                            Type::Any
+                        };
+                        this.assign_name(var, ty, iterable.span);
+                    }
+                    this.block(body);
+                });
+            }
+            StmtKind::While(cond, body, else_branch) => {
+                self.expr(cond);
+                self.branch(|this| this.block(body));
+                if let Some(else_branch) = else_branch {
+                    self.branch(|this| this.block(else_branch));
+                }
+            }
+            StmtKind::Try(body, handlers, finally) => {
+                self.branch(|this| this.block(body));
+                for handler in handlers {
+                    self.branch(|this| {
+                        if let Some(kind) = &handler.kind {
+                            this.expr(kind);
+                        }
+                        if let Some(name) = &handler.name {
+                            this.bind(
+                                name,
+                                Var {
+                                    ty: Type::dict(Type::Str, Type::Any),
+                                    declared: None,
+                                    func: None,
+                                },
+                            );
+                        }
+                        this.block(&handler.body);
+                    });
+                }
+                if let Some(finally) = finally {
+                    self.block(finally);
+                }
+            }
+            StmtKind::Break | StmtKind::Continue | StmtKind::Pass | StmtKind::Error(_) => {}
+        }
+    }
+
+    fn bind(&mut self, name: &str, var: Var) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), var);
+    }
+
+    fn assign(&mut self, target: &Expr, ty: Type, span: Span) {
+        match &target.kind {
+            ExprKind::Identifier(name) => self.assign_name(name, ty, span),
+            ExprKind::Tuple(items) | ExprKind::List(items) => {
+                for item in items {
+                    self.assign(item, Type::Any, span);
+                }
+            }
+            ExprKind::Index(obj, index) => {
+                let container = self.expr(obj);
+                self.expr(index);
+                let expected = match &container {
+                    Type::List(elem) => Some((**elem).clone()),
+                    Type::Dict(_, value) => Some((**value).clone()),
+                    _ => None,
+                };
+                if let Some(expected) = expected
+                    && !ty.is_assignable_to(&expected)
+                {
+                    self.error(
+                        span,
+                        "type-mismatch",
+                        format!("cannot store {} in {}", ty, container),
+                    );
+                }
+            }
+            _ => {
+                self.expr(target);
+            }
+        }
+    }
+
+    // Plain assignment updates the innermost scope that already has the name,
+    // matching the interpreter.
+    fn assign_name(&mut self, name: &str, ty: Type, span: Span) {
+        let existing = self.scopes.iter_mut().rev().find_map(|s| s.get_mut(name));
+        match existing {
+            Some(var) => match var.declared.clone() {
+                Some(declared) => {
+                    if !ty.is_assignable_to(&declared) {
+                        self.error(
+                            span,
+                            "type-mismatch",
+                            format!("'{}' is declared as {}, got {}", name, declared, ty),
+                        );
+                    }
+                }
+                None => {
+                    var.ty = ty;
+                    var.func = None;
+                }
+            },
+            None => self.bind(
+                name,
+                Var {
+                    ty,
+                    declared: None,
+                    func: None,
+                },
+            ),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Var> {
+        self.scopes.iter().rev().find_map(|s| s.get(name))
+    }
+
+    fn identifier(&self, name: &str) -> Type {
+        let found = self
+            .scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, s)| s.get(name).map(|v| (i, v)));
+        if let Some((depth, var)) = found {
+            return match &var.declared {
+                Some(declared) => declared.clone(),
+                None if depth < self.local_base && var.func.is_none() => Type::Any,
+                None => var.ty.clone(),
+            };
+        }
+        if self.hoisted.contains_key(name) || self.ctx.builtin(name).is_some() {
+            return Type::Function;
+        }
+        self.ctx.globals.get(name).cloned().unwrap_or(Type::Any)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Type {
+        match &expr.kind {
+            ExprKind::Literal(value) => Type::of_value(value),
+            ExprKind::Identifier(name) => self.identifier(name),
+            ExprKind::BinaryOp(lhs, op, rhs) => {
+                let l = self.expr(lhs);
+                let r = self.expr(rhs);
+                self.binary(&l, op, &r, expr.span)
+            }
+            ExprKind::UnaryOp(op, operand) => {
+                let ty = self.expr(operand);
+                match op {
+                    TokenKind::Not => Type::Bool,
+                    TokenKind::Minus | TokenKind::Plus | TokenKind::BitNot => {
+                        let numeric = |t: &Type| match (op, t) {
+                            (TokenKind::BitNot, Type::Int) => true,
+                            (TokenKind::BitNot, _) => false,
+                            (_, t) => matches!(t, Type::Int | Type::Float),
+                        };
+                        if is_concrete(&ty) && !ty.members().iter().any(numeric) {
+                            self.error(
+                                expr.span,
+                                "invalid-operand",
+                                format!("bad operand type for unary {}: '{}'", op, ty),
+                            );
+                            return Type::Any;
+                        }
+                        ty
+                    }
+                    _ => Type::Any,
+                }
+            }
+            ExprKind::LogicalOp(lhs, _, rhs) => {
+                let l = self.expr(lhs);
+                let r = self.expr(rhs);
+                l.union(r)
+            }
+            ExprKind::Call(callee, args) => self.call(callee, args, expr.span),
+            ExprKind::List(items) => Type::list(self.join(items)),
+            ExprKind::Tuple(items) => {
+                self.join(items);
+                Type::Tuple
+            }
+            ExprKind::Set(items) => Type::set(self.join(items)),
+            ExprKind::Dictionary(entries) => {
+                let mut key = None;
+                let mut value = None;
+                for (k, v) in entries {
+                    let kt = self.expr(k);
+                    let vt = self.expr(v);
+                    key = Some(key.map_or(kt.clone(), |t: Type| t.union(kt)));
+                    value = Some(value.map_or(vt.clone(), |t: Type| t.union(vt)));
+                }
+                Type::dict(key.unwrap_or(Type::Any), value.unwrap_or(Type::Any))
+            }
+            ExprKind::Index(obj, index) => {
+                let container = self.expr(obj);
+                self.expr(index);
+                match &container {
+                    Type::List(elem) => (**elem).clone(),
+                    Type::Dict(_, value) => (**value).clone(),
+                    Type::Str => Type::Str,
+                    Type::Bytes => Type::Int,
+                    Type::None | Type::Bool | Type::Int | Type::Float | Type::Function => {
+                        self.error(
+                            expr.span,
+                            "invalid-operand",
+                            format!("'{}' is not subscriptable", container),
+                        );
+                        Type::Any
+                    }
+                    _ => Type::Any,
+                }
+            }
+            ExprKind::GetAttr(obj, name) => {
+                let ty = self.expr(obj);
+                self.attribute(&ty, name, expr.span);
+                Type::Function
+            }
+            ExprKind::Slice(obj, start, stop, step) => {
+                let ty = self.expr(obj);
+                for part in [start, stop, step].into_iter().flatten() {
+                    self.expr(part);
+                }
+                match ty {
+                    Type::List(_) | Type::Str | Type::Bytes | Type::Tuple => ty,
+                    _ => Type::Any,
+                }
+            }
+            ExprKind::FString(segments) => {
+                for e in segments.iter().flat_map(FStringSegment::exprs) {
+                    self.expr(e);
+                }
+                Type::Str
+            }
+            ExprKind::ListComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => Type::list(self.comprehension(vars, iterable, cond, |this| this.expr(body))),
+            ExprKind::SetComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => Type::set(self.comprehension(vars, iterable, cond, |this| this.expr(body))),
+            ExprKind::DictComp {
+                key,
+                value,
+                vars,
+                iterable,
+                cond,
+            } => {
+                let mut value_ty = Type::Any;
+                let key_ty = self.comprehension(vars, iterable, cond, |this| {
+                    let k = this.expr(key);
+                    value_ty = this.expr(value);
+                    k
+                });
+                Type::dict(key_ty, value_ty)
+            }
+            ExprKind::Lambda { params, body } => {
+                let mut scope = Scope::new();
+                for param in params {
+                    let (Param::Normal(name, _)
+                    | Param::WithDefault(name, _, _)
+                    | Param::Star(name, _)
+                    | Param::StarStar(name, _)) = param;
+                    if let Param::WithDefault(_, _, default) = param {
+                        self.expr(default);
+                    }
+                    scope.insert(
+                        name.clone(),
+                        Var {
+                            ty: Type::Any,
+                            declared: None,
+                            func: None,
+                        },
+                    );
+                }
+                self.scopes.push(scope);
+                self.expr(body);
+                self.scopes.pop();
+                Type::Function
+            }
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                let t = self.expr(then_branch);
+                let e = self.expr(else_branch);
+                t.union(e)
+            }
+            ExprKind::Error(_) => Type::Any,
+        }
+    }
+
+    fn join(&mut self, items: &[Expr]) -> Type {
+        items
+            .iter()
+            .map(|item| self.expr(item))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .reduce(Type::union)
+            .unwrap_or(Type::Any)
+    }
+
+    fn comprehension(
+        &mut self,
+        vars: &[String],
+        iterable: &Expr,
+        cond: &Option<alloc::boxed::Box<Expr>>,
+        body: impl FnOnce(&mut Self) -> Type,
+    ) -> Type {
+        let elem = self.expr(iterable).element();
+        let mut scope = Scope::new();
+        for var in vars {
+            let ty = if vars.len() == 1 {
+                elem.clone()
+            } else {
+                Type::Any
+            };
+            scope.insert(
+                var.clone(),
+                Var {
+                    ty,
+                    declared: None,
+                    func: None,
+                },
+            );
+        }
+        self.scopes.push(scope);
+        if let Some(cond) = cond {
+            self.expr(cond);
+        }
+        let ty = body(self);
+        self.scopes.pop();
+        ty
+    }
+
+    // Reports attributes that do not exist on builtin types or libraries.
+    fn attribute(&mut self, ty: &Type, name: &str, span: Span) {
+        if let Type::Library(lib) = ty {
+            if let Some(methods) = self.ctx.libraries.get(lib)
+                && !methods.iter().any(|m| m == name)
+            {
+                let hint = suggestion(name, methods);
+                self.error(
+                    span,
+                    "unknown-method",
+                    format!("library '{}' has no method '{}'{}", lib, name, hint),
+                );
+            }
+            return;
+        }
+        if let Some(Err(methods)) = self.ctx.method(ty, name) {
+            let hint = suggestion(name, &methods);
+            self.error(
+                span,
+                "unknown-method",
+                format!("'{}' has no method '{}'{}", ty, name, hint),
+            );
+        }
+    }
+
+    fn call(&mut self, callee: &Expr, args: &[Argument], span: Span) -> Type {
+        let target = match &callee.kind {
+            ExprKind::Identifier(name) => match self.lookup(name) {
+                Some(Var { func: Some(id), .. }) => Callee::User(*id),
+                Some(_) => Callee::Unknown,
+                None => match (self.hoisted.get(name), self.ctx.builtin(name)) {
+                    (Some(&id), _) => Callee::User(id),
+                    (None, Some(entry)) => Callee::Doc(Signature::from_doc(entry, true)),
+                    (None, None) => Callee::Unknown,
+                },
+            },
+            ExprKind::GetAttr(obj, name) => {
+                let ty = self.expr(obj);
+                self.attribute(&ty, name, callee.span);
+                if let Type::Library(lib) = &ty {
+                    match self.ctx.library_method(lib, name) {
+                        Some(method) => Callee::Doc(Signature::from_library_method(method)),
+                        None => Callee::Unknown,
+                    }
+                } else {
+                    match self.ctx.method(&ty, name) {
+                        Some(Ok(Some(entry))) => Callee::Doc(Signature::from_doc(entry, false)),
+                        _ => Callee::Unknown,
+                    }
+                }
+            }
+            _ => {
+                self.expr(callee);
+                Callee::Unknown
+            }
+        };
+
+        let args: Vec<(&Argument, Type, Span)> = args
+            .iter()
+            .map(|arg| {
+                let (Argument::Positional(e)
+                | Argument::Keyword(_, e)
+                | Argument::StarArgs(e)
+                | Argument::KwArgs(e)) = arg;
+                (arg, self.expr(e), e.span)
+            })
+            .collect();
+
+        let sig = match target {
+            Callee::User(id) => self.funcs[id].clone(),
+            Callee::Doc(sig) => sig,
+            Callee::Unknown => return Type::Any,
+        };
+        self.check_args(&sig, &args, span);
+        sig.ret
+    }
+
+    fn check_args(&mut self, sig: &Signature, args: &[(&Argument, Type, Span)], span: Span) {
+        let slots = sig
+            .params
+            .iter()
+            .take_while(|p| matches!(p.kind, ParamKind::Required | ParamKind::Optional))
+            .count();
+        let star = sig.params.iter().find(|p| p.kind == ParamKind::Star);
+        let star_star = sig.params.iter().find(|p| p.kind == ParamKind::StarStar);
+        let mut bound = alloc::vec![false; sig.params.len()];
+        let mut positional = 0;
+        let mut dynamic = false;
+
+        for (arg, ty, arg_span) in args {
+            let param = match arg {
+                Argument::Positional(_) => {
+                    positional += 1;
+                    if positional <= slots {
+                        bound[positional - 1] = true;
+                        Some(&sig.params[positional - 1])
+                    } else {
+                        star
+                    }
+                }
+                Argument::Keyword(name, _) => {
+                    let found = sig.params.iter().position(|p| {
+                        &p.name == name
+                            && matches!(p.kind, ParamKind::Required | ParamKind::Optional)
+                    });
+                    match found {
+                        Some(i) if bound[i] => {
+                            if sig.check_arity {
+                                self.error(
+                                    *arg_span,
+                                    "argument-count",
+                                    format!(
+                                        "{}() got multiple values for argument '{}'",
+                                        sig.name, name
+                                    ),
+                                );
+                            }
+                            None
+                        }
+                        Some(i) => {
+                            bound[i] = true;
+                            Some(&sig.params[i])
+                        }
+                        None if star_star.is_some() => star_star,
+                        None => {
+                            if sig.check_arity {
+                                self.error(
+                                    *arg_span,
+                                    "unknown-argument",
+                                    format!(
+                                        "{}() got an unexpected keyword argument '{}'",
+                                        sig.name, name
+                                    ),
+                                );
+                            }
+                            None
+                        }
+                    }
+                }
+                Argument::StarArgs(_) | Argument::KwArgs(_) => {
+                    dynamic = true;
+                    None
+                }
+            };
+            if let Some(param) = param
+                && !ty.is_assignable_to(&param.ty)
+            {
+                self.error(
+                    *arg_span,
+                    "type-mismatch",
+                    format!(
+                        "argument '{}' of {}() expects {}, got {}",
+                        param.name, sig.name, param.ty, ty
+                    ),
+                );
+            }
+        }
+
+        if !sig.check_arity || dynamic {
+            return;
+        }
+        if positional > slots && star.is_none() {
+            self.error(
+                span,
+                "argument-count",
+                format!(
+                    "{}() takes {} positional argument{} but {} were given",
+                    sig.name,
+                    slots,
+                    if slots == 1 { "" } else { "s" },
+                    positional
+                ),
+            );
+        }
+        for (param, bound) in sig.params.iter().zip(bound) {
+            if param.kind == ParamKind::Required && !bound {
+                self.error(
+                    span,
+                    "argument-count",
+                    format!("{}() missing required argument '{}'", sig.name, param.name),
+                );
+            }
+        }
+    }
+
+    fn binary(&mut self, lhs: &Type, op: &TokenKind, rhs: &Type, span: Span) -> Type {
+        let mut result: Option<Type> = None;
+        for l in lhs.members() {
+            for r in rhs.members() {
+                if let Some(ty) = binary_result(l, op, r) {
+                    result = Some(match result {
+                        Some(acc) => acc.union(ty),
+                        None => ty,
+                    });
+                }
+            }
+        }
+        match result {
+            Some(ty) => ty,
+            None => {
+                self.error(
+                    span,
+                    "invalid-operand",
+                    format!(
+                        "unsupported operand types for {}: '{}' and '{}'",
+                        op, lhs, rhs
+                    ),
+                );
+                Type::Any
+            }
+        }
+    }
+}
+
+// Whether a type describes specific values, so operations on it can be judged.
+fn is_concrete(ty: &Type) -> bool {
+    !ty.members()
+        .iter()
+        .any(|t| matches!(t, Type::Any | Type::Iterable | Type::Library(_)))
+}
+
+fn suggestion(name: &str, candidates: &[String]) -> String {
+    match find_best_match(name, candidates) {
+        Some(best) => format!(" (did you mean '{}'?)", best),
+        None => String::new(),
+    }
+}
+
+// The result of `l op r`, or `None` when the interpreter would reject it.
+fn binary_result(l: &Type, op: &TokenKind, r: &Type) -> Option<Type> {
+    use TokenKind::{
+        BitAnd, BitOr, BitXor, Eq, Gt, GtEq, In, LShift, Lt, LtEq, Minus, NotEq, NotIn, Percent,
+        Plus, RShift, Slash, SlashSlash, Star,
+    };
+
+    if matches!(op, Eq | NotEq | Lt | Gt | LtEq | GtEq | In | NotIn) {
+        return Some(Type::Bool);
+    }
+    if matches!(l, Type::Any | Type::Iterable | Type::Library(_))
+        || matches!(r, Type::Any | Type::Iterable | Type::Library(_))
+    {
+        return Some(Type::Any);
+    }
+
+    let numeric = |t: &Type| matches!(t, Type::Int | Type::Float);
+    let arith = || {
+        if *l == Type::Float || *r == Type::Float {
+            Type::Float
+        } else {
+            Type::Int
+        }
+    };
+
+    match (l, op, r) {
+        (a, Plus | Minus | Star | SlashSlash | Percent, b) if numeric(a) && numeric(b) => {
+            Some(arith())
+        }
+        (a, Slash, b) if numeric(a) && numeric(b) => Some(Type::Float),
+        (Type::Str, Plus, Type::Str) => Some(Type::Str),
+        (Type::Str, Percent, _) => Some(Type::Str),
+        (Type::Bytes, Plus, Type::Bytes) => Some(Type::Bytes),
+        (Type::List(a), Plus, Type::List(b)) => {
+            Some(Type::list((**a).clone().union((**b).clone())))
+        }
+        (Type::Tuple, Plus, Type::Tuple) => Some(Type::Tuple),
+        (Type::Dict(_, _), Plus | BitOr, Type::Dict(_, _)) => Some(l.clone().union(r.clone())),
+        (Type::Set(_), Plus | Minus | BitAnd | BitOr | BitXor, Type::Set(_)) => {
+            Some(l.clone().union(r.clone()))
+        }
+        (Type::Str | Type::Bytes | Type::List(_) | Type::Tuple, Star, Type::Int) => Some(l.clone()),
+        (Type::Int, Star, Type::Str | Type::Bytes | Type::List(_) | Type::Tuple) => Some(r.clone()),
+        (Type::Int, BitAnd | BitOr | BitXor | LShift | RShift, Type::Int) => Some(Type::Int),
+        (
+            Type::None | Type::Bool | Type::Int | Type::Float | Type::Str | Type::Bytes,
+            Plus | Minus | Star | Slash | SlashSlash | Percent | BitAnd | BitOr | BitXor | LShift
+            | RShift,
+            _,
+        ) => None,
+        (
+            Type::List(_) | Type::Tuple | Type::Dict(_, _) | Type::Set(_) | Type::Function,
+            Plus | Minus | Star | Slash | SlashSlash | Percent | BitAnd | BitOr | BitXor | LShift
+            | RShift,
+            _,
+        ) => None,
+        _ => Some(Type::Any),
+    }
+}
+
+// Maps the span start of every `def` to its parameters and body.
+fn find_defs<'s>(stmts: &'s [Stmt], out: &mut BTreeMap<usize, (&'s [Param], &'s [Stmt])>) {
+    for stmt in stmts {
+        match &stmt.kind {
+            StmtKind::Def(_, params, _, body) => {
+                out.insert(stmt.span.start, (params.as_slice(), body.as_slice()));
+                find_defs(body, out);
+            }
+            StmtKind::If(_, then_branch, else_branch) => {
+                find_defs(then_branch, out);
+                if let Some(else_branch) = else_branch {
+                    find_defs(else_branch, out);
+                }
+            }
+            StmtKind::For(_, _, body) => find_defs(body, out),
+            StmtKind::While(_, body, else_branch) => {
+                find_defs(body, out);
+                if let Some(else_branch) = else_branch {
+                    find_defs(else_branch, out);
+                }
+            }
+            StmtKind::Try(body, handlers, finally) => {
+                find_defs(body, out);
+                for handler in handlers {
+                    find_defs(&handler.body, out);
+                }
+                if let Some(finally) = finally {
+                    find_defs(finally, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Opt-in static checking of type annotations.
+//!
+//! The checker walks a parsed script without running it. It infers the types
+//! of expressions and of unannotated function results, then reports
+//! definite mismatches against annotations, builtin signatures from
+//! [`crate::docs`] and the signatures of registered libraries' methods.
+//! Anything it cannot prove wrong is accepted.
+//!
+//! ```ignore
+//! let diagnostics = TypeChecker::new().check_source("x: int = 'a'");
+//! assert_eq!(diagnostics[0].code, "type-mismatch");
+//! ```
+
+mod checker;
+mod types;
+
+pub use self::types::Type;
+
+use crate::ast::{Stmt, StmtKind, Value};
+use crate::docs::{self, DocEntry, LibrarySignature, MethodSignature};
+use crate::interpreter::Interpreter;
+use crate::interpreter::methods::get_native_methods;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::{Span, TokenKind};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found by the checker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub severity: Severity,
+    /// Stable identifier of the check, e.g. `type-mismatch` or `unknown-method`.
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Holds what the checker knows about the environment a script will run in.
+#[derive(Debug, Clone)]
+pub struct TypeChecker {
+    builtins: BTreeMap<String, DocEntry>,
+    methods: BTreeMap<String, DocEntry>,
+    /// Method names of each library, keyed by the library's type name.
+    libraries: BTreeMap<String, Vec<String>>,
+    /// Method signatures of each library that has them, keyed like `libraries`.
+    signatures: BTreeMap<String, &'static LibrarySignature>,
+    globals: BTreeMap<String, Type>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    /// A checker that knows the builtin functions and types only.
+    pub fn new() -> Self {
+        let by_name = |entries: Vec<DocEntry>| {
+            entries
+                .into_iter()
+                .map(|e| (e.name.clone(), e))
+                .collect::<BTreeMap<_, _>>()
+        };
+        Self {
+            builtins: by_name(docs::builtin_functions()),
+            methods: by_name(docs::builtin_methods()),
+            libraries: BTreeMap::new(),
+            signatures: BTreeMap::new(),
+            globals: BTreeMap::new(),
+        }
+    }
+
+    /// A checker that also knows every library and global defined on `interp`.
+    pub fn from_interpreter(interp: &Interpreter) -> Self {
+        let mut checker = Self::new();
+        let env = interp.env.read();
+        for (name, value) in &env.values {
+            if let Value::Foreign(f) = value {
+                checker
+                    .libraries
+                    .insert(f.type_name().to_string(), f.method_names());
+                if let Some(signature) = f.signature() {
+                    checker
+                        .signatures
+                        .insert(f.type_name().to_string(), signature);
+                }
+            }
+            if !matches!(
+                value,
+                Value::NativeFunction(_, _) | Value::NativeFunctionWithKwargs(_, _)
+            ) {
+                checker.globals.insert(name.clone(), Type::of_value(value));
+            }
+        }
+        checker
+    }
+
+    /// Declares a library global `name` with the given methods.
+    pub fn with_library(mut self, name: &str, methods: Vec<String>) -> Self {
+        self.libraries.insert(name.to_string(), methods);
+        self.globals
+            .insert(name.to_string(), Type::Library(name.to_string()));
+        self
+    }
+
+    /// Declares a library global from its signature table, so calls to its
+    /// methods have their arguments checked too.
+    pub fn with_library_signature(mut self, signature: &'static LibrarySignature) -> Self {
+        let methods = signature
+            .methods
+            .iter()
+            .map(|m| m.name.to_string())
+            .collect();
+        self.signatures
+            .insert(signature.name.to_string(), signature);
+        self.with_library(signature.name, methods)
+    }
+
+    /// Declares a global variable defined by the host before the script runs.
+    pub fn with_global(mut self, name: &str, ty: Type) -> Self {
+        self.globals.insert(name.to_string(), ty);
+        self
+    }
+
+    /// Checks a parsed script.
+    pub fn check(&self, stmts: &[Stmt]) -> Vec<Diagnostic> {
+        let mut diagnostics = checker::Checker::new(self, stmts).run(stmts);
+        diagnostics.sort_by_key(|d| (d.span.start, d.span.end));
+        diagnostics
+    }
+
+    /// Lexes, parses and checks `source`. Syntax errors are reported as
+    /// `syntax-error` diagnostics and the rest of the script is still checked.
+    pub fn check_source(&self, source: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (stmts, errors) = Parser::new(tokens).parse();
+        for e in errors {
+            diagnostics.push(Diagnostic {
+                span: e.span,
+                severity: Severity::Error,
+                code: "syntax-error",
+                message: e.message,
+            });
+        }
+        diagnostics.extend(self.check(&stmts));
+        diagnostics
+    }
+
+    fn builtin(&self, name: &str) -> Option<&DocEntry> {
+        self.builtins.get(name)
+    }
+
+    fn library_method(&self, lib: &str, name: &str) -> Option<&'static MethodSignature> {
+        let signature: &'static LibrarySignature = self.signatures.get(lib)?;
+        signature.methods.iter().find(|m| m.name == name)
+    }
+
+    /// The documented method, if the runtime supports `name` on `ty`.
+    /// `None` means `ty` is not a builtin type.
+    fn method(&self, ty: &Type, name: &str) -> Option<Result<Option<&DocEntry>, Vec<String>>> {
+        let (prefix, sample) = match ty {
+            Type::Str => ("str", Value::String(String::new())),
+            Type::Bytes => ("bytes", Value::Bytes(Vec::new())),
+            Type::List(_) => ("list", Value::List(Default::default())),
+            Type::Dict(_, _) => ("dict", Value::Dictionary(Default::default())),
+            Type::Set(_) => ("set", Value::Set(Default::default())),
+            Type::None | Type::Bool | Type::Int | Type::Float | Type::Tuple => ("", Value::None),
+            _ => return None,
+        };
+        let names = get_native_methods(&sample);
+        if !names.iter().any(|m| m == name) {
+            return Some(Err(names));
+        }
+        Some(Ok(self.methods.get(&alloc::format!("{}.{}", prefix, name))))
+    }
+}
+
+// Whether a `def` body cannot fall off its end without a `return`.
+fn always_returns(body: &[Stmt]) -> bool {
+    match body.last().map(|s| &s.kind) {
+        Some(StmtKind::Return(_)) => true,
+        Some(StmtKind::If(_, then_branch, Some(else_branch))) => {
+            always_returns(then_branch) && always_returns(else_branch)
+        }
+        Some(StmtKind::Expression(e)) => matches!(
+            &e.kind,
+            crate::ast::ExprKind::Call(callee, _)
+                if matches!(&callee.kind, crate::ast::ExprKind::Identifier(n) if n == "fail")
+        ),
+        _ => false,
+    }
+}
+
+// The binary operator an augmented assignment applies.
+fn augmented_op(op: &TokenKind) -> TokenKind {
+    match op {
+        TokenKind::PlusAssign => TokenKind::Plus,
+        TokenKind::MinusAssign => TokenKind::Minus,
+        TokenKind::StarAssign => TokenKind::Star,
+        TokenKind::SlashAssign => TokenKind::Slash,
+        TokenKind::PercentAssign => TokenKind::Percent,
+        TokenKind::SlashSlashAssign => TokenKind::SlashSlash,
+        other => other.clone(),
+    }
+}
@@ -0,0 +1,319 @@
+use crate::ast::{Expr, ExprKind, Value};
+use crate::token::{Span, TokenKind};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A static approximation of the values an expression may produce.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// Nothing is known; compatible with every other type.
+    Any,
+    None,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Bytes,
+    List(Box<Type>),
+    Tuple,
+    Dict(Box<Type>, Box<Type>),
+    Set(Box<Type>),
+    /// A record created by `struct()`; its fields are not tracked.
+    Struct,
+    /// Any value that can be iterated: strings, bytes and containers.
+    Iterable,
+    Function,
+    /// A library registered on the interpreter, named by its type name.
+    Library(String),
+    Union(Vec<Type>),
+}
+
+impl Type {
+    pub fn list(elem: Type) -> Type {
+        Type::List(Box::new(elem))
+    }
+
+    pub fn dict(key: Type, value: Type) -> Type {
+        Type::Dict(Box::new(key), Box::new(value))
+    }
+
+    pub fn set(elem: Type) -> Type {
+        Type::Set(Box::new(elem))
+    }
+
+    /// The type of a runtime value, e.g. a global defined before checking.
+    pub fn of_value(value: &Value) -> Type {
+        match value {
+            Value::None => Type::None,
+            Value::Bool(_) => Type::Bool,
+            Value::Int(_) | Value::BigInt(_) => Type::Int,
+            Value::Float(_) => Type::Float,
+            Value::String(_) => Type::Str,
+            Value::Bytes(_) => Type::Bytes,
+            Value::List(_) | Value::Iterable(_) => Type::list(Type::Any),
+            Value::Tuple(_) => Type::Tuple,
+            Value::Dictionary(_) => Type::dict(Type::Any, Type::Any),
+            Value::Set(_) => Type::set(Type::Any),
+            Value::Struct(_) => Type::Struct,
+            Value::Function(_)
+            | Value::NativeFunction(_, _)
+            | Value::NativeFunctionWithKwargs(_, _)
+            | Value::BoundMethod(_, _) => Type::Function,
+            Value::Foreign(f) => Type::Library(f.type_name().into()),
+        }
+    }
+
+    /// Joins two types into the narrowest type that covers both.
+    pub fn union(self, other: Type) -> Type {
+        let mut members = Vec::new();
+        for ty in [self, other] {
+            match ty {
+                Type::Union(inner) => members.extend(inner),
+                ty => members.push(ty),
+            }
+        }
+        let mut unique: Vec<Type> = Vec::new();
+        for ty in members {
+            if ty == Type::Any {
+                return Type::Any;
+            }
+            if !unique.contains(&ty) {
+                unique.push(ty);
+            }
+        }
+        if unique.len() == 1 {
+            unique.remove(0)
+        } else {
+            Type::Union(unique)
+        }
+    }
+
+    /// The alternatives of a union, or the type itself.
+    pub fn members(&self) -> &[Type] {
+        match self {
+            Type::Union(members) => members,
+            ty => core::slice::from_ref(ty),
+        }
+    }
+
+    /// Whether a value of this type may be used where `target` is expected.
+    ///
+    /// Only definite mismatches are rejected: a union is accepted when any of
+    /// its alternatives fits, and `Any` fits everywhere.
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Union(members), _) => members.iter().any(|m| m.is_assignable_to(target)),
+            (_, Type::Union(members)) => members.iter().any(|m| self.is_assignable_to(m)),
+            (Type::Int, Type::Float) => true,
+            (Type::List(a), Type::List(b)) | (Type::Set(a), Type::Set(b)) => a.is_assignable_to(b),
+            (Type::Dict(ak, av), Type::Dict(bk, bv)) => {
+                ak.is_assignable_to(bk) && av.is_assignable_to(bv)
+            }
+            (
+                Type::Str
+                | Type::Bytes
+                | Type::List(_)
+                | Type::Tuple
+                | Type::Dict(_, _)
+                | Type::Set(_)
+                | Type::Iterable,
+                Type::Iterable,
+            ) => true,
+            (a, b) => a == b,
+        }
+    }
+
+    /// The type of the items produced when iterating over this type.
+    pub fn element(&self) -> Type {
+        match self {
+            Type::List(elem) | Type::Set(elem) | Type::Dict(elem, _) => (**elem).clone(),
+            Type::Str => Type::Str,
+            Type::Bytes => Type::Int,
+            _ => Type::Any,
+        }
+    }
+
+    /// Parses a type from the reference docs, e.g. `Option<Int>` or `List<String>`.
+    /// Names the checker does not model map to `Any`.
+    pub fn from_doc(name: &str) -> Type {
+        let alternatives = split_top_level(name, '|');
+        if alternatives.len() > 1 {
+            return alternatives
+                .into_iter()
+                .map(Type::from_doc)
+                .reduce(Type::union)
+                .unwrap_or(Type::Any);
+        }
+
+        let name = name.trim();
+        let (base, args) = match name.split_once('<') {
+            Some((base, rest)) => (
+                base.trim(),
+                split_top_level(rest.strip_suffix('>').unwrap_or(rest), ','),
+            ),
+            None => (name, Vec::new()),
+        };
+        let arg = |i: usize| args.get(i).map_or(Type::Any, |a| Type::from_doc(a));
+
+        match base {
+            "Option" => arg(0).union(Type::None),
+            "List" | "list" => Type::list(arg(0)),
+            "Dict" | "dict" => Type::dict(arg(0), arg(1)),
+            "Set" | "set" => Type::set(arg(0)),
+            "Tuple" | "tuple" => Type::Tuple,
+            "Struct" | "struct" => Type::Struct,
+            "Int" | "int" => Type::Int,
+            "Float" | "float" => Type::Float,
+            "String" | "str" => Type::Str,
+            "Bool" | "bool" => Type::Bool,
+            "Bytes" | "bytes" => Type::Bytes,
+            "None" => Type::None,
+            "Iterable" | "Sequence" => Type::Iterable,
+            "Function" => Type::Function,
+            _ => Type::Any,
+        }
+    }
+
+    /// Parses the Rust type of a library method's parameter or result from its
+    /// [`crate::docs::LibrarySignature`], e.g. `Option<Vec<String>>`. Names the
+    /// checker does not model map to `Any`.
+    pub fn from_rust(name: &str) -> Type {
+        let name = name.trim();
+        let (base, args) = match name.split_once('<') {
+            Some((base, rest)) => (
+                base.trim(),
+                split_top_level(rest.strip_suffix('>').unwrap_or(rest), ','),
+            ),
+            None => (name, Vec::new()),
+        };
+        let arg = |i: usize| args.get(i).map_or(Type::Any, |a| Type::from_rust(a));
+
+        match base {
+            "Option" => arg(0).union(Type::None),
+            "Vec" if args.first() == Some(&"u8") => Type::Bytes,
+            "Vec" => Type::list(arg(0)),
+            "BTreeMap" | "HashMap" => Type::dict(arg(0), arg(1)),
+            "BTreeSet" | "HashSet" => Type::set(arg(0)),
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "usize" | "BigInt" => {
+                Type::Int
+            }
+            "f32" | "f64" => Type::Float,
+            "String" | "&str" => Type::Str,
+            "bool" => Type::Bool,
+            "()" => Type::None,
+            _ => Type::Any,
+        }
+    }
+
+    /// Parses a type annotation such as `int`, `list[str]`, `dict[str, int]`
+    /// or `int | None`. On failure returns the span and text of the first
+    /// name that is not a known type.
+    pub fn from_annotation(expr: &Expr) -> Result<Type, (Span, String)> {
+        match &expr.kind {
+            ExprKind::Literal(Value::None) => Ok(Type::None),
+            ExprKind::Identifier(name) => match name.as_str() {
+                "Any" | "any" => Ok(Type::Any),
+                "None" => Ok(Type::None),
+                "bool" => Ok(Type::Bool),
+                "int" => Ok(Type::Int),
+                "float" => Ok(Type::Float),
+                "str" => Ok(Type::Str),
+                "bytes" => Ok(Type::Bytes),
+                "list" => Ok(Type::list(Type::Any)),
+                "tuple" => Ok(Type::Tuple),
+                "dict" => Ok(Type::dict(Type::Any, Type::Any)),
+                "set" => Ok(Type::set(Type::Any)),
+                "struct" => Ok(Type::Struct),
+                "iterable" => Ok(Type::Iterable),
+                "function" | "callable" => Ok(Type::Function),
+                _ => Err((expr.span, name.clone())),
+            },
+            ExprKind::Index(base, arg) => {
+                let ExprKind::Identifier(base_name) = &base.kind else {
+                    return Err((base.span, String::from("subscript")));
+                };
+                let args: Vec<&Expr> = match &arg.kind {
+                    ExprKind::Tuple(items) => items.iter().collect(),
+                    _ => alloc::vec![&**arg],
+                };
+                let param = |i: usize| match args.get(i) {
+                    Some(e) => Type::from_annotation(e),
+                    None => Ok(Type::Any),
+                };
+                match base_name.as_str() {
+                    "list" => Ok(Type::list(param(0)?)),
+                    "set" => Ok(Type::set(param(0)?)),
+                    "dict" => Ok(Type::dict(param(0)?, param(1)?)),
+                    "tuple" => {
+                        for a in &args {
+                            Type::from_annotation(a)?;
+                        }
+                        Ok(Type::Tuple)
+                    }
+                    _ => Err((base.span, base_name.clone())),
+                }
+            }
+            ExprKind::BinaryOp(lhs, TokenKind::BitOr, rhs) => {
+                Ok(Type::from_annotation(lhs)?.union(Type::from_annotation(rhs)?))
+            }
+            _ => Err((expr.span, String::from("expression"))),
+        }
+    }
+}
+
+// Splits on `sep` outside of `<...>`.
+fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            c if c == sep && depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::None => write!(f, "None"),
+            Type::Bool => write!(f, "bool"),
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::Str => write!(f, "str"),
+            Type::Bytes => write!(f, "bytes"),
+            Type::List(elem) if **elem == Type::Any => write!(f, "list"),
+            Type::List(elem) => write!(f, "list[{}]", elem),
+            Type::Tuple => write!(f, "tuple"),
+            Type::Dict(k, v) if **k == Type::Any && **v == Type::Any => write!(f, "dict"),
+            Type::Dict(k, v) => write!(f, "dict[{}, {}]", k, v),
+            Type::Set(elem) if **elem == Type::Any => write!(f, "set"),
+            Type::Set(elem) => write!(f, "set[{}]", elem),
+            Type::Struct => write!(f, "struct"),
+            Type::Iterable => write!(f, "iterable"),
+            Type::Function => write!(f, "function"),
+            Type::Library(name) => write!(f, "{}", name),
+            Type::Union(members) => {
+                for (i, m) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", m)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
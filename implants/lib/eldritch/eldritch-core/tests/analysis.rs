@@ -1,4 +1,6 @@
-use eldritch_core::{ExprKind, Lexer, Parser, Stmt, analysis::Node, find_node_at_offset};
+use eldritch_core::{
+    ExprKind, Lexer, Parser, Stmt, analysis::Node, find_node_at_offset, shift_spans, walk_exprs,
+};
 
 fn parse(source: &str) -> Vec<Stmt> {
     let mut lexer = Lexer::new(source.to_string());
@@ -104,3 +106,68 @@ fn test_find_node_inside_function() {
         _ => panic!("Expected Expr"),
     }
 }
+
+#[test]
+fn test_find_node_inside_try_blocks() {
+    let source = "try:\n  a = 1\nexcept \"KeyError\" as e:\n  b = 2\nfinally:\n  c = 3";
+    let ast = parse(source);
+
+    for (name, offset) in [("a", 7), ("b", 39), ("c", 56)] {
+        assert_eq!(&source[offset..offset + 1], name);
+        let node = find_node_at_offset(&ast, offset).expect("Should find node");
+        match node {
+            Node::Expr(e) => match &e.kind {
+                ExprKind::Identifier(s) => assert_eq!(s, name),
+                _ => panic!("Expected Identifier '{name}', got {:?}", e.kind),
+            },
+            _ => panic!("Expected Expr"),
+        }
+    }
+
+    // The except kind expression is also reachable
+    let node = find_node_at_offset(&ast, 25).expect("Should find node");
+    match node {
+        Node::Expr(e) => assert!(matches!(e.kind, ExprKind::Literal(_))),
+        _ => panic!("Expected Expr"),
+    }
+}
+
+#[test]
+fn test_find_node_inside_while_loop() {
+    let source = "while ready:\n  a = 1\nelse:\n  b = 2";
+    let ast = parse(source);
+
+    for (name, offset) in [("ready", 6), ("a", 15), ("b", 29)] {
+        let node = find_node_at_offset(&ast, offset).expect("Should find node");
+        match node {
+            Node::Expr(e) => match &e.kind {
+                ExprKind::Identifier(s) => assert_eq!(s, name),
+                _ => panic!("Expected Identifier '{name}', got {:?}", e.kind),
+            },
+            _ => panic!("Expected Expr"),
+        }
+    }
+}
+
+#[test]
+fn test_walk_exprs_visits_nested_expressions() {
+    let source = "def f(a=x):\n    return [g(y) for y in z if w]\nf(v)";
+    let ast = parse(source);
+    let mut names = Vec::new();
+    walk_exprs(&ast, &mut |expr| {
+        if let ExprKind::Identifier(name) = &expr.kind {
+            names.push(name.clone());
+        }
+    });
+    assert_eq!(names, vec!["x", "g", "y", "z", "w", "f", "v"]);
+}
+
+#[test]
+fn test_shift_spans_matches_parsing_in_place() {
+    let prefix = "x = 1\n\n";
+    let source = "def f(a=[1, 2]):\n    try:\n        return {k: a[k] for k in a}\n    except Error as e:\n        print(f\"{e}\")\n";
+    let mut shifted = parse(source);
+    shift_spans(&mut shifted, prefix.chars().count(), 2);
+    let whole = parse(&format!("{}{}", prefix, source));
+    assert_eq!(format!("{:?}", shifted), format!("{:?}", &whole[1..]));
+}
@@ -0,0 +1,156 @@
+mod assert;
+
+use eldritch_core::conversion::{FromValue, ToValue};
+use eldritch_core::{BigInt, Value};
+
+#[test]
+fn test_overflow_promotes() {
+    assert::all_true(
+        r#"
+        9223372036854775807 + 1 == 9223372036854775808
+        str(9223372036854775807 + 1) == "9223372036854775808"
+        type(9223372036854775807 + 1) == "int"
+        -9223372036854775807 - 2 == -9223372036854775809
+        4294967296 * 4294967296 == 18446744073709551616
+        (9223372036854775807 + 1) - 1 == 9223372036854775807
+        -(-9223372036854775807 - 1) == 9223372036854775808
+        abs(-9223372036854775807 - 1) == 9223372036854775808
+        (-9223372036854775807 - 1) // -1 == 9223372036854775808
+        (-9223372036854775807 - 1) % -1 == 0
+        9223372036854775807 % 9223372036854775806 == 1
+        -9223372036854775807 % 9223372036854775807 == 0
+    "#,
+    );
+}
+
+#[test]
+fn test_big_arithmetic() {
+    assert::all_true(
+        r#"
+        123456789012345678901234567890 + 1 == 123456789012345678901234567891
+        123456789012345678901234567890 * 10 == 1234567890123456789012345678900
+        123456789012345678901234567890 // 7 == 17636684144620811271604938270
+        123456789012345678901234567890 % 7 == 0
+        -123456789012345678901234567890 // 7 == -17636684144620811271604938270
+        -123456789012345678901234567891 % 7 == 6
+        123456789012345678901234567890 - 123456789012345678901234567889 == 1
+        type(123456789012345678901234567890 - 123456789012345678901234567889) == "int"
+        18446744073709551616 / 2 == 9223372036854775808.0
+        18446744073709551616 + 0.5 == 18446744073709551616.5
+        float(18446744073709551616) == 18446744073709551616.0
+        int(float("1e20")) == 100000000000000000000
+        int(float("-1e20")) == -100000000000000000000
+        "%d" % 18446744073709551616 == "18446744073709551616"
+        "%x" % 18446744073709551615 == "ffffffffffffffff"
+    "#,
+    );
+    assert::fail("18446744073709551616 // 0", "divide by zero");
+    assert::fail("18446744073709551616 % 0", "modulo by zero");
+}
+
+#[test]
+fn test_big_comparisons() {
+    assert::all_true(
+        r#"
+        18446744073709551616 > 1
+        -18446744073709551616 < -1
+        18446744073709551616 > 1.5
+        18446744073709551616 == 18446744073709551616.0
+        18446744073709551616 != 18446744073709551617
+        sorted([18446744073709551616, 1, -18446744073709551616, 2.5]) == [-18446744073709551616, 1, 2.5, 18446744073709551616]
+        max(1, 18446744073709551616) == 18446744073709551616
+        {18446744073709551616: "a"}[18446744073709551616] == "a"
+        bool(18446744073709551616)
+    "#,
+    );
+}
+
+#[test]
+fn test_big_bitwise() {
+    assert::all_true(
+        r#"
+        1 << 64 == 18446744073709551616
+        (1 << 64) >> 64 == 1
+        (1 << 64) >> 100 == 0
+        -1 >> 100 == -1
+        1 << 62 == 4611686018427387904
+        (1 << 64) | 1 == 18446744073709551617
+        (1 << 64) & ((1 << 64) - 1) == 0
+        ((1 << 64) + 5) ^ (1 << 64) == 5
+        ~(1 << 64) == -18446744073709551617
+        -(1 << 64) & 255 == 0
+    "#,
+    );
+    assert::fail("1 << 512", "shift count too large");
+    assert::fail("1 << -1", "negative shift count");
+}
+
+#[test]
+fn test_big_int_builtins() {
+    assert::all_true(
+        r#"
+        int("18446744073709551615") == 18446744073709551615
+        int("-18446744073709551616") == -18446744073709551616
+        int("ffffffffffffffffff", 16) == 4722366482869645213695
+        int("0x10000000000000000", 0) == 18446744073709551616
+        hex(18446744073709551615) == "0xffffffffffffffff"
+        hex(-18446744073709551616) == "-0x10000000000000000"
+        str(340282366920938463463374607431768211456) == "340282366920938463463374607431768211456"
+    "#,
+    );
+    assert::fail("int('1_8446744073709551615000')", "invalid literal");
+}
+
+#[test]
+fn test_big_int_sizes_out_of_range() {
+    assert::all_true(
+        r#"
+        "ab" * -(1 << 70) == ""
+        -(1 << 70) * [1] == []
+        (1,) * -(1 << 70) == ()
+        {1 << 70: "a"}[1 << 70] == "a"
+    "#,
+    );
+    for code in [
+        "[1, 2][1 << 70]",
+        "(1, 2)[-(1 << 70)]",
+        "'ab'[1 << 70]",
+        "range(5)[1 << 70]",
+    ] {
+        assert::fail(code, "IndexError: int");
+        assert::fail(code, "out of range for i64");
+    }
+    for code in [
+        "range(1 << 70)",
+        "range(0, 10, 1 << 70)",
+        "'x' * (1 << 70)",
+        "(1 << 70) * [1]",
+    ] {
+        assert::fail(
+            code,
+            "ValueError: int 1180591620717411303424 out of range for i64",
+        );
+    }
+    assert::fail("chr(1 << 70)", "chr() arg not in range(0x110000)");
+}
+
+#[test]
+fn test_big_int_conversion() {
+    let max = Value::from_bigint(BigInt::from(u64::MAX));
+    assert!(matches!(max, Value::BigInt(_)));
+    assert_eq!(u64::from_value(&max), Ok(u64::MAX));
+    assert_eq!(u64::MAX.to_value(), max);
+    assert_eq!(7u64.to_value(), Value::Int(7));
+    assert_eq!(
+        i64::from_value(&max),
+        Err("int 18446744073709551615 out of range for i64".to_string())
+    );
+    assert_eq!(
+        u64::from_value(&Value::Int(-1)),
+        Err("int -1 out of range for u64".to_string())
+    );
+    assert_eq!(
+        Value::from_bigint(BigInt::from(i64::MAX)),
+        Value::Int(i64::MAX)
+    );
+}
@@ -83,6 +83,78 @@ fn test_loops() {
     );
 }
 
+#[test]
+fn test_while_loops() {
+    assert::pass(
+        r#"
+        i = 0
+        total = 0
+        while i < 5:
+            total += i
+            i += 1
+        assert_eq(total, 10)
+        assert_eq(i, 5)
+    "#,
+    );
+
+    // break / continue
+    assert::pass(
+        r#"
+        i = 0
+        seen = []
+        while True:
+            i += 1
+            if i == 2:
+                continue
+            if i > 4:
+                break
+            seen.append(i)
+        assert_eq(seen, [1, 3, 4])
+    "#,
+    );
+
+    // else runs only when the loop is not broken out of
+    assert::pass(
+        r#"
+        res = []
+        n = 3
+        while n > 0:
+            n -= 1
+        else:
+            res.append("done")
+
+        while True:
+            break
+        else:
+            res.append("unreachable")
+        assert_eq(res, ["done"])
+    "#,
+    );
+
+    // return from inside a while loop
+    assert::pass(
+        r#"
+        def first_over(items, limit):
+            i = 0
+            while i < len(items):
+                if items[i] > limit:
+                    return items[i]
+                i += 1
+            return None
+        assert_eq(first_over([1, 5, 10], 4), 5)
+        assert_eq(first_over([1, 2], 4), None)
+    "#,
+    );
+
+    assert::fail(
+        r#"
+        while True
+            pass
+    "#,
+        "Expected ':' after while condition.",
+    );
+}
+
 #[test]
 fn test_loop_scoping_rust() {
     use eldritch_core::{Interpreter, Value};
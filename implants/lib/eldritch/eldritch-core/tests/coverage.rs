@@ -0,0 +1,124 @@
+use eldritch_core::{BranchCoverage, CoverageRecorder, FunctionCoverage, Interpreter, NoopPrinter};
+use std::sync::Arc;
+
+const TOME: &str = r#"
+def check(os):
+    if os == "windows":
+        return "dir"
+    elif os == "linux":
+        return "ls"
+    return "unknown"
+
+def unused():
+    pass
+
+for os in ["linux", "linux", "mac"]:
+    check(os)
+"#;
+
+fn run(recorder: &CoverageRecorder, code: &str) {
+    let mut interp = Interpreter::new_with_printer(Arc::new(NoopPrinter));
+    interp.set_coverage(recorder.clone());
+    interp.interpret(code).unwrap();
+}
+
+#[test]
+fn test_coverage_counts_lines_and_functions() {
+    let recorder = CoverageRecorder::new();
+    run(&recorder, TOME);
+    let report = recorder.report(TOME);
+
+    let lines: Vec<(usize, u64)> = report.lines.into_iter().collect();
+    assert_eq!(
+        lines,
+        vec![
+            (2, 1),
+            (3, 3),
+            (4, 0),
+            (5, 3),
+            (6, 2),
+            (7, 1),
+            (9, 1),
+            (10, 0),
+            (12, 1),
+            (13, 3),
+        ]
+    );
+    assert_eq!(
+        report.functions,
+        vec![
+            FunctionCoverage {
+                name: "check".to_string(),
+                line: 2,
+                calls: 3,
+            },
+            FunctionCoverage {
+                name: "unused".to_string(),
+                line: 9,
+                calls: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_coverage_reports_untaken_branches() {
+    let recorder = CoverageRecorder::new();
+    run(&recorder, TOME);
+    let report = recorder.report(TOME);
+
+    let taken: Vec<(usize, usize, Option<u64>)> = report
+        .branches
+        .iter()
+        .map(|b| (b.line, b.branch, b.taken))
+        .collect();
+    // The `elif` is an `if` nested in the `else` of the first
+    assert_eq!(
+        taken,
+        vec![
+            (3, 0, Some(0)),
+            (3, 1, Some(3)),
+            (5, 0, Some(2)),
+            (5, 1, Some(1))
+        ]
+    );
+}
+
+#[test]
+fn test_coverage_marks_branches_of_unrun_ifs() {
+    let code = "def f(x):\n    if x:\n        pass\n";
+    let recorder = CoverageRecorder::new();
+    run(&recorder, code);
+    let report = recorder.report(code);
+
+    assert_eq!(
+        report.branches[1],
+        BranchCoverage {
+            line: 2,
+            block: 0,
+            branch: 1,
+            taken: None,
+        }
+    );
+    assert!(
+        report
+            .lcov("f.eldritch")
+            .contains("BRDA:2,0,0,-\nBRDA:2,0,1,-\n")
+    );
+}
+
+#[test]
+fn test_coverage_accumulates_across_runs() {
+    let code = "x = 1\nif x > 0:\n    x = 2\nelse:\n    x = 3\n";
+    let recorder = CoverageRecorder::new();
+    run(&recorder, code);
+    run(&recorder, code);
+
+    let lcov = recorder.report(code).lcov("main.eldritch");
+    assert_eq!(
+        lcov,
+        "TN:\nSF:main.eldritch\nFNF:0\nFNH:0\n\
+         BRDA:2,0,0,2\nBRDA:2,0,1,0\nBRF:2\nBRH:1\n\
+         DA:1,2\nDA:2,2\nDA:3,2\nDA:5,0\nLF:4\nLH:3\nend_of_record\n"
+    );
+}
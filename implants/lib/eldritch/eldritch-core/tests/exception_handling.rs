@@ -0,0 +1,252 @@
+mod assert;
+
+use eldritch_core::{CancellationToken, ExecutionLimits, Interpreter};
+
+#[test]
+fn test_try_except_catches_error() {
+    assert::pass(
+        r#"
+        res = 0
+        try:
+            x = {}["missing"]
+            res = 1
+        except:
+            res = 2
+        assert_eq(res, 2)
+    "#,
+    );
+
+    // No error means the handler is skipped
+    assert::pass(
+        r#"
+        res = 0
+        try:
+            res = 1
+        except:
+            res = 2
+        assert_eq(res, 1)
+    "#,
+    );
+}
+
+#[test]
+fn test_except_matches_kind() {
+    assert::pass(
+        r#"
+        res = ""
+        try:
+            1 / 0
+        except "KeyError":
+            res = "key"
+        except "ZeroDivisionError":
+            res = "zero"
+        except:
+            res = "other"
+        assert_eq(res, "zero")
+    "#,
+    );
+
+    assert::pass(
+        r#"
+        res = ""
+        try:
+            [1][5]
+        except ("KeyError", "IndexError"):
+            res = "lookup"
+        assert_eq(res, "lookup")
+    "#,
+    );
+
+    // An unmatched kind propagates to the caller
+    assert::fail(
+        r#"
+        try:
+            1 / 0
+        except "KeyError":
+            pass
+    "#,
+        "ZeroDivisionError",
+    );
+
+    assert::fail(
+        r#"
+        try:
+            1 / 0
+        except "KeyErorr":
+            pass
+    "#,
+        "Unknown error kind 'KeyErorr'",
+    );
+
+    assert::fail(
+        r#"
+        try:
+            1 / 0
+        except 5:
+            pass
+    "#,
+        "except clause expects an error kind string",
+    );
+}
+
+#[test]
+fn test_except_binds_error_value() {
+    assert::pass(
+        r#"
+        try:
+            fail("boom")
+        except as e:
+            assert_eq(e["kind"], "RuntimeError")
+            assert("boom" in e["message"])
+            assert_eq(e["span"]["line"], 3)
+
+        def lookup():
+            return {"a": 1}["b"]
+
+        try:
+            lookup()
+        except "KeyError" as err:
+            assert_eq(err["kind"], "KeyError")
+            assert_eq(err["span"]["line"], 10)
+    "#,
+    );
+}
+
+#[test]
+fn test_finally() {
+    assert::pass(
+        r#"
+        log = []
+        try:
+            log.append("try")
+        except:
+            log.append("except")
+        finally:
+            log.append("finally")
+        assert_eq(log, ["try", "finally"])
+
+        log = []
+        try:
+            1 / 0
+        except:
+            log.append("except")
+        finally:
+            log.append("finally")
+        assert_eq(log, ["except", "finally"])
+    "#,
+    );
+
+    // finally runs before an uncaught error propagates
+    let mut interp = Interpreter::new();
+    let res = interp.interpret("log = []\ntry:\n    1 / 0\nfinally:\n    log.append(1)\n");
+    assert!(res.unwrap_err().contains("ZeroDivisionError"));
+    assert_eq!(interp.interpret("len(log)").unwrap().to_string(), "1");
+}
+
+#[test]
+fn test_finally_with_control_flow() {
+    assert::pass(
+        r#"
+        log = []
+        def f():
+            try:
+                return "try"
+            finally:
+                log.append("cleanup")
+        assert_eq(f(), "try")
+        assert_eq(log, ["cleanup"])
+
+        def g():
+            try:
+                1 / 0
+            finally:
+                return "swallowed"
+        assert_eq(g(), "swallowed")
+
+        count = 0
+        for i in range(5):
+            try:
+                if i == 1:
+                    continue
+                if i == 3:
+                    break
+            finally:
+                count += 1
+        assert_eq(count, 4)
+    "#,
+    );
+}
+
+#[test]
+fn test_nested_try() {
+    assert::pass(
+        r#"
+        log = []
+        try:
+            try:
+                {}["x"]
+            except "IndexError":
+                log.append("inner")
+            finally:
+                log.append("inner finally")
+        except "KeyError":
+            log.append("outer")
+        assert_eq(log, ["inner finally", "outer"])
+
+        # Errors raised inside a handler propagate outward
+        try:
+            try:
+                1 / 0
+            except:
+                [][0]
+        except "IndexError" as e:
+            log.append(e["kind"])
+        assert_eq(log[-1], "IndexError")
+    "#,
+    );
+}
+
+#[test]
+fn test_try_syntax_errors() {
+    assert::fail(
+        r#"
+        try:
+            pass
+        x = 1
+    "#,
+        "Expected 'except' or 'finally' after try block.",
+    );
+    assert::fail(
+        r#"
+        try:
+            pass
+        except as:
+            pass
+    "#,
+        "Expected name after 'as'.",
+    );
+}
+
+#[test]
+fn test_budget_errors_are_not_catchable() {
+    let mut interp = Interpreter::new();
+    interp.set_limits(ExecutionLimits::new().with_max_steps(100));
+    let res = interp.interpret(
+        r#"
+caught = False
+try:
+    for i in range(1000):
+        pass
+except:
+    caught = True
+"#,
+    );
+    assert!(res.unwrap_err().contains("StepLimitError"));
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut interp = Interpreter::new();
+    interp.set_cancellation_token(token);
+    let res = interp.interpret("try:\n    x = 1\nexcept:\n    pass\n");
+    assert!(res.unwrap_err().contains("CancelledError"));
+}
@@ -0,0 +1,289 @@
+use eldritch_core::{
+    CancellationToken, ExecutionLimits, ForeignValue, Interpreter, NoopPrinter, Value,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const LONG_LOOP: &str = r#"
+total = 0
+for i in range(10000):
+    for j in range(10000):
+        total += 1
+"#;
+
+fn quiet_interp() -> Interpreter {
+    Interpreter::new_with_printer(Arc::new(NoopPrinter))
+}
+
+#[test]
+fn test_step_limit_aborts_with_traceback() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_steps(50));
+
+    let code = r#"
+def spin():
+    for i in range(100):
+        pass
+spin()
+"#;
+    let err = interp.interpret(code).unwrap_err();
+    assert!(
+        err.contains("StepLimitError: Step limit of 50 exceeded"),
+        "{err}"
+    );
+    assert!(err.contains("in spin"), "{err}");
+}
+
+#[test]
+fn test_step_limit_resets_between_runs() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_steps(100));
+
+    for _ in 0..5 {
+        let res = interp.interpret("for i in range(20):\n    x = i");
+        assert!(res.is_ok(), "{res:?}");
+    }
+}
+
+#[test]
+fn test_step_limit_counts_comprehensions_and_eval() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_steps(100));
+    let err = interp.interpret("[x for x in range(1000)]").unwrap_err();
+    assert!(err.contains("StepLimitError"), "{err}");
+
+    let err = interp
+        .interpret("eval(\"for i in range(1000):\\n    pass\")")
+        .unwrap_err();
+    assert!(err.contains("Step limit of 100 exceeded"), "{err}");
+}
+
+#[test]
+fn test_cancelled_token_stops_before_running() {
+    let mut interp = quiet_interp();
+    let token = interp.cancellation_token();
+    token.cancel();
+
+    let err = interp.interpret("x = 1").unwrap_err();
+    assert!(err.contains("CancelledError"), "{err}");
+
+    token.reset();
+    assert!(interp.interpret("x = 1").is_ok());
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    let mut interp = quiet_interp();
+    let token = CancellationToken::new();
+    interp.set_cancellation_token(token.clone());
+
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        token.cancel();
+    });
+
+    let err = interp.interpret(LONG_LOOP).unwrap_err();
+    canceller.join().unwrap();
+    assert!(
+        err.contains("CancelledError: Execution was cancelled"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_limits_default_to_unbounded() {
+    let interp = quiet_interp();
+    assert_eq!(interp.limits(), ExecutionLimits::default());
+    assert!(!interp.cancellation_token().is_cancelled());
+}
+
+#[test]
+fn test_while_loop_iteration_limit() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_loop_iterations(100));
+
+    // Loops under the limit are unaffected, and the count is per loop
+    interp
+        .interpret("i = 0\nwhile i < 100:\n    i += 1\nj = 0\nwhile j < 100:\n    j += 1\n")
+        .unwrap();
+
+    let err = interp
+        .interpret("try:\n    while True:\n        pass\nexcept:\n    pass\n")
+        .unwrap_err();
+    assert!(
+        err.contains("LoopLimitError: while loop exceeded the maximum of 100 iterations"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_sequence_length_limit() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_sequence_len(1000));
+
+    interp
+        .interpret("s = 'ab' * 500\nl = [0] * 1000\nr = list(range(1000))\nt = f'{1:>1000}'\nx = range(1 << 40)[5]")
+        .unwrap();
+    for code in [
+        "'a' * 1000000000000",
+        "[0] * 1001",
+        "s = 'x' * 600\ns = s + s",
+        "l = [0] * 600\nl += l",
+        "try:\n    b = b'x' * 2000\nexcept:\n    pass",
+        "list(range(1001))",
+        "tuple(range(1001))",
+        "dict(zip(range(1001), range(1001)))",
+        "str(range(1001))",
+        "print(range(1001))",
+        "def f():\n    return set(range(1001))\nf()",
+        "[max(range(1001)) for _ in [0]]",
+        "range(1 << 40)[::2]",
+        "range(5000).index(4999)",
+        "map(str, range(2000))",
+        "filter(None, range(1, 2000))",
+        "f'{1:>1001}'",
+        "'{:>1001}'.format(1)",
+        "'%1001d' % 1",
+    ] {
+        let err = interp.interpret(code).unwrap_err();
+        assert!(err.contains("MemoryLimitError"), "{code}: {err}");
+    }
+}
+
+#[test]
+fn test_eval_depth_limit() {
+    let mut interp = quiet_interp();
+    interp.set_limits(ExecutionLimits::new().with_max_eval_depth(2));
+
+    assert!(interp.interpret("eval(\"eval('1 + 1')\")").is_ok());
+    let err = interp
+        .interpret("eval(\"eval(\\\"eval('1')\\\")\")")
+        .unwrap_err();
+    assert!(err.contains("eval() nesting limit of 2 exceeded"), "{err}");
+
+    // The depth is counted per run
+    assert!(interp.interpret("eval(\"eval('1 + 1')\")").is_ok());
+}
+
+const SPIN: &str = "while True:\n    pass\n";
+
+fn limited(limits: ExecutionLimits) -> Interpreter {
+    let mut interp = quiet_interp();
+    interp.set_limits(limits);
+    interp
+}
+
+// An error a script can't catch: its name, an interpreter that raises it and code that
+// triggers it
+type Uncatchable = (&'static str, fn() -> Interpreter, &'static str);
+
+fn uncatchable() -> Vec<Uncatchable> {
+    let cases: Vec<Uncatchable> = vec![
+        (
+            "StepLimitError",
+            || limited(ExecutionLimits::new().with_max_steps(200)),
+            SPIN,
+        ),
+        (
+            "LoopLimitError",
+            || limited(ExecutionLimits::new().with_max_loop_iterations(100)),
+            SPIN,
+        ),
+        (
+            "MemoryLimitError",
+            || limited(ExecutionLimits::new().with_max_sequence_len(1000)),
+            "x = 'a' * 2000\n",
+        ),
+        (
+            "CancelledError",
+            || {
+                let interp = quiet_interp();
+                let token = interp.cancellation_token();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(50));
+                    token.cancel();
+                });
+                interp
+            },
+            SPIN,
+        ),
+    ];
+    // Timeouts need a clock
+    #[cfg(feature = "std")]
+    let cases = {
+        let mut cases = cases;
+        cases.push((
+            "TimeoutError",
+            || limited(ExecutionLimits::new().with_timeout(Duration::from_millis(50))),
+            SPIN,
+        ));
+        cases
+    };
+    cases
+}
+
+fn indent(code: &str, prefix: &str) -> String {
+    code.lines().map(|l| format!("{prefix}{l}\n")).collect()
+}
+
+#[test]
+fn test_eval_does_not_hide_uncatchable_errors() {
+    for (kind, interp, code) in uncatchable() {
+        let tome = format!("try:\n    eval({code:?})\nexcept:\n    pass\n");
+        let err = interp().interpret(&tome).unwrap_err();
+        assert!(err.contains(kind), "{kind}: {err}");
+    }
+}
+
+#[test]
+fn test_finally_does_not_discard_uncatchable_errors() {
+    for (kind, interp, code) in uncatchable() {
+        let body = indent(code, "        ");
+        for tome in [
+            format!("while True:\n    try:\n{body}    finally:\n        break\n"),
+            format!("for i in [1, 2]:\n    try:\n{body}    finally:\n        continue\n"),
+            format!("def f():\n    try:\n{body}    finally:\n        return 1\nf()\n"),
+        ] {
+            let err = interp().interpret(&tome).unwrap_err();
+            assert!(err.contains(kind), "{kind}: {tome}\n{err}");
+        }
+    }
+}
+
+/// Serves the same module source for every path.
+#[derive(Debug)]
+struct OneModule(&'static str);
+
+impl ForeignValue for OneModule {
+    fn type_name(&self) -> &str {
+        "assets"
+    }
+
+    fn method_names(&self) -> Vec<String> {
+        vec!["read".to_string()]
+    }
+
+    fn call_method(
+        &self,
+        _interp: &mut Interpreter,
+        _name: &str,
+        _args: &[Value],
+        _kwargs: &BTreeMap<String, Value>,
+    ) -> Result<Value, String> {
+        Ok(Value::String(self.0.to_string()))
+    }
+}
+
+#[test]
+fn test_load_does_not_hide_uncatchable_errors() {
+    for (kind, interp, code) in uncatchable() {
+        let mut interp = interp();
+        interp.register_lib(OneModule(code));
+        let err = interp
+            .interpret("try:\n    load(\"spin.eldritch\", \"x\")\nexcept:\n    pass\n")
+            .unwrap_err();
+        assert!(err.contains(kind), "{kind}: {err}");
+    }
+}
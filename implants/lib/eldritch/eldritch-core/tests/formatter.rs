@@ -0,0 +1,102 @@
+use eldritch_core::formatter::format;
+
+fn assert_formats(source: &str, expected: &str) {
+    let formatted = format(source).unwrap();
+    assert_eq!(formatted, expected);
+    // Formatting is idempotent
+    assert_eq!(format(&formatted).unwrap(), expected);
+}
+
+#[test]
+fn test_spacing() {
+    assert_formats(
+        "x=-a+b*2\ny=f(a,b=1,*c,**d)\nz=l[1:-1]\n",
+        "x = -a + b * 2\ny = f(a, b=1, *c, **d)\nz = l[1:-1]\n",
+    );
+    assert_formats(
+        "def f(a,b:int=1,c=2)->int:\n  return lambda q=1:q\n",
+        "def f(a, b: int = 1, c=2) -> int:\n    return lambda q=1: q\n",
+    );
+    assert_formats(
+        "d={'a':1}\nif not x in [1,2]:\n  pass\n",
+        "d = {\"a\": 1}\nif not x in [1, 2]:\n    pass\n",
+    );
+}
+
+#[test]
+fn test_reindents_blocks_and_keeps_elif() {
+    assert_formats(
+        "if a:\n  x = 1\nelif b:\n        x = 2\nelse:\n x = 3\n",
+        "if a:\n    x = 1\nelif b:\n    x = 2\nelse:\n    x = 3\n",
+    );
+}
+
+#[test]
+fn test_quotes() {
+    assert_formats(
+        "a = 'x'\nb = 'say \"hi\"'\nc = r'\\d' + b'y' + f'{a}'\nd = '''it's'''\n",
+        "a = \"x\"\nb = 'say \"hi\"'\nc = r\"\\d\" + b\"y\" + f\"{a}\"\nd = '''it's'''\n",
+    );
+}
+
+#[test]
+fn test_comments() {
+    let source = "#!/usr/bin/env eldritch
+#header
+def f():
+    x = 1 # trailing
+# outdented, but part of the body
+    return x
+x = [
+    1,  # one
+    # before two
+    2,
+]
+# end
+";
+    let expected = "#!/usr/bin/env eldritch
+# header
+def f():
+    x = 1  # trailing
+    # outdented, but part of the body
+    return x
+x = [
+    1,  # one
+    # before two
+    2,
+]
+# end
+";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn test_blank_lines() {
+    assert_formats(
+        "\n\nx = 1\n\n\n\n\ny = 2\ndef f():\n\n    a = 1\n\n\n    b = 2\n\n",
+        "x = 1\n\n\ny = 2\ndef f():\n    a = 1\n\n    b = 2\n",
+    );
+}
+
+#[test]
+fn test_long_lines_are_split() {
+    let source = format!("result = call({})\n", ["argument_name"; 8].join(", "));
+    let expected = format!("result = call(\n{})\n", "    argument_name,\n".repeat(8));
+    assert_formats(&source, &expected);
+    // A trailing comma keeps the split even when the line would fit
+    assert_formats("x = [1, 2,]\n", "x = [\n    1,\n    2,\n]\n");
+    // But not a one element tuple
+    assert_formats("x = (1,)\n", "x = (1,)\n");
+}
+
+#[test]
+fn test_semicolons_stay_on_one_line() {
+    assert_formats("if x: a = 1; b = 2\n", "if x: a = 1; b = 2\n");
+}
+
+#[test]
+fn test_syntax_errors() {
+    let err = format("def (:\n").unwrap_err();
+    assert_eq!(err.span.line, 1);
+    assert!(format("x = 'unterminated\n").is_err());
+}
@@ -0,0 +1,82 @@
+use eldritch_core::{Interpreter, Value};
+
+fn config() -> Value {
+    Interpreter::new()
+        .interpret("{'ports': [22, 443]}")
+        .unwrap()
+}
+
+fn error_of(interp: &mut Interpreter, code: &str) -> String {
+    interp.interpret(code).unwrap_err()
+}
+
+#[test]
+fn test_registered_values_are_frozen() {
+    let mut interp = Interpreter::new();
+    interp.register_module("config", config());
+
+    for (code, kind) in [
+        ("config['new'] = 1", "dict"),
+        ("config.update({'a': 1})", "dict"),
+        ("config.pop('ports')", "dict"),
+        ("config['ports'].append(80)", "list"),
+        ("config['ports'][0] = 80", "list"),
+        ("config['ports'][0] += 1", "list"),
+        ("p = config['ports']\np += [80]", "list"),
+        ("config['ports'].sort()", "list"),
+    ] {
+        let err = error_of(&mut interp, code);
+        assert!(
+            err.contains(&format!("cannot mutate frozen {kind}")),
+            "{code}: {err}"
+        );
+        assert!(err.contains("TypeError"), "{code}: {err}");
+    }
+
+    interp
+        .interpret(
+            r#"
+assert_eq(config["ports"], [22, 443])
+assert_eq(config.get("ports")[1], 443)
+ports = list(config["ports"])
+ports.append(80)
+merged = config["ports"] + [80]
+assert_eq(merged, ports)
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_is_frozen() {
+    let value = config();
+    assert!(!value.is_frozen());
+    assert!(Value::Int(1).is_frozen());
+    assert!(Value::Tuple(vec![Value::Int(1)]).is_frozen());
+    assert!(!Value::Tuple(vec![value.clone()]).is_frozen());
+
+    value.freeze();
+    assert!(value.is_frozen());
+    let Value::Dictionary(d) = &value else {
+        unreachable!()
+    };
+    assert!(d.read().values().all(Value::is_frozen));
+    assert!(Value::Tuple(vec![value.clone()]).is_frozen());
+}
+
+#[test]
+fn test_freeze_handles_cycles_and_sets() {
+    let mut interp = Interpreter::new();
+    let cyclic = interp.interpret("a = [1]\na.append(a)\na").unwrap();
+    cyclic.freeze();
+    assert!(cyclic.is_frozen());
+
+    let set = interp.interpret("{1, 2}").unwrap();
+    set.freeze();
+    interp.define_variable("s", set);
+    let err = error_of(&mut interp, "s.add(3)");
+    assert!(err.contains("cannot mutate frozen set"), "{err}");
+    interp
+        .interpret("assert_eq(s.union({3}), {1, 2, 3})")
+        .unwrap();
+}
@@ -84,6 +84,14 @@ fn test_pprint_basic() {
     assert!(out.contains("\"a\": 1") || out.contains("'a': 1"));
 }
 
+#[test]
+fn test_pprint_struct() {
+    let code = r#"
+    pprint(struct(name="x", tags=[1]))
+    "#;
+    check_output(code, "struct(\n  name=\"x\",\n  tags=[\n    1\n  ]\n)", "");
+}
+
 #[test]
 fn test_print_types() {
     let code = r#"
@@ -0,0 +1,181 @@
+mod assert;
+
+use eldritch_core::{Interpreter, LazyIterable, Value, ValueIter};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_lazy_builtins_behave_like_lists() {
+    assert::all_true(
+        r#"
+        range(3) == [0, 1, 2]
+        list(range(3)) == [0, 1, 2]
+        len(range(0, 10, 3)) == 4
+        len(range(5, 0, -2)) == 3
+        range(10)[-1] == 9
+        range(10)[2:4] == [2, 3]
+        7 in range(10)
+        not (10 in range(10))
+        type(range(1)) == "list"
+        type(enumerate([1])) == "list"
+        type(zip([1])) == "list"
+        range(3) + [3] == [0, 1, 2, 3]
+        [0] + range(1, 3) == [0, 1, 2]
+        range(2) * 2 == [0, 1, 0, 1]
+        2 * range(2) == [0, 1, 0, 1]
+        enumerate("a") + zip([1], "b") == [(0, "a"), (1, "b")]
+        str(range(3)) == "[0, 1, 2]"
+        len(zip([1, 2, 3], "ab")) == 2
+        dict(zip(["a", "b"], [1, 2])) == {"a": 1, "b": 2}
+        set(enumerate("ab")) == {(0, "a"), (1, "b")}
+        [x * 2 for x in range(3)] == [0, 2, 4]
+        not range(0)
+        bool(range(1))
+    "#,
+    );
+}
+
+#[test]
+fn test_lazy_iterables_are_reiterable() {
+    assert::pass(
+        r#"
+        pairs = enumerate(["a", "b"])
+        first = [i for i, _ in pairs]
+        second = [v for _, v in pairs]
+        assert_eq(first, [0, 1])
+        assert_eq(second, ["a", "b"])
+        i, v = zip([1], [2])[0]
+        assert_eq(i + v, 3)
+    "#,
+    );
+}
+
+#[test]
+fn test_lazy_builtins_concatenate_like_lists() {
+    assert::pass(
+        r#"
+        l = [0]
+        alias = l
+        l += range(1, 3)
+        assert_eq(alias, [0, 1, 2])
+        r = range(2)
+        r += [2]
+        assert_eq(r, [0, 1, 2])
+        r = range(2)
+        r *= 2
+        assert_eq(r, [0, 1, 0, 1])
+    "#,
+    );
+}
+
+#[test]
+fn test_lazy_builtins_answer_list_methods() {
+    assert::all_true(
+        r#"
+        range(5).index(3) == 3
+        enumerate("ab").index((1, "b")) == 1
+        "append" in dir(range(1))
+    "#,
+    );
+    assert::fail("range(3).append(3)", "cannot mutate lazy list");
+    assert::fail("zip([1]).sort()", "use list() to get a mutable copy");
+}
+
+#[test]
+fn test_huge_range_is_not_materialized() {
+    assert::pass(
+        r#"
+        total = 0
+        for i in range(1000000000000):
+            if i == 5:
+                break
+            total += i
+        assert_eq(total, 10)
+        assert_eq(len(range(1000000000000)), 1000000000000)
+        r = range(1 << 60)
+        assert_eq(r[5], 5)
+        assert_eq(r[-1], (1 << 60) - 1)
+        assert_eq(r[10:13], [10, 11, 12])
+        assert_eq(r[-4::2], [(1 << 60) - 4, (1 << 60) - 2])
+        assert_eq(r[-1:-3:-1], [(1 << 60) - 1, (1 << 60) - 2])
+        assert_eq(range(10, 0, -3)[1:], [7, 4, 1])
+        assert_eq(enumerate(r)[7], (7, 7))
+        assert_eq(zip(r, "abc")[-1], (2, "c"))
+        assert_eq(map(lambda x: x + 1, range(3)), [1, 2, 3])
+        assert_eq(filter(lambda x: x % 2, range(5)), [1, 3])
+    "#,
+    );
+}
+
+// A host iterable that counts how many items have been pulled from it.
+#[derive(Debug)]
+struct Counter {
+    pulled: Arc<AtomicUsize>,
+}
+
+impl LazyIterable for Counter {
+    fn type_name(&self) -> &str {
+        "counter"
+    }
+
+    fn iter(&self) -> ValueIter {
+        let pulled = self.pulled.clone();
+        Box::new((0..).map(move |i| {
+            pulled.fetch_add(1, Ordering::SeqCst);
+            if i == 100 {
+                Err("ValueError: counter exhausted".to_string())
+            } else {
+                Ok(Value::Int(i))
+            }
+        }))
+    }
+}
+
+#[test]
+fn test_host_iterable_is_consumed_on_demand() {
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let mut interp = Interpreter::new();
+    interp.define_variable(
+        "counter",
+        Value::Iterable(Arc::new(Counter {
+            pulled: pulled.clone(),
+        })),
+    );
+
+    interp
+        .interpret("for i in counter:\n    if i == 3:\n        break\n")
+        .unwrap();
+    assert_eq!(pulled.load(Ordering::SeqCst), 4);
+
+    interp.interpret("3 in counter").unwrap();
+    assert_eq!(pulled.load(Ordering::SeqCst), 8);
+
+    interp.interpret("assert_eq(counter[5], 5)").unwrap();
+    assert_eq!(pulled.load(Ordering::SeqCst), 14);
+    let err = interp.interpret("counter[-1]").unwrap_err();
+    assert!(err.contains("counter exhausted"), "{err}");
+    let err = interp.interpret("counter[150]").unwrap_err();
+    assert!(err.contains("counter exhausted"), "{err}");
+    // A partial pass must not compare equal to the items before the error
+    let err = interp.interpret("counter == list(range(100))").unwrap_err();
+    assert!(err.contains("counter exhausted"), "{err}");
+    let err = interp.interpret("counter < range(200)").unwrap_err();
+    assert!(err.contains("counter exhausted"), "{err}");
+    interp
+        .interpret("assert_eq(counter == [0, 1], False)")
+        .unwrap();
+
+    for code in [
+        "list(counter)",
+        "str(counter)",
+        "print(counter)",
+        "repr(counter)",
+    ] {
+        let err = interp.interpret(code).unwrap_err();
+        assert!(err.contains("counter exhausted"), "{code}: {err}");
+    }
+    let err = interp
+        .interpret("for i in counter:\n    pass\n")
+        .unwrap_err();
+    assert!(err.contains("ValueError"), "{err}");
+}
@@ -375,12 +375,12 @@ fn test_delimiters() {
 fn test_comments() {
     let input = "x = 1 # comment\n# comment line\ny = 2";
     let tokens = lex(input);
+    // Comment lines are blank lines, so they don't add a Newline of their own
     let expected = vec![
         TokenKind::Identifier(String::from("x")),
         TokenKind::Assign,
         TokenKind::Integer(1),
         TokenKind::Newline,
-        TokenKind::Newline,
         TokenKind::Identifier(String::from("y")),
         TokenKind::Assign,
         TokenKind::Integer(2),
@@ -457,3 +457,22 @@ fn test_unknown_char() {
         _ => panic!("Expected Error token"),
     }
 }
+
+#[test]
+fn test_comments_are_kept_as_trivia() {
+    let mut lexer = Lexer::new(String::from("x = 1 # one\n# two\n"));
+    let tokens: Vec<TokenKind> = lexer.scan_tokens().into_iter().map(|t| t.kind).collect();
+    assert!(!tokens.iter().any(|t| matches!(t, TokenKind::Error(_))));
+    let comments: Vec<&str> = lexer.comments().iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(comments, vec!["# one", "# two"]);
+    assert_eq!(lexer.comments()[1].span.line, 2);
+}
+
+#[test]
+fn test_comment_lines_do_not_change_indentation() {
+    let input = "def f():\n    x = 1\n# outdented\n        # indented\n    y = 2\n";
+    let tokens = lex(input);
+    let indents = tokens.iter().filter(|t| **t == TokenKind::Indent).count();
+    let dedents = tokens.iter().filter(|t| **t == TokenKind::Dedent).count();
+    assert_eq!((indents, dedents), (1, 1));
+}
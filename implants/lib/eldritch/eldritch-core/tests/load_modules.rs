@@ -0,0 +1,229 @@
+use eldritch_core::{ForeignValue, Interpreter, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Minimal stand-in for the `assets` library serving modules from memory.
+#[derive(Debug)]
+struct MemoryAssets {
+    files: BTreeMap<String, String>,
+    reads: Arc<AtomicUsize>,
+}
+
+impl ForeignValue for MemoryAssets {
+    fn type_name(&self) -> &str {
+        "assets"
+    }
+
+    fn method_names(&self) -> Vec<String> {
+        vec!["read".to_string()]
+    }
+
+    fn call_method(
+        &self,
+        _interp: &mut Interpreter,
+        name: &str,
+        args: &[Value],
+        _kwargs: &BTreeMap<String, Value>,
+    ) -> Result<Value, String> {
+        match (name, args) {
+            ("read", [Value::String(path)]) => {
+                self.reads.fetch_add(1, Ordering::SeqCst);
+                self.files
+                    .get(path)
+                    .map(|s| Value::String(s.clone()))
+                    .ok_or_else(|| format!("asset not found: {path}"))
+            }
+            _ => Err(format!("unexpected call: {name}")),
+        }
+    }
+}
+
+fn interp_with(files: &[(&str, &str)]) -> (Interpreter, Arc<AtomicUsize>) {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let mut interp = Interpreter::new();
+    interp.register_lib(MemoryAssets {
+        files: files
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        reads: reads.clone(),
+    });
+    (interp, reads)
+}
+
+const UTIL: &str = r#"
+UNITS = ["B", "KB", "MB", "GB"]
+
+def fmt_size(n):
+    i = 0
+    while n >= 1024 and i < len(UNITS) - 1:
+        n = n // 1024
+        i += 1
+    return str(n) + _suffix(i)
+
+def _suffix(i):
+    return UNITS[i]
+"#;
+
+#[test]
+fn test_load_binds_symbols_and_aliases() {
+    let (mut interp, _) = interp_with(&[("lib/util.eldritch", UTIL)]);
+    interp
+        .interpret(
+            r#"
+load("lib/util.eldritch", "fmt_size", units="UNITS")
+assert_eq(fmt_size(2048), "2KB")
+assert_eq(units[0], "B")
+"#,
+        )
+        .unwrap();
+
+    // Module globals other than the requested symbols stay private to the module
+    let err = interp.interpret("_suffix(0)").unwrap_err();
+    assert!(
+        err.contains("Undefined variable"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_load_caches_modules_per_interpreter() {
+    let module = "ITEMS = [0]\ndef items():\n    return ITEMS\n";
+    let (mut interp, reads) = interp_with(&[("items.eldritch", module)]);
+    interp
+        .interpret(
+            r#"
+load("items.eldritch", "items")
+load("items.eldritch", again="items")
+assert_eq(items(), again())
+"#,
+        )
+        .unwrap();
+    interp
+        .interpret("load(\"items.eldritch\", \"items\")\nassert_eq(items(), [0])")
+        .unwrap();
+    assert_eq!(reads.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_load_nested_modules_and_isolation() {
+    let (mut interp, _) = interp_with(&[
+        (
+            "a.eldritch",
+            "load(\"b.eldritch\", \"double\")\ndef quad(x):\n    return double(double(x))\n",
+        ),
+        ("b.eldritch", "x = 100\ndef double(x):\n    return x * 2\n"),
+    ]);
+    interp
+        .interpret(
+            r#"
+x = 1
+load("a.eldritch", "quad")
+assert_eq(quad(3), 12)
+assert_eq(x, 1)
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_load_detects_cycles() {
+    let (mut interp, _) = interp_with(&[
+        ("a.eldritch", "load(\"b.eldritch\", \"b\")\na = 1\n"),
+        ("b.eldritch", "load(\"a.eldritch\", \"a\")\nb = 1\n"),
+    ]);
+    let err = interp.interpret("load(\"a.eldritch\", \"a\")").unwrap_err();
+    assert!(
+        err.contains("load cycle detected: a.eldritch -> b.eldritch -> a.eldritch"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_load_errors_point_at_importer() {
+    let (mut interp, _) = interp_with(&[
+        ("broken.eldritch", "def f(:\n"),
+        ("fails.eldritch", "x = 1 / 0\n"),
+        ("lib/util.eldritch", UTIL),
+    ]);
+
+    let err = interp
+        .interpret("x = 1\nload(\"missing.eldritch\", \"f\")")
+        .unwrap_err();
+    assert!(err.contains("ImportError"), "unexpected error: {err}");
+    assert!(err.contains("asset not found: missing.eldritch"));
+    assert!(
+        err.contains("line 2"),
+        "error should point at the load: {err}"
+    );
+
+    let err = interp
+        .interpret("load(\"broken.eldritch\", \"f\")")
+        .unwrap_err();
+    assert!(err.contains("failed to load 'broken.eldritch'"));
+
+    let err = interp
+        .interpret("load(\"fails.eldritch\", \"x\")")
+        .unwrap_err();
+    assert!(err.contains("ZeroDivisionError"), "unexpected error: {err}");
+
+    let err = interp
+        .interpret("load(\"lib/util.eldritch\", \"nope\")")
+        .unwrap_err();
+    assert!(err.contains("module 'lib/util.eldritch' has no symbol 'nope'"));
+
+    let err = interp
+        .interpret("load(\"lib/util.eldritch\", \"_suffix\")")
+        .unwrap_err();
+    assert!(err.contains("cannot load private symbol '_suffix'"));
+
+    let err = interp.interpret("load(\"lib/util.eldritch\")").unwrap_err();
+    assert!(err.contains("load() requires at least one symbol"));
+
+    // Load errors can be handled like any other error
+    interp
+        .interpret(
+            r#"
+try:
+    load("missing.eldritch", "f")
+except "ImportError" as e:
+    assert("missing.eldritch" in e["message"])
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_load_requires_assets_library() {
+    let mut interp = Interpreter::new();
+    let err = interp.interpret("load(\"a.eldritch\", \"a\")").unwrap_err();
+    assert!(err.contains("load() requires the assets library"));
+}
+
+#[test]
+fn test_loaded_globals_are_frozen() {
+    let (mut interp, _) = interp_with(&[("lib/util.eldritch", UTIL)]);
+    let err = interp
+        .interpret(
+            r#"
+load("lib/util.eldritch", units="UNITS")
+units.append("TB")
+"#,
+        )
+        .unwrap_err();
+    assert!(err.contains("cannot mutate frozen list"), "{err}");
+
+    // Copies are mutable and the module keeps its original value
+    interp
+        .interpret(
+            r#"
+load("lib/util.eldritch", "fmt_size", units="UNITS")
+mine = list(units)
+mine.append("TB")
+assert_eq(len(units), 4)
+assert_eq(fmt_size(1024 * 1024 * 1024 * 1024), "1024GB")
+"#,
+        )
+        .unwrap();
+}
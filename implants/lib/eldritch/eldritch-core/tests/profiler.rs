@@ -0,0 +1,118 @@
+use eldritch_core::{ForeignValue, Interpreter, NoopPrinter, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Every reading is one millisecond after the last, so timings follow the number of hooks run
+fn ticking_clock() -> Duration {
+    static NOW: AtomicU64 = AtomicU64::new(0);
+    Duration::from_millis(NOW.fetch_add(1, Ordering::SeqCst))
+}
+
+#[derive(Debug)]
+struct Files;
+
+impl ForeignValue for Files {
+    fn type_name(&self) -> &str {
+        "file"
+    }
+
+    fn method_names(&self) -> Vec<String> {
+        vec!["read".to_string()]
+    }
+
+    fn call_method(
+        &self,
+        _interp: &mut Interpreter,
+        _name: &str,
+        _args: &[Value],
+        _kwargs: &BTreeMap<String, Value>,
+    ) -> Result<Value, String> {
+        Ok(Value::String("data".to_string()))
+    }
+}
+
+fn profiled() -> Interpreter {
+    let mut interp =
+        Interpreter::new_with_printer(Arc::new(NoopPrinter)).with_profiler_clock(ticking_clock);
+    interp.register_lib(Files);
+    interp
+}
+
+const TOME: &str = r#"
+def fact(n):
+    if n <= 1:
+        return 1
+    return n * fact(n - 1)
+
+def load():
+    for i in range(3):
+        file.read("/tmp/x")
+    return fact(4)
+
+load()
+"#;
+
+#[test]
+fn test_profile_counts_calls_per_function_method_and_line() {
+    let mut interp = profiled();
+    interp.interpret(TOME).unwrap();
+    let profile = interp.take_profile().unwrap();
+
+    assert_eq!(profile.functions["load"].calls, 1);
+    assert_eq!(profile.functions["fact"].calls, 4);
+    assert_eq!(profile.functions["<module>"].calls, 1);
+    assert_eq!(profile.natives["file.read"].calls, 3);
+    assert!(!profile.functions.contains_key("read"));
+    // The body of `fact` runs once per call, the recursive line for all but the last
+    assert_eq!(profile.lines[&3].calls, 4);
+    assert_eq!(profile.lines[&5].calls, 3);
+    assert_eq!(profile.lines[&9].calls, 3);
+
+    for stats in profile.functions.values().chain(profile.lines.values()) {
+        assert!(stats.exclusive <= stats.inclusive, "{stats:?}");
+    }
+    let module = profile.functions["<module>"];
+    let load = profile.functions["load"];
+    assert!(load.inclusive < module.inclusive);
+    // Recursive calls are only timed once
+    assert!(profile.functions["fact"].inclusive < load.inclusive);
+
+    // Taking the profile resets it
+    assert_eq!(interp.take_profile().unwrap().functions.len(), 0);
+}
+
+#[test]
+fn test_profile_folded_stacks() {
+    let mut interp = profiled();
+    interp.interpret(TOME).unwrap();
+    let folded = interp.take_profile().unwrap().folded();
+
+    let stacks: Vec<&str> = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().0)
+        .collect();
+    assert!(stacks.contains(&"<module>;load"), "{folded}");
+    assert!(stacks.contains(&"<module>;load;file.read"), "{folded}");
+    assert!(stacks.contains(&"<module>;load;fact;fact;fact"), "{folded}");
+    for line in folded.lines() {
+        let (_, nanos) = line.rsplit_once(' ').unwrap();
+        assert!(nanos.parse::<u64>().unwrap() > 0);
+    }
+}
+
+#[test]
+fn test_profile_survives_errors_and_is_off_by_default() {
+    let mut interp = profiled();
+    interp
+        .interpret("def boom():\n    return {}['x']\nboom()")
+        .unwrap_err();
+    let profile = interp.take_profile().unwrap();
+    assert_eq!(profile.functions["boom"].calls, 1);
+    assert_eq!(profile.functions["<module>"].calls, 1);
+
+    let mut plain = Interpreter::new_with_printer(Arc::new(NoopPrinter));
+    plain.interpret("1 + 1").unwrap();
+    assert!(plain.take_profile().is_none());
+}
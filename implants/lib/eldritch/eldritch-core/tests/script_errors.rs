@@ -0,0 +1,102 @@
+use eldritch_core::{EldritchErrorKind, ForeignValue, Interpreter, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+struct FailingLib;
+
+impl ForeignValue for FailingLib {
+    fn type_name(&self) -> &str {
+        "vault"
+    }
+
+    fn method_names(&self) -> Vec<String> {
+        vec!["open".to_string()]
+    }
+
+    fn call_method(
+        &self,
+        _interp: &mut Interpreter,
+        _name: &str,
+        _args: &[Value],
+        _kwargs: &BTreeMap<String, Value>,
+    ) -> Result<Value, String> {
+        Err("ValueError: vault is sealed".to_string())
+    }
+}
+
+const SCRIPT: &str =
+    "def outer():\n    return inner()\n\ndef inner():\n    return {}[\"missing\"]\n\nouter()\n";
+
+#[test]
+fn test_run_returns_structured_error() {
+    let err = Interpreter::new().run(SCRIPT).unwrap_err();
+    assert_eq!(err.kind, EldritchErrorKind::KeyError);
+    assert_eq!(err.code, "E0005");
+    assert!(err.message.contains("missing"), "{}", err.message);
+    assert_eq!(err.span.line, 5);
+    assert_eq!(err.library, None);
+    assert!(err.hint.is_some());
+
+    let frames: Vec<(&str, usize, Option<&str>)> = err
+        .traceback
+        .iter()
+        .map(|f| (f.function.as_str(), f.line, f.source.as_deref()))
+        .collect();
+    assert_eq!(
+        frames,
+        vec![
+            ("<module>", 7, Some("outer()")),
+            ("outer", 2, Some("return inner()")),
+            ("inner", 5, Some("return {}[\"missing\"]")),
+        ]
+    );
+
+    let snippet = err.snippet.as_ref().unwrap();
+    assert_eq!(snippet.text, "return {}[\"missing\"]");
+    assert_eq!(&snippet.text[snippet.column..snippet.column + 2], "{}");
+}
+
+#[test]
+fn test_render_matches_interpret() {
+    let rendered = Interpreter::new().run(SCRIPT).unwrap_err().render();
+    assert_eq!(Interpreter::new().interpret(SCRIPT).unwrap_err(), rendered);
+    assert!(
+        rendered.contains("  File \"<script>\", line 2, in outer\n    return inner()\n"),
+        "{rendered}"
+    );
+    assert!(rendered.ends_with("           ^-- here"), "{rendered}");
+}
+
+#[test]
+fn test_library_errors_name_the_library() {
+    let mut interp = Interpreter::new();
+    interp.register_lib(FailingLib);
+    let err = interp.run("vault.open()").unwrap_err();
+    assert_eq!(err.kind, EldritchErrorKind::ValueError);
+    assert_eq!(err.message, "vault is sealed");
+    assert_eq!(err.library.as_deref(), Some("vault"));
+    assert!(err.render().contains("(raised by the 'vault' library)"));
+}
+
+#[test]
+fn test_with_filename_and_syntax_errors() {
+    let err = Interpreter::new()
+        .run("x = 1\ny = x + )\n")
+        .unwrap_err()
+        .with_filename("tomes/demo/main.eldritch");
+    assert_eq!(err.kind, EldritchErrorKind::SyntaxError);
+    assert!(err.snippet.is_some(), "{err:?}");
+
+    let err = Interpreter::new()
+        .run("def f():\n    return z\nf()\n")
+        .unwrap_err()
+        .with_filename("tomes/demo/main.eldritch");
+    assert!(
+        err.traceback
+            .iter()
+            .all(|f| f.filename == "tomes/demo/main.eldritch")
+    );
+
+    let err = Interpreter::new().run("x = \"unterminated").unwrap_err();
+    assert_eq!(err.kind, EldritchErrorKind::SyntaxError);
+}
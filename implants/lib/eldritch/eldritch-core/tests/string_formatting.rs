@@ -0,0 +1,136 @@
+mod assert;
+
+#[test]
+fn test_format_spec() {
+    assert::pass(
+        r#"
+        assert_eq("{:>6}|{:<6}|{:^6}".format("ab", "cd", "ef"), "    ab|cd    |  ef  ")
+        assert_eq("{:*^7}".format("mid"), "**mid**")
+        assert_eq("{:5}|{:5}".format(42, "x"), "   42|x    ")
+        assert_eq("{:05d}".format(-42), "-0042")
+        assert_eq("{:+d} {: d}".format(7, 7), "+7  7")
+        assert_eq("{:,}".format(1234567), "1,234,567")
+        assert_eq("{:_}".format(1234567), "1_234_567")
+        assert_eq("{:x} {:X} {:o} {:b}".format(255, 255, 8, 5), "ff FF 10 101")
+        assert_eq("{:#x} {:#o} {:#b}".format(255, 8, 5), "0xff 0o10 0b101")
+        assert_eq("{:#06x}".format(255), "0x00ff")
+        assert_eq("{:c}".format(65), "A")
+        assert_eq("{:.2f}".format(3.14159), "3.14")
+        assert_eq("{:8.3f}".format(-2.5), "  -2.500")
+        assert_eq("{:,.2f}".format(1234567.891), "1,234,567.89")
+        assert_eq("{:e}".format(12345.678), "1.234568e+04")
+        assert_eq("{:.2E}".format(0.000123), "1.23E-04")
+        assert_eq("{:g} {:g} {:g}".format(0.5, 100000.0, 1234567.0), "0.5 100000 1.23457e+06")
+        assert_eq("{:.1%}".format(0.256), "25.6%")
+        assert_eq("{:.3}".format("abcdef"), "abc")
+        assert_eq("{:.2f}".format(3), "3.00")
+        assert_eq("{}".format(1.5), "1.5")
+        assert_eq("{:>4}".format(True), "True")
+    "#,
+    );
+}
+
+#[test]
+fn test_format_fields() {
+    assert::pass(
+        r#"
+        assert_eq("{1} {0} {1}".format("a", "b"), "b a b")
+        assert_eq("{0:>3}|{1:<3}|".format(1, 2), "  1|2  |")
+        assert_eq("{{}} {}".format(1), "{} 1")
+        assert_eq("{name}".format(), "{name}")
+    "#,
+    );
+    assert::fail(
+        "'{:d}'.format('x')",
+        "Unknown format code 'd' for object of type 'string'",
+    );
+    assert::fail(
+        "'{:s}'.format(1)",
+        "Unknown format code 's' for object of type 'int'",
+    );
+    assert::fail(
+        "'{:+}'.format('x')",
+        "Sign not allowed in string format specifier",
+    );
+    assert::fail(
+        "'{:.2d}'.format(1)",
+        "Precision not allowed in integer format specifier",
+    );
+    assert::fail("'{:>>>}'.format(1)", "Invalid format specifier");
+    assert::fail("'{1}'.format(1)", "IndexError");
+}
+
+#[test]
+fn test_fstring_format_spec() {
+    assert::pass(
+        r#"
+        name = "svc"
+        pid = 42
+        cpu = 3.14159
+        d = {"a:b": 1}
+        assert_eq(f"{name:<6}|{pid:>5}|{cpu:.1f}", "svc   |   42|3.1")
+        assert_eq(f"{pid:#x} {pid:08b}", "0x2a 00101010")
+        assert_eq(f"{d['a:b']:>3}", "  1")
+        assert_eq(f"{[1, 2][0:1]}", "[1]")
+        assert_eq(f"{pid + 1:,}", "43")
+        assert_eq(f"{name:}", "svc")
+        rows = [("a", 1), ("bbb", 22)]
+        lines = [f"{n:<4}{v:>3}" for n, v in rows]
+        assert_eq(lines, ["a     1", "bbb  22"])
+    "#,
+    );
+    assert::fail(
+        "x = 1\nf\"{x:q}\"",
+        "Unknown format code 'q' for object of type 'int'",
+    );
+}
+
+#[test]
+fn test_nested_format_fields() {
+    assert::pass(
+        r#"
+        width = 6
+        cols = {"w": 4}
+        assert_eq(f"{'x':>{5}}", "    x")
+        assert_eq(f"{'ab':>{width}}|", "    ab|")
+        assert_eq(f"{3.14159:{width}.{width - 4}f}", "  3.14")
+        assert_eq(f"{'x':{'*'}^{cols['w'] + 1}}", "**x**")
+        assert_eq(f"{'x':>{width}}" * 2, "     x     x")
+        assert_eq("{:>{}}".format("x", 3), "  x")
+        assert_eq("{:{}{}}".format(7, "<", 4) + "|", "7   |")
+        assert_eq("{0:>{1}}|{0:<{1}}|".format("ab", 4), "  ab|ab  |")
+        assert_eq("{:.{}f}".format(2.5, 3), "2.500")
+    "#,
+    );
+    assert::fail("f\"{'x':>{missing}}\"", "Undefined variable: 'missing'");
+    assert::fail("'{:>{}}'.format('x')", "IndexError");
+    assert::fail("'{:{}}'.format(1, 'q')", "Unknown format code 'q'");
+    assert::fail("'{:>{w}}'.format('x')", "Invalid format specifier '>{w}'");
+}
+
+#[test]
+fn test_percent_format() {
+    assert::pass(
+        r#"
+        assert_eq("%s %d" % ("a", 1), "a 1")
+        assert_eq("%-6s|%6s|" % ("ab", "cd"), "ab    |    cd|")
+        assert_eq("%05d %+d % d" % (42, 7, 7), "00042 +7  7")
+        assert_eq("%.2f %8.3f" % (3.14159, -2.5), "3.14   -2.500")
+        assert_eq("%x %X %#x %#o" % (255, 255, 255, 8), "ff FF 0xff 0o10")
+        assert_eq("%e" % 12345.678, "1.234568e+04")
+        assert_eq("%g %g" % (0.5, 1234567.0), "0.5 1.23457e+06")
+        assert_eq("%c%c" % (72, "i"), "Hi")
+        assert_eq("%.3s" % "abcdef", "abc")
+        assert_eq("%*d|%-*d|" % (4, 1, 3, 2), "   1|2  |")
+        assert_eq("%(host)s:%(port)d" % {"host": "10.0.0.1", "port": 22}, "10.0.0.1:22")
+        assert_eq("100%%" % (), "100%")
+    "#,
+    );
+    assert::fail("'%d' % 'x'", "%d format: a number is required, not string");
+    assert::fail("'%s %s' % ('a',)", "not enough arguments for format string");
+    assert::fail("'%s' % ('a', 'b')", "not all arguments converted");
+    assert::fail("'%(a)s' % ('x',)", "format requires a mapping");
+    assert::fail("'%(b)s' % {'a': 1}", "KeyError");
+    assert::fail("'%q' % 1", "unsupported format character 'q'");
+    assert::fail("'%5' % 1", "incomplete format");
+}
@@ -0,0 +1,67 @@
+mod assert;
+
+#[test]
+fn test_struct_fields() {
+    assert::all_true(
+        r#"
+        struct(a=1, b="x").a == 1
+        struct(a=1, b="x").b == "x"
+        struct(a=struct(b=[1, 2])).a.b[1] == 2
+        type(struct()) == "struct"
+        dir(struct(b=1, a=2)) == ["a", "b"]
+        bool(struct())
+    "#,
+    );
+}
+
+#[test]
+fn test_struct_in_function() {
+    assert::pass(
+        r#"
+        def endpoint(host, port):
+            return struct(host=host, port=port)
+
+        def describe(e):
+            return e.host + ":" + str(e.port)
+
+        assert_eq(describe(endpoint("example.com", 443)), "example.com:443")
+    "#,
+    );
+}
+
+#[test]
+fn test_struct_equality_and_ordering() {
+    assert::all_true(
+        r#"
+        struct(a=1, b=2) == struct(b=2, a=1)
+        struct(a=1) != struct(a=2)
+        struct(a=1) != struct(b=1)
+        struct(a=1) != {"a": 1}
+        struct(a=1) < struct(a=2)
+        sorted([struct(a=3), struct(a=1)])[0].a == 1
+        len({struct(a=1): "x", struct(a=1): "y"}) == 1
+        len(set([struct(a=1), struct(a=1), struct(a=2)])) == 2
+    "#,
+    );
+}
+
+#[test]
+fn test_struct_repr() {
+    assert::all_true(
+        r#"
+        str(struct()) == "struct()"
+        str(struct(b="x", a=1)) == 'struct(a=1, b="x")'
+    "#,
+    );
+}
+
+#[test]
+fn test_struct_errors() {
+    assert::fail("struct(a=1).b", "'struct' object has no attribute 'b'");
+    assert::fail(
+        "def f():\n    return struct(a=1).b\nf()",
+        "'struct' object has no attribute 'b'",
+    );
+    assert::fail("struct(1)", "struct() takes no positional arguments");
+    assert::fail("s = struct(a=1)\ns.a = 2", "cannot assign");
+}
@@ -0,0 +1,250 @@
+use eldritch_core::docs::{LibrarySignature, MethodSignature, ParamSignature};
+use eldritch_core::typecheck::{Diagnostic, Severity, Type, TypeChecker};
+use eldritch_core::{ForeignValue, Interpreter, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+fn check(source: &str) -> Vec<Diagnostic> {
+    TypeChecker::new().check_source(source)
+}
+
+fn codes(source: &str) -> Vec<&'static str> {
+    check(source).into_iter().map(|d| d.code).collect()
+}
+
+fn assert_clean(source: &str) {
+    let diagnostics = check(source);
+    assert!(diagnostics.is_empty(), "{:#?}", diagnostics);
+}
+
+#[test]
+fn test_annotated_assignment() {
+    assert_clean("x: int = 1\ny: float = 2\nz: list[str] = ['a']\nw: int | None = None");
+    assert_eq!(codes("x: int = 'a'"), vec!["type-mismatch"]);
+    assert_eq!(
+        codes("x: dict[str, int] = {'a': 'b'}"),
+        vec!["type-mismatch"]
+    );
+}
+
+#[test]
+fn test_reassigning_declared_variable() {
+    let diagnostics = check("x: int = 1\nx = 'a'");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "type-mismatch");
+    assert_eq!(diagnostics[0].span.line, 2);
+    assert!(diagnostics[0].message.contains("declared as int"));
+}
+
+#[test]
+fn test_call_arguments() {
+    let source = "def f(a: int, b: str = 'x'):\n    return a\n";
+    assert_clean(&format!("{}f(1)\nf(1, 'y')\nf(a=1, b='z')", source));
+    assert_eq!(codes(&format!("{}f('a')", source)), vec!["type-mismatch"]);
+    assert_eq!(codes(&format!("{}f()", source)), vec!["argument-count"]);
+    assert_eq!(
+        codes(&format!("{}f(1, 'a', 2)", source)),
+        vec!["argument-count"]
+    );
+    assert_eq!(
+        codes(&format!("{}f(1, c=2)", source)),
+        vec!["unknown-argument"]
+    );
+}
+
+#[test]
+fn test_return_types() {
+    assert_eq!(
+        codes("def f() -> int:\n    return 'a'"),
+        vec!["return-type"]
+    );
+    assert_clean("def f() -> int | None:\n    if True:\n        return 1\n    return None");
+}
+
+#[test]
+fn test_inferred_return_types_cross_functions() {
+    let source = "
+def name():
+    return 'bob'
+
+def greet():
+    return name()
+
+x: int = greet()
+";
+    let diagnostics = check(source);
+    assert_eq!(diagnostics.len(), 1, "{:#?}", diagnostics);
+    assert!(diagnostics[0].message.contains("expected int, got str"));
+}
+
+#[test]
+fn test_functions_called_before_definition() {
+    assert_eq!(
+        codes("def main():\n    helper(1)\n\ndef helper(s: str):\n    pass"),
+        vec!["type-mismatch"]
+    );
+}
+
+#[test]
+fn test_binary_operators() {
+    assert_eq!(codes("x = 1 + 'a'"), vec!["invalid-operand"]);
+    assert_clean("x = 1 + 2.0\ny = 'a' * 3\nz = [1] + [2]\nw = {1} | {2}\nv = 'a %s' % 1");
+}
+
+#[test]
+fn test_builtin_signatures() {
+    assert_clean("x: int = len('abc')\ny: list[int] = range(3)\nz: str = str(1)");
+    assert_eq!(codes("x: str = len('abc')"), vec!["type-mismatch"]);
+    assert_eq!(codes("chr('a')"), vec!["type-mismatch"]);
+    assert_eq!(codes("len()"), vec!["argument-count"]);
+}
+
+#[test]
+fn test_builtin_methods() {
+    assert_clean("s = 'a,b'\nparts: list[str] = s.split(',')\nl = []\nl.append(1)");
+    let diagnostics = check("l = [1]\nl.apend(2)");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "unknown-method");
+    assert!(diagnostics[0].message.contains("did you mean 'append'"));
+    assert_eq!(codes("'a'.startswith(1)"), vec!["type-mismatch"]);
+}
+
+#[test]
+fn test_unknown_annotation_is_warning() {
+    let diagnostics = check("x: widget = 1");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].code, "unknown-type");
+}
+
+#[test]
+fn test_branches_are_joined() {
+    assert_clean("if True:\n    x = 1\nelse:\n    x = 'a'\ny = x + 1");
+}
+
+#[test]
+fn test_globals_may_change_before_call() {
+    assert_clean("conn = None\ndef use():\n    conn.send()\nconn = make()\nuse()");
+}
+
+#[test]
+fn test_syntax_errors_are_reported() {
+    assert!(codes("def (:").contains(&"syntax-error"));
+}
+
+#[derive(Debug)]
+struct Widget;
+
+impl ForeignValue for Widget {
+    fn type_name(&self) -> &str {
+        "widget"
+    }
+
+    fn method_names(&self) -> Vec<String> {
+        vec!["spin".to_string()]
+    }
+
+    fn call_method(
+        &self,
+        _interp: &mut Interpreter,
+        _name: &str,
+        _args: &[Value],
+        _kwargs: &BTreeMap<String, Value>,
+    ) -> Result<Value, String> {
+        Ok(Value::None)
+    }
+}
+
+#[test]
+fn test_library_methods_from_interpreter() {
+    let mut interp = Interpreter::new();
+    interp.define_variable("widget", Value::Foreign(Arc::new(Widget)));
+    interp.define_variable("count", Value::Int(1));
+    let checker = TypeChecker::from_interpreter(&interp);
+
+    assert!(checker.check_source("widget.spin()").is_empty());
+    let diagnostics = checker.check_source("widget.spn()");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "unknown-method");
+    assert_eq!(
+        checker.check_source("x = count + 'a'")[0].code,
+        "invalid-operand"
+    );
+}
+
+#[test]
+fn test_with_global() {
+    let checker = TypeChecker::new().with_global("name", Type::Str);
+    assert_eq!(
+        checker.check_source("x: int = name")[0].code,
+        "type-mismatch"
+    );
+}
+
+static FILE: LibrarySignature = LibrarySignature {
+    name: "file",
+    doc: "",
+    methods: &[
+        MethodSignature {
+            name: "read",
+            params: &[ParamSignature {
+                name: "path",
+                type_name: "String",
+                optional: false,
+            }],
+            returns: "String",
+            doc: "",
+        },
+        MethodSignature {
+            name: "list",
+            params: &[
+                ParamSignature {
+                    name: "paths",
+                    type_name: "Vec<String>",
+                    optional: false,
+                },
+                ParamSignature {
+                    name: "limit",
+                    type_name: "Option<i64>",
+                    optional: true,
+                },
+            ],
+            returns: "Vec<BTreeMap<String, Value>>",
+            doc: "",
+        },
+    ],
+};
+
+#[test]
+fn test_library_signatures() {
+    let checker = TypeChecker::new().with_library_signature(&FILE);
+    let codes = |source: &str| -> Vec<&'static str> {
+        checker
+            .check_source(source)
+            .into_iter()
+            .map(|d| d.code)
+            .collect()
+    };
+
+    assert!(codes("x: str = file.read('/etc/passwd')").is_empty());
+    assert!(
+        codes(
+            "file.list(['/tmp'])
+file.list(('/tmp',), limit=1)"
+        )
+        .is_empty()
+    );
+    assert_eq!(codes("x: int = file.read('a')"), vec!["type-mismatch"]);
+    assert_eq!(codes("file.read(1)"), vec!["type-mismatch"]);
+    assert_eq!(codes("file.read()"), vec!["argument-count"]);
+    assert_eq!(codes("file.read('a', 'b')"), vec!["argument-count"]);
+    assert_eq!(
+        codes("file.read(pth='a')"),
+        vec!["argument-count", "unknown-argument"]
+    );
+    assert_eq!(
+        codes("file.list(['/tmp'], limit='1')"),
+        vec!["type-mismatch"]
+    );
+    assert_eq!(codes("file.lst()"), vec!["unknown-method"]);
+}
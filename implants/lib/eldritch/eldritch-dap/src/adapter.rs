@@ -0,0 +1,265 @@
+use crate::debugger::{Breakpoints, Command, Debugger, THREAD_ID};
+use crate::protocol::{Client, Request, read_message};
+use eldritch::conversion::ToValue;
+use eldritch::file::fake::FileLibraryFake;
+use eldritch::process::fake::ProcessLibraryFake;
+use eldritch::{CancellationToken, Interpreter, Printer, Span};
+use eldritch_core::EldritchErrorKind;
+use eldritch_mockagent::MockAgent;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+// The `launch` arguments, as set in a `.vscode/launch.json` configuration
+struct Launch {
+    program: String,
+    source: String,
+    stop_on_entry: bool,
+    no_debug: bool,
+    params: BTreeMap<String, String>,
+}
+
+/// Serves one debug session over a pair of streams: launches the tome when the client is
+/// done configuring breakpoints, then relays requests to it until the client disconnects.
+pub struct Adapter {
+    client: Arc<Client>,
+    breakpoints: Breakpoints,
+    pause: Arc<AtomicBool>,
+    token: CancellationToken,
+    launch: Option<Launch>,
+    // Set while the tome is running; requests sent to it are served when it pauses
+    inbox: Arc<Mutex<Option<Sender<Command>>>>,
+    tome: Option<JoinHandle<()>>,
+}
+
+impl Adapter {
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            client: Arc::new(Client::new(out)),
+            breakpoints: Breakpoints::default(),
+            pause: Arc::new(AtomicBool::new(false)),
+            token: CancellationToken::new(),
+            launch: None,
+            inbox: Arc::new(Mutex::new(None)),
+            tome: None,
+        }
+    }
+
+    pub fn run(mut self, mut input: impl BufRead) -> anyhow::Result<()> {
+        while let Some(message) = read_message(&mut input)? {
+            // Only requests are expected; anything else is ignored
+            let Ok(request) = serde_json::from_value::<Request>(message) else {
+                continue;
+            };
+            if !self.handle(request) {
+                break;
+            }
+        }
+        self.stop();
+        Ok(())
+    }
+
+    // Returns false once the client has disconnected
+    fn handle(&mut self, request: Request) -> bool {
+        match request.command.as_str() {
+            "initialize" => self.client.respond(
+                &request,
+                json!({
+                    "supportsConfigurationDoneRequest": true,
+                    "supportsEvaluateForHovers": true,
+                    "supportsTerminateRequest": true,
+                }),
+            ),
+            "launch" => match parse_launch(&request) {
+                Ok(launch) => {
+                    self.launch = Some(launch);
+                    self.client.respond(&request, json!({}));
+                    self.client.event("initialized", json!({}));
+                }
+                Err(e) => self.client.fail(&request, &e),
+            },
+            "setBreakpoints" => self.set_breakpoints(&request),
+            "setExceptionBreakpoints" => self.client.respond(&request, json!({})),
+            "configurationDone" => match self.launch.take() {
+                Some(launch) => {
+                    self.start(launch);
+                    self.client.respond(&request, json!({}));
+                }
+                None => self.client.fail(&request, "No tome was launched"),
+            },
+            "threads" => self.client.respond(
+                &request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "tome" }] }),
+            ),
+            "pause" => {
+                self.pause.store(true, Ordering::SeqCst);
+                self.client.respond(&request, json!({}));
+            }
+            "terminate" => {
+                self.stop();
+                self.client.respond(&request, json!({}));
+            }
+            "disconnect" => {
+                self.stop();
+                self.client.respond(&request, json!({}));
+                return false;
+            }
+            "stackTrace" | "scopes" | "variables" | "evaluate" | "continue" | "next" | "stepIn"
+            | "stepOut" => {
+                let inbox = self.inbox.lock().unwrap_or_else(|e| e.into_inner());
+                match inbox.as_ref() {
+                    Some(tome) => {
+                        let _ = tome.send(Command::Request(request));
+                    }
+                    None => self.client.fail(&request, "The tome is not running"),
+                }
+            }
+            _ => self.client.fail(&request, "Unsupported request"),
+        }
+        true
+    }
+
+    fn set_breakpoints(&self, request: &Request) {
+        let args = &request.arguments;
+        let path = args["source"]["path"].as_str().unwrap_or_default();
+        let lines: Vec<usize> = args["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|breakpoint| breakpoint["line"].as_u64())
+            .map(|line| line as usize)
+            .collect();
+
+        self.breakpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_string(), lines.iter().copied().collect());
+        let breakpoints: Vec<_> = lines
+            .iter()
+            .map(|line| json!({ "verified": true, "line": line }))
+            .collect();
+        self.client
+            .respond(request, json!({ "breakpoints": breakpoints }));
+    }
+
+    // Runs the tome on its own thread, against the mock agent and fake libraries
+    fn start(&mut self, launch: Launch) {
+        let (sender, commands) = mpsc::channel();
+        *self.inbox.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+        let debugger = Arc::new(Debugger::new(
+            self.client.clone(),
+            launch.program.clone(),
+            self.breakpoints.clone(),
+            self.pause.clone(),
+            commands,
+            launch.stop_on_entry,
+        ));
+
+        let client = self.client.clone();
+        let inbox = self.inbox.clone();
+        let token = self.token.clone();
+        self.tome = Some(thread::spawn(move || {
+            let printer = Arc::new(OutputPrinter(client.clone()));
+            let mut interp = Interpreter::new_with_printer(printer)
+                .with_default_libs()
+                .with_agent(Arc::new(MockAgent::new()))
+                .with_cancellation_token(token);
+            interp.register_lib(FileLibraryFake::default());
+            interp.register_lib(ProcessLibraryFake);
+            if !launch.no_debug {
+                interp = interp.with_debug_hook(debugger.clone());
+            }
+            interp.define_variable("input_params", launch.params.to_value());
+
+            let exit_code = match interp.run(&launch.source) {
+                Ok(_) => 0,
+                Err(e) => {
+                    if e.kind != EldritchErrorKind::CancelledError {
+                        let error = e.with_filename(&launch.program);
+                        client.event(
+                            "output",
+                            json!({ "category": "stderr", "output": format!("{}\n", error) }),
+                        );
+                    }
+                    1
+                }
+            };
+
+            inbox.lock().unwrap_or_else(|e| e.into_inner()).take();
+            debugger.drain();
+            client.event("exited", json!({ "exitCode": exit_code }));
+            client.event("terminated", json!({}));
+        }));
+    }
+
+    // Stops the tome, resuming it first if it is paused, and waits for it to exit
+    fn stop(&mut self) {
+        self.token.cancel();
+        if let Some(tome) = self
+            .inbox
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            let _ = tome.send(Command::Disconnect);
+        }
+        if let Some(tome) = self.tome.take() {
+            let _ = tome.join();
+        }
+    }
+}
+
+fn parse_launch(request: &Request) -> Result<Launch, String> {
+    let args = &request.arguments;
+    let program = args["program"]
+        .as_str()
+        .ok_or("The launch configuration is missing 'program'")?
+        .to_string();
+    let source =
+        fs::read_to_string(&program).map_err(|e| format!("Failed to read {}: {}", program, e))?;
+    let params = args["params"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| {
+            let value = match value.as_str() {
+                Some(s) => s.to_string(),
+                None => value.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect();
+
+    Ok(Launch {
+        program,
+        source,
+        stop_on_entry: args["stopOnEntry"].as_bool().unwrap_or(false),
+        no_debug: args["noDebug"].as_bool().unwrap_or(false),
+        params,
+    })
+}
+
+// Sends what the tome prints to the client's debug console
+#[derive(Debug)]
+struct OutputPrinter(Arc<Client>);
+
+impl Printer for OutputPrinter {
+    fn print_out(&self, _span: &Span, s: &str) {
+        self.0.event(
+            "output",
+            json!({ "category": "stdout", "output": format!("{}\n", s) }),
+        );
+    }
+
+    fn print_err(&self, _span: &Span, s: &str) {
+        self.0.event(
+            "output",
+            json!({ "category": "stderr", "output": format!("{}\n", s) }),
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use crate::protocol::{Client, Request};
+use eldritch_core::introspection::get_type_name;
+use eldritch_core::{DebugHook, Environment, Interpreter, Value};
+use serde_json::{Value as Json, json};
+use spin::RwLock;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+
+/// Tomes run on a single thread, which is the only one reported to the client.
+pub const THREAD_ID: i64 = 1;
+
+/// Breakpoint lines by the path of the file they were set in.
+pub type Breakpoints = Arc<Mutex<BTreeMap<String, BTreeSet<usize>>>>;
+
+/// What the adapter passes to the tome's thread.
+pub enum Command {
+    /// A request that needs the tome to be paused, such as `stackTrace` or `next`.
+    Request(Request),
+    /// The client disconnected, so a paused tome should resume and stop.
+    Disconnect,
+}
+
+// Where to pause next, relative to the line the tome was paused on
+#[derive(Clone, Copy)]
+enum Step {
+    Continue,
+    In,
+    Over { depth: usize, line: usize },
+    Out { depth: usize },
+}
+
+impl Step {
+    fn done(self, depth: usize, line: usize) -> bool {
+        match self {
+            Step::Continue => false,
+            Step::In => true,
+            Step::Over {
+                depth: from,
+                line: from_line,
+            } => depth < from || (depth == from && line != from_line),
+            Step::Out { depth: from } => depth < from,
+        }
+    }
+}
+
+// What a `variablesReference` handed to the client points at
+enum Reference {
+    Locals(Arc<RwLock<Environment>>),
+    Globals(Arc<RwLock<Environment>>),
+    Value(Value),
+}
+
+struct State {
+    step: Step,
+    // Whether the next pause is the one requested with `stopOnEntry`
+    entry: bool,
+    // The checkpoint last seen, as (depth, line)
+    last: Option<(usize, usize)>,
+    // The innermost scope of each frame, by depth
+    envs: Vec<Arc<RwLock<Environment>>>,
+    // Handed out while paused; reference `n` is `references[n - 1]`
+    references: Vec<Reference>,
+}
+
+/// Pauses the tome on breakpoints, steps and pause requests, and answers the requests that
+/// inspect it while it is paused.
+pub struct Debugger {
+    client: Arc<Client>,
+    program: String,
+    breakpoints: Breakpoints,
+    pause: Arc<AtomicBool>,
+    commands: Mutex<Receiver<Command>>,
+    state: Mutex<State>,
+}
+
+impl Debugger {
+    pub fn new(
+        client: Arc<Client>,
+        program: String,
+        breakpoints: Breakpoints,
+        pause: Arc<AtomicBool>,
+        commands: Receiver<Command>,
+        stop_on_entry: bool,
+    ) -> Self {
+        Self {
+            client,
+            program,
+            breakpoints,
+            pause,
+            commands: Mutex::new(commands),
+            state: Mutex::new(State {
+                step: if stop_on_entry {
+                    Step::In
+                } else {
+                    Step::Continue
+                },
+                entry: stop_on_entry,
+                last: None,
+                envs: Vec::new(),
+                references: Vec::new(),
+            }),
+        }
+    }
+
+    /// Fails the requests sent after the tome stopped running.
+    pub fn drain(&self) {
+        let commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        while let Ok(command) = commands.recv() {
+            if let Command::Request(request) = command {
+                self.client.fail(&request, "The tome is not running");
+            }
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Reports the pause and serves requests until the client resumes or disconnects
+    fn stop(&self, interp: &mut Interpreter, reason: &str, line: usize) {
+        self.client.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        );
+
+        let commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        while let Ok(Command::Request(request)) = commands.recv() {
+            let depth = interp.call_stack.len();
+            let step = match request.command.as_str() {
+                "continue" => Step::Continue,
+                "next" => Step::Over { depth, line },
+                "stepIn" => Step::In,
+                "stepOut" => Step::Out { depth },
+                "stackTrace" => {
+                    self.stack_trace(interp, line, &request);
+                    continue;
+                }
+                "scopes" => {
+                    self.scopes(&request);
+                    continue;
+                }
+                "variables" => {
+                    self.variables(&request);
+                    continue;
+                }
+                "evaluate" => {
+                    self.evaluate(interp, &request);
+                    continue;
+                }
+                _ => {
+                    self.client.fail(&request, "Unsupported request");
+                    continue;
+                }
+            };
+            let mut state = self.state();
+            state.step = step;
+            state.references.clear();
+            self.client
+                .respond(&request, json!({ "allThreadsContinued": true }));
+            return;
+        }
+        // Disconnected: the adapter has cancelled the tome, which stops once resumed
+    }
+
+    fn stack_trace(&self, interp: &Interpreter, line: usize, request: &Request) {
+        let name = Path::new(&self.program)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.program.clone());
+        let source = json!({ "name": name, "path": self.program });
+        let frame = |id: usize, name: &str, line: usize| json!({ "id": id, "name": name, "line": line, "column": 1, "source": source });
+
+        // Each entry of the call stack is a caller, with the line it made the call from
+        let mut frames = vec![frame(
+            interp.call_stack.len(),
+            &interp.current_func_name,
+            line,
+        )];
+        for (depth, caller) in interp.call_stack.iter().enumerate().rev() {
+            frames.push(frame(depth, &caller.name, caller.line));
+        }
+        self.client.respond(
+            request,
+            json!({ "stackFrames": frames, "totalFrames": frames.len() }),
+        );
+    }
+
+    fn scopes(&self, request: &Request) {
+        let mut state = self.state();
+        let frame = request.arguments["frameId"].as_u64().unwrap_or_default() as usize;
+        let Some(env) = state.envs.get(frame).cloned() else {
+            self.client.fail(request, "Unknown frame");
+            return;
+        };
+
+        let mut scopes = Vec::new();
+        if frame > 0 || env.read().parent.is_some() {
+            let reference = state.reference(Reference::Locals(env.clone()));
+            scopes.push(
+                json!({ "name": "Locals", "variablesReference": reference, "expensive": false }),
+            );
+        }
+        let reference = state.reference(Reference::Globals(root(env)));
+        scopes.push(
+            json!({ "name": "Globals", "variablesReference": reference, "expensive": false }),
+        );
+        self.client.respond(request, json!({ "scopes": scopes }));
+    }
+
+    fn variables(&self, request: &Request) {
+        let mut state = self.state();
+        let reference = request.arguments["variablesReference"]
+            .as_u64()
+            .unwrap_or_default() as usize;
+        let children: Vec<(String, Value)> = match reference
+            .checked_sub(1)
+            .and_then(|idx| state.references.get(idx))
+        {
+            Some(Reference::Locals(env)) => locals(env),
+            Some(Reference::Globals(env)) => env
+                .read()
+                .values
+                .iter()
+                .filter(|(name, value)| !is_builtin(name, value))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            Some(Reference::Value(value)) => children(value),
+            None => {
+                self.client.fail(request, "Unknown variables reference");
+                return;
+            }
+        };
+
+        let variables: Vec<Json> = children
+            .into_iter()
+            .map(|(name, value)| {
+                let mut variable = state.describe(value);
+                variable["name"] = json!(name);
+                variable
+            })
+            .collect();
+        self.client
+            .respond(request, json!({ "variables": variables }));
+    }
+
+    fn evaluate(&self, interp: &mut Interpreter, request: &Request) {
+        let expression = request.arguments["expression"].as_str().unwrap_or_default();
+        let env = request.arguments["frameId"]
+            .as_u64()
+            .and_then(|frame| self.state().envs.get(frame as usize).cloned())
+            .unwrap_or_else(|| interp.env.clone());
+
+        match interp.evaluate_in(env, expression) {
+            Ok(value) => {
+                let mut result = self.state().describe(value);
+                result["result"] = result["value"].take();
+                self.client.respond(request, result);
+            }
+            Err(e) => self.client.fail(request, &e),
+        }
+    }
+}
+
+impl DebugHook for Debugger {
+    fn on_line(&self, interp: &mut Interpreter, line: usize) {
+        let depth = interp.call_stack.len();
+        let reason = {
+            let mut state = self.state();
+            state.envs.resize(depth + 1, interp.env.clone());
+            state.envs[depth] = interp.env.clone();
+            // A statement and the calls on its line share a pause
+            if state.last.replace((depth, line)) == Some((depth, line)) {
+                return;
+            }
+
+            if self.pause.swap(false, Ordering::SeqCst) {
+                "pause"
+            } else if state.step.done(depth, line) {
+                if state.entry {
+                    state.entry = false;
+                    "entry"
+                } else {
+                    "step"
+                }
+            } else if self
+                .breakpoints
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&self.program)
+                .is_some_and(|lines| lines.contains(&line))
+            {
+                "breakpoint"
+            } else {
+                return;
+            }
+        };
+        self.stop(interp, reason, line);
+    }
+}
+
+impl State {
+    fn reference(&mut self, reference: Reference) -> usize {
+        self.references.push(reference);
+        self.references.len()
+    }
+
+    // The value, type and children reference of a variable or evaluation result
+    fn describe(&mut self, value: Value) -> Json {
+        let type_name = get_type_name(&value);
+        let repr = format!("{:?}", value);
+        let reference = if has_children(&value) {
+            self.reference(Reference::Value(value))
+        } else {
+            0
+        };
+        json!({ "value": repr, "type": type_name, "variablesReference": reference })
+    }
+}
+
+fn root(mut env: Arc<RwLock<Environment>>) -> Arc<RwLock<Environment>> {
+    loop {
+        let parent = env.read().parent.clone();
+        match parent {
+            Some(parent) => env = parent,
+            None => return env,
+        }
+    }
+}
+
+// The variables of every scope in `env` below the globals, innermost first
+fn locals(env: &Arc<RwLock<Environment>>) -> Vec<(String, Value)> {
+    let mut variables: BTreeMap<String, Value> = BTreeMap::new();
+    let mut scope = Some(env.clone());
+    while let Some(env) = scope {
+        let env = env.read();
+        if env.parent.is_none() {
+            break;
+        }
+        for (name, value) in &env.values {
+            variables
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+        scope = env.parent.clone();
+    }
+    variables.into_iter().collect()
+}
+
+// Builtins and libraries live in the globals too, but aren't worth listing
+fn is_builtin(name: &str, value: &Value) -> bool {
+    name == "pass"
+        || matches!(
+            value,
+            Value::NativeFunction(..) | Value::NativeFunctionWithKwargs(..) | Value::Foreign(_)
+        )
+}
+
+fn has_children(value: &Value) -> bool {
+    match value {
+        Value::List(items) => !items.read().is_empty(),
+        Value::Tuple(items) => !items.is_empty(),
+        Value::Set(items) => !items.read().is_empty(),
+        Value::Dictionary(items) => !items.read().is_empty(),
+        Value::Struct(fields) => !fields.is_empty(),
+        _ => false,
+    }
+}
+
+fn children(value: &Value) -> Vec<(String, Value)> {
+    let indexed = |items: Vec<Value>| {
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| (idx.to_string(), item))
+            .collect()
+    };
+    match value {
+        Value::List(items) => indexed(items.read().clone()),
+        Value::Tuple(items) => indexed(items.clone()),
+        Value::Set(items) => indexed(items.read().iter().cloned().collect()),
+        Value::Dictionary(items) => items
+            .read()
+            .iter()
+            .map(|(key, value)| (format!("{:?}", key), value.clone()))
+            .collect(),
+        Value::Struct(fields) => fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,14 @@
+//! A Debug Adapter Protocol server for Eldritch. Editors start it and talk to it over
+//! stdin and stdout to run a tome with breakpoints, stepping, stack inspection and
+//! evaluation. Tomes run against the mock agent and the fake `file` and `process`
+//! libraries, so debugging them has no side effects on the host.
+
+mod adapter;
+mod debugger;
+mod protocol;
+
+use adapter::Adapter;
+
+fn main() -> anyhow::Result<()> {
+    Adapter::new(std::io::stdout()).run(std::io::stdin().lock())
+}
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::Mutex;
+
+/// A request from the client, e.g. `{"seq": 3, "command": "next", "arguments": {...}}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// Reads the next message, framed with a `Content-Length` header as in LSP, or `None` once
+/// the client has closed the stream.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            let value = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            length = Some(value);
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Sends responses and events to the client. Shared by the adapter and the thread running
+/// the tome, which reports output and stops.
+pub struct Client {
+    out: Mutex<(Box<dyn Write + Send>, i64)>,
+}
+
+impl Client {
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Mutex::new((Box::new(out), 0)),
+        }
+    }
+
+    pub fn respond(&self, request: &Request, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": true,
+            "command": request.command,
+            "body": body,
+        }));
+    }
+
+    pub fn fail(&self, request: &Request, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": false,
+            "command": request.command,
+            "message": message,
+            "body": { "error": { "id": 1, "format": message } },
+        }));
+    }
+
+    pub fn event(&self, event: &str, body: Value) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+
+    fn send(&self, mut message: Value) {
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        out.1 += 1;
+        message["seq"] = json!(out.1);
+        let body = message.to_string();
+        // A client that went away is noticed when its input closes
+        let _ = write!(out.0, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let _ = out.0.flush();
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client").finish_non_exhaustive()
+    }
+}
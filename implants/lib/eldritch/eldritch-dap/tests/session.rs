@@ -0,0 +1,268 @@
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const TOME: &str = r#"def add(a, b):
+    total = a + b
+    return total
+
+items = [1, 2]
+result = add(items[0], items[1])
+print(result)
+"#;
+
+// A debug session with the adapter binary, driven like an editor would
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: i64,
+    events: VecDeque<Value>,
+}
+
+impl Session {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_eldritch-dap"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        Self {
+            stdin: child.stdin.take().unwrap(),
+            stdout: BufReader::new(child.stdout.take().unwrap()),
+            child,
+            seq: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    fn send(&mut self, command: &str, arguments: Value) -> i64 {
+        self.seq += 1;
+        let body = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        })
+        .to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        self.stdin.flush().unwrap();
+        self.seq
+    }
+
+    fn read(&mut self) -> Value {
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            assert!(
+                self.stdout.read_line(&mut header).unwrap() > 0,
+                "adapter exited"
+            );
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    // Sends a request and returns its response, keeping the events that arrive meanwhile
+    fn request(&mut self, command: &str, arguments: Value) -> Value {
+        let seq = self.send(command, arguments);
+        loop {
+            let message = self.read();
+            if message["type"] == "response" && message["request_seq"] == seq {
+                return message;
+            }
+            self.events.push_back(message);
+        }
+    }
+
+    fn body(&mut self, command: &str, arguments: Value) -> Value {
+        let response = self.request(command, arguments);
+        assert_eq!(response["success"], true, "{response}");
+        response["body"].clone()
+    }
+
+    fn event(&mut self, name: &str) -> Value {
+        loop {
+            let message = match self.events.pop_front() {
+                Some(message) => message,
+                None => self.read(),
+            };
+            if message["event"] == name {
+                return message["body"].clone();
+            }
+        }
+    }
+
+    fn launch(&mut self, program: &str, arguments: Value) {
+        self.body("initialize", json!({ "adapterID": "eldritch" }));
+        let mut arguments = arguments;
+        arguments["program"] = json!(program);
+        self.body("launch", arguments);
+        self.event("initialized");
+    }
+
+    fn top_frame(&mut self) -> Value {
+        let trace = self.body("stackTrace", json!({ "threadId": 1 }));
+        trace["stackFrames"][0].clone()
+    }
+
+    fn finish(mut self) {
+        self.body("disconnect", json!({}));
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+fn write_tome(dir: &tempfile::TempDir, source: &str) -> String {
+    let path = dir.path().join("main.eldritch");
+    std::fs::write(&path, source).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_breakpoints_stepping_and_inspection() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = write_tome(&dir, TOME);
+    let mut session = Session::start();
+    session.launch(&program, json!({}));
+
+    let breakpoints = session.body(
+        "setBreakpoints",
+        json!({ "source": { "path": program }, "breakpoints": [{ "line": 2 }] }),
+    );
+    assert_eq!(breakpoints["breakpoints"][0]["verified"], true);
+    session.body("configurationDone", json!({}));
+
+    assert_eq!(session.event("stopped")["reason"], "breakpoint");
+    let trace = session.body("stackTrace", json!({ "threadId": 1 }));
+    let frames = trace["stackFrames"].as_array().unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0]["name"], "add");
+    assert_eq!(frames[0]["line"], 2);
+    assert_eq!(frames[0]["source"]["path"], program);
+    assert_eq!(frames[1]["name"], "<module>");
+    assert_eq!(frames[1]["line"], 6);
+
+    let frame_id = frames[0]["id"].clone();
+    let scopes = session.body("scopes", json!({ "frameId": frame_id }));
+    assert_eq!(scopes["scopes"][0]["name"], "Locals");
+    assert_eq!(scopes["scopes"][1]["name"], "Globals");
+    let locals = session.body(
+        "variables",
+        json!({ "variablesReference": scopes["scopes"][0]["variablesReference"] }),
+    );
+    let locals: Vec<(&str, &str)> = locals["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| (v["name"].as_str().unwrap(), v["value"].as_str().unwrap()))
+        .collect();
+    assert_eq!(locals, [("a", "1"), ("b", "2")]);
+
+    // Globals hide builtins and expand containers
+    let globals = session.body(
+        "variables",
+        json!({ "variablesReference": scopes["scopes"][1]["variablesReference"] }),
+    );
+    let globals = globals["variables"].as_array().unwrap();
+    assert!(globals.iter().all(|v| v["name"] != "print"));
+    let items = globals.iter().find(|v| v["name"] == "items").unwrap();
+    assert_eq!(items["value"], "[1, 2]");
+    assert_eq!(items["type"], "list");
+    let children = session.body(
+        "variables",
+        json!({ "variablesReference": items["variablesReference"] }),
+    );
+    assert_eq!(children["variables"][1]["name"], "1");
+    assert_eq!(children["variables"][1]["value"], "2");
+
+    let result = session.body(
+        "evaluate",
+        json!({ "expression": "a * 10", "frameId": frame_id, "context": "repl" }),
+    );
+    assert_eq!(result["result"], "10");
+    let error = session.request("evaluate", json!({ "expression": "missing" }));
+    assert_eq!(error["success"], false);
+    assert!(error["message"].as_str().unwrap().contains("NameError"));
+
+    session.body("next", json!({ "threadId": 1 }));
+    assert_eq!(session.event("stopped")["reason"], "step");
+    assert_eq!(session.top_frame()["line"], 3);
+
+    session.body("stepOut", json!({ "threadId": 1 }));
+    assert_eq!(session.event("stopped")["reason"], "step");
+    let frame = session.top_frame();
+    assert_eq!(frame["name"], "<module>");
+    assert_eq!(frame["line"], 7);
+    let result = session.body("evaluate", json!({ "expression": "result" }));
+    assert_eq!(result["result"], "3");
+
+    session.body("continue", json!({ "threadId": 1 }));
+    let output = session.event("output");
+    assert_eq!(output["category"], "stdout");
+    assert_eq!(output["output"], "3\n");
+    assert_eq!(session.event("exited")["exitCode"], 0);
+    session.event("terminated");
+    session.finish();
+}
+
+#[test]
+fn test_stop_on_entry_step_in_and_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = write_tome(
+        &dir,
+        "def lookup(config):\n    return config[input_params['key']]\n\nlookup({})\n",
+    );
+    let mut session = Session::start();
+    session.launch(
+        &program,
+        json!({ "stopOnEntry": true, "params": { "key": "port" } }),
+    );
+    session.body("configurationDone", json!({}));
+
+    assert_eq!(session.event("stopped")["reason"], "entry");
+    assert_eq!(session.top_frame()["line"], 1);
+
+    session.body("next", json!({ "threadId": 1 }));
+    session.event("stopped");
+    assert_eq!(session.top_frame()["line"], 4);
+    session.body("stepIn", json!({ "threadId": 1 }));
+    session.event("stopped");
+    let frame = session.top_frame();
+    assert_eq!(frame["name"], "lookup");
+    assert_eq!(frame["line"], 2);
+
+    session.body("continue", json!({ "threadId": 1 }));
+    let output = session.event("output");
+    assert_eq!(output["category"], "stderr");
+    let output = output["output"].as_str().unwrap();
+    assert!(output.contains("KeyError"), "{output}");
+    assert!(output.contains("line 2, in lookup"), "{output}");
+    assert_eq!(session.event("exited")["exitCode"], 1);
+
+    // Requests for a tome that has exited fail instead of hanging
+    let response = session.request("stackTrace", json!({ "threadId": 1 }));
+    assert_eq!(response["success"], false);
+    session.finish();
+}
+
+#[test]
+fn test_disconnect_stops_a_paused_tome() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = write_tome(&dir, "while True:\n    pass\n");
+    let mut session = Session::start();
+    session.launch(&program, json!({}));
+    session.body("configurationDone", json!({}));
+
+    session.body("pause", json!({ "threadId": 1 }));
+    assert_eq!(session.event("stopped")["reason"], "pause");
+    session.finish();
+}
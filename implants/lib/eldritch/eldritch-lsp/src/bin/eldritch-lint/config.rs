@@ -0,0 +1,95 @@
+use anyhow::{bail, Context};
+use eldritch_lsp::linter::LintRegistry;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Which rules to run, read from a YAML file such as:
+///
+/// ```yaml
+/// disable:
+///   - unused-parameter
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Runs only these rules. All rules run if it is left out.
+    pub enable: Option<Vec<String>>,
+    /// Skips these rules, even if they are enabled.
+    #[serde(default)]
+    pub disable: Vec<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let yaml = std::fs::read_to_string(path)?;
+        Self::parse(&yaml)
+    }
+
+    pub fn parse(yaml: &str) -> anyhow::Result<Self> {
+        // An empty file is an empty config rather than an error
+        if yaml.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(yaml).context("invalid config")
+    }
+
+    /// Unregisters the rules this config turns off. Fails if it names a rule the registry
+    /// doesn't have, which is most likely a typo.
+    pub fn apply(&self, registry: &mut LintRegistry) -> anyhow::Result<()> {
+        let names = self.enable.iter().flatten().chain(&self.disable);
+        for name in names {
+            if !registry.rules().any(|rule| rule.name() == name) {
+                bail!("unknown rule '{}'", name);
+            }
+        }
+        registry.retain(|rule| {
+            let name = rule.name().to_string();
+            self.enable
+                .as_ref()
+                .is_none_or(|enable| enable.contains(&name))
+                && !self.disable.contains(&name)
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(registry: &LintRegistry) -> Vec<&'static str> {
+        registry.rules().map(|rule| rule.name()).collect()
+    }
+
+    #[test]
+    fn test_disable() {
+        let mut registry = LintRegistry::default();
+        let config = Config::parse("disable:\n  - unused-parameter\n  - wrong-arity\n").unwrap();
+        config.apply(&mut registry).unwrap();
+        let rules = rules(&registry);
+        assert!(!rules.contains(&"unused-parameter"));
+        assert!(!rules.contains(&"wrong-arity"));
+        assert!(rules.contains(&"undefined-name"));
+    }
+
+    #[test]
+    fn test_enable() {
+        let mut registry = LintRegistry::default();
+        let config = Config::parse(
+            "enable: [undefined-name, unused-variable]\ndisable: [unused-variable]\n",
+        )
+        .unwrap();
+        config.apply(&mut registry).unwrap();
+        assert_eq!(rules(&registry), vec!["undefined-name"]);
+    }
+
+    #[test]
+    fn test_unknown_rule() {
+        let mut registry = LintRegistry::default();
+        let config = Config::parse("disable: [unused-varaible]\n").unwrap();
+        let err = config.apply(&mut registry).unwrap_err();
+        assert_eq!(err.to_string(), "unknown rule 'unused-varaible'");
+        assert!(Config::parse("ignore: [unused-variable]\n").is_err());
+        assert!(Config::parse("").unwrap().enable.is_none());
+    }
+}
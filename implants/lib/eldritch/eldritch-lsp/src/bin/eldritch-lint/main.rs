@@ -0,0 +1,196 @@
+//! Runs the `eldritch-lsp` lint rules over directories of tomes, for CI.
+
+use clap::{Arg, ArgAction, Command};
+use eldritch_core::{Lexer, Parser};
+use eldritch_lsp::linter::{LintContext, LintRegistry};
+use eldritch_lsp::span_to_range;
+use eldritch_lsp::stdlib::StdlibIndex;
+use eldritch_lsp::symbols::SymbolTable;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use walkdir::WalkDir;
+
+mod config;
+mod output;
+
+use crate::config::Config;
+use crate::output::{FileReport, Format};
+
+const DEFAULT_CONFIG: &str = ".eldritch-lint.yml";
+const STDLIB_DIR: &str = "implants/lib/eldritch/stdlib";
+
+// Expand directories into the .eldritch files beneath them
+fn tome_files(paths: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        for entry in WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let is_tome = entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "eldritch");
+            if entry.file_type().is_file() && (is_tome || entry.depth() == 0) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files
+}
+
+// The stdlib crates of the repository the working directory is in, if any
+fn find_stdlib() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(STDLIB_DIR))
+        .find(|dir| dir.is_dir())
+}
+
+/// Lints one document: its syntax errors, then the rules over the statements the parser
+/// recovered.
+fn lint(
+    registry: &LintRegistry,
+    stdlib: &StdlibIndex,
+    path: &Path,
+    source: &str,
+) -> Vec<Diagnostic> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens();
+    let (ast, errors) = Parser::new(tokens).parse();
+
+    let mut diagnostics: Vec<Diagnostic> = errors
+        .into_iter()
+        .map(|err| Diagnostic {
+            range: span_to_range(source, err.span),
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NumberOrString::String("syntax-error".to_string())),
+            source: Some("eldritch-parser".to_string()),
+            message: err.message,
+            ..Default::default()
+        })
+        .collect();
+
+    let symbols = SymbolTable::build(source, &ast);
+    let context = LintContext::new(source, &ast, &symbols, stdlib).with_path(path);
+    diagnostics.extend(registry.run(&context));
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+fn main() {
+    let matches = Command::new("eldritch-lint")
+        .about("Lint Eldritch tomes")
+        .arg(
+            Arg::new("PATHS")
+                .help("Tome files or directories to lint")
+                .action(ArgAction::Append)
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["text", "json", "sarif"])
+                .default_value("text")
+                .help("Print diagnostics as rustc-style text, a JSON array or a SARIF log"),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Read the rules to enable and disable from FILE [default: .eldritch-lint.yml if present]"),
+        )
+        .arg(
+            Arg::new("stdlib")
+                .long("stdlib")
+                .value_name("DIR")
+                .help("Read library methods from the stdlib crates in DIR instead of the built-in ones [default: implants/lib/eldritch/stdlib above the working directory]"),
+        )
+        .arg(
+            Arg::new("deny-warnings")
+                .long("deny-warnings")
+                .action(ArgAction::SetTrue)
+                .help("Exit with an error on warnings too"),
+        )
+        .arg(
+            Arg::new("list-rules")
+                .long("list-rules")
+                .action(ArgAction::SetTrue)
+                .help("List the lint rules and exit"),
+        )
+        .get_matches();
+
+    let mut registry = LintRegistry::default();
+    if matches.get_flag("list-rules") {
+        for rule in registry.rules() {
+            println!("{:<18} {}", rule.name(), rule.description());
+        }
+        return;
+    }
+
+    let config = match matches.get_one::<String>("config") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(PathBuf::from(DEFAULT_CONFIG)).filter(|path| path.is_file()),
+    };
+    if let Some(path) = config {
+        if let Err(e) = Config::load(&path).and_then(|config| config.apply(&mut registry)) {
+            eprintln!("error: {}: {:#}", path.display(), e);
+            exit(2);
+        }
+    }
+
+    let mut stdlib = StdlibIndex::from_signatures(&eldritch::signatures());
+    let stdlib_dir = matches
+        .get_one::<String>("stdlib")
+        .map(PathBuf::from)
+        .or_else(find_stdlib);
+    if let Some(dir) = stdlib_dir {
+        stdlib.scan(dir);
+    }
+
+    let paths: Vec<String> = matches
+        .get_many::<String>("PATHS")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let mut reports = Vec::new();
+    for path in tome_files(&paths) {
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                exit(2);
+            }
+        };
+        let diagnostics = lint(&registry, &stdlib, &path, &source);
+        reports.push(FileReport {
+            path: path.display().to_string(),
+            source,
+            diagnostics,
+        });
+    }
+
+    let format: Format = matches
+        .get_one::<String>("format")
+        .and_then(|format| format.parse().ok())
+        .unwrap_or_default();
+    print!("{}", format.render(&reports, &registry));
+
+    let count = |severity| {
+        reports
+            .iter()
+            .flat_map(|report| &report.diagnostics)
+            .filter(|d| d.severity == Some(severity))
+            .count()
+    };
+    let errors = count(DiagnosticSeverity::ERROR);
+    let warnings = count(DiagnosticSeverity::WARNING);
+    if errors > 0 || (warnings > 0 && matches.get_flag("deny-warnings")) {
+        exit(1);
+    }
+}
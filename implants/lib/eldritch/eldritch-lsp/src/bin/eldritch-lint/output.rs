@@ -0,0 +1,272 @@
+use eldritch_lsp::linter::LintRegistry;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+/// The diagnostics of one linted file.
+pub struct FileReport {
+    pub path: String,
+    pub source: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// rustc-style messages with the offending source line, for people.
+    #[default]
+    Text,
+    /// One JSON array of every diagnostic, for scripts.
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning dashboards.
+    Sarif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            _ => Err(format!("unknown format '{}'", s)),
+        }
+    }
+}
+
+impl Format {
+    pub fn render(self, reports: &[FileReport], registry: &LintRegistry) -> String {
+        match self {
+            Format::Text => text(reports),
+            Format::Json => json_array(reports),
+            Format::Sarif => sarif(reports, registry),
+        }
+    }
+}
+
+fn code(diagnostic: &Diagnostic) -> &str {
+    match &diagnostic.code {
+        Some(NumberOrString::String(code)) => code,
+        _ => "",
+    }
+}
+
+fn severity(diagnostic: &Diagnostic) -> &'static str {
+    match diagnostic.severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "info",
+        _ => "hint",
+    }
+}
+
+fn text(reports: &[FileReport]) -> String {
+    let mut out = String::new();
+    let (mut errors, mut warnings) = (0, 0);
+    for report in reports {
+        let lines: Vec<&str> = report.source.lines().collect();
+        for d in &report.diagnostics {
+            match d.severity {
+                Some(DiagnosticSeverity::ERROR) => errors += 1,
+                Some(DiagnosticSeverity::WARNING) => warnings += 1,
+                _ => {}
+            }
+            let start = d.range.start;
+            let line = lines.get(start.line as usize).copied().unwrap_or_default();
+            let number = (start.line + 1).to_string();
+            let gutter = " ".repeat(number.len());
+
+            // Underline to the end of the span, or of the line if the span runs past it
+            let width = line.chars().count() as u32;
+            let end = if d.range.end.line == start.line {
+                d.range.end.character.min(width)
+            } else {
+                width
+            };
+            let carets = "^".repeat(end.saturating_sub(start.character).max(1) as usize);
+            let indent = " ".repeat(start.character.min(width) as usize);
+
+            let _ = writeln!(out, "{}[{}]: {}", severity(d), code(d), d.message);
+            let _ = writeln!(
+                out,
+                "{}--> {}:{}:{}",
+                gutter,
+                report.path,
+                start.line + 1,
+                start.character + 1
+            );
+            let _ = writeln!(out, "{} |", gutter);
+            let _ = writeln!(out, "{} | {}", number, line);
+            let _ = writeln!(out, "{} | {}{}", gutter, indent, carets);
+            let _ = writeln!(out);
+        }
+    }
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+    let _ = writeln!(
+        out,
+        "{}: {}, {}",
+        plural(reports.len(), "file"),
+        plural(errors, "error"),
+        plural(warnings, "warning")
+    );
+    out
+}
+
+fn json_array(reports: &[FileReport]) -> String {
+    let diagnostics: Vec<Value> = reports
+        .iter()
+        .flat_map(|report| {
+            report.diagnostics.iter().map(|d| {
+                json!({
+                    "file": report.path,
+                    "line": d.range.start.line + 1,
+                    "column": d.range.start.character + 1,
+                    "end_line": d.range.end.line + 1,
+                    "end_column": d.range.end.character + 1,
+                    "severity": severity(d),
+                    "code": code(d),
+                    "message": d.message,
+                })
+            })
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&diagnostics).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn sarif(reports: &[FileReport], registry: &LintRegistry) -> String {
+    let mut rules = vec![json!({
+        "id": "syntax-error",
+        "shortDescription": { "text": "Code that does not parse" },
+    })];
+    rules.extend(registry.rules().map(|rule| {
+        json!({
+            "id": rule.name(),
+            "shortDescription": { "text": rule.description() },
+        })
+    }));
+
+    let results: Vec<Value> = reports
+        .iter()
+        .flat_map(|report| {
+            let uri = report.path.replace('\\', "/");
+            report.diagnostics.iter().map(move |d| {
+                let level = match d.severity {
+                    Some(DiagnosticSeverity::ERROR) => "error",
+                    Some(DiagnosticSeverity::WARNING) => "warning",
+                    _ => "note",
+                };
+                json!({
+                    "ruleId": code(d),
+                    "level": level,
+                    "message": { "text": d.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri },
+                            "region": {
+                                "startLine": d.range.start.line + 1,
+                                "startColumn": d.range.start.character + 1,
+                                "endLine": d.range.end.line + 1,
+                                "endColumn": d.range.end.character + 1,
+                            },
+                        },
+                    }],
+                })
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "eldritch-lint",
+                    "informationUri": "https://docs.realm.pub/user-guide/eldritch",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    let mut out = serde_json::to_string_pretty(&log).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    fn report() -> FileReport {
+        FileReport {
+            path: "tomes/ls/main.eldritch".to_string(),
+            source: "def f():\n    data = 1\n".to_string(),
+            diagnostics: vec![Diagnostic {
+                range: Range {
+                    start: Position {
+                        line: 1,
+                        character: 4,
+                    },
+                    end: Position {
+                        line: 1,
+                        character: 8,
+                    },
+                },
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("unused-variable".to_string())),
+                message: "Unused variable 'data'".to_string(),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            Format::Text.render(&[report()], &LintRegistry::default()),
+            "warning[unused-variable]: Unused variable 'data'\n \
+             --> tomes/ls/main.eldritch:2:5\n  |\n2 |     data = 1\n  |     ^^^^\n\n\
+             1 file: 0 errors, 1 warning\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let out = Format::Json.render(&[report()], &LintRegistry::default());
+        let value: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(
+            value,
+            json!([{
+                "file": "tomes/ls/main.eldritch",
+                "line": 2,
+                "column": 5,
+                "end_line": 2,
+                "end_column": 9,
+                "severity": "warning",
+                "code": "unused-variable",
+                "message": "Unused variable 'data'",
+            }])
+        );
+    }
+
+    #[test]
+    fn test_sarif() {
+        let out = Format::Sarif.render(&[report()], &LintRegistry::default());
+        let value: Value = serde_json::from_str(&out).unwrap();
+        let run = &value["runs"][0];
+        assert_eq!(value["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "syntax-error");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "undefined-name");
+        assert_eq!(run["results"][0]["ruleId"], "unused-variable");
+        assert_eq!(run["results"][0]["level"], "warning");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            5
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use eldritch_core::{shift_spans, Lexer, Parser, Span, Stmt};
+use std::collections::HashMap;
+use tower_lsp::lsp_types::TextDocumentContentChangeEvent;
+
+use crate::position_to_offset;
+
+/// A syntax error, with its span in the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub span: Span,
+    pub message: String,
+}
+
+/// The source of one top-level statement and what it parsed to, with spans relative to
+/// the start of `text`.
+#[derive(Debug)]
+struct Chunk {
+    text: String,
+    stmts: Vec<Stmt>,
+    errors: Vec<SyntaxError>,
+}
+
+impl Chunk {
+    fn parse(text: &str) -> Self {
+        let tokens = Lexer::new(text.to_string()).scan_tokens();
+        let (stmts, errors) = Parser::new(tokens).parse();
+        Self {
+            text: text.to_string(),
+            stmts,
+            errors: errors
+                .into_iter()
+                .map(|e| SyntaxError {
+                    span: e.span,
+                    message: e.message,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// An open document and its statements, kept parsed between edits.
+///
+/// The text is split into its top-level statements, and an edit only re-parses the
+/// statements whose text it changed. The rest are reused with their spans moved.
+#[derive(Debug)]
+pub struct Document {
+    /// The version the client last sent.
+    pub version: i32,
+    text: String,
+    chunks: Vec<Chunk>,
+    ast: Vec<Stmt>,
+    errors: Vec<SyntaxError>,
+    reparsed: usize,
+}
+
+impl Document {
+    pub fn new(text: String, version: i32) -> Self {
+        let mut document = Self {
+            version,
+            text,
+            chunks: Vec::new(),
+            ast: Vec::new(),
+            errors: Vec::new(),
+            reparsed: 0,
+        };
+        document.reparse();
+        document
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The statements parsed from the document, including those recovered around syntax
+    /// errors. Spans are character offsets into [`Document::text`].
+    pub fn ast(&self) -> &[Stmt] {
+        &self.ast
+    }
+
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+
+    /// How many top-level statements the last update had to parse.
+    pub fn reparsed(&self) -> usize {
+        self.reparsed
+    }
+
+    /// Applies the changes of a `textDocument/didChange` in order. A change without a
+    /// range replaces the whole text.
+    pub fn apply_changes(&mut self, changes: Vec<TextDocumentContentChangeEvent>, version: i32) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start =
+                        byte_offset(&self.text, position_to_offset(&self.text, range.start));
+                    let end = byte_offset(&self.text, position_to_offset(&self.text, range.end));
+                    self.text.replace_range(start..end.max(start), &change.text);
+                }
+                None => self.text = change.text,
+            }
+        }
+        self.version = version;
+        self.reparse();
+    }
+
+    fn reparse(&mut self) {
+        let mut previous: HashMap<String, Vec<Chunk>> = HashMap::new();
+        for chunk in self.chunks.drain(..).rev() {
+            previous.entry(chunk.text.clone()).or_default().push(chunk);
+        }
+
+        self.reparsed = 0;
+        self.ast.clear();
+        self.errors.clear();
+        let (mut offset, mut line) = (0, 0);
+        for text in split_statements(&self.text) {
+            let chunk = match previous.get_mut(text).and_then(Vec::pop) {
+                Some(chunk) => chunk,
+                None => {
+                    self.reparsed += 1;
+                    Chunk::parse(text)
+                }
+            };
+
+            let mut stmts = chunk.stmts.clone();
+            shift_spans(&mut stmts, offset, line);
+            self.ast.extend(stmts);
+            self.errors.extend(chunk.errors.iter().map(|e| SyntaxError {
+                span: Span::new(
+                    e.span.start + offset,
+                    e.span.end + offset,
+                    e.span.line + line,
+                ),
+                message: e.message.clone(),
+            }));
+
+            offset += text.chars().count();
+            line += text.matches('\n').count();
+            self.chunks.push(chunk);
+        }
+    }
+}
+
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// Splits `text` where each top-level statement starts, so each piece parses on its own.
+/// Blank and comment lines stay with the statement before them, and `elif`, `else`,
+/// `except` and `finally` clauses with the statement they belong to. Lines inside
+/// brackets, strings or after a `\` continue the statement they are part of.
+pub fn split_statements(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    // The quote and whether it is tripled, while inside a string
+    let mut string: Option<(char, bool)> = None;
+    let mut continued = false;
+    let mut line_start = 0;
+
+    for line in text.split_inclusive('\n') {
+        let continues = depth > 0 || string.is_some() || continued;
+        let starts_statement = line
+            .chars()
+            .next()
+            .is_some_and(|c| !c.is_whitespace() && c != '#');
+        if !continues && starts_statement && !is_clause(line) && line_start > start {
+            pieces.push(&text[start..line_start]);
+            start = line_start;
+        }
+
+        continued = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            let tripled = {
+                let mut rest = chars.clone();
+                rest.next() == Some(c) && rest.next() == Some(c)
+            };
+            match string {
+                Some((quote, triple)) => match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '\n' if !triple => string = None,
+                    c if c == quote && (!triple || tripled) => {
+                        if triple {
+                            chars.nth(1);
+                        }
+                        string = None;
+                    }
+                    _ => {}
+                },
+                None => match c {
+                    '#' => break,
+                    '"' | '\'' => {
+                        if tripled {
+                            chars.nth(1);
+                        }
+                        string = Some((c, tripled));
+                    }
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                    '\\' if matches!(chars.peek(), Some('\n') | Some('\r')) => continued = true,
+                    _ => {}
+                },
+            }
+        }
+        line_start += line.len();
+    }
+    if start < text.len() || pieces.is_empty() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+// Whether a line continues the statement above it, like `else:` does an `if`
+fn is_clause(line: &str) -> bool {
+    ["elif", "else", "except", "finally"].iter().any(|keyword| {
+        line.strip_prefix(keyword)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| !c.is_alphanumeric() && c != '_')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    fn full_parse(text: &str) -> (Vec<Stmt>, Vec<SyntaxError>) {
+        let chunk = Chunk::parse(text);
+        (chunk.stmts, chunk.errors)
+    }
+
+    fn edit(
+        line: u32,
+        character: u32,
+        end_character: u32,
+        text: &str,
+    ) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position { line, character },
+                end: Position {
+                    line,
+                    character: end_character,
+                },
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    const SOURCE: &str = "# Lists files\nroot = \"/tmp\"\n\ndef walk(path,\n         depth=1):\n    for f in file.list(path):\n        print(f)\n    return '''done\nwalking'''\n\nif root:\n    walk(root)\nelse:\n    fail(\"no root\")\nwalk(root)\n";
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(
+            split_statements(SOURCE),
+            vec![
+                "# Lists files\n",
+                "root = \"/tmp\"\n\n",
+                "def walk(path,\n         depth=1):\n    for f in file.list(path):\n        print(f)\n    return '''done\nwalking'''\n\n",
+                "if root:\n    walk(root)\nelse:\n    fail(\"no root\")\n",
+                "walk(root)\n",
+            ]
+        );
+        assert_eq!(
+            split_statements("x = 1 + \\\n2\ny = '#('\n"),
+            vec!["x = 1 + \\\n2\n", "y = '#('\n"]
+        );
+        assert_eq!(split_statements(""), vec![""]);
+    }
+
+    #[test]
+    fn test_edits_reparse_only_changed_statements() {
+        let mut document = Document::new(SOURCE.to_string(), 1);
+        assert_eq!(document.reparsed(), 5);
+        assert!(document.errors().is_empty());
+
+        // Rename the loop variable inside `walk`
+        document.apply_changes(vec![edit(5, 8, 9, "entry"), edit(6, 14, 15, "entry")], 2);
+        assert_eq!(document.version, 2);
+        assert_eq!(document.reparsed(), 1);
+        let (ast, _) = full_parse(document.text());
+        assert_eq!(format!("{:?}", document.ast()), format!("{:?}", ast));
+
+        // A new statement at the top moves every statement below it, and takes the
+        // comment that followed it
+        document.apply_changes(vec![edit(0, 0, 0, "limit = 3\n")], 3);
+        assert_eq!(document.reparsed(), 1);
+        let (ast, _) = full_parse(document.text());
+        assert_eq!(format!("{:?}", document.ast()), format!("{:?}", ast));
+    }
+
+    #[test]
+    fn test_syntax_errors_are_positioned_in_the_document() {
+        let mut document = Document::new(SOURCE.to_string(), 1);
+        document.apply_changes(vec![edit(14, 10, 10, " +")], 2);
+        let (_, errors) = full_parse(document.text());
+        assert!(!errors.is_empty());
+        assert_eq!(document.errors(), &errors[..]);
+    }
+
+    #[test]
+    fn test_full_text_change() {
+        let mut document = Document::new(SOURCE.to_string(), 1);
+        document.apply_changes(
+            vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "x = 1\n".to_string(),
+            }],
+            2,
+        );
+        assert_eq!(document.text(), "x = 1\n");
+        assert_eq!(document.ast().len(), 1);
+    }
+}
@@ -0,0 +1,57 @@
+//! Static analysis of Eldritch documents, shared by the `eldritch-lsp` language server and
+//! the `eldritch-lint` command line linter.
+
+pub mod document;
+pub mod linter;
+pub mod signature;
+pub mod stdlib;
+pub mod symbols;
+pub mod workspace;
+
+use eldritch_core::Span;
+use tower_lsp::lsp_types::{Position, Range};
+
+/// Converts a character offset into an LSP line/character position.
+pub fn offset_to_position(text: &str, offset: usize) -> Position {
+    let mut line = 0;
+    let mut character = 0;
+    for c in text.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += 1;
+        }
+    }
+    Position { line, character }
+}
+
+/// Converts an LSP line/character position into a character offset.
+pub fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut line = 0;
+    let mut character = 0;
+    for (offset, c) in text.chars().enumerate() {
+        if line == position.line && character == position.character {
+            return offset;
+        }
+        if c == '\n' {
+            if line == position.line {
+                // Past the end of the line
+                return offset;
+            }
+            line += 1;
+            character = 0;
+        } else {
+            character += 1;
+        }
+    }
+    text.chars().count()
+}
+
+/// Converts a span of character offsets into an LSP range.
+pub fn span_to_range(text: &str, span: Span) -> Range {
+    Range {
+        start: offset_to_position(text, span.start),
+        end: offset_to_position(text, span.end),
+    }
+}
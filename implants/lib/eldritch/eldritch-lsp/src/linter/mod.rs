@@ -0,0 +1,271 @@
+mod rules;
+
+use crate::stdlib::StdlibIndex;
+use crate::symbols::SymbolTable;
+use eldritch_core::{Lexer, Span, Stmt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString};
+
+pub use rules::{
+    ShadowedBuiltin, UndeclaredParam, UndefinedName, UnknownMethod, UnreachableCode,
+    UnusedParameter, UnusedVariable, WrongArity,
+};
+
+/// Everything a rule may inspect about one document.
+pub struct LintContext<'a> {
+    pub source: &'a str,
+    /// The parsed AST, including the statements recovered around syntax errors.
+    pub ast: &'a [Stmt],
+    pub symbols: &'a SymbolTable,
+    pub stdlib: &'a StdlibIndex,
+    /// The `paramdefs` names from the tome's metadata.yml, if the document has one next to it.
+    pub paramdefs: Option<Vec<String>>,
+    /// Whether the document is a `*_test.eldritch` file, which `golem test` runs with the
+    /// `tome` library.
+    pub is_test: bool,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn new(
+        source: &'a str,
+        ast: &'a [Stmt],
+        symbols: &'a SymbolTable,
+        stdlib: &'a StdlibIndex,
+    ) -> Self {
+        Self {
+            source,
+            ast,
+            symbols,
+            stdlib,
+            paramdefs: None,
+            is_test: false,
+        }
+    }
+
+    /// Fills in what the document's location says about it: the tome metadata next to it
+    /// and whether it is a tome test.
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.paramdefs = path.parent().and_then(paramdefs);
+        self.is_test = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with("_test.eldritch"));
+        self
+    }
+
+    /// A diagnostic from `rule` covering `span`.
+    pub fn diagnostic(
+        &self,
+        rule: &dyn LintRule,
+        span: Span,
+        severity: DiagnosticSeverity,
+        message: String,
+    ) -> Diagnostic {
+        Diagnostic {
+            range: crate::span_to_range(self.source, span),
+            severity: Some(severity),
+            code: Some(NumberOrString::String(rule.name().to_string())),
+            source: Some("eldritch-lint".to_string()),
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// Like [`LintContext::diagnostic`], for code that can be removed; editors fade it out.
+    pub fn unnecessary(&self, rule: &dyn LintRule, span: Span, message: String) -> Diagnostic {
+        Diagnostic {
+            tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+            ..self.diagnostic(rule, span, DiagnosticSeverity::WARNING, message)
+        }
+    }
+}
+
+/// A trait defining a single linting rule.
+///
+/// Rules follow a two-phase check for performance:
+/// 1. `should_lint`: A lightweight string check.
+/// 2. `check`: A deeper AST or full-text analysis.
+pub trait LintRule: Send + Sync {
+    /// Returns the unique name of the rule (e.g., "unused-variable"). It is also the code of
+    /// the diagnostics the rule reports, and what `# eldritch: ignore[...]` comments name.
+    fn name(&self) -> &'static str;
+
+    /// A one line summary of what the rule reports, e.g. for `eldritch-lint --list-rules`.
+    fn description(&self) -> &'static str;
+
+    /// Quickly determines if this rule is relevant for the given source code.
+    /// This optimization prevents expensive AST traversals for rules that definitely won't match.
+    fn should_lint(&self, _source: &str) -> bool {
+        // Default to true for safety, but implementations should override this
+        // with fast checks (e.g., source.contains("forbidden"))
+        true
+    }
+
+    /// Runs the linting logic.
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic>;
+}
+
+/// Registry to hold and manage all active lint rules.
+pub struct LintRegistry {
+    rules: Vec<Box<dyn LintRule>>,
+}
+
+impl LintRegistry {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn register<R: LintRule + 'static>(&mut self, rule: R) {
+        self.rules.push(Box::new(rule));
+    }
+
+    /// The registered rules, in the order they run.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    /// Unregisters the rules for which `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&dyn LintRule) -> bool) {
+        self.rules.retain(|rule| keep(rule.as_ref()));
+    }
+
+    /// Runs all registered rules against the document, dropping the diagnostics that are
+    /// suppressed on their line.
+    pub fn run(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for rule in &self.rules {
+            if rule.should_lint(cx.source) {
+                diagnostics.extend(rule.check(cx));
+            }
+        }
+
+        let suppressions = suppressions(cx.source);
+        diagnostics.retain(|d| {
+            let code = match &d.code {
+                Some(NumberOrString::String(code)) => code.as_str(),
+                _ => "",
+            };
+            match suppressions.get(&(d.range.start.line as usize + 1)) {
+                Some(None) => false,
+                Some(Some(codes)) => !codes.iter().any(|c| c == code),
+                None => true,
+            }
+        });
+        diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+        diagnostics
+    }
+}
+
+impl Default for LintRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(UndefinedName::new());
+        registry.register(UnusedVariable);
+        registry.register(UnusedParameter);
+        registry.register(UnreachableCode);
+        registry.register(ShadowedBuiltin::new());
+        registry.register(UnknownMethod);
+        registry.register(WrongArity);
+        registry.register(UndeclaredParam);
+        registry
+    }
+}
+
+const IGNORE: &str = "# eldritch: ignore";
+
+// The codes suppressed on each line by a trailing `# eldritch: ignore[code, ...]` comment;
+// `None` if a bare `# eldritch: ignore` suppresses every code
+fn suppressions(source: &str) -> HashMap<usize, Option<Vec<String>>> {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.scan_tokens();
+    lexer
+        .comments()
+        .iter()
+        .filter_map(|comment| {
+            let rest = comment.text.strip_prefix(IGNORE)?;
+            let codes = rest.strip_prefix('[').and_then(|r| r.split_once(']'));
+            let codes = codes.map(|(codes, _)| {
+                codes
+                    .split(',')
+                    .map(|code| code.trim().to_string())
+                    .collect()
+            });
+            if codes.is_none() && !rest.trim().is_empty() {
+                return None;
+            }
+            Some((comment.span.line, codes))
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    paramdefs: Vec<ParamDef>,
+}
+
+#[derive(Deserialize)]
+struct ParamDef {
+    name: String,
+}
+
+/// The `paramdefs` names declared by the metadata.yml in `tome_dir`, if there is a readable one.
+pub fn paramdefs(tome_dir: &Path) -> Option<Vec<String>> {
+    let yaml = std::fs::read_to_string(tome_dir.join("metadata.yml")).ok()?;
+    let metadata: Metadata = serde_yaml::from_str(&yaml).ok()?;
+    Some(metadata.paramdefs.into_iter().map(|p| p.name).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eldritch_core::Parser;
+
+    pub(super) fn lint(registry: &LintRegistry, source: &str) -> Vec<(u32, String)> {
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (ast, _) = Parser::new(tokens).parse();
+        let symbols = SymbolTable::build(source, &ast);
+        let stdlib = StdlibIndex::new();
+        let cx = LintContext::new(source, &ast, &symbols, &stdlib);
+        registry
+            .run(&cx)
+            .into_iter()
+            .map(|d| {
+                let Some(NumberOrString::String(code)) = d.code else {
+                    panic!("diagnostic without a code");
+                };
+                (d.range.start.line + 1, code)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let source = "def f(a, b):\n    x = y  # eldritch: ignore[unused-variable]\n    z = 1  # eldritch: ignore\n    return w  # eldritch: ignore[unused-parameter, undefined-name]\n";
+        assert_eq!(
+            lint(&LintRegistry::default(), source),
+            vec![
+                (1, "unused-parameter".to_string()),
+                (1, "unused-parameter".to_string()),
+                (2, "undefined-name".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paramdefs() {
+        let dir = std::env::temp_dir().join(format!("eldritch-lint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("metadata.yml"),
+            "name: List files\nparamdefs:\n- name: path\n  type: string\n",
+        )
+        .unwrap();
+        assert_eq!(paramdefs(&dir), Some(vec!["path".to_string()]));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(paramdefs(&dir), None);
+    }
+}
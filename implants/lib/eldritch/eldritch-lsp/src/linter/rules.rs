@@ -0,0 +1,541 @@
+use super::{LintContext, LintRule};
+use crate::symbols::SymbolKind;
+use eldritch_core::introspection::find_best_match;
+use eldritch_core::{
+    walk_exprs, Argument, Expr, ExprKind, Interpreter, NoopPrinter, Span, Stmt, StmtKind, Value,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Globals the agent defines for every tome.
+const HOST_GLOBALS: &[&str] = &["input_params"];
+
+// Names of the builtin functions, which a fresh interpreter defines
+fn builtin_names() -> HashSet<String> {
+    let interp = Interpreter::new_with_printer(Arc::new(NoopPrinter));
+    let names = interp.env.read().values.keys().cloned().collect();
+    names
+}
+
+// `lib` in `lib.method`, when `lib` is a library rather than a user variable of that name
+fn library<'e>(cx: &LintContext, obj: &'e Expr) -> Option<&'e str> {
+    let ExprKind::Identifier(name) = &obj.kind else {
+        return None;
+    };
+    let shadowed = cx
+        .symbols
+        .references
+        .iter()
+        .any(|(span, _)| *span == obj.span);
+    (!shadowed && cx.stdlib.is_library(name)).then_some(name.as_str())
+}
+
+/// `undefined-name`: a name that is neither defined by the tome nor a builtin, library or
+/// host global. Reading it fails with a `NameError`.
+pub struct UndefinedName {
+    builtins: HashSet<String>,
+}
+
+impl UndefinedName {
+    pub fn new() -> Self {
+        Self {
+            builtins: builtin_names(),
+        }
+    }
+}
+
+impl Default for UndefinedName {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LintRule for UndefinedName {
+    fn name(&self) -> &'static str {
+        "undefined-name"
+    }
+
+    fn description(&self) -> &'static str {
+        "Names that are not defined by the tome, a builtin, a library or the agent"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.symbols
+            .unresolved
+            .iter()
+            .filter(|(_, name)| {
+                !self.builtins.contains(name)
+                    && !cx.stdlib.is_library(name)
+                    && !HOST_GLOBALS.contains(&name.as_str())
+                    && (!cx.is_test || name != "tome")
+            })
+            .map(|(span, name)| {
+                cx.diagnostic(
+                    self,
+                    *span,
+                    DiagnosticSeverity::ERROR,
+                    format!("name '{}' is not defined", name),
+                )
+            })
+            .collect()
+    }
+}
+
+/// `unused-variable`: a local variable of a function that is never read. Names starting
+/// with `_` are exempt.
+pub struct UnusedVariable;
+
+impl LintRule for UnusedVariable {
+    fn name(&self) -> &'static str {
+        "unused-variable"
+    }
+
+    fn description(&self) -> &'static str {
+        "Local variables of a function that are never read"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        unused(cx, self, SymbolKind::Variable, "local variable")
+    }
+}
+
+/// `unused-parameter`: a function parameter that is never read. Names starting with `_`
+/// are exempt.
+pub struct UnusedParameter;
+
+impl LintRule for UnusedParameter {
+    fn name(&self) -> &'static str {
+        "unused-parameter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Function parameters that are never read"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        unused(cx, self, SymbolKind::Parameter, "parameter")
+    }
+}
+
+// Module level variables aren't reported, as other tomes may `load()` them
+fn unused(cx: &LintContext, rule: &dyn LintRule, kind: SymbolKind, what: &str) -> Vec<Diagnostic> {
+    let mut used = vec![false; cx.symbols.symbols.len()];
+    for (_, id) in &cx.symbols.references {
+        used[*id] = true;
+    }
+    cx.symbols
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(id, s)| {
+            s.kind == kind && s.parent.is_some() && !used[*id] && !s.name.starts_with('_')
+        })
+        .map(|(_, s)| cx.unnecessary(rule, s.span, format!("{} '{}' is never used", what, s.name)))
+        .collect()
+}
+
+/// `unreachable-code`: statements after a `return`, `break` or `continue`, or after an
+/// `if`/`else` whose branches all end in one.
+pub struct UnreachableCode;
+
+impl UnreachableCode {
+    fn block(&self, cx: &LintContext, stmts: &[Stmt], diagnostics: &mut Vec<Diagnostic>) {
+        for (idx, stmt) in stmts.iter().enumerate() {
+            self.nested(cx, stmt, diagnostics);
+            if exits(stmt) {
+                if let (Some(first), Some(last)) = (stmts.get(idx + 1), stmts.last()) {
+                    let span = Span::new(first.span.start, last.span.end, first.span.line);
+                    diagnostics.push(cx.unnecessary(self, span, "code is unreachable".to_string()));
+                }
+                return;
+            }
+        }
+    }
+
+    fn nested(&self, cx: &LintContext, stmt: &Stmt, diagnostics: &mut Vec<Diagnostic>) {
+        match &stmt.kind {
+            StmtKind::If(_, then_branch, else_branch)
+            | StmtKind::While(_, then_branch, else_branch) => {
+                self.block(cx, then_branch, diagnostics);
+                if let Some(else_branch) = else_branch {
+                    self.block(cx, else_branch, diagnostics);
+                }
+            }
+            StmtKind::Def(_, _, _, body) | StmtKind::For(_, _, body) => {
+                self.block(cx, body, diagnostics)
+            }
+            StmtKind::Try(body, handlers, finally) => {
+                self.block(cx, body, diagnostics);
+                for handler in handlers {
+                    self.block(cx, &handler.body, diagnostics);
+                }
+                if let Some(finally) = finally {
+                    self.block(cx, finally, diagnostics);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Whether the code after `stmt` in its block can never run
+fn exits(stmt: &Stmt) -> bool {
+    match &stmt.kind {
+        StmtKind::Return(_) | StmtKind::Break | StmtKind::Continue => true,
+        StmtKind::If(_, then_branch, Some(else_branch)) => {
+            then_branch.iter().any(exits) && else_branch.iter().any(exits)
+        }
+        _ => false,
+    }
+}
+
+impl LintRule for UnreachableCode {
+    fn name(&self) -> &'static str {
+        "unreachable-code"
+    }
+
+    fn description(&self) -> &'static str {
+        "Statements after a return, break or continue"
+    }
+
+    fn should_lint(&self, source: &str) -> bool {
+        source.contains("return") || source.contains("break") || source.contains("continue")
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        self.block(cx, cx.ast, &mut diagnostics);
+        diagnostics
+    }
+}
+
+/// `shadowed-builtin`: a definition that hides a builtin function or a library, such as a
+/// loop variable called `file`, after which `file.read()` no longer reaches the library.
+pub struct ShadowedBuiltin {
+    builtins: HashSet<String>,
+}
+
+impl ShadowedBuiltin {
+    pub fn new() -> Self {
+        Self {
+            builtins: builtin_names(),
+        }
+    }
+}
+
+impl Default for ShadowedBuiltin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LintRule for ShadowedBuiltin {
+    fn name(&self) -> &'static str {
+        "shadowed-builtin"
+    }
+
+    fn description(&self) -> &'static str {
+        "Definitions that hide a builtin function or a library"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.symbols
+            .symbols
+            .iter()
+            .filter_map(|s| {
+                let message = if cx.stdlib.is_library(&s.name) {
+                    format!("'{}' shadows the {} library", s.name, s.name)
+                } else if self.builtins.contains(&s.name) {
+                    format!("'{}' shadows the builtin {}()", s.name, s.name)
+                } else {
+                    return None;
+                };
+                Some(cx.diagnostic(self, s.span, DiagnosticSeverity::WARNING, message))
+            })
+            .collect()
+    }
+}
+
+/// `unknown-method`: a library method that the library doesn't define, e.g. `file.reed`.
+pub struct UnknownMethod;
+
+impl LintRule for UnknownMethod {
+    fn name(&self) -> &'static str {
+        "unknown-method"
+    }
+
+    fn description(&self) -> &'static str {
+        "Library methods that the library does not define"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        walk_exprs(cx.ast, &mut |expr| {
+            let ExprKind::GetAttr(obj, method) = &expr.kind else {
+                return;
+            };
+            let Some(lib) = library(cx, obj) else {
+                return;
+            };
+            // Libraries missing from the index can't be checked
+            let Some(methods) = cx.stdlib.methods.get(lib) else {
+                return;
+            };
+            if methods.iter().any(|m| &m.name == method) {
+                return;
+            }
+            let names: Vec<String> = methods.iter().map(|m| m.name.clone()).collect();
+            let mut message = format!("library '{}' has no method '{}'", lib, method);
+            if let Some(suggestion) = find_best_match(method, &names) {
+                message.push_str(&format!("; did you mean '{}'?", suggestion));
+            }
+            diagnostics.push(cx.diagnostic(self, expr.span, DiagnosticSeverity::ERROR, message));
+        });
+        diagnostics
+    }
+}
+
+/// `wrong-arity`: a library method called with too many or too few arguments, or with a
+/// keyword it doesn't take.
+pub struct WrongArity;
+
+impl LintRule for WrongArity {
+    fn name(&self) -> &'static str {
+        "wrong-arity"
+    }
+
+    fn description(&self) -> &'static str {
+        "Library methods called with missing, extra or unknown arguments"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        walk_exprs(cx.ast, &mut |expr| {
+            let ExprKind::Call(callee, args) = &expr.kind else {
+                return;
+            };
+            let ExprKind::GetAttr(obj, name) = &callee.kind else {
+                return;
+            };
+            let Some(method) = library(cx, obj).and_then(|lib| cx.stdlib.method(lib, name)) else {
+                return;
+            };
+
+            let mut positional = 0;
+            let mut keywords = Vec::new();
+            for arg in args {
+                match arg {
+                    Argument::Positional(_) => positional += 1,
+                    Argument::Keyword(name, _) => keywords.push(name),
+                    // The arguments can't be counted
+                    Argument::StarArgs(_) | Argument::KwArgs(_) => return,
+                }
+            }
+
+            let callee = format!("{}.{}()", obj_name(obj), name);
+            let problem = if positional > method.args.len() {
+                let plural = if method.args.len() == 1 { "" } else { "s" };
+                Some(format!(
+                    "{} takes {} argument{} but {} were given",
+                    callee,
+                    method.args.len(),
+                    plural,
+                    positional
+                ))
+            } else if let Some(keyword) = keywords
+                .iter()
+                .find(|k| !method.args.iter().any(|(arg, _)| arg == **k))
+            {
+                Some(format!(
+                    "{} got an unexpected keyword argument '{}'",
+                    callee, keyword
+                ))
+            } else {
+                method
+                    .args
+                    .iter()
+                    .enumerate()
+                    .find(|(idx, (arg, optional))| {
+                        !optional && *idx >= positional && !keywords.contains(&arg)
+                    })
+                    .map(|(_, (arg, _))| format!("{} missing required argument '{}'", callee, arg))
+            };
+            if let Some(message) = problem {
+                diagnostics.push(cx.diagnostic(
+                    self,
+                    expr.span,
+                    DiagnosticSeverity::ERROR,
+                    message,
+                ));
+            }
+        });
+        diagnostics
+    }
+}
+
+fn obj_name(obj: &Expr) -> &str {
+    match &obj.kind {
+        ExprKind::Identifier(name) => name,
+        _ => "",
+    }
+}
+
+/// `undeclared-param`: an `input_params["name"]` or `input_params.get("name")` key that the
+/// `paramdefs` of the tome's metadata.yml don't declare. Tavern never sets such a key.
+pub struct UndeclaredParam;
+
+impl LintRule for UndeclaredParam {
+    fn name(&self) -> &'static str {
+        "undeclared-param"
+    }
+
+    fn description(&self) -> &'static str {
+        "input_params keys that the tome's metadata.yml does not declare"
+    }
+
+    fn should_lint(&self, source: &str) -> bool {
+        source.contains("input_params")
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let Some(paramdefs) = &cx.paramdefs else {
+            return Vec::new();
+        };
+        let is_params =
+            |expr: &Expr| matches!(&expr.kind, ExprKind::Identifier(n) if n == "input_params");
+
+        let mut diagnostics = Vec::new();
+        walk_exprs(cx.ast, &mut |expr| {
+            let key = match &expr.kind {
+                ExprKind::Index(obj, key) if is_params(obj) => key,
+                ExprKind::Call(callee, args) => match (&callee.kind, args.first()) {
+                    (ExprKind::GetAttr(obj, method), Some(Argument::Positional(key)))
+                        if method == "get" && is_params(obj) =>
+                    {
+                        key
+                    }
+                    _ => return,
+                },
+                _ => return,
+            };
+            if let ExprKind::Literal(Value::String(name)) = &key.kind {
+                if !paramdefs.contains(name) {
+                    diagnostics.push(cx.diagnostic(
+                        self,
+                        expr.span,
+                        DiagnosticSeverity::ERROR,
+                        format!("input_params[\"{}\"] is not declared in metadata.yml", name),
+                    ));
+                }
+            }
+        });
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::lint;
+    use super::super::{LintContext, LintRegistry};
+    use super::*;
+    use crate::stdlib::{parse_library, StdlibIndex};
+    use crate::symbols::SymbolTable;
+    use eldritch_core::{Lexer, Parser};
+
+    fn registry(rule: impl LintRule + 'static) -> LintRegistry {
+        let mut registry = LintRegistry::new();
+        registry.register(rule);
+        registry
+    }
+
+    fn lines(diagnostics: Vec<(u32, String)>) -> Vec<u32> {
+        diagnostics.into_iter().map(|(line, _)| line).collect()
+    }
+
+    #[test]
+    fn test_undefined_name() {
+        let source = "def f():\n    return g() + later\nlater = 1\nfor i in range(2):\n    if i:\n        print(prev)\n    prev = i\nload(\"lib.eldritch\", \"helper\")\nhelper(input_params, file, missing, len)\n";
+        let diagnostics = lint(&registry(UndefinedName::new()), source);
+        assert_eq!(lines(diagnostics), vec![2, 9]);
+    }
+
+    #[test]
+    fn test_unused_variables_and_parameters() {
+        let source = "top = 1\ndef f(a, b, _c):\n    x = 1\n    _y = 2\n    for i in range(a):\n        pass\n    return [z for z in []]\n";
+        assert_eq!(lines(lint(&registry(UnusedVariable), source)), vec![3, 5]);
+        assert_eq!(lines(lint(&registry(UnusedParameter), source)), vec![2]);
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        let source = "def f(x):\n    if x:\n        return 1\n        print(x)\n    else:\n        return 2\n    x = 3\n    return x\n\nfor i in range(3):\n    continue\n    print(i)\n";
+        assert_eq!(
+            lines(lint(&registry(UnreachableCode), source)),
+            vec![4, 7, 12]
+        );
+    }
+
+    #[test]
+    fn test_shadowed_builtin() {
+        let source = "for file in ['a']:\n    print(file)\nlen = 3\nfiles = 1\n";
+        let diagnostics = lint(&registry(ShadowedBuiltin::new()), source);
+        assert_eq!(lines(diagnostics), vec![1, 3]);
+    }
+
+    fn lint_with_stdlib(registry: &LintRegistry, source: &str) -> Vec<String> {
+        let library = "#[eldritch_library(\"file\")]\npub trait FileLibrary {\n    #[eldritch_method]\n    fn read(&self, path: String) -> Result<String, String>;\n    #[eldritch_method]\n    fn list(&self, path: Option<String>) -> Result<Vec<String>, String>;\n}\n";
+        let (module, docs, methods) = parse_library(library).unwrap();
+        let mut stdlib = StdlibIndex::new();
+        stdlib.modules.insert(module.clone());
+        stdlib.module_docs.insert(module.clone(), docs);
+        stdlib.methods.insert(module, methods);
+
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (ast, _) = Parser::new(tokens).parse();
+        let symbols = SymbolTable::build(source, &ast);
+        let cx = LintContext::new(source, &ast, &symbols, &stdlib);
+        registry.run(&cx).into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn test_unknown_method() {
+        let source = "file.reed('/etc/passwd')\nfile.list()\nsys.anything()\n";
+        assert_eq!(
+            lint_with_stdlib(&registry(UnknownMethod), source),
+            vec!["library 'file' has no method 'reed'; did you mean 'read'?"]
+        );
+    }
+
+    #[test]
+    fn test_wrong_arity() {
+        let source = "file.read()\nfile.read('a', 'b')\nfile.read(path='a')\nfile.list(dir='/')\nfile.list()\nfile.read(*args)\n";
+        assert_eq!(
+            lint_with_stdlib(&registry(WrongArity), source),
+            vec![
+                "file.read() missing required argument 'path'",
+                "file.read() takes 1 argument but 2 were given",
+                "file.list() got an unexpected keyword argument 'dir'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_undeclared_param() {
+        let source = "print(input_params['path'], input_params.get('tyop', 1))\n";
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (ast, _) = Parser::new(tokens).parse();
+        let symbols = SymbolTable::build(source, &ast);
+        let stdlib = StdlibIndex::new();
+        let registry = registry(UndeclaredParam);
+
+        let mut cx = LintContext::new(source, &ast, &symbols, &stdlib);
+        assert!(registry.run(&cx).is_empty());
+        cx.paramdefs = Some(vec!["path".to_string()]);
+        let messages: Vec<String> = registry.run(&cx).into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            vec!["input_params[\"tyop\"] is not declared in metadata.yml"]
+        );
+    }
+}
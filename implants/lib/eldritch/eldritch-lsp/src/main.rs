@@ -1,40 +1,77 @@
-use tower_lsp::jsonrpc::Result;
-use tower_lsp::lsp_types::*;
-use tower_lsp::{Client, LanguageServer, LspService, Server};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tower_lsp::jsonrpc::Result;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer, LspService, Server};
 
-mod linter;
-mod stdlib;
-
-use linter::LintRegistry;
-use stdlib::StdlibIndex;
-use eldritch_core::{Parser, Lexer};
+use eldritch_core::analysis::Node;
+use eldritch_core::formatter;
+use eldritch_core::typecheck::{self, TypeChecker};
+use eldritch_core::{find_node_at_offset, ExprKind, Stmt};
+use eldritch_lsp::document::Document;
+use eldritch_lsp::linter::{LintContext, LintRegistry};
+use eldritch_lsp::signature::{self, BuiltinDocs};
+use eldritch_lsp::stdlib::StdlibIndex;
+use eldritch_lsp::symbols::{SymbolKind, SymbolTable};
+use eldritch_lsp::workspace::WorkspaceIndex;
+use eldritch_lsp::{offset_to_position, position_to_offset, span_to_range};
 
 struct Backend {
     client: Client,
     /// Registry of lint rules.
     linter: LintRegistry,
+    /// Static checker for type annotations.
+    type_checker: TypeChecker,
+    /// Docs of builtin functions and methods, for hover and signature help.
+    builtin_docs: BuiltinDocs,
     /// Index of the standard library.
     stdlib: Arc<RwLock<StdlibIndex>>,
-    /// In-memory cache of document contents.
-    documents: Arc<RwLock<HashMap<Url, String>>>,
+    /// Open documents, parsed as they are edited.
+    documents: Arc<RwLock<HashMap<Url, Document>>>,
+    /// Definitions of every tome in the workspace, for workspace symbols and names
+    /// defined in other files.
+    workspace: Arc<RwLock<WorkspaceIndex>>,
 }
 
 impl Backend {
-    async fn validate_document(&self, uri: Url, text: &str, version: Option<i32>) {
-        let mut diagnostics = Vec::new();
+    /// The parsed contents of `uri`, with its symbols resolved.
+    async fn analyze(&self, uri: &Url) -> Option<(String, Vec<Stmt>, SymbolTable)> {
+        let documents = self.documents.read().await;
+        let document = documents.get(uri)?;
+        let text = document.text().to_string();
+        let ast = document.ast().to_vec();
+        drop(documents);
+        let table = SymbolTable::build(&text, &ast);
+        Some((text, ast, table))
+    }
 
-        // 1. Parsing Phase (using eldritch-core)
-        let mut lexer = Lexer::new(text.to_string());
-        let tokens = lexer.scan_tokens();
+    /// Re-indexes an edited document, so other documents see its definitions.
+    async fn index_document(&self, uri: &Url) {
+        let Ok(path) = uri.to_file_path() else {
+            return;
+        };
+        let documents = self.documents.read().await;
+        if let Some(document) = documents.get(uri) {
+            self.workspace
+                .write()
+                .await
+                .update(&path, document.text(), document.ast());
+        }
+    }
 
-        let mut parser = Parser::new(tokens);
-        let (ast_stmts, parse_errors) = parser.parse();
+    async fn validate_document(&self, uri: Url) {
+        let documents = self.documents.read().await;
+        let Some(document) = documents.get(&uri) else {
+            return;
+        };
+        let text = document.text();
+        let ast_stmts = document.ast();
+        let version = Some(document.version);
+        let mut diagnostics = Vec::new();
 
-        // 2. Syntax Error Diagnostics
-        for err in parse_errors {
+        // 1. Syntax Error Diagnostics, from parsing the edited statements
+        for err in document.errors().iter().cloned() {
             // Convert Span to LSP Range
             // eldritch_core::token::Span has {start, end, line}.
             // This is slightly tricky because Span in `token.rs` only has `line` and byte indices.
@@ -46,8 +83,14 @@ impl Backend {
             let line_idx = err.span.line.saturating_sub(1) as u32;
 
             let range = Range {
-                start: Position { line: line_idx, character: 0 },
-                end: Position { line: line_idx, character: u32::MAX }, // Highlight full line if column unknown
+                start: Position {
+                    line: line_idx,
+                    character: 0,
+                },
+                end: Position {
+                    line: line_idx,
+                    character: u32::MAX,
+                }, // Highlight full line if column unknown
             };
 
             diagnostics.push(Diagnostic {
@@ -60,13 +103,42 @@ impl Backend {
             });
         }
 
-        // 3. Linting Phase (Best Effort)
-        // Pass the AST if parsing succeeded partially (ast_stmts might contain valid stmts even with errors).
-        let lint_diags = self.linter.run(Some(&ast_stmts), text);
-        diagnostics.extend(lint_diags);
+        // 2. Linting Phase (Best Effort)
+        // Lint the AST even if parsing only succeeded partially (ast_stmts holds the recovered statements).
+        let symbols = SymbolTable::build(text, ast_stmts);
+        let stdlib = self.stdlib.read().await;
+        let mut context = LintContext::new(text, ast_stmts, &symbols, &stdlib);
+        if let Ok(path) = uri.to_file_path() {
+            context = context.with_path(&path);
+        }
+        diagnostics.extend(self.linter.run(&context));
+        drop(stdlib);
+
+        // 3. Type Checking Phase
+        // Annotation mismatches and unknown methods; spans are character offsets into `text`.
+        for diag in self.type_checker.check(ast_stmts) {
+            diagnostics.push(Diagnostic {
+                range: Range {
+                    start: offset_to_position(text, diag.span.start),
+                    end: offset_to_position(text, diag.span.end),
+                },
+                severity: Some(match diag.severity {
+                    typecheck::Severity::Error => DiagnosticSeverity::ERROR,
+                    typecheck::Severity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                code: Some(NumberOrString::String(diag.code.to_string())),
+                source: Some("eldritch-typecheck".to_string()),
+                message: diag.message,
+                ..Default::default()
+            });
+        }
+
+        drop(documents);
 
         // 4. Publish Diagnostics
-        self.client.publish_diagnostics(uri, diagnostics, version).await;
+        self.client
+            .publish_diagnostics(uri, diagnostics, version)
+            .await;
     }
 }
 
@@ -74,7 +146,10 @@ impl Backend {
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         let root_uri = params.root_uri.or_else(|| {
-            params.root_path.map(|path| Url::from_file_path(path).unwrap())
+            params
+                .workspace_folders
+                .and_then(|folders| folders.into_iter().next())
+                .map(|folder| folder.uri)
         });
 
         if let Some(uri) = root_uri {
@@ -89,18 +164,34 @@ impl LanguageServer for Backend {
                     let mut index = stdlib_ref.write().await;
                     index.scan(stdlib_path);
                 });
+
+                let workspace_ref = self.workspace.clone();
+                tokio::spawn(async move {
+                    let mut index = workspace_ref.write().await;
+                    index.scan(&path);
+                });
             }
         }
 
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
                 )),
                 completion_provider: Some(CompletionOptions {
                     trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
                     ..Default::default()
                 }),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    ..Default::default()
+                }),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
                 ..Default::default()
             },
             ..Default::default()
@@ -118,22 +209,41 @@ impl LanguageServer for Backend {
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        let uri = params.text_document.uri.clone();
-        let text = params.text_document.text.clone();
-        let version = Some(params.text_document.version);
+        let uri = params.text_document.uri;
+        let document = Document::new(params.text_document.text, params.text_document.version);
 
-        self.documents.write().await.insert(uri.clone(), text.clone());
-        self.validate_document(uri, &text, version).await;
+        self.documents.write().await.insert(uri.clone(), document);
+        self.index_document(&uri).await;
+        self.validate_document(uri).await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri;
-        let version = Some(params.text_document.version);
-        // We use Full sync, so the first change event has the full text
-        if let Some(change) = params.content_changes.into_iter().next() {
-            self.documents.write().await.insert(uri.clone(), change.text.clone());
-            self.validate_document(uri, &change.text, version).await;
+        let version = params.text_document.version;
+        {
+            let mut documents = self.documents.write().await;
+            let Some(document) = documents.get_mut(&uri) else {
+                return;
+            };
+            // Edits are relative to the version before them, so a stale one can't be applied
+            if version <= document.version {
+                return;
+            }
+            document.apply_changes(params.content_changes, version);
+            log::debug!("Re-parsed {} statement(s) of {}", document.reparsed(), uri);
         }
+        self.index_document(&uri).await;
+        self.validate_document(uri).await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.write().await.remove(&uri);
+        // Unsaved edits are gone, so the file on disk is what other documents see again
+        if let Ok(path) = uri.to_file_path() {
+            self.workspace.write().await.update_from_disk(&path);
+        }
+        self.client.publish_diagnostics(uri, Vec::new(), None).await;
     }
 
     async fn completion(&self, _params: CompletionParams) -> Result<Option<CompletionResponse>> {
@@ -157,7 +267,8 @@ impl LanguageServer for Backend {
 
         // Add Keywords
         let keywords = vec![
-            "def", "if", "else", "for", "while", "return", "import", "true", "false", "none"
+            "def", "if", "else", "for", "while", "return", "import", "true", "false", "none",
+            "try", "except", "finally", "as",
         ];
         for kw in keywords {
             items.push(CompletionItem {
@@ -169,6 +280,244 @@ impl LanguageServer for Backend {
 
         Ok(Some(CompletionResponse::Array(items)))
     }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let Some((text, ast, table)) = self.analyze(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&text, position.position);
+        let stdlib = self.stdlib.read().await;
+
+        Ok(
+            signature::hover(&ast, &table, &stdlib, &self.builtin_docs, offset).map(|value| {
+                Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value,
+                    }),
+                    range: None,
+                }
+            }),
+        )
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let Some((text, ast, table)) = self.analyze(&uri).await else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&text, position.position);
+
+        if let Some(id) = table.symbol_at(offset) {
+            return Ok(Some(GotoDefinitionResponse::Scalar(Location {
+                uri,
+                range: span_to_range(&text, table.symbols[id].span),
+            })));
+        }
+
+        // Names the document uses without defining, such as those `load()` brings in, may
+        // be defined by another tome
+        let Some(Node::Expr(expr)) = find_node_at_offset(&ast, offset) else {
+            return Ok(None);
+        };
+        let ExprKind::Identifier(name) = &expr.kind else {
+            return Ok(None);
+        };
+        let Ok(path) = uri.to_file_path() else {
+            return Ok(None);
+        };
+        let workspace = self.workspace.read().await;
+        let locations: Vec<Location> = workspace
+            .definitions(name, &path)
+            .into_iter()
+            .filter_map(|s| {
+                Some(Location {
+                    uri: Url::from_file_path(&s.path).ok()?,
+                    range: s.range,
+                })
+            })
+            .collect();
+        Ok((!locations.is_empty()).then_some(GotoDefinitionResponse::Array(locations)))
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let position = params.text_document_position;
+        let uri = position.text_document.uri;
+        let Some((text, _, table)) = self.analyze(&uri).await else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&text, position.position);
+        let Some(id) = table.symbol_at(offset) else {
+            return Ok(None);
+        };
+
+        let mut spans = Vec::new();
+        if params.context.include_declaration {
+            spans.push(table.symbols[id].span);
+        }
+        spans.extend(table.references_to(id));
+        Ok(Some(
+            spans
+                .into_iter()
+                .map(|span| Location {
+                    uri: uri.clone(),
+                    range: span_to_range(&text, span),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let position = params.text_document_position_params;
+        let Some((text, _, table)) = self.analyze(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&text, position.position);
+        let Some((callee, active)) = signature::call_context(&text, offset) else {
+            return Ok(None);
+        };
+        let (receiver, name) = signature::split_callee(&callee);
+        let stdlib = self.stdlib.read().await;
+        let docs = signature::resolve(receiver, name, &table, &stdlib, &self.builtin_docs);
+        if docs.is_empty() {
+            return Ok(None);
+        }
+
+        let signatures = docs
+            .into_iter()
+            .map(|doc| SignatureInformation {
+                parameters: Some(
+                    doc.params
+                        .iter()
+                        .map(|p| ParameterInformation {
+                            label: ParameterLabel::Simple(p.clone()),
+                            documentation: None,
+                        })
+                        .collect(),
+                ),
+                documentation: (!doc.docs.is_empty()).then(|| {
+                    Documentation::MarkupContent(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: doc.docs.clone(),
+                    })
+                }),
+                active_parameter: None,
+                label: doc.label,
+            })
+            .collect();
+        Ok(Some(SignatureHelp {
+            signatures,
+            active_signature: Some(0),
+            active_parameter: Some(active as u32),
+        }))
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let Some(text) = self
+            .documents
+            .read()
+            .await
+            .get(&params.text_document.uri)
+            .map(|d| d.text().to_string())
+        else {
+            return Ok(None);
+        };
+        // Documents with syntax errors are left alone; the diagnostics already point at them
+        let Ok(formatted) = formatter::format(&text) else {
+            return Ok(None);
+        };
+        if formatted == text {
+            return Ok(Some(Vec::new()));
+        }
+        Ok(Some(vec![TextEdit {
+            range: Range {
+                start: Position {
+                    line: 0,
+                    character: 0,
+                },
+                end: offset_to_position(&text, text.chars().count()),
+            },
+            new_text: formatted,
+        }]))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let Some((text, _, table)) = self.analyze(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        Ok(Some(DocumentSymbolResponse::Nested(outline(
+            &text, &table, None,
+        ))))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let workspace = self.workspace.read().await;
+        let symbols = workspace
+            .search(&params.query)
+            .into_iter()
+            .filter_map(|s| {
+                #[allow(deprecated)]
+                Some(SymbolInformation {
+                    name: s.name.clone(),
+                    kind: match s.kind {
+                        SymbolKind::Function => tower_lsp::lsp_types::SymbolKind::FUNCTION,
+                        _ => tower_lsp::lsp_types::SymbolKind::VARIABLE,
+                    },
+                    tags: None,
+                    deprecated: None,
+                    location: Location {
+                        uri: Url::from_file_path(&s.path).ok()?,
+                        range: s.range,
+                    },
+                    container_name: s.container.clone(),
+                })
+            })
+            .collect();
+        Ok(Some(symbols))
+    }
+}
+
+/// The functions and variables defined directly in `parent`, with their own
+/// definitions nested beneath them.
+fn outline(text: &str, table: &SymbolTable, parent: Option<usize>) -> Vec<DocumentSymbol> {
+    table
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.parent == parent && s.kind != SymbolKind::Parameter)
+        .map(|(id, s)| {
+            let children = if s.kind == SymbolKind::Function {
+                Some(outline(text, table, Some(id)))
+            } else {
+                None
+            };
+            #[allow(deprecated)]
+            DocumentSymbol {
+                name: s.name.clone(),
+                detail: s.detail.clone(),
+                kind: match s.kind {
+                    SymbolKind::Function => tower_lsp::lsp_types::SymbolKind::FUNCTION,
+                    _ => tower_lsp::lsp_types::SymbolKind::VARIABLE,
+                },
+                tags: None,
+                deprecated: None,
+                range: span_to_range(text, s.full_span),
+                selection_range: span_to_range(text, s.span),
+                children,
+            }
+        })
+        .collect()
 }
 
 #[tokio::main]
@@ -181,8 +530,13 @@ async fn main() {
     let (service, socket) = LspService::new(|client| Backend {
         client,
         linter: LintRegistry::default(),
-        stdlib: Arc::new(RwLock::new(StdlibIndex::new())),
+        type_checker: TypeChecker::new(),
+        builtin_docs: BuiltinDocs::new(),
+        stdlib: Arc::new(RwLock::new(StdlibIndex::from_signatures(
+            &eldritch::signatures(),
+        ))),
         documents: Arc::new(RwLock::new(HashMap::new())),
+        workspace: Arc::new(RwLock::new(WorkspaceIndex::new())),
     });
     Server::new(stdin, stdout, socket).serve(service).await;
 }
@@ -0,0 +1,295 @@
+use crate::stdlib::{LibraryMethod, StdlibIndex};
+use crate::symbols::{Symbol, SymbolKind, SymbolTable};
+use eldritch_core::analysis::Node;
+use eldritch_core::docs::{self, DocEntry};
+use eldritch_core::{find_node_at_offset, ExprKind, Stmt};
+
+/// Documentation of something that can be called: a builtin, a method or a user `def`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableDoc {
+    /// One line signature, e.g. `len(s: Any) -> Int`.
+    pub label: String,
+    /// Label of each parameter as it appears in `label`.
+    pub params: Vec<String>,
+    /// Markdown description.
+    pub docs: String,
+}
+
+impl CallableDoc {
+    fn from_entry(name: &str, entry: &DocEntry) -> Self {
+        let params: Vec<String> = entry
+            .params
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.type_name))
+            .collect();
+        let mut label = format!("{}({})", name, params.join(", "));
+        if let Some(ret) = &entry.returns {
+            label.push_str(&format!(" -> {}", ret));
+        }
+        let mut docs = entry.description.clone();
+        if !entry.params.is_empty() {
+            docs.push_str("\n\n**Parameters**\n");
+            for p in &entry.params {
+                docs.push_str(&format!(
+                    "- `{}` ({}): {}\n",
+                    p.name, p.type_name, p.description
+                ));
+            }
+        }
+        Self {
+            label,
+            params,
+            docs,
+        }
+    }
+
+    fn from_library(module: &str, method: &LibraryMethod) -> Self {
+        Self {
+            label: method.signature(module),
+            params: method
+                .params
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", name, ty))
+                .collect(),
+            docs: method.docs.clone(),
+        }
+    }
+
+    fn from_symbol(symbol: &Symbol) -> Self {
+        Self {
+            label: symbol.detail.clone().unwrap_or_else(|| symbol.name.clone()),
+            params: symbol.params.clone(),
+            docs: String::new(),
+        }
+    }
+
+    /// Hover text: the signature in a code block followed by the description.
+    pub fn markdown(&self) -> String {
+        let mut text = format!("```eldritch\n{}\n```", self.label);
+        if !self.docs.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&self.docs);
+        }
+        text
+    }
+}
+
+/// Reference docs for builtin functions and methods on builtin types, from eldritch-core.
+#[derive(Debug)]
+pub struct BuiltinDocs {
+    functions: Vec<DocEntry>,
+    methods: Vec<DocEntry>,
+}
+
+impl BuiltinDocs {
+    pub fn new() -> Self {
+        Self {
+            functions: docs::builtin_functions(),
+            methods: docs::builtin_methods(),
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<CallableDoc> {
+        self.functions
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| CallableDoc::from_entry(name, e))
+    }
+
+    /// Every builtin type method called `name`, e.g. `list.pop` and `dict.pop`.
+    pub fn methods(&self, name: &str) -> Vec<CallableDoc> {
+        self.methods
+            .iter()
+            .filter(|e| e.name.split_once('.').is_some_and(|(_, m)| m == name))
+            .map(|e| CallableDoc::from_entry(&e.name, e))
+            .collect()
+    }
+}
+
+impl Default for BuiltinDocs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves `receiver.name` or a plain `name` to its documentation.
+///
+/// A receiver that names a library (and is not shadowed by a user variable)
+/// resolves to that library's method; any other receiver matches the methods of
+/// builtin types with that name, as the receiver's type is not known.
+pub fn resolve(
+    receiver: Option<&str>,
+    name: &str,
+    table: &SymbolTable,
+    stdlib: &StdlibIndex,
+    builtins: &BuiltinDocs,
+) -> Vec<CallableDoc> {
+    match receiver {
+        Some(receiver) => {
+            let shadowed = table.symbols.iter().any(|s| s.name == receiver);
+            if !shadowed {
+                if let Some(method) = stdlib.method(receiver, name) {
+                    return vec![CallableDoc::from_library(receiver, method)];
+                }
+            }
+            builtins.methods(name)
+        }
+        None => match table.function(name) {
+            Some(symbol) => vec![CallableDoc::from_symbol(symbol)],
+            None => builtins.function(name).into_iter().collect(),
+        },
+    }
+}
+
+/// Markdown shown when hovering `offset`: user symbols first, then builtins
+/// and library methods.
+pub fn hover(
+    ast: &[Stmt],
+    table: &SymbolTable,
+    stdlib: &StdlibIndex,
+    builtins: &BuiltinDocs,
+    offset: usize,
+) -> Option<String> {
+    if let Some(id) = table.symbol_at(offset) {
+        let symbol = &table.symbols[id];
+        let label = match symbol.kind {
+            SymbolKind::Function => symbol.detail.clone().unwrap_or_else(|| symbol.name.clone()),
+            SymbolKind::Parameter => format!("(parameter) {}", symbol.name),
+            SymbolKind::Variable => format!("(variable) {}", symbol.name),
+        };
+        return Some(format!("```eldritch\n{}\n```", label));
+    }
+
+    let Node::Expr(expr) = find_node_at_offset(ast, offset)? else {
+        return None;
+    };
+    let docs = match &expr.kind {
+        ExprKind::GetAttr(obj, name) => {
+            // Only the attribute name, not the dot or the receiver
+            if offset < expr.span.end.saturating_sub(name.chars().count()) {
+                return None;
+            }
+            let receiver = match &obj.kind {
+                ExprKind::Identifier(receiver) => receiver.as_str(),
+                _ => "",
+            };
+            resolve(Some(receiver), name, table, stdlib, builtins)
+        }
+        ExprKind::Identifier(name) => {
+            if let Some(docs) = stdlib.module_docs.get(name).filter(|d| !d.is_empty()) {
+                return Some(format!("```eldritch\n(library) {}\n```\n\n{}", name, docs));
+            }
+            resolve(None, name, table, stdlib, builtins)
+        }
+        _ => return None,
+    };
+    let text: Vec<String> = docs.iter().map(CallableDoc::markdown).collect();
+    (!text.is_empty()).then(|| text.join("\n\n---\n\n"))
+}
+
+/// Finds the call the cursor is inside of while typing: returns the callee
+/// text (e.g. `file.read` or `greet`) and the index of the argument at `offset`.
+pub fn call_context(text: &str, offset: usize) -> Option<(String, usize)> {
+    let chars: Vec<char> = text.chars().take(offset).collect();
+    let mut depth = 0usize;
+    let mut commas = 0;
+    let mut quote: Option<char> = None;
+    let mut open = None;
+
+    // Scan backwards; quotes are tracked naively, which is enough on one call
+    for i in (0..chars.len()).rev() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth > 0 => depth -= 1,
+            '(' => {
+                open = Some(i);
+                break;
+            }
+            '[' | '{' => return None,
+            ',' if depth == 0 => commas += 1,
+            '\n' if depth == 0 && i + 1 < chars.len() && chars[i + 1] != ' ' => {
+                // A new unindented line can't be inside the call
+                return None;
+            }
+            _ => {}
+        }
+    }
+
+    let open = open?;
+    let mut start = open;
+    while start > 0 && (chars[start - 1].is_alphanumeric() || matches!(chars[start - 1], '_' | '.'))
+    {
+        start -= 1;
+    }
+    let callee: String = chars[start..open].iter().collect();
+    let callee = callee.trim_start_matches('.').to_string();
+    if callee.is_empty() || callee.ends_with('.') {
+        return None;
+    }
+    Some((callee, commas))
+}
+
+/// Splits `a.b.c` into the receiver's last segment (`b`) and the name (`c`).
+pub fn split_callee(callee: &str) -> (Option<&str>, &str) {
+    match callee.rsplit_once('.') {
+        Some((receiver, name)) => (receiver.rsplit('.').next(), name),
+        None => (None, callee),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_context() {
+        assert_eq!(
+            call_context("file.read(", 10),
+            Some(("file.read".to_string(), 0))
+        );
+        assert_eq!(
+            call_context("greet('a, b', [1, 2], ", 22),
+            Some(("greet".to_string(), 2))
+        );
+        assert_eq!(call_context("f(g(1), ", 8), Some(("f".to_string(), 1)));
+        assert_eq!(call_context("x = [1, ", 8), None);
+        assert_eq!(call_context("f(1)", 4), None);
+    }
+
+    #[test]
+    fn test_hover() {
+        let source = "def f(a):\n    return a\nf(1)\nx = len([1])\nx.pop()\n";
+        let tokens = eldritch_core::Lexer::new(source.to_string()).scan_tokens();
+        let (ast, _) = eldritch_core::Parser::new(tokens).parse();
+        let table = SymbolTable::build(source, &ast);
+        let stdlib = StdlibIndex::new();
+        let builtins = BuiltinDocs::new();
+        let at = |needle: &str| source.find(needle).unwrap();
+
+        let call = hover(&ast, &table, &stdlib, &builtins, at("f(1)")).unwrap();
+        assert!(call.contains("def f(a)"));
+        let param = hover(&ast, &table, &stdlib, &builtins, at("a\nf")).unwrap();
+        assert!(param.contains("(parameter) a"));
+        let len = hover(&ast, &table, &stdlib, &builtins, at("len")).unwrap();
+        assert!(len.contains("len(s: Any) -> Int"));
+        let pop = hover(&ast, &table, &stdlib, &builtins, at("pop")).unwrap();
+        assert!(pop.contains("list.pop"));
+    }
+
+    #[test]
+    fn test_builtin_docs() {
+        let docs = BuiltinDocs::new();
+        let len = docs.function("len").unwrap();
+        assert_eq!(len.label, "len(s: Any) -> Int");
+        assert!(docs.methods("pop").len() >= 3);
+        assert!(docs.function("nope").is_none());
+    }
+}
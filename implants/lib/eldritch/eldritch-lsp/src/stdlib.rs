@@ -1,7 +1,46 @@
-use std::collections::HashSet;
-use std::path::PathBuf;
+use eldritch_core::docs::LibrarySignature;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Documentation of one library method, parsed from the doc comment of its
+/// `#[eldritch_method]` declaration.
+#[derive(Debug, Clone, Default)]
+pub struct LibraryMethod {
+    pub name: String,
+    /// The full doc comment, as markdown.
+    pub docs: String,
+    /// `(name, type)` of each documented parameter.
+    pub params: Vec<(String, String)>,
+    /// Documented return type, if any.
+    pub returns: Option<String>,
+    /// `(name, optional)` of each argument taken by the Rust method, which calls are
+    /// checked against. `Option` arguments may be left out.
+    pub args: Vec<(String, bool)>,
+}
+
+impl LibraryMethod {
+    /// A one line signature such as `file.read(path: str) -> str`.
+    pub fn signature(&self, module: &str) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect();
+        let mut label = format!("{}.{}({})", module, self.name, params.join(", "));
+        if let Some(ret) = &self.returns {
+            label.push_str(&format!(" -> {}", ret));
+        }
+        label
+    }
+}
+
+/// Libraries every agent registers, used until the stdlib has been scanned.
+pub const CORE_MODULES: &[&str] = &[
+    "agent", "assets", "crypto", "file", "http", "pivot", "process", "random", "regex", "report",
+    "sys", "time",
+];
+
 /// Manages an index of the Eldritch Standard Library.
 ///
 /// This index allows the LSP to be aware of available standard library modules
@@ -10,17 +49,51 @@ use walkdir::WalkDir;
 #[derive(Debug)]
 pub struct StdlibIndex {
     /// A set of known module names (e.g., "http", "file", "sys").
-    /// In a real implementation, this might map names to function signatures.
     pub modules: HashSet<String>,
+    /// Module level documentation, keyed by module name.
+    pub module_docs: HashMap<String, String>,
+    /// Documented methods of each module, keyed by module name.
+    pub methods: HashMap<String, Vec<LibraryMethod>>,
 }
 
 impl StdlibIndex {
     pub fn new() -> Self {
         Self {
             modules: HashSet::new(),
+            module_docs: HashMap::new(),
+            methods: HashMap::new(),
         }
     }
 
+    /// An index of the libraries built into the server, from the signature tables
+    /// `#[eldritch_library]` generates. Scanning a stdlib checkout replaces the libraries it
+    /// finds, so edits show up before the server is rebuilt.
+    pub fn from_signatures(libraries: &[&LibrarySignature]) -> Self {
+        let mut index = Self::new();
+        for library in libraries {
+            let methods = library
+                .methods
+                .iter()
+                .map(|method| {
+                    let docs: Vec<&str> = method.doc.lines().collect();
+                    let mut parsed = parse_method(method.name.to_string(), &docs);
+                    parsed.args = method
+                        .params
+                        .iter()
+                        .map(|p| (p.name.to_string(), p.optional))
+                        .collect();
+                    parsed
+                })
+                .collect();
+            index.modules.insert(library.name.to_string());
+            index
+                .module_docs
+                .insert(library.name.to_string(), library.doc.to_string());
+            index.methods.insert(library.name.to_string(), methods);
+        }
+        index
+    }
+
     /// Recursively scans the given root path for Eldritch standard library modules.
     ///
     /// It looks for files with `.eldritch` extension or directories that imply module structure.
@@ -43,6 +116,7 @@ impl StdlibIndex {
                         if !module_name.is_empty() {
                             self.modules.insert(module_name.to_string());
                             log::debug!("Found stdlib module: {}", module_name);
+                            self.index_library(path);
                         }
                     }
                 }
@@ -51,11 +125,22 @@ impl StdlibIndex {
 
         // Add hardcoded core modules if scanning fails or structure is different
         if self.modules.is_empty() {
-             log::warn!("Stdlib scan yielded no results. Using fallback core modules.");
-             let defaults = vec!["agent", "assets", "crypto", "file", "http", "pivot", "process", "random", "regex", "report", "sys", "time"];
-             for m in defaults {
-                 self.modules.insert(m.to_string());
-             }
+            log::warn!("Stdlib scan yielded no results. Using fallback core modules.");
+            for m in CORE_MODULES {
+                self.modules.insert(m.to_string());
+            }
+        }
+    }
+
+    /// Reads the method docs of the library crate at `path`.
+    fn index_library(&mut self, path: &Path) {
+        let Ok(source) = std::fs::read_to_string(path.join("src/lib.rs")) else {
+            return;
+        };
+        if let Some((module, docs, methods)) = parse_library(&source) {
+            self.modules.insert(module.clone());
+            self.module_docs.insert(module.clone(), docs);
+            self.methods.insert(module, methods);
         }
     }
 
@@ -65,4 +150,220 @@ impl StdlibIndex {
         completions.sort();
         completions
     }
+
+    /// Whether `name` is the global of a library.
+    pub fn is_library(&self, name: &str) -> bool {
+        self.modules.contains(name) || CORE_MODULES.contains(&name)
+    }
+
+    /// Looks up the documentation of `module.name`.
+    pub fn method(&self, module: &str, name: &str) -> Option<&LibraryMethod> {
+        self.methods.get(module)?.iter().find(|m| m.name == name)
+    }
+}
+
+impl Default for StdlibIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses the `#[eldritch_library("name")]` trait of a library crate into its
+/// name, its doc comment and its documented methods.
+pub fn parse_library(source: &str) -> Option<(String, String, Vec<LibraryMethod>)> {
+    let mut module = None;
+    let mut module_docs = String::new();
+    let mut methods = Vec::new();
+    let mut docs: Vec<&str> = Vec::new();
+    // Set while reading the doc comment and signature of a method, to its renamed name if any
+    let mut method: Option<Option<String>> = None;
+    let mut signature = String::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("#[eldritch_library(\"") {
+            module = rest.split('"').next().map(str::to_string);
+            docs.clear();
+        } else if let Some(rest) = line.strip_prefix("#[eldritch_method") {
+            let rename = rest
+                .strip_prefix("(\"")
+                .and_then(|r| r.split('"').next())
+                .map(str::to_string);
+            method = Some(rename);
+            docs.clear();
+        } else if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.strip_prefix(' ').unwrap_or(doc));
+        } else if line.starts_with("pub trait") && module.is_some() && module_docs.is_empty() {
+            module_docs = docs.join("\n");
+            docs.clear();
+        } else if let Some(rename) = &method {
+            // Trailing comments would hide the end of the signature
+            let line = line.split("//").next().unwrap_or(line).trim_end();
+            signature.push_str(line);
+            if !(line.ends_with(';') || line.ends_with('{')) {
+                continue;
+            }
+            let fn_name = signature
+                .split("fn ")
+                .nth(1)
+                .and_then(|s| s.split('(').next())
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let mut parsed = parse_method(rename.clone().unwrap_or(fn_name), &docs);
+            parsed.args = parse_args(&signature);
+            methods.push(parsed);
+            method = None;
+            signature.clear();
+            docs.clear();
+        }
+    }
+
+    module.map(|m| (m, module_docs, methods))
+}
+
+fn parse_method(name: String, docs: &[&str]) -> LibraryMethod {
+    #[derive(PartialEq)]
+    enum Section {
+        Other,
+        Parameters,
+        Returns,
+    }
+
+    let mut params = Vec::new();
+    let mut returns = None;
+    let mut section = Section::Other;
+    for line in docs {
+        match line.trim() {
+            "**Parameters**" => section = Section::Parameters,
+            "**Returns**" => section = Section::Returns,
+            l if l.starts_with("**") => section = Section::Other,
+            l => {
+                let Some(rest) = l.strip_prefix("- `") else {
+                    continue;
+                };
+                let Some((head, tail)) = rest.split_once('`') else {
+                    continue;
+                };
+                match section {
+                    Section::Parameters => {
+                        // - `name` (`type`): description
+                        let ty = tail
+                            .trim_start()
+                            .strip_prefix('(')
+                            .and_then(|t| t.split_once(')'))
+                            .map(|(t, _)| t.replace('`', ""))
+                            .unwrap_or_else(|| "Any".to_string());
+                        params.push((head.to_string(), ty));
+                    }
+                    Section::Returns if returns.is_none() => returns = Some(head.to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    LibraryMethod {
+        name,
+        docs: docs.join("\n"),
+        params,
+        returns,
+        args: Vec::new(),
+    }
+}
+
+// The arguments of a Rust method signature, skipping `self` and the interpreter, which
+// the macros pass in themselves
+fn parse_args(signature: &str) -> Vec<(String, bool)> {
+    let Some(start) = signature.find('(') else {
+        return Vec::new();
+    };
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut arg = String::new();
+    for c in signature[start + 1..].chars() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(std::mem::take(&mut arg));
+                continue;
+            }
+            _ => {}
+        }
+        arg.push(c);
+    }
+    args.push(arg);
+
+    args.iter()
+        .filter_map(|arg| {
+            let (name, ty) = arg.split_once(':')?;
+            let ty = ty.trim();
+            if ty.contains("Interpreter") {
+                return None;
+            }
+            Some((name.trim().to_string(), ty.starts_with("Option<")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_signatures() {
+        let index = StdlibIndex::from_signatures(&eldritch::signatures());
+        assert!(index.is_library("file"));
+        let read = index.method("file", "read").unwrap();
+        assert_eq!(read.args, vec![("path".to_string(), false)]);
+        assert_eq!(read.params, vec![("path".to_string(), "str".to_string())]);
+        assert_eq!(read.signature("file"), "file.read(path: str) -> str");
+        let list = index.method("file", "list").unwrap();
+        assert_eq!(list.args, vec![("path".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_parse_library_args() {
+        let source = r#"
+#[eldritch_library("file")]
+pub trait FileLibrary {
+    #[eldritch_method]
+    /// Lists a directory.
+    fn list(&self, path: Option<String>) -> Result<Vec<BTreeMap<String, Value>>, String>;
+
+    #[eldritch_method("write")]
+    fn write_file(
+        &self,
+        path: String,
+        data: BTreeMap<String, Value>,
+    ) -> Result<(), String>;
+
+    #[eldritch_method]
+    fn eval(&self, interp: &mut Interpreter, code: String) -> Result<Value, String>; // eval is reserved
+
+    #[eldritch_method]
+    fn is_dir(&self, path: String) -> Result<bool, String>;
+}
+"#;
+        let (module, _, methods) = parse_library(source).unwrap();
+        assert_eq!(module, "file");
+        let args: Vec<(&str, Vec<(String, bool)>)> = methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.args.clone()))
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                ("list", vec![("path".to_string(), true)]),
+                (
+                    "write",
+                    vec![("path".to_string(), false), ("data".to_string(), false)]
+                ),
+                ("eval", vec![("code".to_string(), false)]),
+                ("is_dir", vec![("path".to_string(), false)]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,636 @@
+use eldritch_core::{Argument, Expr, ExprKind, FStringSegment, Param, Span, Stmt, StmtKind, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Parameter,
+    Variable,
+}
+
+/// A name bound by the document: a `def`, a parameter or a variable.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Where the name itself is written at its first binding.
+    pub span: Span,
+    /// The whole definition, e.g. a `def` including its body.
+    pub full_span: Span,
+    /// The function the symbol is defined in, if any.
+    pub parent: Option<usize>,
+    /// Source of a `def` header, e.g. `def greet(name: str) -> str`.
+    pub detail: Option<String>,
+    /// Parameter labels of a `def`, e.g. `name: str = "bob"`.
+    pub params: Vec<String>,
+}
+
+/// Definitions and uses of every user defined name in a document.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    pub symbols: Vec<Symbol>,
+    /// Every place a symbol is used, including re-assignments.
+    pub references: Vec<(Span, usize)>,
+    /// Names read but never defined in a scope that encloses the read. Builtins and
+    /// libraries are not known to the table, so they end up here too.
+    pub unresolved: Vec<(Span, String)>,
+}
+
+impl SymbolTable {
+    /// Resolves every name in `ast`. `text` is the source the AST was parsed from.
+    pub fn build(text: &str, ast: &[Stmt]) -> Self {
+        let mut builder = Builder {
+            chars: text.chars().collect(),
+            table: SymbolTable::default(),
+            scopes: vec![HashMap::new()],
+            parent: None,
+            defs: HashMap::new(),
+            unresolved: Vec::new(),
+            in_annotation: false,
+        };
+        builder.function_scope(&[], ast);
+        builder.finish()
+    }
+
+    /// The symbol defined or referenced at `offset`.
+    pub fn symbol_at(&self, offset: usize) -> Option<usize> {
+        let contains = |span: &Span| span.start <= offset && offset <= span.end;
+        self.symbols
+            .iter()
+            .position(|s| contains(&s.span))
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|(span, _)| contains(span))
+                    .map(|(_, id)| *id)
+            })
+    }
+
+    /// Every use of symbol `id`, in document order.
+    pub fn references_to(&self, id: usize) -> Vec<Span> {
+        let mut spans: Vec<Span> = self
+            .references
+            .iter()
+            .filter(|(_, r)| *r == id)
+            .map(|(span, _)| *span)
+            .collect();
+        spans.sort_by_key(|s| s.start);
+        spans
+    }
+
+    /// The function named `name`, preferring module level definitions.
+    pub fn function(&self, name: &str) -> Option<&Symbol> {
+        let mut functions = self
+            .symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Function && s.name == name);
+        let first = functions.next()?;
+        Some(
+            std::iter::once(first)
+                .chain(functions)
+                .find(|s| s.parent.is_none())
+                .unwrap_or(first),
+        )
+    }
+}
+
+struct Builder {
+    chars: Vec<char>,
+    table: SymbolTable,
+    scopes: Vec<HashMap<String, usize>>,
+    /// The function whose body is being walked.
+    parent: Option<usize>,
+    /// Symbols of `def` statements by span start.
+    defs: HashMap<usize, usize>,
+    /// Names that had no definition in scope when read, with the function reading them.
+    unresolved: Vec<(Span, String, Option<usize>)>,
+    /// Set while walking a type annotation, whose names are types rather than variables.
+    in_annotation: bool,
+}
+
+impl Builder {
+    // Keeps the unresolved names that aren't defined later in an enclosing scope either,
+    // as happens when a loop reads a variable its previous iteration assigned.
+    fn finish(mut self) -> SymbolTable {
+        let symbols = &self.table.symbols;
+        let defined_around = |name: &str, mut scope: Option<usize>| loop {
+            if symbols.iter().any(|s| s.name == name && s.parent == scope) {
+                return true;
+            }
+            match scope {
+                Some(id) => scope = symbols[id].parent,
+                None => return false,
+            }
+        };
+        self.table.unresolved = self
+            .unresolved
+            .into_iter()
+            .filter(|(_, name, scope)| !defined_around(name, *scope))
+            .map(|(span, name, _)| (span, name))
+            .collect();
+        self.table
+    }
+
+    fn text(&self, span: Span) -> String {
+        let end = span.end.min(self.chars.len());
+        let mut start = span.start.min(end);
+        // Plain string literal spans start after the opening quote
+        while start > 0 && matches!(self.chars[start - 1], '"' | '\'') {
+            start -= 1;
+        }
+        self.chars[start..end].iter().collect()
+    }
+
+    // Finds `word` as a whole identifier in `from..to`.
+    fn find_word(&self, word: &str, from: usize, to: usize) -> Option<Span> {
+        let word: Vec<char> = word.chars().collect();
+        let to = to.min(self.chars.len());
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut i = from;
+        while i + word.len() <= to {
+            if self.chars[i..i + word.len()] == word[..]
+                && (i == 0 || !is_ident(self.chars[i - 1]))
+                && self.chars.get(i + word.len()).is_none_or(|c| !is_ident(*c))
+            {
+                return Some(Span::new(i, i + word.len(), self.line_at(i)));
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn line_at(&self, offset: usize) -> usize {
+        1 + self.chars[..offset].iter().filter(|c| **c == '\n').count()
+    }
+
+    fn resolve(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev().find_map(|s| s.get(name).copied())
+    }
+
+    fn declare(&mut self, name: &str, kind: SymbolKind, span: Span, full_span: Span) -> usize {
+        let id = self.table.symbols.len();
+        self.table.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            span,
+            full_span,
+            parent: self.parent,
+            detail: None,
+            params: Vec::new(),
+        });
+        self.scopes.last_mut().unwrap().insert(name.to_string(), id);
+        id
+    }
+
+    // Binds `name` like the interpreter does: an existing variable in an
+    // enclosing scope is updated, otherwise a new one is created.
+    fn bind(&mut self, name: &str, span: Span, full_span: Span) {
+        match self.resolve(name) {
+            Some(id) => self.table.references.push((span, id)),
+            None => {
+                self.declare(name, SymbolKind::Variable, span, full_span);
+            }
+        }
+    }
+
+    // Walks a function body (or the module) in a new scope. Nested `def`s are
+    // declared up front and their bodies walked last, since they run after
+    // the enclosing code has defined its names.
+    fn function_scope(&mut self, params: &[(String, Span)], body: &[Stmt]) {
+        for (name, span) in params {
+            self.declare(name, SymbolKind::Parameter, *span, *span);
+        }
+        let mut nested = Vec::new();
+        self.hoist(body);
+        self.block(body, &mut nested);
+        for stmt in nested {
+            self.def_body(stmt);
+        }
+    }
+
+    fn hoist(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            match &stmt.kind {
+                StmtKind::Def(name, params, ret, _) => {
+                    let name_span = self
+                        .find_word(name, stmt.span.start + 3, stmt.span.end)
+                        .unwrap_or(stmt.span);
+                    let id = self.declare(name, SymbolKind::Function, name_span, stmt.span);
+                    let params: Vec<String> = params.iter().map(|p| self.param_label(p)).collect();
+                    let mut detail = format!("def {}({})", name, params.join(", "));
+                    if let Some(ret) = ret {
+                        detail.push_str(&format!(" -> {}", self.text(ret.span)));
+                    }
+                    let symbol = &mut self.table.symbols[id];
+                    symbol.detail = Some(detail);
+                    symbol.params = params;
+                    self.defs.insert(stmt.span.start, id);
+                }
+                StmtKind::If(_, then_branch, else_branch) => {
+                    self.hoist(then_branch);
+                    if let Some(else_branch) = else_branch {
+                        self.hoist(else_branch);
+                    }
+                }
+                StmtKind::For(_, _, body) => self.hoist(body),
+                StmtKind::While(_, body, else_branch) => {
+                    self.hoist(body);
+                    if let Some(else_branch) = else_branch {
+                        self.hoist(else_branch);
+                    }
+                }
+                StmtKind::Try(body, handlers, finally) => {
+                    self.hoist(body);
+                    for handler in handlers {
+                        self.hoist(&handler.body);
+                    }
+                    if let Some(finally) = finally {
+                        self.hoist(finally);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn param_label(&self, param: &Param) -> String {
+        let (prefix, name, annotation, default) = match param {
+            Param::Normal(n, a) => ("", n, a, None),
+            Param::WithDefault(n, a, d) => ("", n, a, Some(d)),
+            Param::Star(n, a) => ("*", n, a, None),
+            Param::StarStar(n, a) => ("**", n, a, None),
+        };
+        let mut label = format!("{}{}", prefix, name);
+        if let Some(annotation) = annotation {
+            label.push_str(&format!(": {}", self.text(annotation.span)));
+        }
+        if let Some(default) = default {
+            label.push_str(&format!(" = {}", self.text(default.span)));
+        }
+        label
+    }
+
+    fn def_body(&mut self, stmt: &Stmt) {
+        let StmtKind::Def(_, params, ret, body) = &stmt.kind else {
+            return;
+        };
+        let id = self.defs[&stmt.span.start];
+        let header_start = self.table.symbols[id].span.end;
+        let header_end = body.first().map_or(stmt.span.end, |s| s.span.start);
+
+        // Annotations and defaults are evaluated in the enclosing scope
+        for param in params {
+            match param {
+                Param::Normal(_, a) | Param::Star(_, a) | Param::StarStar(_, a) => {
+                    if let Some(a) = a {
+                        self.annotation(a);
+                    }
+                }
+                Param::WithDefault(_, a, d) => {
+                    if let Some(a) = a {
+                        self.annotation(a);
+                    }
+                    self.expr(d);
+                }
+            }
+        }
+        if let Some(ret) = ret {
+            self.annotation(ret);
+        }
+
+        let mut search_from = header_start;
+        let params: Vec<(String, Span)> = params
+            .iter()
+            .map(|p| {
+                let (Param::Normal(n, _)
+                | Param::WithDefault(n, _, _)
+                | Param::Star(n, _)
+                | Param::StarStar(n, _)) = p;
+                let span = self
+                    .find_word(n, search_from, header_end)
+                    .unwrap_or(stmt.span);
+                search_from = span.end;
+                (n.clone(), span)
+            })
+            .collect();
+
+        let saved_parent = self.parent.replace(id);
+        self.scopes.push(HashMap::new());
+        self.function_scope(&params, body);
+        self.scopes.pop();
+        self.parent = saved_parent;
+    }
+
+    fn annotation(&mut self, expr: &Expr) {
+        let outer = std::mem::replace(&mut self.in_annotation, true);
+        self.expr(expr);
+        self.in_annotation = outer;
+    }
+
+    // `load("module", "name", alias="name")` binds `name` and `alias` in the current scope
+    fn load(&mut self, args: &[Argument], full_span: Span) {
+        for arg in args.iter().skip(1) {
+            let (name, from) = match arg {
+                Argument::Positional(e) => match &e.kind {
+                    ExprKind::Literal(Value::String(name)) => (name, e.span.start),
+                    _ => continue,
+                },
+                Argument::Keyword(name, _) => (name, full_span.start),
+                _ => continue,
+            };
+            if let Some(span) = self.find_word(name, from, full_span.end) {
+                self.bind(name, span, full_span);
+            }
+        }
+    }
+
+    fn block<'s>(&mut self, stmts: &'s [Stmt], nested: &mut Vec<&'s Stmt>) {
+        for stmt in stmts {
+            self.stmt(stmt, nested);
+        }
+    }
+
+    fn stmt<'s>(&mut self, stmt: &'s Stmt, nested: &mut Vec<&'s Stmt>) {
+        match &stmt.kind {
+            StmtKind::Expression(e) => {
+                self.expr(e);
+                if let ExprKind::Call(callee, args) = &e.kind {
+                    if matches!(&callee.kind, ExprKind::Identifier(name) if name == "load") {
+                        self.load(args, stmt.span);
+                    }
+                }
+            }
+            StmtKind::Assignment(target, annotation, value) => {
+                self.expr(value);
+                if let Some(annotation) = annotation {
+                    self.annotation(annotation);
+                }
+                self.target(target, stmt.span);
+            }
+            StmtKind::AugmentedAssignment(target, _, value) => {
+                self.expr(value);
+                self.expr(target);
+            }
+            StmtKind::If(cond, then_branch, else_branch) => {
+                self.expr(cond);
+                self.block(then_branch, nested);
+                if let Some(else_branch) = else_branch {
+                    self.block(else_branch, nested);
+                }
+            }
+            StmtKind::Return(value) => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+            }
+            StmtKind::Def(..) => nested.push(stmt),
+            StmtKind::For(vars, iterable, body) => {
+                self.expr(iterable);
+                let mut from = stmt.span.start;
+                for var in vars {
+                    if let Some(span) = self.find_word(var, from, iterable.span.start) {
+                        self.bind(var, span, stmt.span);
+                        from = span.end;
+                    }
+                }
+                self.block(body, nested);
+            }
+            StmtKind::While(cond, body, else_branch) => {
+                self.expr(cond);
+                self.block(body, nested);
+                if let Some(else_branch) = else_branch {
+                    self.block(else_branch, nested);
+                }
+            }
+            StmtKind::Try(body, handlers, finally) => {
+                self.block(body, nested);
+                for handler in handlers {
+                    if let Some(kind) = &handler.kind {
+                        self.expr(kind);
+                    }
+                    if let Some(name) = &handler.name {
+                        let from = handler
+                            .kind
+                            .as_ref()
+                            .map_or(handler.span.start, |k| k.span.end);
+                        let to = handler
+                            .body
+                            .first()
+                            .map_or(handler.span.end, |s| s.span.start);
+                        if let Some(span) = self.find_word(name, from, to) {
+                            self.bind(name, span, handler.span);
+                        }
+                    }
+                    self.block(&handler.body, nested);
+                }
+                if let Some(finally) = finally {
+                    self.block(finally, nested);
+                }
+            }
+            StmtKind::Break | StmtKind::Continue | StmtKind::Pass | StmtKind::Error(_) => {}
+        }
+    }
+
+    fn target(&mut self, target: &Expr, full_span: Span) {
+        match &target.kind {
+            ExprKind::Identifier(name) => self.bind(name, target.span, full_span),
+            ExprKind::Tuple(items) | ExprKind::List(items) => {
+                for item in items {
+                    self.target(item, full_span);
+                }
+            }
+            _ => self.expr(target),
+        }
+    }
+
+    // Binds comprehension or lambda variables written between `from` and `to`.
+    fn local_scope(&mut self, names: &[String], from: usize, to: usize) {
+        self.scopes.push(HashMap::new());
+        let mut from = from;
+        for name in names {
+            if let Some(span) = self.find_word(name, from, to) {
+                self.declare(name, SymbolKind::Variable, span, span);
+                from = span.end;
+            }
+        }
+    }
+
+    fn comprehension(&mut self, vars: &[String], iterable: &Expr, from: usize, parts: &[&Expr]) {
+        self.expr(iterable);
+        let for_kw = self
+            .find_word("for", from, iterable.span.start)
+            .map_or(from, |s| s.end);
+        self.local_scope(vars, for_kw, iterable.span.start);
+        for part in parts {
+            self.expr(part);
+        }
+        self.scopes.pop();
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Identifier(name) => match self.resolve(name) {
+                Some(id) => self.table.references.push((expr.span, id)),
+                None if !self.in_annotation => {
+                    self.unresolved.push((expr.span, name.clone(), self.parent));
+                }
+                None => {}
+            },
+            ExprKind::Literal(_) | ExprKind::Error(_) => {}
+            ExprKind::BinaryOp(l, _, r) | ExprKind::LogicalOp(l, _, r) => {
+                self.expr(l);
+                self.expr(r);
+            }
+            ExprKind::UnaryOp(_, e) => self.expr(e),
+            ExprKind::Call(callee, args) => {
+                self.expr(callee);
+                for arg in args {
+                    let (Argument::Positional(e)
+                    | Argument::Keyword(_, e)
+                    | Argument::StarArgs(e)
+                    | Argument::KwArgs(e)) = arg;
+                    self.expr(e);
+                }
+            }
+            ExprKind::List(items) | ExprKind::Tuple(items) | ExprKind::Set(items) => {
+                for item in items {
+                    self.expr(item);
+                }
+            }
+            ExprKind::Dictionary(entries) => {
+                for (k, v) in entries {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Index(obj, index) => {
+                self.expr(obj);
+                self.expr(index);
+            }
+            ExprKind::GetAttr(obj, _) => self.expr(obj),
+            ExprKind::Slice(obj, start, stop, step) => {
+                self.expr(obj);
+                for part in [start, stop, step].into_iter().flatten() {
+                    self.expr(part);
+                }
+            }
+            ExprKind::FString(segments) => {
+                for e in segments.iter().flat_map(FStringSegment::exprs) {
+                    self.expr(e);
+                }
+            }
+            ExprKind::ListComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            }
+            | ExprKind::SetComp {
+                body,
+                vars,
+                iterable,
+                cond,
+            } => {
+                let mut parts = vec![&**body];
+                parts.extend(cond.as_deref());
+                self.comprehension(vars, iterable, body.span.end, &parts);
+            }
+            ExprKind::DictComp {
+                key,
+                value,
+                vars,
+                iterable,
+                cond,
+            } => {
+                let mut parts = vec![&**key, &**value];
+                parts.extend(cond.as_deref());
+                self.comprehension(vars, iterable, value.span.end, &parts);
+            }
+            ExprKind::Lambda { params, body } => {
+                let names: Vec<String> = params
+                    .iter()
+                    .map(|p| {
+                        let (Param::Normal(n, _)
+                        | Param::WithDefault(n, _, _)
+                        | Param::Star(n, _)
+                        | Param::StarStar(n, _)) = p;
+                        n.clone()
+                    })
+                    .collect();
+                for p in params {
+                    if let Param::WithDefault(_, _, d) = p {
+                        self.expr(d);
+                    }
+                }
+                self.local_scope(&names, expr.span.start, body.span.start);
+                self.expr(body);
+                self.scopes.pop();
+            }
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.expr(then_branch);
+                self.expr(else_branch);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eldritch_core::{Lexer, Parser};
+
+    fn table(source: &str) -> SymbolTable {
+        let tokens = Lexer::new(source.to_string()).scan_tokens();
+        let (ast, _) = Parser::new(tokens).parse();
+        SymbolTable::build(source, &ast)
+    }
+
+    #[test]
+    fn test_function_definition_and_references() {
+        let source = "def greet(name):\n    return name\n\ngreet('a')\ngreet('b')\n";
+        let table = table(source);
+        let call = source.rfind("greet").unwrap();
+        let id = table.symbol_at(call).unwrap();
+        let symbol = &table.symbols[id];
+        assert_eq!(symbol.kind, SymbolKind::Function);
+        assert_eq!(symbol.span.start, 4);
+        assert_eq!(symbol.detail.as_deref(), Some("def greet(name)"));
+        assert_eq!(table.references_to(id).len(), 2);
+
+        let param_use = source.find("return name").unwrap() + 7;
+        let param = table.symbol_at(param_use).unwrap();
+        assert_eq!(table.symbols[param].kind, SymbolKind::Parameter);
+        assert_eq!(table.symbols[param].span.start, 10);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let source = "x = 1\ndef f():\n    y = x\n    return y\nx = 2\n[x for x in range(3)]\n";
+        let table = table(source);
+        let x = table.symbol_at(0).unwrap();
+        // The global is re-assigned and read inside f; the comprehension variable is separate
+        assert_eq!(table.references_to(x).len(), 2);
+        let y = table.symbol_at(source.find("y =").unwrap()).unwrap();
+        let f = table.symbol_at(source.find("f(").unwrap()).unwrap();
+        assert_eq!(table.symbols[y].parent, Some(f));
+        let comp = table.symbol_at(source.find("[x").unwrap() + 1).unwrap();
+        assert_ne!(comp, x);
+    }
+
+    #[test]
+    fn test_signature_detail() {
+        let source = "def f(a: int, b = 'x', *rest) -> str:\n    pass\n";
+        let table = table(source);
+        let f = table.function("f").unwrap();
+        assert_eq!(
+            f.detail.as_deref(),
+            Some("def f(a: int, b = 'x', *rest) -> str")
+        );
+        assert_eq!(f.params, vec!["a: int", "b = 'x'", "*rest"]);
+    }
+}
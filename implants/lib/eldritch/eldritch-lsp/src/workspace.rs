@@ -0,0 +1,170 @@
+use eldritch_core::{Lexer, Parser, Stmt};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::Range;
+use walkdir::WalkDir;
+
+use crate::span_to_range;
+use crate::symbols::{SymbolKind, SymbolTable};
+
+/// A function, or a variable defined at the top of a file, found in the workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub path: PathBuf,
+    /// Where the name is written at its definition.
+    pub range: Range,
+    /// The function a nested `def` is defined in.
+    pub container: Option<String>,
+}
+
+/// The definitions of every `.eldritch` file in the workspace, kept up to date as
+/// documents are edited.
+#[derive(Debug, Default)]
+pub struct WorkspaceIndex {
+    files: HashMap<PathBuf, Vec<WorkspaceSymbol>>,
+}
+
+impl WorkspaceIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes every `.eldritch` file beneath `root`, skipping hidden directories, build
+    /// output and dependencies.
+    pub fn scan(&mut self, root: &Path) {
+        let entries = WalkDir::new(root).into_iter().filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0 || !(name.starts_with('.') || name == "target" || name == "node_modules")
+        });
+        for entry in entries.filter_map(|e| e.ok()) {
+            let is_tome = entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "eldritch");
+            if entry.file_type().is_file() && is_tome {
+                self.update_from_disk(entry.path());
+            }
+        }
+        log::info!("Indexed {} files under {:?}", self.files.len(), root);
+    }
+
+    /// Re-indexes `path` from the statements parsed from `text`.
+    pub fn update(&mut self, path: &Path, text: &str, ast: &[Stmt]) {
+        let table = SymbolTable::build(text, ast);
+        let symbols = table
+            .symbols
+            .iter()
+            .filter(|s| match s.kind {
+                SymbolKind::Function => true,
+                SymbolKind::Variable => s.parent.is_none(),
+                SymbolKind::Parameter => false,
+            })
+            .map(|s| WorkspaceSymbol {
+                name: s.name.clone(),
+                kind: s.kind,
+                path: path.to_path_buf(),
+                range: span_to_range(text, s.span),
+                container: s.parent.map(|p| table.symbols[p].name.clone()),
+            })
+            .collect();
+        self.files.insert(path.to_path_buf(), symbols);
+    }
+
+    /// Re-indexes `path` from disk, e.g. once its editor is closed, or forgets it if it
+    /// cannot be read.
+    pub fn update_from_disk(&mut self, path: &Path) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                let tokens = Lexer::new(text.clone()).scan_tokens();
+                let (ast, _) = Parser::new(tokens).parse();
+                self.update(path, &text, &ast);
+            }
+            Err(_) => {
+                self.files.remove(path);
+            }
+        }
+    }
+
+    /// Symbols whose names contain `query`, ignoring case, ordered by file and position.
+    pub fn search(&self, query: &str) -> Vec<&WorkspaceSymbol> {
+        let query = query.to_lowercase();
+        let mut found: Vec<&WorkspaceSymbol> = self
+            .files
+            .values()
+            .flatten()
+            .filter(|s| s.name.to_lowercase().contains(&query))
+            .collect();
+        found.sort_by_key(|s| (&s.path, s.range.start.line, s.range.start.character));
+        found
+    }
+
+    /// Functions and top-level variables named `name` in files other than `exclude`, for
+    /// names a document uses without defining.
+    pub fn definitions(&self, name: &str, exclude: &Path) -> Vec<&WorkspaceSymbol> {
+        let mut found: Vec<&WorkspaceSymbol> = self
+            .files
+            .iter()
+            .filter(|(path, _)| path.as_path() != exclude)
+            .flat_map(|(_, symbols)| symbols)
+            .filter(|s| s.name == name && s.container.is_none())
+            .collect();
+        found.sort_by_key(|s| (&s.path, s.range.start.line));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(files: &[(&str, &str)]) -> WorkspaceIndex {
+        let mut index = WorkspaceIndex::new();
+        for (path, text) in files {
+            let tokens = Lexer::new(text.to_string()).scan_tokens();
+            let (ast, _) = Parser::new(tokens).parse();
+            index.update(Path::new(path), text, &ast);
+        }
+        index
+    }
+
+    #[test]
+    fn test_search() {
+        let index = index(&[
+            ("b.eldritch", "def list_users():\n    users = []\n    def fmt(u):\n        return u\n    return users\n"),
+            ("a.eldritch", "USER_LIMIT = 5\nfor user in range(2):\n    pass\n"),
+        ]);
+        let found: Vec<(&str, Option<&str>)> = index
+            .search("USER")
+            .iter()
+            .map(|s| (s.name.as_str(), s.container.as_deref()))
+            .collect();
+        // Locals are left out, and loop variables at the top of a file are kept
+        assert_eq!(
+            found,
+            vec![("USER_LIMIT", None), ("user", None), ("list_users", None)]
+        );
+        assert_eq!(
+            index.search("fmt")[0].container.as_deref(),
+            Some("list_users")
+        );
+        assert_eq!(index.search("fmt")[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn test_definitions_in_other_files() {
+        let mut index = index(&[
+            ("lib.eldritch", "def helper():\n    pass\n"),
+            ("main.eldritch", "def helper():\n    pass\nhelper()\n"),
+        ]);
+        let found = index.definitions("helper", Path::new("main.eldritch"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, Path::new("lib.eldritch"));
+
+        index.update(Path::new("lib.eldritch"), "", &[]);
+        assert!(index
+            .definitions("helper", Path::new("main.eldritch"))
+            .is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// A tome directory with `main.eldritch` holding `source`
+fn tome(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("eldritch-lint-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("main.eldritch"), source).unwrap();
+    dir
+}
+
+fn lint(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_eldritch-lint"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_lint_exit_codes() {
+    let clean = tome("clean", "print(\"hello\")\n");
+    let warns = tome("warns", "def f(path):\n    return 1\n");
+    let fails = tome("fails", "print(missing)\n");
+
+    let out = lint(&[clean.to_str().unwrap()]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("1 file: 0 errors, 0 warnings"));
+
+    let out = lint(&[warns.to_str().unwrap()]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("warning[unused-parameter]"));
+    let out = lint(&["--deny-warnings", warns.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(1));
+
+    let out = lint(&["--format", "json", fails.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(diagnostics[0]["code"], "undefined-name");
+    assert_eq!(diagnostics[0]["line"], 1);
+
+    let config = fails.join("lint.yml");
+    fs::write(&config, "disable: [undefined-name]\n").unwrap();
+    let out = lint(&[
+        "--config",
+        config.to_str().unwrap(),
+        fails.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+
+    for dir in [clean, warns, fails] {
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
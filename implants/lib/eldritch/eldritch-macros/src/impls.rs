@@ -1,8 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    FnArg, ItemStruct, ItemTrait, Lit, Meta, NestedMeta, Signature, TraitItem, Type, TypeReference,
-    parse_quote,
+    Attribute, FnArg, GenericArgument, ItemStruct, ItemTrait, Lit, Meta, NestedMeta, PathArguments,
+    ReturnType, Signature, TraitItem, Type, TypeReference, parse_quote,
 };
 
 /// Expands the `#[eldritch_library]` attribute.
@@ -10,10 +10,14 @@ use syn::{
 /// This macro:
 /// 1. Injects `Debug + Send + Sync` supertraits.
 /// 2. Scans for methods annotated with `#[eldritch_method]`.
-/// 3. Injects 3 helper methods directly into the trait with default implementations:
+/// 3. Injects 4 helper methods directly into the trait with default implementations:
 ///    - `_eldritch_type_name`: Returns the library name.
 ///    - `_eldritch_method_names`: Returns a list of exposed method names.
 ///    - `_eldritch_call_method`: Dispatches calls to the actual methods.
+///    - `_eldritch_signature`: Returns the library's signature table.
+/// 4. Emits the signature table next to the trait as a `const` named after it, e.g.
+///    `FILE_LIBRARY_SIGNATURE` for `FileLibrary`, describing each method's parameters,
+///    return type and doc comment.
 #[allow(clippy::collapsible_if)]
 pub fn expand_eldritch_library(
     attr: TokenStream,
@@ -39,6 +43,7 @@ pub fn expand_eldritch_library(
 
     let mut method_dispatches = Vec::new();
     let mut method_registrations = Vec::new();
+    let mut method_signatures = Vec::new();
 
     for item in &mut trait_def.items {
         if let TraitItem::Method(method) = item {
@@ -78,10 +83,42 @@ pub fn expand_eldritch_library(
                     #(#cfg_attrs)*
                     names.push(alloc::string::String::from(#bind_name));
                 });
+
+                let params = signature_params(&method.sig);
+                let returns = return_type_string(&method.sig.output);
+                let doc = doc_string(&method.attrs);
+                method_signatures.push(quote! {
+                    #(#cfg_attrs)*
+                    eldritch_core::docs::MethodSignature {
+                        name: #bind_name,
+                        params: &[#(#params),*],
+                        returns: #returns,
+                        doc: #doc,
+                    }
+                });
             }
         }
     }
 
+    let signature_ident = syn::Ident::new(
+        &format!(
+            "{}_SIGNATURE",
+            screaming_snake_case(&trait_name.to_string())
+        ),
+        trait_name.span(),
+    );
+    let vis = &trait_def.vis;
+    let lib_doc = doc_string(&trait_def.attrs);
+    let signature = quote! {
+        /// The methods of the library, as generated by `#[eldritch_library]`.
+        #vis const #signature_ident: eldritch_core::docs::LibrarySignature =
+            eldritch_core::docs::LibrarySignature {
+                name: #lib_name_str,
+                doc: #lib_doc,
+                methods: &[#(#method_signatures),*],
+            };
+    };
+
     // Inject helper methods directly into the trait
     trait_def.items.push(parse_quote! {
         fn _eldritch_type_name(&self) -> &str {
@@ -89,6 +126,12 @@ pub fn expand_eldritch_library(
         }
     });
 
+    trait_def.items.push(parse_quote! {
+        fn _eldritch_signature(&self) -> &'static eldritch_core::docs::LibrarySignature {
+            &#signature_ident
+        }
+    });
+
     trait_def.items.push(parse_quote! {
         fn _eldritch_method_names(&self) -> alloc::vec::Vec<alloc::string::String> {
             let mut names = alloc::vec::Vec::new();
@@ -120,7 +163,10 @@ pub fn expand_eldritch_library(
         }
     });
 
-    Ok(quote!(#trait_def))
+    Ok(quote! {
+        #trait_def
+        #signature
+    })
 }
 
 /// Expands the `#[eldritch_library_impl]` attribute.
@@ -158,6 +204,10 @@ pub fn expand_eldritch_library_impl(
             ) -> Result<eldritch_core::Value, alloc::string::String> {
                 <Self as #trait_ident>::_eldritch_call_method(self, interp, name, args, kwargs)
             }
+
+            fn signature(&self) -> Option<&'static eldritch_core::docs::LibrarySignature> {
+                Some(<Self as #trait_ident>::_eldritch_signature(self))
+            }
         }
     })
 }
@@ -184,6 +234,101 @@ fn is_interpreter_type(ty: &Type) -> bool {
     false
 }
 
+// `FileLibrary` -> `FILE_LIBRARY`
+fn screaming_snake_case(ident: &str) -> String {
+    let mut out = String::new();
+    for (idx, c) in ident.chars().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            out.push('_');
+        }
+        out.extend(c.to_uppercase());
+    }
+    out
+}
+
+// Renders a type as written, e.g. `Option<Vec<String>>` rather than the spaced-out
+// `Option < Vec < String > >` that `quote` produces
+fn type_string(ty: &Type) -> String {
+    let tokens = quote!(#ty).to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let mut out = String::new();
+    for (idx, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+            if word(chars.get(idx.wrapping_sub(1))) && word(chars.get(idx + 1)) {
+                out.push(' ');
+            }
+        } else {
+            out.push(c);
+            if c == ',' {
+                out.push(' ');
+            }
+        }
+    }
+    out
+}
+
+// The type a method returns to scripts; errors are raised, so `Result<T, E>` returns `T`
+#[allow(clippy::collapsible_if)]
+fn return_type_string(output: &ReturnType) -> String {
+    let ReturnType::Type(_, ty) = output else {
+        return "()".to_string();
+    };
+    if let Type::Path(type_path) = &**ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Result" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(ok)) = args.args.first() {
+                        return type_string(ok);
+                    }
+                }
+            }
+        }
+    }
+    type_string(ty)
+}
+
+// The `///` lines of a doc comment, joined with newlines
+fn doc_string(attrs: &[Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                Lit::Str(lit) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+// The `ParamSignature`s of the arguments scripts pass, skipping `self` and the interpreter
+fn signature_params(sig: &Signature) -> Vec<TokenStream> {
+    sig.inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) if !is_interpreter_type(&pat_type.ty) => Some(pat_type),
+            _ => None,
+        })
+        .map(|pat_type| {
+            let pat = &pat_type.pat;
+            let name = quote!(#pat).to_string();
+            let type_name = type_string(&pat_type.ty);
+            let optional = is_option_type(&pat_type.ty);
+            quote! {
+                eldritch_core::docs::ParamSignature {
+                    name: #name,
+                    type_name: #type_name,
+                    optional: #optional,
+                }
+            }
+        })
+        .collect()
+}
+
 fn generate_args_parsing(sig: &Signature) -> Result<(TokenStream, TokenStream), syn::Error> {
     let mut parsing = Vec::new();
     let mut call_args = Vec::new();
@@ -38,6 +38,7 @@ fn test_expand_library_impl() {
     let s = result.to_string();
 
     assert!(s.contains("impl eldritch_core :: ForeignValue for MyStruct"));
+    assert!(s.contains("fn signature (& self)"));
     assert!(s.contains("< Self as MyLib > :: _eldritch_type_name"));
 }
 
@@ -90,3 +91,55 @@ fn test_arg_parsing_option() {
     // Check default handling
     assert!(s.contains("Default :: default ()"));
 }
+
+#[test]
+fn test_signature_table() {
+    let attr = quote!("file");
+    let item = quote! {
+        /// Reads files.
+        pub trait FileLibrary {
+            #[eldritch_method("list")]
+            /// Lists a directory.
+            ///
+            /// **Parameters**
+            /// - `path` (`Option<str>`): The directory.
+            fn list_dir(
+                &self,
+                interp: &mut Interpreter,
+                path: Option<String>,
+                env: BTreeMap<String, Vec<String>>,
+            ) -> Result<Vec<BTreeMap<String, Value>>, String>;
+
+            #[eldritch_method]
+            #[cfg(feature = "stdlib")]
+            fn touch(&self, path: String);
+        }
+    };
+
+    let result = expand_eldritch_library(attr, item).unwrap();
+    let s = result.to_string();
+
+    assert!(
+        s.contains("pub const FILE_LIBRARY_SIGNATURE : eldritch_core :: docs :: LibrarySignature")
+    );
+    assert!(s.contains("fn _eldritch_signature"));
+    assert!(s.contains("doc : \"Reads files.\""));
+
+    // Renamed, with the interpreter left out and the types as written
+    assert!(s.contains("name : \"list\""));
+    assert!(!s.contains("name : \"interp\""));
+    assert!(s.contains("name : \"path\" , type_name : \"Option<String>\" , optional : true"));
+    assert!(s.contains(
+        "name : \"env\" , type_name : \"BTreeMap<String, Vec<String>>\" , optional : false"
+    ));
+    assert!(s.contains("returns : \"Vec<BTreeMap<String, Value>>\""));
+    assert!(s.contains(
+        "doc : \"Lists a directory.\\n\\n**Parameters**\\n- `path` (`Option<str>`): The directory.\""
+    ));
+
+    // Methods behind a cfg keep it in the table, and default to returning None
+    assert!(s.contains(
+        "# [cfg (feature = \"stdlib\")] eldritch_core :: docs :: MethodSignature { name : \"touch\""
+    ));
+    assert!(s.contains("returns : \"()\""));
+}
@@ -0,0 +1,29 @@
+//! Writes the signature of every library method as JSON, for the docs site, the LSP and
+//! the tome builder.
+//!
+//! ```sh
+//! cargo run -p eldritch --bin eldritch-signatures [OUTPUT]
+//! ```
+//!
+//! Prints to stdout when no output path is given.
+
+use std::process::ExitCode;
+
+use eldritch_core::docs::signatures_json;
+
+fn main() -> ExitCode {
+    let json = signatures_json(&eldritch::signatures());
+    match std::env::args().nth(1) {
+        Some(path) => match std::fs::write(&path, json) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("failed to write {}: {}", path, e);
+                ExitCode::FAILURE
+            }
+        },
+        None => {
+            print!("{}", json);
+            ExitCode::SUCCESS
+        }
+    }
+}
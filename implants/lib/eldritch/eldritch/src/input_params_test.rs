@@ -1,8 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::Interpreter;
-    use eldritch_core::Value;
-    use spin::RwLock;
+    use eldritch_core::{Freezable, Value};
     use std::collections::BTreeMap;
     use std::sync::Arc;
 
@@ -19,7 +18,7 @@ mod tests {
         );
         params.insert(Value::String("key2".to_string()), Value::Int(42));
 
-        let params_val = Value::Dictionary(Arc::new(RwLock::new(params)));
+        let params_val = Value::Dictionary(Arc::new(Freezable::new(params)));
 
         // Define variable in interpreter
         interp.define_variable("input_params", params_val);
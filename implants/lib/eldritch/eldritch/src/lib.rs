@@ -24,14 +24,17 @@ pub use eldritch_repl as repl;
 
 // Re-export core types
 pub use eldritch_core::{
-    BufferPrinter, Environment, ForeignValue, Interpreter as CoreInterpreter, NoopPrinter, Printer,
-    Span, StdoutPrinter, TokenKind, Value, conversion, format_tprint, pretty_format,
+    BranchCoverage, BufferPrinter, CancellationToken, CoverageRecorder, CoverageReport, DebugHook,
+    Environment, ExecutionLimits, ForeignValue, Freezable, Interpreter as CoreInterpreter,
+    NoopPrinter, Printer, Profile, ProfileStats, ScriptError, Span, StdoutPrinter, TokenKind,
+    TracebackFrame, Value, conversion, format_tprint, formatter, pretty_format, typecheck,
 };
 pub use eldritch_macros as macros;
 
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use eldritch_core::docs::LibrarySignature;
 
 #[cfg(feature = "stdlib")]
 use eldritch_agent::Context;
@@ -51,6 +54,8 @@ use crate::assets::std::StdAssetsLibrary;
 #[cfg(feature = "stdlib")]
 use crate::chain::std::StdChainLibrary;
 #[cfg(feature = "stdlib")]
+use crate::crypto::std::PureCryptoLibrary;
+#[cfg(feature = "stdlib")]
 use crate::crypto::std::StdCryptoLibrary;
 #[cfg(feature = "stdlib")]
 use crate::dns::std::StdDnsLibrary;
@@ -63,6 +68,8 @@ use crate::pivot::std::StdPivotLibrary;
 #[cfg(feature = "stdlib")]
 use crate::process::std::StdProcessLibrary;
 #[cfg(feature = "stdlib")]
+use crate::random::std::SeededRandomLibrary;
+#[cfg(feature = "stdlib")]
 use crate::random::std::StdRandomLibrary;
 #[cfg(feature = "stdlib")]
 use crate::regex::std::StdRegexLibrary;
@@ -71,6 +78,8 @@ use crate::report::std::StdReportLibrary;
 #[cfg(feature = "stdlib")]
 use crate::sys::std::StdSysLibrary;
 #[cfg(feature = "stdlib")]
+use crate::time::std::FrozenTimeLibrary;
+#[cfg(feature = "stdlib")]
 use crate::time::std::StdTimeLibrary;
 
 #[cfg(feature = "fake_agent")]
@@ -102,10 +111,56 @@ use crate::sys::fake::SysLibraryFake;
 #[cfg(feature = "fake_time")]
 use crate::time::fake::TimeLibraryFake;
 
+/// The signature table of every library, generated by `#[eldritch_library]`, in the order
+/// the user guide lists them. `eldritch-signatures` writes them out as JSON.
+pub fn signatures() -> Vec<&'static LibrarySignature> {
+    alloc::vec![
+        &agent::AGENT_LIBRARY_SIGNATURE,
+        &assets::ASSETS_LIBRARY_SIGNATURE,
+        &chain::CHAIN_LIBRARY_SIGNATURE,
+        &crypto::CRYPTO_LIBRARY_SIGNATURE,
+        &dns::DNS_LIBRARY_SIGNATURE,
+        &file::FILE_LIBRARY_SIGNATURE,
+        &http::HTTP_LIBRARY_SIGNATURE,
+        &pivot::PIVOT_LIBRARY_SIGNATURE,
+        &process::PROCESS_LIBRARY_SIGNATURE,
+        &random::RANDOM_LIBRARY_SIGNATURE,
+        &regex::REGEX_LIBRARY_SIGNATURE,
+        &report::REPORT_LIBRARY_SIGNATURE,
+        &sys::SYS_LIBRARY_SIGNATURE,
+        &time::TIME_LIBRARY_SIGNATURE,
+    ]
+}
+
 pub struct Interpreter {
     inner: CoreInterpreter,
 }
 
+/// Settings for [`Interpreter::sandboxed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandboxConfig {
+    /// Seed for every value the `random` library returns.
+    pub seed: u64,
+    /// The Unix timestamp `time.now()` reports until the tome sleeps.
+    pub epoch: i64,
+    pub limits: ExecutionLimits,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            // 2024-01-01T00:00:00Z
+            epoch: 1_704_067_200,
+            limits: ExecutionLimits::new()
+                .with_max_steps(1_000_000)
+                .with_max_loop_iterations(100_000)
+                .with_max_sequence_len(16 * 1024 * 1024)
+                .with_max_eval_depth(4),
+        }
+    }
+}
+
 impl Default for Interpreter {
     fn default() -> Self {
         Self::new()
@@ -164,6 +219,26 @@ impl Interpreter {
         self
     }
 
+    /// An interpreter for untrusted tomes, such as community tomes run by golem or the
+    /// playground. Only libraries without side effects are registered: `crypto` without its
+    /// file functions, `regex`, `random` seeded from `config.seed` and `time` frozen at
+    /// `config.epoch`. `config.limits` bounds steps, loop iterations, sequence sizes and
+    /// `eval()` nesting, and no wall-clock timeout is set by default, so a tome prints the
+    /// same output and fails at the same point on every run.
+    #[cfg(feature = "stdlib")]
+    pub fn sandboxed(config: SandboxConfig) -> Self {
+        let mut interp = Self::new().with_limits(config.limits);
+        interp.inner.register_lib(PureCryptoLibrary);
+        interp.inner.register_lib(StdRegexLibrary);
+        interp
+            .inner
+            .register_lib(SeededRandomLibrary::new(config.seed));
+        interp
+            .inner
+            .register_lib(FrozenTimeLibrary::new(config.epoch));
+        interp
+    }
+
     #[cfg(feature = "stdlib")]
     pub fn with_agent(mut self, agent: Arc<dyn Agent>) -> Self {
         // Agent library needs a task_id. For general usage (outside of imix tasks),
@@ -200,6 +275,8 @@ impl Interpreter {
         self
     }
 
+    // Each library is behind a feature, so nothing mutates `self` when none are enabled
+    #[allow(unused_mut)]
     pub fn with_fake_agent(mut self) -> Self {
         #[cfg(feature = "fake_agent")]
         self.inner.register_lib(AgentLibraryFake);
@@ -252,6 +329,44 @@ impl Interpreter {
         self
     }
 
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.inner.set_limits(limits);
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.inner.set_cancellation_token(token);
+        self
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.inner.cancellation_token()
+    }
+
+    /// Lets a debugger pause every run before each line.
+    pub fn with_debug_hook(mut self, hook: Arc<dyn DebugHook>) -> Self {
+        self.inner.set_debug_hook(hook);
+        self
+    }
+
+    /// Counts the statements of every run in `recorder`, for coverage reports.
+    pub fn with_coverage(mut self, recorder: CoverageRecorder) -> Self {
+        self.inner.set_coverage(recorder);
+        self
+    }
+
+    /// Records per-function, per-line and per-library-method timings for every run.
+    #[cfg(feature = "std")]
+    pub fn with_profiler(mut self) -> Self {
+        self.inner = self.inner.with_profiler();
+        self
+    }
+
+    /// Returns the timings recorded since profiling was enabled or last taken.
+    pub fn take_profile(&mut self) -> Option<Profile> {
+        self.inner.take_profile()
+    }
+
     pub fn register_lib(&mut self, lib: impl ForeignValue + 'static) {
         self.inner.register_lib(lib);
     }
@@ -262,6 +377,11 @@ impl Interpreter {
         self.inner.interpret(input)
     }
 
+    #[allow(clippy::result_large_err)]
+    pub fn run(&mut self, input: &str) -> Result<Value, ScriptError> {
+        self.inner.run(input)
+    }
+
     pub fn define_variable(&mut self, name: &str, value: Value) {
         self.inner.define_variable(name, value);
     }
@@ -273,6 +393,11 @@ impl Interpreter {
     pub fn complete(&self, code: &str, cursor: usize) -> (usize, Vec<String>) {
         self.inner.complete(code, cursor)
     }
+
+    /// A type checker that knows the libraries and globals registered so far.
+    pub fn type_checker(&self) -> typecheck::TypeChecker {
+        typecheck::TypeChecker::from_interpreter(&self.inner)
+    }
 }
 
 #[cfg(all(test, feature = "fake_bindings"))]
@@ -283,3 +408,9 @@ mod input_params_test;
 
 #[cfg(test)]
 mod process_report_test;
+
+#[cfg(all(test, feature = "stdlib"))]
+mod sandbox_test;
+
+#[cfg(all(test, feature = "std"))]
+mod signatures_test;
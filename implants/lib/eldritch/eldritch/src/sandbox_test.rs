@@ -0,0 +1,98 @@
+use crate::{BufferPrinter, Interpreter, SandboxConfig};
+use alloc::string::String;
+use alloc::sync::Arc;
+
+const TOME: &str = r#"
+start = time.now()
+time.sleep(30)
+print(time.now() - start)
+print(random.int(0, 1000000), random.string(12), random.uuid())
+print(random.bytes(4), random.bool())
+print(crypto.sha256(b"sandbox"), regex.match("id=42", r"id=(\d+)"))
+print({"b": 2, "a": 1}, set([3, 1, 2]))
+"#;
+
+fn run(config: SandboxConfig, code: &str) -> (Result<(), String>, String) {
+    let printer = Arc::new(BufferPrinter::new());
+    let mut interp = Interpreter::sandboxed(config).with_printer(printer.clone());
+    let result = interp.interpret(code).map(|_| ());
+    (result, printer.read())
+}
+
+#[test]
+fn test_sandboxed_output_is_identical_across_runs() {
+    let (result, first) = run(SandboxConfig::default(), TOME);
+    assert!(result.is_ok(), "{result:?}");
+    assert!(first.starts_with("30\n"), "{first}");
+
+    for _ in 0..3 {
+        let (result, output) = run(SandboxConfig::default(), TOME);
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(output, first);
+    }
+
+    let reseeded = SandboxConfig {
+        seed: 1,
+        ..SandboxConfig::default()
+    };
+    let (_, output) = run(reseeded, TOME);
+    assert_ne!(output, first);
+}
+
+#[test]
+fn test_sandboxed_registers_only_pure_libraries() {
+    let (result, output) = run(SandboxConfig::default(), "print(libs())");
+    assert!(result.is_ok(), "{result:?}");
+    assert_eq!(output, "[\"crypto\", \"random\", \"regex\", \"time\"]");
+
+    let (result, _) = run(SandboxConfig::default(), "file.read('/etc/passwd')");
+    assert!(result.unwrap_err().contains("NameError"));
+
+    let (result, _) = run(
+        SandboxConfig::default(),
+        "crypto.hash_file('/etc/passwd', 'SHA256')",
+    );
+    assert!(result.unwrap_err().contains("can't access files"));
+}
+
+#[test]
+fn test_sandboxed_enforces_limits() {
+    let (result, _) = run(SandboxConfig::default(), "while True:\n    pass");
+    assert!(result.unwrap_err().contains("LoopLimitError"));
+
+    let (result, _) = run(SandboxConfig::default(), "x = 'a' * (1 << 40)");
+    assert!(result.unwrap_err().contains("MemoryLimitError"));
+
+    // Builtins, unpacking and format widths that would allocate without bound
+    for code in [
+        "list(range(1 << 40))",
+        "tuple(range(1 << 40))",
+        "set(range(1 << 40))",
+        "sorted(range(1 << 40))",
+        "reversed(range(1 << 40))",
+        "max(range(1 << 40))",
+        "print(range(1 << 40))",
+        "list(enumerate(range(1 << 40)))",
+        "s = set()\ns.update(range(1 << 40))",
+        "a, b = range(1 << 40)",
+        "l = [0] * 1000000\nfor i in range(10):\n    l.extend(l)",
+        "f'{1:>100000000000}'",
+        "f'{1.5:.100000000000f}'",
+        "'{:>100000000000}'.format(1)",
+        "'%100000000000d' % 1",
+        "try:\n    list(range(1 << 40))\nexcept:\n    pass",
+    ] {
+        let (result, _) = run(SandboxConfig::default(), code);
+        let err = result.unwrap_err();
+        assert!(err.contains("MemoryLimitError"), "{code}: {err}");
+    }
+
+    let nested =
+        "code = '1'\nfor i in range(5):\n    code = 'eval(' + repr(code) + ')'\neval(code)";
+    let (result, _) = run(SandboxConfig::default(), nested);
+    assert!(
+        result
+            .unwrap_err()
+            .contains("eval() nesting limit of 4 exceeded")
+    );
+}
@@ -0,0 +1,148 @@
+use crate::signatures;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use eldritch_core::docs::signatures_json;
+
+const USER_GUIDE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../../../docs/_docs/user-guide/eldritch.md"
+);
+
+// The parameter names of each `### lib.method` section, from the signature line below it,
+// e.g. `file.read(path: str) -> str`
+fn documented_methods() -> BTreeMap<String, Vec<String>> {
+    let guide = std::fs::read_to_string(USER_GUIDE).unwrap();
+    let libraries: Vec<&str> = signatures().iter().map(|l| l.name).collect();
+    let mut methods = BTreeMap::new();
+    let mut lines = guide.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line.strip_prefix("### ") else {
+            continue;
+        };
+        let name = name.trim();
+        let Some((library, _)) = name.split_once('.') else {
+            continue;
+        };
+        if !libraries.contains(&library) {
+            continue;
+        }
+        let signature = lines
+            .by_ref()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or_default();
+        let params = signature
+            .trim_matches('`')
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('('))
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(params, _)| params)
+            .unwrap_or_else(|| panic!("### {name} has no `{name}(...)` signature line"));
+        methods.insert(name.to_string(), param_names(params));
+    }
+    methods
+}
+
+// `a: str, b: Dict<str, int>` -> `["a", "b"]`
+fn param_names(params: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in params.chars().chain(core::iter::once(',')) {
+        match c {
+            '<' | '[' => depth += 1,
+            '>' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                let name = current.split(':').next().unwrap_or_default().trim();
+                if !name.is_empty() {
+                    names.push(name.to_string());
+                }
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    names
+}
+
+#[test]
+fn test_every_method_is_documented() {
+    let documented = documented_methods();
+    let mut missing = Vec::new();
+    let mut unknown: Vec<&String> = documented.keys().collect();
+    for library in signatures() {
+        for method in library.methods {
+            let name = format!("{}.{}", library.name, method.name);
+            unknown.retain(|n| **n != name);
+            if !documented.contains_key(&name) {
+                missing.push(name);
+            }
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "methods without a section in the user guide: {missing:?}"
+    );
+    assert!(
+        unknown.is_empty(),
+        "user guide sections for methods that do not exist: {unknown:?}"
+    );
+}
+
+#[test]
+fn test_documented_parameters_match() {
+    let documented = documented_methods();
+    for library in signatures() {
+        for method in library.methods {
+            let name = format!("{}.{}", library.name, method.name);
+            let Some(params) = documented.get(&name) else {
+                continue;
+            };
+            let actual: Vec<&str> = method.params.iter().map(|p| p.name).collect();
+            assert_eq!(params, &actual, "user guide parameters of {name}");
+        }
+    }
+}
+
+// The `- `name` (`type`): ...` items of a doc comment's **Parameters** section
+#[test]
+fn test_doc_comment_parameters_match() {
+    for library in signatures() {
+        for method in library.methods {
+            let Some((_, section)) = method.doc.split_once("**Parameters**") else {
+                continue;
+            };
+            let documented: Vec<&str> = section
+                .lines()
+                .skip(1)
+                .take_while(|l| !l.trim().is_empty())
+                .filter_map(|l| l.trim().strip_prefix("- `"))
+                .filter_map(|l| l.split_once('`'))
+                .map(|(name, _)| name)
+                .collect();
+            let actual: Vec<&str> = method.params.iter().map(|p| p.name).collect();
+            assert_eq!(
+                documented, actual,
+                "doc comment parameters of {}.{}",
+                library.name, method.name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_signatures_json() {
+    let json = signatures_json(&signatures());
+    assert!(
+        json.starts_with("[\n  {\n    \"name\": \"agent\""),
+        "{json}"
+    );
+    assert!(
+        json.contains(r#"{ "name": "path", "type": "String", "optional": false }"#),
+        "{json}"
+    );
+    assert!(json.ends_with("]\n"));
+}
@@ -9,6 +9,8 @@ use alloc::sync::Arc;
 #[cfg(feature = "stdlib")]
 use alloc::vec::Vec;
 #[cfg(feature = "stdlib")]
+use eldritch_core::Freezable;
+#[cfg(feature = "stdlib")]
 use eldritch_core::Value;
 #[cfg(feature = "stdlib")]
 use eldritch_core::conversion::{FromValue, ToValue};
@@ -16,8 +18,6 @@ use eldritch_core::conversion::{FromValue, ToValue};
 use pb::c2;
 #[cfg(feature = "stdlib")]
 use pb::eldritch;
-#[cfg(feature = "stdlib")]
-use spin::RwLock;
 
 // --- Wrappers ---
 
@@ -68,7 +68,7 @@ impl ToValue for TaskWrapper {
         // Tome is complex, let's represent it as a dict or None for now
         // For strict correctness we might want a TomeWrapper, but often scripts just need the ID.
         // If needed, we can expand Tome.
-        Value::Dictionary(Arc::new(RwLock::new(map)))
+        Value::Dictionary(Arc::new(Freezable::new(map)))
     }
 }
 
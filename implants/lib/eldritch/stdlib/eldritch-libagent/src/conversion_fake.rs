@@ -1,9 +1,8 @@
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::sync::Arc;
-use eldritch_core::Value;
 use eldritch_core::conversion::{FromValue, ToValue};
-use spin::RwLock;
+use eldritch_core::{Freezable, Value};
 
 #[derive(Debug, Clone)]
 pub struct TaskWrapper;
@@ -43,6 +42,6 @@ impl ToValue for TaskWrapper {
             Value::String("quest_name".to_string()),
             Value::String("fake".to_string()),
         );
-        Value::Dictionary(Arc::new(RwLock::new(map)))
+        Value::Dictionary(Arc::new(Freezable::new(map)))
     }
 }
@@ -15,7 +15,10 @@ pub fn report_task_output(
     output: String,
     error: Option<String>,
 ) -> Result<(), String> {
-    let task_error = error.map(|msg| TaskError { msg });
+    let task_error = error.map(|msg| TaskError {
+        msg,
+        ..Default::default()
+    });
 
     let message_val = match context {
         Context::Task(tc) => {
@@ -19,6 +19,8 @@ fn convert_json_to_value(json: serde_json::Value) -> Result<Value, String> {
         serde_json::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
                 Ok(Value::Int(i))
+            } else if let Some(u) = n.as_u64() {
+                Ok(u.to_value())
             } else if let Some(f) = n.as_f64() {
                 Ok(Value::Float(f))
             } else {
@@ -84,6 +86,14 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_json_u64() -> Result<(), String> {
+        let res = from_json(r#"18446744073709551615"#.to_string())?;
+        assert_eq!(res, u64::MAX.to_value());
+        assert_eq!(res.to_string(), "18446744073709551615");
+        Ok(())
+    }
+
     #[test]
     fn test_from_json_invalid() {
         let res = from_json(r#"{"test":"#.to_string());
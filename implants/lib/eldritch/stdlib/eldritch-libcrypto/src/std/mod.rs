@@ -16,10 +16,13 @@ pub mod from_json_impl;
 pub mod hash_file_impl;
 pub mod is_json_impl;
 pub mod md5_impl;
+mod pure;
 pub mod sha1_impl;
 pub mod sha256_impl;
 pub mod to_json_impl;
 
+pub use pure::PureCryptoLibrary;
+
 #[derive(Default, Debug)]
 #[eldritch_library_impl(CryptoLibrary)]
 pub struct StdCryptoLibrary;
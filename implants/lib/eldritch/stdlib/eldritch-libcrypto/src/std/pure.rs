@@ -0,0 +1,101 @@
+use super::StdCryptoLibrary;
+use crate::CryptoLibrary;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use eldritch_core::Value;
+use eldritch_macros::eldritch_library_impl;
+
+/// The `crypto` library without the functions that read or write files, for running
+/// untrusted tomes.
+#[derive(Default, Debug)]
+#[eldritch_library_impl(CryptoLibrary)]
+pub struct PureCryptoLibrary;
+
+fn file_access_denied<T>(method: &str) -> Result<T, String> {
+    Err(format!("crypto.{method} can't access files in a sandbox"))
+}
+
+impl CryptoLibrary for PureCryptoLibrary {
+    fn aes_encrypt(&self, key: Vec<u8>, iv: Vec<u8>, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        StdCryptoLibrary.aes_encrypt(key, iv, data)
+    }
+
+    fn aes_decrypt(&self, key: Vec<u8>, iv: Vec<u8>, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        StdCryptoLibrary.aes_decrypt(key, iv, data)
+    }
+
+    fn aes_decrypt_file(&self, _src: String, _dst: String, _key: String) -> Result<(), String> {
+        file_access_denied("aes_decrypt_file")
+    }
+
+    fn aes_encrypt_file(&self, _src: String, _dst: String, _key: String) -> Result<(), String> {
+        file_access_denied("aes_encrypt_file")
+    }
+
+    fn md5(&self, data: Vec<u8>) -> Result<String, String> {
+        StdCryptoLibrary.md5(data)
+    }
+
+    fn sha1(&self, data: Vec<u8>) -> Result<String, String> {
+        StdCryptoLibrary.sha1(data)
+    }
+
+    fn sha256(&self, data: Vec<u8>) -> Result<String, String> {
+        StdCryptoLibrary.sha256(data)
+    }
+
+    fn hash_file(&self, _file: String, _algo: String) -> Result<String, String> {
+        file_access_denied("hash_file")
+    }
+
+    fn encode_b64(&self, content: String, encode_type: Option<String>) -> Result<String, String> {
+        StdCryptoLibrary.encode_b64(content, encode_type)
+    }
+
+    fn decode_b64(&self, content: String, encode_type: Option<String>) -> Result<String, String> {
+        StdCryptoLibrary.decode_b64(content, encode_type)
+    }
+
+    fn is_json(&self, content: String) -> Result<bool, String> {
+        StdCryptoLibrary.is_json(content)
+    }
+
+    fn from_json(&self, content: String) -> Result<Value, String> {
+        StdCryptoLibrary.from_json(content)
+    }
+
+    fn to_json(&self, content: Value) -> Result<String, String> {
+        StdCryptoLibrary.to_json(content)
+    }
+
+    fn encode_utf16le(&self, content: String) -> Result<Vec<u8>, String> {
+        StdCryptoLibrary.encode_utf16le(content)
+    }
+
+    fn decode_utf16le(&self, content: Vec<u8>) -> Result<String, String> {
+        StdCryptoLibrary.decode_utf16le(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pure_crypto_refuses_files() {
+        let lib = PureCryptoLibrary;
+        assert_eq!(
+            lib.sha256(b"abc".to_vec()).unwrap(),
+            StdCryptoLibrary.sha256(b"abc".to_vec()).unwrap()
+        );
+        let err = lib
+            .hash_file("/etc/passwd".into(), "SHA256".into())
+            .unwrap_err();
+        assert!(err.contains("can't access files"), "{err}");
+        assert!(
+            lib.aes_encrypt_file("a".into(), "b".into(), "k".into())
+                .is_err()
+        );
+    }
+}
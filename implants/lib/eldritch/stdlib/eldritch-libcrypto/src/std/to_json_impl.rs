@@ -1,6 +1,7 @@
 use alloc::format;
 use alloc::string::{String, ToString};
 use eldritch_core::Value;
+use eldritch_core::conversion::FromValue;
 
 pub fn to_json(content: Value) -> Result<String, String> {
     let json_value = convert_value_to_json(&content)?;
@@ -12,6 +13,10 @@ fn convert_value_to_json(val: &Value) -> Result<serde_json::Value, String> {
         Value::None => Ok(serde_json::Value::Null),
         Value::Bool(b) => Ok(serde_json::Value::Bool(*b)),
         Value::Int(i) => Ok(serde_json::json!(i)),
+        // Only unsigned 64-bit values fit in a JSON number beyond i64
+        Value::BigInt(i) => u64::from_value(val)
+            .map(|u| serde_json::json!(u))
+            .map_err(|_| format!("int {i} is too large to serialize to JSON")),
         Value::Float(f) => Ok(serde_json::json!(f)),
         Value::String(s) => Ok(serde_json::Value::String(s.clone())),
         Value::Bytes(_b) => {
@@ -33,6 +38,13 @@ fn convert_value_to_json(val: &Value) -> Result<serde_json::Value, String> {
             }
             Ok(serde_json::Value::Array(res))
         }
+        Value::Iterable(it) => {
+            let mut res = alloc::vec::Vec::new();
+            for item in it.iter() {
+                res.push(convert_value_to_json(&item?)?);
+            }
+            Ok(serde_json::Value::Array(res))
+        }
         Value::Dictionary(d) => {
             let dict = d.read();
             let mut res = serde_json::Map::new();
@@ -46,6 +58,13 @@ fn convert_value_to_json(val: &Value) -> Result<serde_json::Value, String> {
             }
             Ok(serde_json::Value::Object(res))
         }
+        Value::Struct(fields) => {
+            let mut res = serde_json::Map::new();
+            for (k, v) in fields.iter() {
+                res.insert(k.clone(), convert_value_to_json(v)?);
+            }
+            Ok(serde_json::Value::Object(res))
+        }
         Value::Set(_) => Err("Object of type 'set' is not JSON serializable".to_string()),
         Value::Function(_)
         | Value::NativeFunction(_, _)
@@ -63,6 +82,7 @@ mod tests {
     use super::*;
     use alloc::collections::BTreeMap;
     use alloc::vec::Vec;
+    use eldritch_core::Freezable;
     use eldritch_core::conversion::ToValue;
 
     #[test]
@@ -109,6 +129,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn to_json_struct() -> Result<(), String> {
+        let mut fields = BTreeMap::new();
+        fields.insert("port".to_string(), 443i64.to_value());
+        fields.insert("host".to_string(), "example.com".to_string().to_value());
+        let val = Value::Struct(alloc::sync::Arc::new(fields));
+
+        let res = to_json(val)?;
+        assert_eq!(res, r#"{"host":"example.com","port":443}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn to_json_lazy_iterable() -> Result<(), String> {
+        let val = eldritch_core::Interpreter::new().interpret("{'ports': range(3)}")?;
+        let res = to_json(val)?;
+        assert_eq!(res, r#"{"ports":[0,1,2]}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn to_json_big_int() -> Result<(), String> {
+        let val = u64::MAX.to_value();
+        assert_eq!(to_json(val)?, "18446744073709551615");
+
+        let val = Value::from_bigint(eldritch_core::BigInt::from(u64::MAX) + 1);
+        assert!(to_json(val).is_err());
+        Ok(())
+    }
+
     #[test]
     fn to_json_invalid_bytes() {
         let val = Value::Bytes(vec![0xFF]);
@@ -118,7 +168,7 @@ mod tests {
 
     #[test]
     fn to_json_invalid_set() {
-        let val = Value::Set(alloc::sync::Arc::new(spin::RwLock::new(
+        let val = Value::Set(alloc::sync::Arc::new(Freezable::new(
             alloc::collections::BTreeSet::new(),
         )));
         let res = to_json(val);
@@ -138,7 +188,7 @@ mod tests {
 
     #[test]
     fn to_json_invalid_function() {
-        let val = Value::Set(alloc::sync::Arc::new(spin::RwLock::new(
+        let val = Value::Set(alloc::sync::Arc::new(Freezable::new(
             alloc::collections::BTreeSet::new(),
         )));
         assert!(to_json(val).is_err());
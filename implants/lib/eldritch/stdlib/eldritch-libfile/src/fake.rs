@@ -4,6 +4,7 @@ use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
 use eldritch_macros::eldritch_library_impl;
 use spin::Mutex;
@@ -14,7 +15,7 @@ enum FsEntry {
     Dir(BTreeMap<String, FsEntry>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[eldritch_library_impl(FileLibrary)]
 pub struct FileLibraryFake {
     root: Arc<Mutex<FsEntry>>,
@@ -202,7 +203,7 @@ impl FileLibrary for FileLibraryFake {
     }
 
     fn list_named_pipes(&self, _detailed: Option<bool>) -> Result<Value, String> {
-        Ok(Value::List(alloc::sync::Arc::new(spin::RwLock::new(
+        Ok(Value::List(alloc::sync::Arc::new(Freezable::new(
             alloc::vec![
                 Value::String("fake_pipe1".to_string()),
                 Value::String("fake_pipe2".to_string()),
@@ -101,7 +101,7 @@ pub trait FileLibrary {
     ///
     /// **Parameters**
     /// - `path` (`str`): The file path to follow.
-    /// - `fn` (`function(str)`): A callback function that takes a string (the new line) as an argument.
+    /// - `fn_val` (`function(str)`): A callback function that takes a string (the new line) as an argument.
     ///
     /// **Returns**
     /// - `None` (This function may block indefinitely or until interrupted).
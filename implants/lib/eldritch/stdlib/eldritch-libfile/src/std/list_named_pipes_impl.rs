@@ -1,17 +1,19 @@
+#[cfg(target_os = "windows")]
 use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
-use spin::RwLock;
 
 fn make_list(items: Vec<Value>) -> Value {
-    Value::List(Arc::new(RwLock::new(items)))
+    Value::List(Arc::new(Freezable::new(items)))
 }
 
+#[cfg(target_os = "windows")]
 fn make_dict(map: BTreeMap<String, Value>) -> Value {
-    Value::Dictionary(Arc::new(RwLock::new(
+    Value::Dictionary(Arc::new(Freezable::new(
         map.into_iter()
             .map(|(k, v)| (Value::String(k), v))
             .collect(),
@@ -2,9 +2,9 @@ use super::HttpLibrary;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::sync::Arc;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
 use eldritch_macros::eldritch_library_impl;
-use spin::RwLock;
 
 #[derive(Default, Debug)]
 #[eldritch_library_impl(HttpLibrary)]
@@ -50,7 +50,7 @@ impl HttpLibrary for HttpLibraryFake {
         );
         map.insert(
             "headers".into(),
-            Value::Dictionary(Arc::new(RwLock::new(headers_map))),
+            Value::Dictionary(Arc::new(Freezable::new(headers_map))),
         );
 
         Ok(map)
@@ -90,7 +90,7 @@ impl HttpLibrary for HttpLibraryFake {
         );
         map.insert(
             "headers".into(),
-            Value::Dictionary(Arc::new(RwLock::new(headers_map))),
+            Value::Dictionary(Arc::new(Freezable::new(headers_map))),
         );
 
         Ok(map)
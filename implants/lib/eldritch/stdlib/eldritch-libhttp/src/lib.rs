@@ -27,6 +27,7 @@ pub trait HttpLibrary {
     /// - `uri` (`str`): The URL to download from.
     /// - `dst` (`str`): The local destination path.
     /// - `allow_insecure` (`Option<bool>`): If true, ignore SSL certificate verification.
+    ///
     /// **Returns**
     /// - `None`
     ///
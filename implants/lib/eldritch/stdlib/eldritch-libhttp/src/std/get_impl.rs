@@ -2,12 +2,12 @@ use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
 use reqwest::{
     blocking::Client,
     header::{HeaderMap, HeaderName, HeaderValue},
 };
-use spin::RwLock;
 
 pub fn get(
     mut uri: String,
@@ -59,7 +59,7 @@ pub fn get(
     }
     map.insert(
         "headers".into(),
-        Value::Dictionary(Arc::new(RwLock::new(headers_map))),
+        Value::Dictionary(Arc::new(Freezable::new(headers_map))),
     );
 
     let bytes = resp
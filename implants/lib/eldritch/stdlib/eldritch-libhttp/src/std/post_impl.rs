@@ -2,9 +2,9 @@ use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use spin::RwLock;
 
 pub fn post(
     mut uri: String,
@@ -59,7 +59,7 @@ pub fn post(
     }
     map.insert(
         "headers".into(),
-        Value::Dictionary(Arc::new(RwLock::new(headers_map))),
+        Value::Dictionary(Arc::new(Freezable::new(headers_map))),
     );
 
     let bytes = resp
@@ -125,9 +125,9 @@ mod tests {
         let list = lib.list(Some(true)).unwrap();
         assert_eq!(list.len(), 3);
         // environ should be present when include_env is true
-        assert!(list[0].get("environ").is_some());
-        assert!(list[1].get("environ").is_some());
-        assert!(list[2].get("environ").is_some());
+        assert!(list[0].contains_key("environ"));
+        assert!(list[1].contains_key("environ"));
+        assert!(list[2].contains_key("environ"));
     }
 
     #[test]
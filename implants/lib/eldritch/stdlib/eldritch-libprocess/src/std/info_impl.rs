@@ -2,8 +2,8 @@ use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
-use spin::RwLock;
 use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
 
 pub fn info(pid: Option<i64>) -> Result<BTreeMap<String, Value>, String> {
@@ -41,7 +41,7 @@ pub fn info(pid: Option<i64>) -> Result<BTreeMap<String, Value>, String> {
         }
         map.insert(
             "environ".to_string(),
-            Value::Dictionary(Arc::new(RwLock::new(env_map))),
+            Value::Dictionary(Arc::new(Freezable::new(env_map))),
         );
 
         map.insert(
@@ -2,7 +2,10 @@ use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
 
 pub fn bool() -> Result<bool, String> {
-    let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
+    bool_with(&mut rand_chacha::ChaCha20Rng::from_entropy())
+}
+
+pub fn bool_with(rng: &mut impl Rng) -> Result<bool, String> {
     Ok(rng.r#gen::<bool>())
 }
 
@@ -5,10 +5,13 @@ use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
 
 pub fn bytes(len: i64) -> Result<Vec<u8>, String> {
+    bytes_with(&mut rand_chacha::ChaCha20Rng::from_entropy(), len)
+}
+
+pub fn bytes_with(rng: &mut impl Rng, len: i64) -> Result<Vec<u8>, String> {
     if len < 0 {
         return Err("Length cannot be negative".to_string());
     }
-    let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
     let mut bytes = vec![0u8; len as usize];
     rng.fill(&mut bytes[..]);
     Ok(bytes)
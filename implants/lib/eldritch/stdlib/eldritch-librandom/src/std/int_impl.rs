@@ -3,10 +3,13 @@ use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
 
 pub fn int(min: i64, max: i64) -> Result<i64, String> {
+    int_with(&mut rand_chacha::ChaCha20Rng::from_entropy(), min, max)
+}
+
+pub fn int_with(rng: &mut impl Rng, min: i64, max: i64) -> Result<i64, String> {
     if min >= max {
         return Err("Invalid range".to_string());
     }
-    let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
     Ok(rng.gen_range(min..max))
 }
 
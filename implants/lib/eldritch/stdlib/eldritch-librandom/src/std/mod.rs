@@ -6,9 +6,12 @@ use eldritch_macros::eldritch_library_impl;
 pub mod bool_impl;
 pub mod bytes_impl;
 pub mod int_impl;
+mod seeded;
 pub mod string_impl;
 pub mod uuid_impl;
 
+pub use seeded::SeededRandomLibrary;
+
 #[derive(Default, Debug)]
 #[eldritch_library_impl(RandomLibrary)]
 pub struct StdRandomLibrary;
@@ -0,0 +1,76 @@
+use super::{bool_impl, bytes_impl, int_impl, string_impl, uuid_impl};
+use crate::RandomLibrary;
+use alloc::string::String;
+use alloc::vec::Vec;
+use eldritch_macros::eldritch_library_impl;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use std::sync::Mutex;
+
+/// A `random` library that draws every value from one stream seeded by the host, so a tome
+/// sees the same values each time it runs.
+#[derive(Debug)]
+#[eldritch_library_impl(RandomLibrary)]
+pub struct SeededRandomLibrary {
+    rng: Mutex<ChaCha20Rng>,
+}
+
+impl SeededRandomLibrary {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(ChaCha20Rng::seed_from_u64(seed)),
+        }
+    }
+
+    fn rng(&self) -> std::sync::MutexGuard<'_, ChaCha20Rng> {
+        self.rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RandomLibrary for SeededRandomLibrary {
+    fn bool(&self) -> Result<bool, String> {
+        bool_impl::bool_with(&mut *self.rng())
+    }
+
+    fn bytes(&self, len: i64) -> Result<Vec<u8>, String> {
+        bytes_impl::bytes_with(&mut *self.rng(), len)
+    }
+
+    fn int(&self, min: i64, max: i64) -> Result<i64, String> {
+        int_impl::int_with(&mut *self.rng(), min, max)
+    }
+
+    fn string(&self, len: i64, charset: Option<String>) -> Result<String, String> {
+        string_impl::string_with(&mut *self.rng(), len, charset)
+    }
+
+    fn uuid(&self) -> Result<String, String> {
+        uuid_impl::uuid_with(&mut *self.rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let a = SeededRandomLibrary::new(7);
+        let b = SeededRandomLibrary::new(7);
+        for _ in 0..10 {
+            assert_eq!(a.int(0, 1000).unwrap(), b.int(0, 1000).unwrap());
+        }
+        assert_eq!(a.string(16, None).unwrap(), b.string(16, None).unwrap());
+        assert_eq!(a.uuid().unwrap(), b.uuid().unwrap());
+        assert!(uuid::Uuid::parse_str(&a.uuid().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let a = SeededRandomLibrary::new(1);
+        let b = SeededRandomLibrary::new(2);
+        assert_ne!(a.bytes(32).unwrap(), b.bytes(32).unwrap());
+    }
+}
@@ -1,12 +1,20 @@
 use alloc::string::{String, ToString};
+use rand::Rng;
 use rand::distributions::{Alphanumeric, DistString, Distribution, Uniform};
 use rand_chacha::rand_core::SeedableRng;
 
 pub fn string(len: i64, charset: Option<String>) -> Result<String, String> {
+    string_with(&mut rand_chacha::ChaCha20Rng::from_entropy(), len, charset)
+}
+
+pub fn string_with(
+    rng: &mut impl Rng,
+    len: i64,
+    charset: Option<String>,
+) -> Result<String, String> {
     if len < 0 {
         return Err("Length cannot be negative".to_string());
     }
-    let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
     let res = match charset {
         Some(charset) => {
             let strlen = charset.chars().count();
@@ -16,12 +24,12 @@ pub fn string(len: i64, charset: Option<String>) -> Result<String, String> {
             let rand_dist = Uniform::from(0..strlen);
             let mut s = String::new();
             for _ in 0..len {
-                let index = rand_dist.sample(&mut rng);
+                let index = rand_dist.sample(rng);
                 s.push(charset.chars().nth(index).unwrap());
             }
             s
         }
-        None => Alphanumeric.sample_string(&mut rng, len as usize),
+        None => Alphanumeric.sample_string(rng, len as usize),
     };
 
     Ok(res)
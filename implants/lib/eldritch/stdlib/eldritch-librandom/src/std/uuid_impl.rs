@@ -1,9 +1,17 @@
 use alloc::string::{String, ToString};
+use rand::Rng;
 
 pub fn uuid() -> Result<String, String> {
     Ok(uuid::Uuid::new_v4().to_string())
 }
 
+pub fn uuid_with(rng: &mut impl Rng) -> Result<String, String> {
+    let bytes: [u8; 16] = rng.r#gen();
+    Ok(uuid::Builder::from_random_bytes(bytes)
+        .into_uuid()
+        .to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +70,7 @@ pub trait SysLibrary {
     /// - `args` (`List<str>`): List of arguments.
     /// - `disown` (`Option<bool>`): If `True`, runs in background/detached.
     /// - `env_vars` (`Option<Dict<str, str>>`): Environment variables to set.
+    /// - `input` (`Option<str>`): Text written to the program's stdin.
     ///
     /// **Returns**
     /// - `Dict`: Output containing `stdout`, `stderr`, and `status` (exit code).
@@ -3,8 +3,8 @@ use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use anyhow::Result;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
-use spin::RwLock;
 use std::process;
 use sysinfo::{Pid, ProcessExt, System, SystemExt, UserExt};
 
@@ -48,7 +48,7 @@ pub fn get_user() -> Result<BTreeMap<String, Value>> {
             .collect();
         dict_user.insert(
             Value::String("groups".to_string()),
-            Value::List(Arc::new(RwLock::new(groups))),
+            Value::List(Arc::new(Freezable::new(groups))),
         );
 
         #[cfg(not(target_os = "windows"))]
@@ -80,12 +80,12 @@ pub fn get_user() -> Result<BTreeMap<String, Value>> {
                 .collect();
             dict_euser.insert(
                 Value::String("groups".to_string()),
-                Value::List(Arc::new(RwLock::new(egroups))),
+                Value::List(Arc::new(Freezable::new(egroups))),
             );
 
             dict_res.insert(
                 "euid".to_string(),
-                Value::Dictionary(Arc::new(RwLock::new(dict_euser))),
+                Value::Dictionary(Arc::new(Freezable::new(dict_euser))),
             );
 
             let gid = match process.group_id() {
@@ -103,7 +103,7 @@ pub fn get_user() -> Result<BTreeMap<String, Value>> {
 
         dict_res.insert(
             "uid".to_string(),
-            Value::Dictionary(Arc::new(RwLock::new(dict_user))),
+            Value::Dictionary(Arc::new(Freezable::new(dict_user))),
         );
         return Ok(dict_res);
     }
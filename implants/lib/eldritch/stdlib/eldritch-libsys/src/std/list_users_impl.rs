@@ -3,8 +3,8 @@ use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use anyhow::Result;
+use eldritch_core::Freezable;
 use eldritch_core::Value;
-use spin::RwLock;
 use sysinfo::{System, SystemExt, UserExt};
 
 pub fn list_users() -> Result<Vec<BTreeMap<String, Value>>> {
@@ -47,7 +47,7 @@ pub fn list_users() -> Result<Vec<BTreeMap<String, Value>>> {
 
         dict_user.insert(
             "groups".to_string(),
-            Value::List(Arc::new(RwLock::new(groups))),
+            Value::List(Arc::new(Freezable::new(groups))),
         );
 
         users_list.push(dict_user);
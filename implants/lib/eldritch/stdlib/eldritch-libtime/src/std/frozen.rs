@@ -0,0 +1,63 @@
+use super::{format_to_epoch_impl, format_to_readable_impl, sleep_impl};
+use crate::TimeLibrary;
+use alloc::string::String;
+use eldritch_macros::eldritch_library_impl;
+use std::sync::Mutex;
+
+/// A `time` library whose clock starts at a fixed timestamp and only moves when the tome
+/// sleeps. Sleeping returns immediately, so runs are fast and repeatable.
+#[derive(Debug)]
+#[eldritch_library_impl(TimeLibrary)]
+pub struct FrozenTimeLibrary {
+    epoch: i64,
+    slept: Mutex<f64>,
+}
+
+impl FrozenTimeLibrary {
+    pub fn new(epoch: i64) -> Self {
+        Self {
+            epoch,
+            slept: Mutex::new(0.0),
+        }
+    }
+}
+
+impl TimeLibrary for FrozenTimeLibrary {
+    fn format_to_epoch(&self, input: String, format: String) -> Result<i64, String> {
+        format_to_epoch_impl::format_to_epoch(input, format)
+    }
+
+    fn format_to_readable(&self, input: i64, format: String) -> Result<String, String> {
+        format_to_readable_impl::format_to_readable(input, format)
+    }
+
+    fn now(&self) -> Result<i64, String> {
+        let slept = *self.slept.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.epoch.saturating_add(slept as i64))
+    }
+
+    fn sleep(&self, secs: f64) -> Result<(), String> {
+        sleep_impl::check_duration(secs)?;
+        *self.slept.lock().unwrap_or_else(|e| e.into_inner()) += secs;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frozen_clock_advances_only_on_sleep() {
+        let lib = FrozenTimeLibrary::new(1_700_000_000);
+        assert_eq!(lib.now().unwrap(), 1_700_000_000);
+        assert_eq!(lib.now().unwrap(), 1_700_000_000);
+
+        let start = std::time::Instant::now();
+        lib.sleep(90.5).unwrap();
+        assert!(start.elapsed().as_secs() < 1);
+        assert_eq!(lib.now().unwrap(), 1_700_000_090);
+
+        assert!(lib.sleep(-1.0).is_err());
+    }
+}
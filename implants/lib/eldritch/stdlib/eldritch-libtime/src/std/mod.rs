@@ -4,9 +4,12 @@ use eldritch_macros::eldritch_library_impl;
 
 pub mod format_to_epoch_impl;
 pub mod format_to_readable_impl;
+mod frozen;
 pub mod now_impl;
 pub mod sleep_impl;
 
+pub use frozen::FrozenTimeLibrary;
+
 #[derive(Debug, Default)]
 #[eldritch_library_impl(TimeLibrary)]
 pub struct StdTimeLibrary;
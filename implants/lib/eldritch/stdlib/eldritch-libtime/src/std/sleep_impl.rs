@@ -1,13 +1,18 @@
 use std::{thread, time};
 
 pub fn sleep(secs: f64) -> Result<(), String> {
+    check_duration(secs)?;
+    thread::sleep(time::Duration::from_secs_f64(secs));
+    Ok(())
+}
+
+pub fn check_duration(secs: f64) -> Result<(), String> {
     if secs < 0.0 {
         return Err("sleep length must be non-negative".to_string());
     }
     if !secs.is_finite() {
         return Err("sleep length must be a finite number".to_string());
     }
-    thread::sleep(time::Duration::from_secs_f64(secs));
     Ok(())
 }
 
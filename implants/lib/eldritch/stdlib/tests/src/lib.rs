@@ -1,10 +1,9 @@
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use eldritch::{Interpreter, Value};
+    use eldritch::{Freezable, Interpreter, Value};
     use glob::glob;
     use serde::Deserialize;
-    use spin::RwLock;
     use std::collections::BTreeMap;
     use std::fs;
     use std::path::PathBuf;
@@ -131,7 +130,7 @@ mod tests {
                 input_params.insert(Value::String(param.name), val);
             }
         }
-        let input_params_val = Value::Dictionary(Arc::new(RwLock::new(input_params)));
+        let input_params_val = Value::Dictionary(Arc::new(Freezable::new(input_params)));
         interp.define_variable("input_params", input_params_val);
 
         match interp.interpret(&code) {
@@ -4,7 +4,7 @@ use alloc::vec::Vec;
 use core::sync::atomic::{AtomicBool, Ordering};
 use eldritch_agent::{Agent, Context};
 use pb::c2;
-use pb::eldritch::Process;
+use pb::eldritch::{Credential, File, Process};
 use std::sync::{Arc, Mutex, RwLock};
 
 extern crate alloc;
@@ -14,6 +14,10 @@ pub struct MockAgent {
     pub assets: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
     pub should_fail_fetch: AtomicBool,
     pub reported_processes: Arc<Mutex<Vec<Process>>>,
+    pub reported_credentials: Arc<Mutex<Vec<Credential>>>,
+    /// Reported files, with the chunks of each file joined into one.
+    pub reported_files: Arc<Mutex<Vec<File>>>,
+    pub reported_output: Arc<Mutex<Vec<c2::ReportOutputRequest>>>,
 }
 
 impl MockAgent {
@@ -26,6 +30,9 @@ impl MockAgent {
             assets: Arc::new(Mutex::new(BTreeMap::new())),
             should_fail_fetch: AtomicBool::new(false),
             reported_processes: Arc::new(Mutex::new(Vec::new())),
+            reported_credentials: Arc::new(Mutex::new(Vec::new())),
+            reported_files: Arc::new(Mutex::new(Vec::new())),
+            reported_output: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -74,15 +81,30 @@ impl Agent for MockAgent {
 
     fn report_credential(
         &self,
-        _req: c2::ReportCredentialRequest,
+        req: c2::ReportCredentialRequest,
     ) -> Result<c2::ReportCredentialResponse, String> {
+        if let Some(credential) = req.credential {
+            self.reported_credentials.lock().unwrap().push(credential);
+        }
         Ok(c2::ReportCredentialResponse::default())
     }
 
     fn report_file(
         &self,
-        _req: std::sync::mpsc::Receiver<c2::ReportFileRequest>,
+        req: std::sync::mpsc::Receiver<c2::ReportFileRequest>,
     ) -> Result<c2::ReportFileResponse, String> {
+        let mut files = self.reported_files.lock().unwrap();
+        for msg in req {
+            let Some(chunk) = msg.chunk else {
+                continue;
+            };
+            // Metadata is only sent with the first chunk of each file
+            if chunk.metadata.is_some() || files.is_empty() {
+                files.push(chunk);
+            } else if let Some(file) = files.last_mut() {
+                file.chunk.extend(chunk.chunk);
+            }
+        }
         Ok(c2::ReportFileResponse::default())
     }
 
@@ -111,8 +133,9 @@ impl Agent for MockAgent {
 
     fn report_output(
         &self,
-        _req: c2::ReportOutputRequest,
+        req: c2::ReportOutputRequest,
     ) -> Result<c2::ReportOutputResponse, String> {
+        self.reported_output.lock().unwrap().push(req);
         Ok(c2::ReportOutputResponse::default())
     }
 
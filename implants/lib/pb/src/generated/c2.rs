@@ -186,8 +186,37 @@ pub struct ShellTask {
 /// TaskError provides information when task execution fails.
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct TaskError {
+    /// The rendered error, as shown to operators.
     #[prost(string, tag = "1")]
     pub msg: ::prost::alloc::string::String,
+    /// The kind of script error, e.g. "TypeError". Empty when the task did not fail in its script.
+    #[prost(string, tag = "2")]
+    pub kind: ::prost::alloc::string::String,
+    /// A stable identifier for the kind, e.g. "E0002", that does not change if the kind is renamed.
+    #[prost(string, tag = "3")]
+    pub code: ::prost::alloc::string::String,
+    /// The calls that led to the error, outermost first.
+    #[prost(message, repeated, tag = "4")]
+    pub traceback: ::prost::alloc::vec::Vec<task_error::Frame>,
+    /// The library whose method raised the error, e.g. "file".
+    #[prost(string, tag = "5")]
+    pub library: ::prost::alloc::string::String,
+    /// Advice for common mistakes, such as a misspelled variable.
+    #[prost(string, tag = "6")]
+    pub hint: ::prost::alloc::string::String,
+}
+/// Nested message and enum types in `TaskError`.
+pub mod task_error {
+    /// Frame is one call in the traceback of a failed script.
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+    pub struct Frame {
+        #[prost(string, tag = "1")]
+        pub function: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub filename: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "3")]
+        pub line: u32,
+    }
 }
 /// TaskOutput provides information about a running task.
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
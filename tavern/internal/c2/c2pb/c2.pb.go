@@ -666,8 +666,19 @@ func (x *ShellTask) GetJwt() string {
 
 // TaskError provides information when task execution fails.
 type TaskError struct {
-	state         protoimpl.MessageState `protogen:"open.v1"`
-	Msg           string                 `protobuf:"bytes,1,opt,name=msg,proto3" json:"msg,omitempty"`
+	state protoimpl.MessageState `protogen:"open.v1"`
+	// The rendered error, as shown to operators.
+	Msg string `protobuf:"bytes,1,opt,name=msg,proto3" json:"msg,omitempty"`
+	// The kind of script error, e.g. "TypeError". Empty when the task did not fail in its script.
+	Kind string `protobuf:"bytes,2,opt,name=kind,proto3" json:"kind,omitempty"`
+	// A stable identifier for the kind, e.g. "E0002", that does not change if the kind is renamed.
+	Code string `protobuf:"bytes,3,opt,name=code,proto3" json:"code,omitempty"`
+	// The calls that led to the error, outermost first.
+	Traceback []*TaskError_Frame `protobuf:"bytes,4,rep,name=traceback,proto3" json:"traceback,omitempty"`
+	// The library whose method raised the error, e.g. "file".
+	Library string `protobuf:"bytes,5,opt,name=library,proto3" json:"library,omitempty"`
+	// Advice for common mistakes, such as a misspelled variable.
+	Hint          string `protobuf:"bytes,6,opt,name=hint,proto3" json:"hint,omitempty"`
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
 }
@@ -709,6 +720,41 @@ func (x *TaskError) GetMsg() string {
 	return ""
 }
 
+func (x *TaskError) GetKind() string {
+	if x != nil {
+		return x.Kind
+	}
+	return ""
+}
+
+func (x *TaskError) GetCode() string {
+	if x != nil {
+		return x.Code
+	}
+	return ""
+}
+
+func (x *TaskError) GetTraceback() []*TaskError_Frame {
+	if x != nil {
+		return x.Traceback
+	}
+	return nil
+}
+
+func (x *TaskError) GetLibrary() string {
+	if x != nil {
+		return x.Library
+	}
+	return ""
+}
+
+func (x *TaskError) GetHint() string {
+	if x != nil {
+		return x.Hint
+	}
+	return ""
+}
+
 // TaskOutput provides information about a running task.
 type TaskOutput struct {
 	state  protoimpl.MessageState `protogen:"open.v1"`
@@ -1988,6 +2034,67 @@ func (x *CreatePortalResponse) GetMote() *portalpb.Mote {
 	return nil
 }
 
+// Frame is one call in the traceback of a failed script.
+type TaskError_Frame struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Function      string                 `protobuf:"bytes,1,opt,name=function,proto3" json:"function,omitempty"`
+	Filename      string                 `protobuf:"bytes,2,opt,name=filename,proto3" json:"filename,omitempty"`
+	Line          uint32                 `protobuf:"varint,3,opt,name=line,proto3" json:"line,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *TaskError_Frame) Reset() {
+	*x = TaskError_Frame{}
+	mi := &file_c2_proto_msgTypes[29]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *TaskError_Frame) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*TaskError_Frame) ProtoMessage() {}
+
+func (x *TaskError_Frame) ProtoReflect() protoreflect.Message {
+	mi := &file_c2_proto_msgTypes[29]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use TaskError_Frame.ProtoReflect.Descriptor instead.
+func (*TaskError_Frame) Descriptor() ([]byte, []int) {
+	return file_c2_proto_rawDescGZIP(), []int{7, 0}
+}
+
+func (x *TaskError_Frame) GetFunction() string {
+	if x != nil {
+		return x.Function
+	}
+	return ""
+}
+
+func (x *TaskError_Frame) GetFilename() string {
+	if x != nil {
+		return x.Filename
+	}
+	return ""
+}
+
+func (x *TaskError_Frame) GetLine() uint32 {
+	if x != nil {
+		return x.Line
+	}
+	return 0
+}
+
 var File_c2_proto protoreflect.FileDescriptor
 
 const file_c2_proto_rawDesc = "" +
@@ -2052,9 +2159,18 @@ const file_c2_proto_rawDesc = "" +
 	"\vsequence_id\x18\x04 \x01(\x04R\n" +
 	"sequenceId\x12\x1b\n" +
 	"\tstream_id\x18\x05 \x01(\tR\bstreamId\x12\x10\n" +
-	"\x03jwt\x18\x06 \x01(\tR\x03jwt\"\x1d\n" +
+	"\x03jwt\x18\x06 \x01(\tR\x03jwt\"\xfb\x01\n" +
 	"\tTaskError\x12\x10\n" +
-	"\x03msg\x18\x01 \x01(\tR\x03msg\"\xe3\x01\n" +
+	"\x03msg\x18\x01 \x01(\tR\x03msg\x12\x12\n" +
+	"\x04kind\x18\x02 \x01(\tR\x04kind\x12\x12\n" +
+	"\x04code\x18\x03 \x01(\tR\x04code\x121\n" +
+	"\ttraceback\x18\x04 \x03(\v2\x13.c2.TaskError.FrameR\ttraceback\x12\x18\n" +
+	"\alibrary\x18\x05 \x01(\tR\alibrary\x12\x12\n" +
+	"\x04hint\x18\x06 \x01(\tR\x04hint\x1aS\n" +
+	"\x05Frame\x12\x1a\n" +
+	"\bfunction\x18\x01 \x01(\tR\bfunction\x12\x1a\n" +
+	"\bfilename\x18\x02 \x01(\tR\bfilename\x12\x12\n" +
+	"\x04line\x18\x03 \x01(\rR\x04line\"\xe3\x01\n" +
 	"\n" +
 	"TaskOutput\x12\x0e\n" +
 	"\x02id\x18\x01 \x01(\x03R\x02id\x12\x16\n" +
@@ -2159,7 +2275,7 @@ func file_c2_proto_rawDescGZIP() []byte {
 }
 
 var file_c2_proto_enumTypes = make([]protoimpl.EnumInfo, 3)
-var file_c2_proto_msgTypes = make([]protoimpl.MessageInfo, 29)
+var file_c2_proto_msgTypes = make([]protoimpl.MessageInfo, 30)
 var file_c2_proto_goTypes = []any{
 	(ReportFileKind)(0),                  // 0: c2.ReportFileKind
 	(Transport_Type)(0),                  // 1: c2.Transport.Type
@@ -2193,12 +2309,13 @@ var file_c2_proto_goTypes = []any{
 	(*ReportOutputResponse)(nil),         // 29: c2.ReportOutputResponse
 	(*CreatePortalRequest)(nil),          // 30: c2.CreatePortalRequest
 	(*CreatePortalResponse)(nil),         // 31: c2.CreatePortalResponse
-	(*epb.Tome)(nil),                     // 32: eldritch.Tome
-	(*timestamppb.Timestamp)(nil),        // 33: google.protobuf.Timestamp
-	(*epb.Credential)(nil),               // 34: eldritch.Credential
-	(*epb.File)(nil),                     // 35: eldritch.File
-	(*epb.ProcessList)(nil),              // 36: eldritch.ProcessList
-	(*portalpb.Mote)(nil),                // 37: portal.Mote
+	(*TaskError_Frame)(nil),              // 32: c2.TaskError.Frame
+	(*epb.Tome)(nil),                     // 33: eldritch.Tome
+	(*timestamppb.Timestamp)(nil),        // 34: google.protobuf.Timestamp
+	(*epb.Credential)(nil),               // 35: eldritch.Credential
+	(*epb.File)(nil),                     // 36: eldritch.File
+	(*epb.ProcessList)(nil),              // 37: eldritch.ProcessList
+	(*portalpb.Mote)(nil),                // 38: portal.Mote
 }
 var file_c2_proto_depIdxs = []int32{
 	1,  // 0: c2.Transport.type:type_name -> c2.Transport.Type
@@ -2207,57 +2324,58 @@ var file_c2_proto_depIdxs = []int32{
 	3,  // 3: c2.Beacon.agent:type_name -> c2.Agent
 	5,  // 4: c2.Beacon.available_transports:type_name -> c2.AvailableTransports
 	2,  // 5: c2.Host.platform:type_name -> c2.Host.Platform
-	32, // 6: c2.Task.tome:type_name -> eldritch.Tome
-	10, // 7: c2.TaskOutput.error:type_name -> c2.TaskError
-	33, // 8: c2.TaskOutput.exec_started_at:type_name -> google.protobuf.Timestamp
-	33, // 9: c2.TaskOutput.exec_finished_at:type_name -> google.protobuf.Timestamp
-	10, // 10: c2.ShellTaskOutput.error:type_name -> c2.TaskError
-	33, // 11: c2.ShellTaskOutput.exec_started_at:type_name -> google.protobuf.Timestamp
-	33, // 12: c2.ShellTaskOutput.exec_finished_at:type_name -> google.protobuf.Timestamp
-	6,  // 13: c2.ClaimTasksRequest.beacon:type_name -> c2.Beacon
-	8,  // 14: c2.ClaimTasksResponse.tasks:type_name -> c2.Task
-	9,  // 15: c2.ClaimTasksResponse.shell_tasks:type_name -> c2.ShellTask
-	14, // 16: c2.FetchAssetRequest.task_context:type_name -> c2.TaskContext
-	15, // 17: c2.FetchAssetRequest.shell_task_context:type_name -> c2.ShellTaskContext
-	14, // 18: c2.ReportCredentialRequest.task_context:type_name -> c2.TaskContext
-	15, // 19: c2.ReportCredentialRequest.shell_task_context:type_name -> c2.ShellTaskContext
-	34, // 20: c2.ReportCredentialRequest.credential:type_name -> eldritch.Credential
-	14, // 21: c2.ReportFileRequest.task_context:type_name -> c2.TaskContext
-	15, // 22: c2.ReportFileRequest.shell_task_context:type_name -> c2.ShellTaskContext
-	0,  // 23: c2.ReportFileRequest.kind:type_name -> c2.ReportFileKind
-	35, // 24: c2.ReportFileRequest.chunk:type_name -> eldritch.File
-	14, // 25: c2.ReportProcessListRequest.task_context:type_name -> c2.TaskContext
-	15, // 26: c2.ReportProcessListRequest.shell_task_context:type_name -> c2.ShellTaskContext
-	36, // 27: c2.ReportProcessListRequest.list:type_name -> eldritch.ProcessList
-	14, // 28: c2.ReportTaskOutputMessage.context:type_name -> c2.TaskContext
-	11, // 29: c2.ReportTaskOutputMessage.output:type_name -> c2.TaskOutput
-	15, // 30: c2.ReportShellTaskOutputMessage.context:type_name -> c2.ShellTaskContext
-	13, // 31: c2.ReportShellTaskOutputMessage.output:type_name -> c2.ShellTaskOutput
-	26, // 32: c2.ReportOutputRequest.task_output:type_name -> c2.ReportTaskOutputMessage
-	27, // 33: c2.ReportOutputRequest.shell_task_output:type_name -> c2.ReportShellTaskOutputMessage
-	14, // 34: c2.CreatePortalRequest.task_context:type_name -> c2.TaskContext
-	15, // 35: c2.CreatePortalRequest.shell_task_context:type_name -> c2.ShellTaskContext
-	37, // 36: c2.CreatePortalRequest.mote:type_name -> portal.Mote
-	37, // 37: c2.CreatePortalResponse.mote:type_name -> portal.Mote
-	16, // 38: c2.C2.ClaimTasks:input_type -> c2.ClaimTasksRequest
-	18, // 39: c2.C2.FetchAsset:input_type -> c2.FetchAssetRequest
-	20, // 40: c2.C2.ReportCredential:input_type -> c2.ReportCredentialRequest
-	22, // 41: c2.C2.ReportFile:input_type -> c2.ReportFileRequest
-	24, // 42: c2.C2.ReportProcessList:input_type -> c2.ReportProcessListRequest
-	28, // 43: c2.C2.ReportOutput:input_type -> c2.ReportOutputRequest
-	30, // 44: c2.C2.CreatePortal:input_type -> c2.CreatePortalRequest
-	17, // 45: c2.C2.ClaimTasks:output_type -> c2.ClaimTasksResponse
-	19, // 46: c2.C2.FetchAsset:output_type -> c2.FetchAssetResponse
-	21, // 47: c2.C2.ReportCredential:output_type -> c2.ReportCredentialResponse
-	23, // 48: c2.C2.ReportFile:output_type -> c2.ReportFileResponse
-	25, // 49: c2.C2.ReportProcessList:output_type -> c2.ReportProcessListResponse
-	29, // 50: c2.C2.ReportOutput:output_type -> c2.ReportOutputResponse
-	31, // 51: c2.C2.CreatePortal:output_type -> c2.CreatePortalResponse
-	45, // [45:52] is the sub-list for method output_type
-	38, // [38:45] is the sub-list for method input_type
-	38, // [38:38] is the sub-list for extension type_name
-	38, // [38:38] is the sub-list for extension extendee
-	0,  // [0:38] is the sub-list for field type_name
+	33, // 6: c2.Task.tome:type_name -> eldritch.Tome
+	32, // 7: c2.TaskError.traceback:type_name -> c2.TaskError.Frame
+	10, // 8: c2.TaskOutput.error:type_name -> c2.TaskError
+	34, // 9: c2.TaskOutput.exec_started_at:type_name -> google.protobuf.Timestamp
+	34, // 10: c2.TaskOutput.exec_finished_at:type_name -> google.protobuf.Timestamp
+	10, // 11: c2.ShellTaskOutput.error:type_name -> c2.TaskError
+	34, // 12: c2.ShellTaskOutput.exec_started_at:type_name -> google.protobuf.Timestamp
+	34, // 13: c2.ShellTaskOutput.exec_finished_at:type_name -> google.protobuf.Timestamp
+	6,  // 14: c2.ClaimTasksRequest.beacon:type_name -> c2.Beacon
+	8,  // 15: c2.ClaimTasksResponse.tasks:type_name -> c2.Task
+	9,  // 16: c2.ClaimTasksResponse.shell_tasks:type_name -> c2.ShellTask
+	14, // 17: c2.FetchAssetRequest.task_context:type_name -> c2.TaskContext
+	15, // 18: c2.FetchAssetRequest.shell_task_context:type_name -> c2.ShellTaskContext
+	14, // 19: c2.ReportCredentialRequest.task_context:type_name -> c2.TaskContext
+	15, // 20: c2.ReportCredentialRequest.shell_task_context:type_name -> c2.ShellTaskContext
+	35, // 21: c2.ReportCredentialRequest.credential:type_name -> eldritch.Credential
+	14, // 22: c2.ReportFileRequest.task_context:type_name -> c2.TaskContext
+	15, // 23: c2.ReportFileRequest.shell_task_context:type_name -> c2.ShellTaskContext
+	0,  // 24: c2.ReportFileRequest.kind:type_name -> c2.ReportFileKind
+	36, // 25: c2.ReportFileRequest.chunk:type_name -> eldritch.File
+	14, // 26: c2.ReportProcessListRequest.task_context:type_name -> c2.TaskContext
+	15, // 27: c2.ReportProcessListRequest.shell_task_context:type_name -> c2.ShellTaskContext
+	37, // 28: c2.ReportProcessListRequest.list:type_name -> eldritch.ProcessList
+	14, // 29: c2.ReportTaskOutputMessage.context:type_name -> c2.TaskContext
+	11, // 30: c2.ReportTaskOutputMessage.output:type_name -> c2.TaskOutput
+	15, // 31: c2.ReportShellTaskOutputMessage.context:type_name -> c2.ShellTaskContext
+	13, // 32: c2.ReportShellTaskOutputMessage.output:type_name -> c2.ShellTaskOutput
+	26, // 33: c2.ReportOutputRequest.task_output:type_name -> c2.ReportTaskOutputMessage
+	27, // 34: c2.ReportOutputRequest.shell_task_output:type_name -> c2.ReportShellTaskOutputMessage
+	14, // 35: c2.CreatePortalRequest.task_context:type_name -> c2.TaskContext
+	15, // 36: c2.CreatePortalRequest.shell_task_context:type_name -> c2.ShellTaskContext
+	38, // 37: c2.CreatePortalRequest.mote:type_name -> portal.Mote
+	38, // 38: c2.CreatePortalResponse.mote:type_name -> portal.Mote
+	16, // 39: c2.C2.ClaimTasks:input_type -> c2.ClaimTasksRequest
+	18, // 40: c2.C2.FetchAsset:input_type -> c2.FetchAssetRequest
+	20, // 41: c2.C2.ReportCredential:input_type -> c2.ReportCredentialRequest
+	22, // 42: c2.C2.ReportFile:input_type -> c2.ReportFileRequest
+	24, // 43: c2.C2.ReportProcessList:input_type -> c2.ReportProcessListRequest
+	28, // 44: c2.C2.ReportOutput:input_type -> c2.ReportOutputRequest
+	30, // 45: c2.C2.CreatePortal:input_type -> c2.CreatePortalRequest
+	17, // 46: c2.C2.ClaimTasks:output_type -> c2.ClaimTasksResponse
+	19, // 47: c2.C2.FetchAsset:output_type -> c2.FetchAssetResponse
+	21, // 48: c2.C2.ReportCredential:output_type -> c2.ReportCredentialResponse
+	23, // 49: c2.C2.ReportFile:output_type -> c2.ReportFileResponse
+	25, // 50: c2.C2.ReportProcessList:output_type -> c2.ReportProcessListResponse
+	29, // 51: c2.C2.ReportOutput:output_type -> c2.ReportOutputResponse
+	31, // 52: c2.C2.CreatePortal:output_type -> c2.CreatePortalResponse
+	46, // [46:53] is the sub-list for method output_type
+	39, // [39:46] is the sub-list for method input_type
+	39, // [39:39] is the sub-list for extension type_name
+	39, // [39:39] is the sub-list for extension extendee
+	0,  // [0:39] is the sub-list for field type_name
 }
 
 func init() { file_c2_proto_init() }
@@ -2295,7 +2413,7 @@ func file_c2_proto_init() {
 			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
 			RawDescriptor: unsafe.Slice(unsafe.StringData(file_c2_proto_rawDesc), len(file_c2_proto_rawDesc)),
 			NumEnums:      3,
-			NumMessages:   29,
+			NumMessages:   30,
 			NumExtensions: 0,
 			NumServices:   1,
 		},
@@ -1,4 +1,4 @@
-import { ExtensionContext } from 'vscode';
+import { DebugAdapterExecutable, ExtensionContext, debug } from 'vscode';
 import {
     LanguageClient,
     LanguageClientOptions,
@@ -27,6 +27,13 @@ export function activate(context: ExtensionContext) {
 
     // Start the client. This will also launch the server
     client.start();
+
+    // Debug tomes with the Eldritch debug adapter, which speaks DAP over stdio
+    context.subscriptions.push(
+        debug.registerDebugAdapterDescriptorFactory('eldritch', {
+            createDebugAdapterDescriptor: () => new DebugAdapterExecutable("eldritch-dap"),
+        })
+    );
 }
 
 export function deactivate(): Thenable<void> | undefined {
@@ -1,31 +1,33 @@
 use std::{
-    fs, iter,
+    cell::RefCell,
+    collections::BTreeMap,
+    fs,
     path::{Path, PathBuf},
 };
 
-use gazebo::prelude::*;
-use itertools::Either;
-use starlark::{
-    starlark_module,
-    environment::{FrozenModule, Globals, GlobalsBuilder, Module},
-    eval::Evaluator,
-    syntax::{AstModule, Dialect},
-};
+use anyhow::anyhow;
 use eldritch::{
-    file::FileLibrary,
-    process::ProcessLibrary,
-    sys::SysLibrary,
+    agent::fake::AgentLibraryFake, assets::fake::FakeAssetsLibrary, chain::fake::FakeChainLibrary,
+    crypto::fake::CryptoLibraryFake, dns::fake::DnsLibraryFake, file::fake::FileLibraryFake,
+    http::fake::HttpLibraryFake, pivot::fake::PivotLibraryFake, process::fake::ProcessLibraryFake,
+    random::fake::RandomLibraryFake, regex::fake::RegexLibraryFake,
+    report::fake::ReportLibraryFake, sys::fake::SysLibraryFake, time::fake::TimeLibraryFake,
+    Interpreter,
+};
+use eldritch_core::{
+    conversion::ToValue, ExprKind, Lexer, Parser, Span, Stmt, StmtKind, TokenKind,
 };
 
 use crate::types::Message;
 
-#[derive(Debug)]
 pub struct Context {
     pub check: bool,
     pub info: bool,
     pub run: bool,
-    pub prelude: Vec<FrozenModule>,
-    pub module: Option<Module>,
+    /// The sources of the prelude files, run before every file.
+    pub prelude: Vec<(String, String)>,
+    /// The interpreter shared by every evaluation in interactive mode.
+    pub module: Option<RefCell<Interpreter>>,
 }
 
 impl Context {
@@ -36,19 +38,15 @@ impl Context {
         prelude: &[PathBuf],
         module: bool,
     ) -> anyhow::Result<Self> {
-        let globals = globals();
-
-        let prelude = prelude.try_map(|x| {
-            let env = Module::new();
-
-            let mut eval = Evaluator::new(&env);
-            let module = AstModule::parse_file(x, &dialect())?;
-            eval.eval_module(module, &globals)?;
-            env.freeze()
-        })?;
+        let prelude = prelude
+            .iter()
+            .map(|x| Ok((x.to_string_lossy().into_owned(), fs::read_to_string(x)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        // Fail early on a prelude that doesn't run, rather than on every file
+        Self::new_module(&prelude)?;
 
         let module = if module {
-            Some(Self::new_module(&prelude))
+            Some(RefCell::new(Self::new_module(&prelude)?))
         } else {
             None
         };
@@ -62,56 +60,63 @@ impl Context {
         })
     }
 
-    fn new_module(prelude: &[FrozenModule]) -> Module {
-        let module = Module::new();
-        for p in prelude {
-            module.import_public_symbols(p);
+    /// An interpreter with every library faked, `input_params` empty and the prelude run.
+    fn new_module(prelude: &[(String, String)]) -> anyhow::Result<Interpreter> {
+        let mut interp = Interpreter::new();
+        interp.register_lib(AgentLibraryFake);
+        interp.register_lib(FakeAssetsLibrary);
+        interp.register_lib(FakeChainLibrary::new());
+        interp.register_lib(CryptoLibraryFake);
+        interp.register_lib(DnsLibraryFake);
+        interp.register_lib(FileLibraryFake::default());
+        interp.register_lib(HttpLibraryFake);
+        interp.register_lib(PivotLibraryFake);
+        interp.register_lib(ProcessLibraryFake);
+        interp.register_lib(RandomLibraryFake);
+        interp.register_lib(RegexLibraryFake);
+        interp.register_lib(ReportLibraryFake);
+        interp.register_lib(SysLibraryFake);
+        interp.register_lib(TimeLibraryFake);
+        interp.define_variable("input_params", BTreeMap::<String, String>::new().to_value());
+
+        for (path, source) in prelude {
+            interp
+                .run(source)
+                .map_err(|e| anyhow!("{}", e.with_filename(path)))?;
         }
-        module
+        Ok(interp)
     }
 
-    fn go(&self, file: &str, ast: AstModule) -> impl Iterator<Item = Message> {
-        let mut warnings = Either::Left(iter::empty());
-        let mut errors = Either::Left(iter::empty());
+    fn go(&self, file: &str, content: String) -> Vec<Message> {
+        let ast = match parse(file, &content) {
+            Ok(ast) => ast,
+            Err(errors) => return errors,
+        };
+
+        let mut messages = Vec::new();
         if self.info {
-            self.info(&ast);
+            messages.extend(self.info(file, &content, &ast));
         }
         if self.check {
-            warnings = Either::Right(self.check(&ast));
+            messages.extend(self.check(file, &content, &ast));
         }
         if self.run {
-            errors = Either::Right(self.run(file, ast));
-        }
-        warnings.chain(errors)
-    }
-
-    // Convert an anyhow over iterator of Message, into an iterator of Message
-    fn err(
-        file: &str,
-        result: anyhow::Result<impl Iterator<Item = Message>>,
-    ) -> impl Iterator<Item = Message> {
-        match result {
-            Err(e) => Either::Left(iter::once(Message::from_anyhow(file, e))),
-            Ok(res) => Either::Right(res),
+            messages.extend(self.run(file, &content));
         }
+        messages
     }
 
     pub fn expression(&self, content: String) -> impl Iterator<Item = Message> {
-        let file = "expression";
-        Self::err(
-            file,
-            AstModule::parse(file, content, &dialect()).map(|module| self.go(file, module)),
-        )
+        self.go("expression", content).into_iter()
     }
 
     pub fn file(&self, file: &Path) -> impl Iterator<Item = Message> {
         let filename = &file.to_string_lossy();
-        Self::err(
-            filename,
-            fs::read_to_string(file)
-                .map(|content| self.file_with_contents(filename, content))
-                .map_err(|e| e.into()),
-        )
+        match fs::read_to_string(file) {
+            Ok(content) => self.go(filename, content),
+            Err(e) => vec![Message::from_anyhow(filename, e.into())],
+        }
+        .into_iter()
     }
 
     pub fn file_with_contents(
@@ -119,64 +124,166 @@ impl Context {
         filename: &str,
         content: String,
     ) -> impl Iterator<Item = Message> {
-        Self::err(
-            filename,
-            AstModule::parse(filename, content, &dialect()).map(|module| self.go(filename, module)),
-        )
-    }
-
-    fn run(&self, file: &str, ast: AstModule) -> impl Iterator<Item = Message> {
-        let new_module;
-        let module = match self.module.as_ref() {
-            Some(module) => module,
-            None => {
-                new_module = Self::new_module(&self.prelude);
-                &new_module
-            }
+        self.go(filename, content).into_iter()
+    }
+
+    fn run(&self, file: &str, content: &str) -> Option<Message> {
+        let result = match self.module.as_ref() {
+            Some(module) => module.borrow_mut().run(content),
+            None => match Self::new_module(&self.prelude) {
+                Ok(mut interp) => interp.run(content),
+                Err(e) => return Some(Message::from_anyhow(file, e)),
+            },
         };
-        let mut eval = Evaluator::new(module);
-        eval.enable_terminal_breakpoint_console();
-        let globals = globals();
-        Self::err(file, eval.eval_module(ast, &globals).map(|_| iter::empty()))
+        result
+            .err()
+            .map(|e| Message::from_script_error(file, content, e))
     }
 
-    fn info(&self, module: &AstModule) {
-        let exports = module.exported_symbols();
-        println!("Exports {} symbol(s)", exports.len());
-        for (loc, name) in exports {
-            println!("* {} {}", loc, name)
-        }
+    fn info(&self, file: &str, content: &str, ast: &[Stmt]) -> Vec<Message> {
+        exported_symbols(ast)
+            .into_iter()
+            .map(|(span, name)| Message::export(file, content, span, name))
+            .collect()
     }
 
-    fn check(&self, module: &AstModule) -> impl Iterator<Item = Message> {
-        let mut globals = Vec::new();
-        for x in &self.prelude {
-            globals.extend(x.names());
-        }
-        let globals = if self.prelude.is_empty() {
-            None
-        } else {
-            Some(globals.as_slice())
+    // Type errors and unknown methods, checked against the libraries and prelude globals
+    fn check(&self, file: &str, content: &str, ast: &[Stmt]) -> Vec<Message> {
+        let checker = match self.module.as_ref() {
+            Some(module) => module.borrow().type_checker(),
+            None => match Self::new_module(&self.prelude) {
+                Ok(interp) => interp.type_checker(),
+                Err(e) => return vec![Message::from_anyhow(file, e)],
+            },
         };
-
-        module.lint(globals).into_iter().map(Message::from_lint)
+        checker
+            .check(ast)
+            .into_iter()
+            .map(|d| Message::from_diagnostic(file, content, d))
+            .collect()
     }
 }
 
-pub fn globals() -> Globals {
-    #[starlark_module]
-    fn stdlib(builder: &mut GlobalsBuilder) {
-        const file: FileLibrary = FileLibrary();
-        const process: ProcessLibrary = ProcessLibrary();
-        const sys: SysLibrary = SysLibrary();
+/// Lexes and parses `content` with the grammar imix runs, returning every syntax error if
+/// there are any.
+fn parse(file: &str, content: &str) -> Result<Vec<Stmt>, Vec<Message>> {
+    let tokens = Lexer::new(content.to_string()).scan_tokens();
+    let mut errors: Vec<Message> = tokens
+        .iter()
+        .filter_map(|token| match &token.kind {
+            TokenKind::Error(msg) => Some(Message::syntax_error(
+                file,
+                content,
+                token.span,
+                msg.clone(),
+            )),
+            _ => None,
+        })
+        .collect();
+    let (ast, parse_errors) = Parser::new(tokens).parse();
+    errors.extend(
+        parse_errors
+            .into_iter()
+            .map(|e| Message::syntax_error(file, content, e.span, e.message)),
+    );
+    if errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(errors)
     }
+}
 
-    GlobalsBuilder::new().with(stdlib).build()
+// The top-level functions and variables a file defines, with the statement defining each
+fn exported_symbols(ast: &[Stmt]) -> Vec<(Span, String)> {
+    let mut exports: Vec<(Span, String)> = Vec::new();
+    for stmt in ast {
+        let name = match &stmt.kind {
+            StmtKind::Def(name, ..) => name.clone(),
+            StmtKind::Assignment(target, ..) => match &target.kind {
+                ExprKind::Identifier(name) => name.clone(),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        if !name.starts_with('_') && !exports.iter().any(|(_, n)| *n == name) {
+            exports.push((stmt.span, name));
+        }
+    }
+    exports
 }
 
-pub fn dialect() -> Dialect {
-    Dialect {
-        enable_f_strings: true,
-        ..Dialect::Extended
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Severity;
+
+    // A context that only does what the flags ask for, as `main` builds it
+    fn context(check: bool, info: bool, run: bool) -> Context {
+        Context::new(check, info, run, &[], false).unwrap()
+    }
+
+    fn evaluate(ctx: &Context, content: &str) -> Vec<Message> {
+        ctx.file_with_contents("test.eldritch", content.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let messages = evaluate(&context(false, false, true), "x = (1,\n");
+        assert!(!messages.is_empty());
+        let message = &messages[0];
+        assert!(matches!(message.severity, Severity::Error));
+        assert_eq!(message.name, "syntax-error");
+        assert_eq!(message.path, "test.eldritch");
+        assert!(message.span.is_some());
+    }
+
+    #[test]
+    fn test_type_error() {
+        let messages = evaluate(&context(true, false, false), "x = 1\ny: int = 'a'\n");
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        let message = &messages[0];
+        assert!(matches!(message.severity, Severity::Error));
+        assert_eq!(message.name, "type-mismatch");
+        assert_eq!(message.span.unwrap().begin_line, 1);
+    }
+
+    #[test]
+    fn test_runtime_error() {
+        let messages = evaluate(&context(false, false, true), "x = 1\ny = x / 0\n");
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        let message = &messages[0];
+        assert!(matches!(message.severity, Severity::Error));
+        assert_eq!(message.name, "ZeroDivisionError");
+        assert_eq!(message.span.unwrap().begin_line, 1);
+        assert_eq!(message.original.as_deref(), Some("x / 0"));
+        assert!(message
+            .full_error_with_span
+            .as_deref()
+            .unwrap()
+            .contains("test.eldritch"));
+
+        let ctx = context(false, false, true);
+        assert!(evaluate(&ctx, "file.exists('/')\n").is_empty());
+    }
+
+    #[test]
+    fn test_info_lists_exports() {
+        let content = "def f():\n    pass\nx = 1\n_private = 2\nx = 3\nf()\n";
+        let messages = evaluate(&context(false, true, false), content);
+        let exports: Vec<_> = messages
+            .iter()
+            .map(|m| {
+                let span = m.span.unwrap();
+                (m.name.as_str(), m.description.as_str(), span.begin_line)
+            })
+            .collect();
+        assert_eq!(
+            exports,
+            [("export", "exports `f`", 0), ("export", "exports `x`", 2)]
+        );
+        assert!(messages
+            .iter()
+            .all(|m| matches!(m.severity, Severity::Advice)));
     }
 }
@@ -14,26 +14,26 @@ use serde::de::DeserializeOwned;
 
 use crate::{
     eval::Context,
-    types::{Message as StarlarkMessage, Severity},
+    types::{Message as EldritchMessage, Severity},
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 struct Backend {
     connection: Connection,
-    starlark: Context,
+    eldritch: Context,
 }
 
 fn to_severity(x: Severity) -> DiagnosticSeverity {
     match x {
-        Severity::Error => DiagnosticSeverity::Error,
-        Severity::Warning => DiagnosticSeverity::Warning,
-        Severity::Advice => DiagnosticSeverity::Hint,
-        Severity::Disabled => DiagnosticSeverity::Information,
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Advice => DiagnosticSeverity::HINT,
+        Severity::Disabled => DiagnosticSeverity::INFORMATION,
     }
 }
 
-fn to_diagnostic(x: StarlarkMessage) -> Diagnostic {
+fn to_diagnostic(x: EldritchMessage) -> Diagnostic {
     let range = match x.span {
         Some(s) => Range::new(
             Position::new(s.begin_line as u32, s.begin_column as u32),
@@ -45,7 +45,7 @@ fn to_diagnostic(x: StarlarkMessage) -> Diagnostic {
         range,
         Some(to_severity(x.severity)),
         Some(NumberOrString::String(x.name)),
-        None,
+        Some("eldritch".to_owned()),
         x.description,
         None,
         None,
@@ -56,16 +56,19 @@ fn to_diagnostic(x: StarlarkMessage) -> Diagnostic {
 impl Backend {
     fn server_capabilities() -> ServerCapabilities {
         ServerCapabilities {
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::Full)),
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
             ..ServerCapabilities::default()
         }
     }
 
     fn validate(&self, uri: Url, version: Option<i64>, text: String) {
-        self.log_message(MessageType::Info, format!("Validating: {}", uri.path()).as_str());
+        self.log_message(
+            MessageType::INFO,
+            format!("Validating: {}", uri.path()).as_str(),
+        );
         let diags = self
-            .starlark
-            .file_with_contents(&uri.to_string(), text)
+            .eldritch
+            .file_with_contents(uri.as_ref(), text)
             .map(to_diagnostic)
             .collect();
         self.publish_diagnostics(uri, diags, version)
@@ -117,7 +120,7 @@ impl Backend {
     }
 
     fn main_loop(&self, _params: InitializeParams) -> anyhow::Result<()> {
-        self.log_message(MessageType::Info, "Eldritch Language Server Initialised");
+        self.log_message(MessageType::INFO, "Eldritch Language Server Initialised");
         for msg in &self.connection.receiver {
             match msg {
                 Message::Request(req) => {
@@ -144,18 +147,18 @@ impl Backend {
     }
 }
 
-pub fn server(starlark: Context) -> anyhow::Result<()> {
+pub fn server(eldritch: Context) -> anyhow::Result<()> {
     // Note that  we must have our logging only write out to stderr.
     eprintln!("Starting Eldritch Language Server v{v}", v=VERSION);
 
     let (connection, io_threads) = Connection::stdio();
     // Run the server and wait for the two threads to end (typically by trigger LSP Exit event).
-    let server_capabilities = serde_json::to_value(&Backend::server_capabilities()).unwrap();
+    let server_capabilities = serde_json::to_value(Backend::server_capabilities()).unwrap();
     let initialization_params = connection.initialize(server_capabilities)?;
     let initialization_params = serde_json::from_value(initialization_params).unwrap();
     Backend {
         connection,
-        starlark,
+        eldritch,
     }
     .main_loop(initialization_params)?;
     io_threads.join()?;
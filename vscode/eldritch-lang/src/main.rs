@@ -1,21 +1,15 @@
-// Features we use
-#![feature(box_syntax)]
-//
-// Plugins
-#![cfg_attr(feature = "custom_linter", feature(plugin))]
-#![cfg_attr(feature = "custom_linter", allow(deprecated))] // :(
-#![cfg_attr(feature = "custom_linter", plugin(gazebo_lint))]
-// Disagree these are good hints
-#![allow(clippy::type_complexity)]
-
-use std::{ffi::OsStr, fmt, fmt::Display, fs, path::PathBuf, sync::Arc};
+use std::{
+    ffi::OsStr,
+    fmt,
+    fmt::Display,
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
 
 use anyhow::anyhow;
+use clap::Parser;
 use eval::Context;
-use gazebo::prelude::*;
-use itertools::Either;
-use starlark::read_line::ReadLine;
-use structopt::{clap::AppSettings, StructOpt};
 use walkdir::WalkDir;
 
 use crate::types::{LintMessage, Message, Severity};
@@ -24,58 +18,54 @@ mod eval;
 mod lsp;
 mod types;
 
-#[derive(Debug, StructOpt)]
-#[structopt(
-    name = "eldritch-lang",
-    about = "Evaluate Eldritch tomes",
-    global_settings(&[AppSettings::ColoredHelp]),
-)]
+#[derive(Debug, Parser)]
+#[command(name = "eldritch-lang", about = "Evaluate Eldritch tomes")]
 pub struct Args {
-    #[structopt(
+    #[arg(
         long = "interactive",
-        long = "repl",
-        short = "i",
+        visible_alias = "repl",
+        short = 'i',
         help = "Start an interactive REPL."
     )]
     interactive: bool,
 
-    #[structopt(long = "lsp", help = "Start an LSP server.")]
+    #[arg(long = "lsp", help = "Start an LSP server.")]
     lsp: bool,
 
-    #[structopt(long = "check", help = "Run checks and lints.")]
+    #[arg(long = "check", help = "Run checks and lints.")]
     check: bool,
 
-    #[structopt(long = "info", help = "Show information about the code.")]
+    #[arg(long = "info", help = "Show information about the code.")]
     info: bool,
 
-    #[structopt(long = "json", help = "Show output as JSON lines.")]
+    #[arg(long = "json", help = "Show output as JSON lines.")]
     json: bool,
 
-    #[structopt(
+    #[arg(
         long = "repeat",
         help = "Number of times to repeat the execution",
         default_value = "1"
     )]
     repeat: usize,
 
-    #[structopt(
+    #[arg(
         long = "extension",
         help = "File extension when searching directories."
     )]
     extension: Option<String>,
 
-    #[structopt(long = "prelude", help = "Files to load in advance.")]
+    #[arg(long = "prelude", help = "Files to load in advance.")]
     prelude: Vec<PathBuf>,
 
-    #[structopt(
+    #[arg(
         long = "expression",
-        short = "e",
-        name = "EXPRESSION",
+        short = 'e',
+        value_name = "EXPRESSION",
         help = "Expressions to evaluate."
     )]
     evaluate: Vec<String>,
 
-    #[structopt(name = "FILE", help = "Files to evaluate.")]
+    #[arg(value_name = "FILE", help = "Files to evaluate.")]
     // String instead of PathBuf so we can expand @file things
     files: Vec<String>,
 }
@@ -101,23 +91,23 @@ fn expand_args(args: Vec<String>) -> anyhow::Result<Vec<PathBuf>> {
 
 // Treat directories as things to recursively walk for .<extension> files,
 // and everything else as normal files.
-fn expand_dirs(extension: &str, xs: Vec<PathBuf>) -> impl Iterator<Item = PathBuf> {
-    let extension = Arc::new(extension.to_owned());
-    xs.into_iter().flat_map(move |x| {
-        // Have to keep cloning extension so we keep ownership
-        let extension = extension.dupe();
+fn expand_dirs(extension: &str, xs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut res = Vec::with_capacity(xs.len());
+    for x in xs {
         if x.is_dir() {
-            Either::Left(
+            res.extend(
                 WalkDir::new(x)
+                    .sort_by_file_name()
                     .into_iter()
                     .filter_map(|e| e.ok())
-                    .filter(move |e| e.path().extension() == Some(OsStr::new(extension.as_str())))
+                    .filter(|e| e.path().extension() == Some(OsStr::new(extension)))
                     .map(|e| e.into_path()),
-            )
+            );
         } else {
-            Either::Right(box vec![x].into_iter())
+            res.push(x);
         }
-    })
+    }
+    res
 }
 
 #[derive(Default)]
@@ -171,12 +161,14 @@ fn drain(xs: impl Iterator<Item = Message>, json: bool, stats: &mut Stats) {
 }
 
 fn interactive(ctx: &Context) -> anyhow::Result<()> {
-    let mut rl = ReadLine::new();
+    let mut lines = io::stdin().lock().lines();
     loop {
-        match rl.read_line("$> ")? {
+        print!("$> ");
+        io::stdout().flush()?;
+        match lines.next() {
             Some(line) => {
                 let mut stats = Stats::default();
-                drain(ctx.expression(line), false, &mut stats);
+                drain(ctx.expression(line?), false, &mut stats);
             }
             // User pressed EOF - disconnected terminal, or similar
             None => return Ok(()),
@@ -185,17 +177,17 @@ fn interactive(ctx: &Context) -> anyhow::Result<()> {
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::from_args();
+    let args = Args::parse();
     let ext = args
         .extension
         .as_ref()
-        .map_or("tome", |x| x.as_str())
-        .trim_start_match('.');
+        .map_or("eldritch", |x| x.as_str())
+        .trim_start_matches('.');
     let mut ctx = Context::new(
         args.check,
         args.info,
         !args.check && !args.info,
-        &expand_dirs(ext, args.prelude).collect::<Vec<_>>(),
+        &expand_dirs(ext, args.prelude),
         args.interactive,
     )?;
 
@@ -1,135 +1,208 @@
- use std::fmt::{self, Display};
-
- use gazebo::prelude::*;
- use serde::Serialize;
- use starlark::{
-     codemap::ResolvedSpan,
-     errors::{Diagnostic, Lint},
- };
- 
- /// A standardised set of severities.
- #[derive(Debug, Serialize, Dupe, Clone, Copy)]
- #[serde(rename_all = "lowercase")]
- pub enum Severity {
-     Error,
-     Warning,
-     // Not all severities are used right now
-     #[allow(dead_code)]
-     Advice,
-     Disabled,
- }
- 
- impl Display for Severity {
-     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-         f.write_str(match self {
-             Severity::Error => "Error",
-             Severity::Warning => "Warning",
-             Severity::Advice => "Advice",
-             Severity::Disabled => "Disabled",
-         })
-     }
- }
- 
- #[derive(Debug, Clone)]
- pub struct Message {
-     pub path: String,
-     pub span: Option<ResolvedSpan>,
-     pub severity: Severity,
-     pub name: String,
-     pub description: String,
-     pub full_error_with_span: Option<String>,
-     /// The text referred to by span
-     pub original: Option<String>,
- }
- 
- impl Display for Message {
-     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-         write!(f, "{}: {}:", self.severity, self.path)?;
-         if let Some(span) = self.span {
-             write!(f, "{}", span)?;
-         }
-         write!(f, " {}", self.description)
-     }
- }
- 
- impl Message {
-     pub fn from_anyhow(file: &str, x: anyhow::Error) -> Self {
-         match x.downcast_ref::<Diagnostic>() {
-             Some(
-                 d @ Diagnostic {
-                     message,
-                     span: Some(span),
-                     ..
-                 },
-             ) => {
-                 let original = span.file.source_span(span.span).to_owned();
-                 let resolved_span = span.resolve_span();
-                 Self {
-                     path: span.file.filename().to_owned(),
-                     span: Some(resolved_span),
-                     severity: Severity::Error,
-                     name: "error".to_owned(),
-                     description: format!("{:#}", message),
-                     full_error_with_span: Some(d.to_string()),
-                     original: Some(original),
-                 }
-             }
-             _ => Self {
-                 path: file.to_owned(),
-                 span: None,
-                 severity: Severity::Error,
-                 name: "error".to_owned(),
-                 description: format!("{:#}", x),
-                 full_error_with_span: None,
-                 original: None,
-             },
-         }
-     }
- 
-     pub fn from_lint(x: Lint) -> Self {
-         Self {
-             path: x.location.file.filename().to_owned(),
-             span: Some(x.location.resolve_span()),
-             severity: if x.serious {
-                 Severity::Warning
-             } else {
-                 // Start with all non-serious errors disabled, and ramp up from there
-                 Severity::Disabled
-             },
-             name: x.short_name,
-             description: x.problem,
-             full_error_with_span: None,
-             original: Some(x.original),
-         }
-     }
- }
- 
- /// A JSON-deriving type that gives a stable interface to downstream types.
- /// Do NOT change this type, change Message instead.
- #[derive(Debug, Clone, Serialize)]
- pub struct LintMessage {
-     path: String,
-     line: Option<usize>,
-     char: Option<usize>,
-     code: String,
-     severity: Severity,
-     name: String,
-     description: Option<String>,
-     #[serde(skip_serializing_if = "Option::is_none")]
-     original: Option<String>,
- }
- 
- impl LintMessage {
-     pub fn new(x: Message) -> Self {
-         Self {
-             path: x.path,
-             line: x.span.map(|x| x.begin_line + 1),
-             char: x.span.map(|x| x.begin_column + 1),
-             code: "STARLARK".to_owned(),
-             severity: x.severity,
-             name: x.name,
-             description: Some(x.description),
-             original: x.original,
-         }
-     }
- }
\ No newline at end of file
+use std::fmt::{self, Display};
+
+use eldritch_core::{typecheck, ScriptError, Span};
+use serde::Serialize;
+
+/// A standardised set of severities.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Advice,
+    // Not all severities are used right now
+    #[allow(dead_code)]
+    Disabled,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Advice => "Advice",
+            Severity::Disabled => "Disabled",
+        })
+    }
+}
+
+/// A span resolved to 0-based lines and columns, counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedSpan {
+    pub begin_line: usize,
+    pub begin_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl ResolvedSpan {
+    /// Resolves a span of character offsets into `source`.
+    pub fn resolve(source: &str, span: Span) -> Self {
+        let (begin_line, begin_column) = line_column(source, span.start);
+        let (end_line, end_column) = line_column(source, span.end.max(span.start));
+        Self {
+            begin_line,
+            begin_column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut line = 0;
+    let mut column = 0;
+    for c in source.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+impl Display for ResolvedSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.begin_line + 1,
+            self.begin_column + 1,
+            self.end_line + 1,
+            self.end_column + 1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub path: String,
+    pub span: Option<ResolvedSpan>,
+    pub severity: Severity,
+    pub name: String,
+    pub description: String,
+    pub full_error_with_span: Option<String>,
+    /// The text referred to by span
+    pub original: Option<String>,
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}:", self.severity, self.path)?;
+        if let Some(span) = self.span {
+            write!(f, "{}", span)?;
+        }
+        write!(f, " {}", self.description)
+    }
+}
+
+// The text of `source` that `span` covers
+fn original(source: &str, span: Span) -> String {
+    source
+        .chars()
+        .skip(span.start)
+        .take(span.end.saturating_sub(span.start))
+        .collect()
+}
+
+impl Message {
+    pub fn from_anyhow(file: &str, x: anyhow::Error) -> Self {
+        Self {
+            path: file.to_owned(),
+            span: None,
+            severity: Severity::Error,
+            name: "error".to_owned(),
+            description: format!("{:#}", x),
+            full_error_with_span: None,
+            original: None,
+        }
+    }
+
+    /// A syntax error from the lexer or parser.
+    pub fn syntax_error(file: &str, source: &str, span: Span, message: String) -> Self {
+        Self {
+            path: file.to_owned(),
+            span: Some(ResolvedSpan::resolve(source, span)),
+            severity: Severity::Error,
+            name: "syntax-error".to_owned(),
+            description: message,
+            full_error_with_span: None,
+            original: Some(original(source, span)),
+        }
+    }
+
+    /// An error raised while running the file, with its traceback.
+    pub fn from_script_error(file: &str, source: &str, x: ScriptError) -> Self {
+        let x = x.with_filename(file);
+        Self {
+            path: file.to_owned(),
+            span: Some(ResolvedSpan::resolve(source, x.span)),
+            severity: Severity::Error,
+            name: x.kind.to_string(),
+            description: x.message.clone(),
+            full_error_with_span: Some(x.render()),
+            original: Some(original(source, x.span)),
+        }
+    }
+
+    /// A top-level function or variable the file defines, listed by `--info`.
+    pub fn export(file: &str, source: &str, span: Span, name: String) -> Self {
+        Self {
+            path: file.to_owned(),
+            span: Some(ResolvedSpan::resolve(source, span)),
+            severity: Severity::Advice,
+            name: "export".to_owned(),
+            description: format!("exports `{}`", name),
+            full_error_with_span: None,
+            original: None,
+        }
+    }
+
+    /// A type checker finding, reported under its check's code, e.g. `unknown-method`.
+    pub fn from_diagnostic(file: &str, source: &str, x: typecheck::Diagnostic) -> Self {
+        Self {
+            path: file.to_owned(),
+            span: Some(ResolvedSpan::resolve(source, x.span)),
+            severity: match x.severity {
+                typecheck::Severity::Error => Severity::Error,
+                typecheck::Severity::Warning => Severity::Warning,
+            },
+            name: x.code.to_owned(),
+            description: x.message,
+            full_error_with_span: None,
+            original: Some(original(source, x.span)),
+        }
+    }
+}
+
+/// A JSON-deriving type that gives a stable interface to downstream types.
+/// Do NOT change this type, change Message instead.
+#[derive(Debug, Clone, Serialize)]
+pub struct LintMessage {
+    path: String,
+    line: Option<usize>,
+    char: Option<usize>,
+    code: String,
+    severity: Severity,
+    name: String,
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original: Option<String>,
+}
+
+impl LintMessage {
+    pub fn new(x: Message) -> Self {
+        Self {
+            path: x.path,
+            line: x.span.map(|x| x.begin_line + 1),
+            char: x.span.map(|x| x.begin_column + 1),
+            code: "ELDRITCH".to_owned(),
+            severity: x.severity,
+            name: x.name,
+            description: Some(x.description),
+            original: x.original,
+        }
+    }
+}